@@ -0,0 +1,145 @@
+//! Database backend
+//!
+//! Picks MongoDB or a SQL database from the scheme of `DB_URI` and builds the
+//! matching migrations and auth stores. SQL backends are compiled in with the
+//! `postgres` or `sqlite` feature.
+
+use std::sync::Arc;
+
+use database::init::{Database, DatabaseConfig, init_database};
+use database::migrations::{MigrationRunner, Migrator};
+use database::mongo::MongoConnection;
+use database::transaction::Transactions;
+use database::utils::DbResult;
+
+#[cfg(feature = "sql")]
+use database::sql::{Dialect, SqlConnection, SqlMigrationRunner};
+
+use crate::state::AppState;
+
+/// Connected database the application runs on
+#[derive(Clone)]
+pub enum Backend {
+    Mongo(Arc<MongoConnection>),
+    #[cfg(feature = "sql")]
+    Sql(Arc<SqlConnection>),
+}
+
+impl Backend {
+    /// Connect to the database named by the connection string
+    pub async fn connect(config: DatabaseConfig) -> DbResult<Backend> {
+        #[cfg(feature = "sql")]
+        if database::sql::is_sql_url(&config.connection_string) {
+            return Ok(Backend::Sql(Arc::new(SqlConnection::new(config).await?)));
+        }
+
+        Ok(Backend::Mongo(Arc::new(init_database(config).await?)))
+    }
+
+    /// Backend name for logs
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::Mongo(_) => "MongoDB",
+            #[cfg(feature = "sql")]
+            Backend::Sql(db) => match db.dialect() {
+                Dialect::Postgres => "PostgreSQL",
+                Dialect::Sqlite => "SQLite",
+            },
+        }
+    }
+
+    /// Connection as the backend-agnostic database handle
+    pub fn database(&self) -> Arc<dyn Database> {
+        match self {
+            Backend::Mongo(db) => db.clone(),
+            #[cfg(feature = "sql")]
+            Backend::Sql(db) => db.clone(),
+        }
+    }
+
+    /// Runner with all application migrations registered
+    pub fn migrator(&self) -> Box<dyn Migrator> {
+        match self {
+            Backend::Mongo(db) => Box::new(
+                MigrationRunner::new(db.database())
+                    .with_migrations(auth::store::database::migrations::migrations()),
+            ),
+            #[cfg(feature = "sql")]
+            Backend::Sql(db) => Box::new(
+                SqlMigrationRunner::new(db.pool().clone())
+                    .with_migrations(auth::store::sql::migrations::migrations()),
+            ),
+        }
+    }
+
+    /// Create missing indexes once, before any worker starts
+    pub async fn prepare(&self) {
+        match self {
+            Backend::Mongo(db) => ensure_mongo_indexes(&db.database()).await,
+            // SQL indexes are created by the migrations
+            #[cfg(feature = "sql")]
+            Backend::Sql(_) => {}
+        }
+    }
+
+    /// Build the auth module state on this backend's stores
+    pub fn auth_state(&self, state: &AppState) -> auth::routes::AppState {
+        match self {
+            Backend::Mongo(db) => {
+                let mongo_db = db.database();
+                let users = Arc::new(auth::store::database::MongoUserStore::new(
+                    mongo_db.collection(auth::store::database::collections::USERS),
+                )) as Arc<dyn auth::store::UserStore>;
+
+                let transactions = Transactions::mongo(db.client().clone());
+                auth::routes::init(
+                    &mongo_db,
+                    users,
+                    state.jwt_secret.clone(),
+                    state.jwt_expiry_minutes,
+                    state.refresh_token_expiry_days,
+                    state.email.clone(),
+                    Some(state.sms.clone()),
+                    state.email_from.clone(),
+                    state.app_name.clone(),
+                    state.frontend_url.clone(),
+                )
+                .with_transactions(transactions)
+            }
+            #[cfg(feature = "sql")]
+            Backend::Sql(db) => auth::routes::init_sql(
+                db,
+                auth::routes::AuthSettings {
+                    jwt_secret:                state.jwt_secret.clone(),
+                    jwt_expiry_minutes:        state.jwt_expiry_minutes,
+                    refresh_token_expiry_days: state.refresh_token_expiry_days,
+                    email:                     state.email.clone(),
+                    sms:                       Some(state.sms.clone()),
+                    email_from:                state.email_from.clone(),
+                    app_name:                  state.app_name.clone(),
+                    frontend_url:              state.frontend_url.clone(),
+                },
+            ),
+        }
+    }
+}
+
+async fn ensure_mongo_indexes(db: &mongodb::Database) {
+    for report in auth::store::database::ensure_indexes(db).await {
+        if !report.created.is_empty() {
+            middleware::tracing::info!(
+                "Created indexes on {}: {}",
+                report.collection,
+                report.created.join(", ")
+            );
+        }
+        for failure in &report.failed {
+            middleware::tracing::warn!(
+                "Failed to create index {} on {}: {}",
+                failure.name,
+                report.collection,
+                failure.error
+            );
+        }
+    }
+}
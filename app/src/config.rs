@@ -6,6 +6,11 @@ pub struct AppConfig {
     // Database
     pub db_uri: String,
     pub db_name: String,
+    pub db_migrate_on_start: bool,
+    pub db_max_connections: u32,
+    pub db_min_connections: u32,
+    pub db_connect_timeout_secs: u64,
+    pub db_connect_retries: u32,
 
     // Server
     pub server_ip: String,
@@ -34,6 +39,7 @@ pub struct AppConfig {
     pub sms_account_sid: String,
     pub sms_auth_token: String,
     pub sms_from_number: String,
+    pub phone_default_region: String,
 }
 
 impl AppConfig {
@@ -51,6 +57,26 @@ impl AppConfig {
             // Database
             db_uri: env::var("DB_URI").expect("DB_URI must be set in .env"),
             db_name: env::var("DB_NAME").expect("DB_NAME must be set in .env"),
+            db_migrate_on_start: env::var("DB_MIGRATE_ON_START")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .expect("DB_MIGRATE_ON_START must be true or false"),
+            db_max_connections: env::var("DB_MAX_CONNECTIONS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .expect("DB_MAX_CONNECTIONS must be a valid number"),
+            db_min_connections: env::var("DB_MIN_CONNECTIONS")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .expect("DB_MIN_CONNECTIONS must be a valid number"),
+            db_connect_timeout_secs: env::var("DB_CONNECT_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .expect("DB_CONNECT_TIMEOUT_SECS must be a valid number"),
+            db_connect_retries: env::var("DB_CONNECT_RETRIES")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .expect("DB_CONNECT_RETRIES must be a valid number"),
 
             // Server
             server_ip: env::var("SERVER_IP").expect("SERVER_IP must be set in .env"),
@@ -91,6 +117,7 @@ impl AppConfig {
             sms_auth_token: env::var("SMS_AUTH_TOKEN").expect("SMS_AUTH_TOKEN must be set in .env"),
             sms_from_number: env::var("SMS_FROM_NUMBER")
                 .expect("SMS_FROM_NUMBER must be set in .env"),
+            phone_default_region: env::var("PHONE_DEFAULT_REGION").unwrap_or_else(|_| "KE".to_string()),
         }
     }
 }
@@ -1,3 +1,5 @@
+pub mod backend;
 pub mod config;
+pub mod migrate;
 pub mod routes;
 pub mod state;
@@ -2,6 +2,7 @@ use actix_cors::Cors;
 use actix_web::{App, HttpServer, web};
 use std::sync::Arc;
 
+use app::backend::Backend;
 use app::config::AppConfig;
 use app::routes::init_routes;
 use app::state::AppState;
@@ -10,7 +11,7 @@ use app::state::AppState;
 use middleware::logger::init_with_level;
 
 // Utils - Email
-use database::init::{DatabaseConfig, init_database};
+use database::init::DatabaseConfig;
 use utils::email::{EmailService, SmtpConfig};
 use utils::sms::{SmsService, TwilioConfig};
 use utils::websocket::{WsServerConfig, WsService};
@@ -40,9 +41,29 @@ async fn main() -> std::io::Result<()> {
         }
     );
 
-    let db_config = DatabaseConfig::new(&config.db_uri, &config.db_name);
-    let db = Arc::new(init_database(db_config).expect("Failed to initialize database"));
-    middleware::tracing::info!("MongoDB connected successfully, database");
+    let db_config = DatabaseConfig::new(&config.db_uri, &config.db_name)
+        .with_pool(config.db_max_connections, config.db_min_connections)
+        .with_timeout(config.db_connect_timeout_secs)
+        .with_retry(config.db_connect_retries, 500);
+    let backend = Backend::connect(db_config).await.expect("Failed to initialize database");
+    let db = backend.database();
+    middleware::tracing::info!("{} connected successfully", backend.name());
+
+    // Admin command: `app migrate [status|up|down <version>]`
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("migrate") {
+        return app::migrate::run_command(backend.migrator().as_ref(), &args[1..])
+            .await
+            .map_err(|e| std::io::Error::other(e.to_string()));
+    }
+
+    if config.db_migrate_on_start {
+        app::migrate::migrate(backend.migrator().as_ref())
+            .await
+            .expect("Failed to run database migrations");
+    }
+
+    backend.prepare().await;
 
     // Initialize email service
     middleware::tracing::info!("Initializing email service: {}", config.email_provider);
@@ -68,12 +89,12 @@ async fn main() -> std::io::Result<()> {
             &config.sms_account_sid,
             &config.sms_auth_token,
             &config.sms_from_number,
-        ))),
+        )).with_default_region(&config.phone_default_region)),
         _ => Arc::new(SmsService::twilio(TwilioConfig::new(
             &config.sms_account_sid,
             &config.sms_auth_token,
             &config.sms_from_number,
-        ))),
+        )).with_default_region(&config.phone_default_region)),
     };
 
     // Initialize WebSocket service
@@ -83,9 +104,6 @@ async fn main() -> std::io::Result<()> {
     );
     let ws = Arc::new(WsService::new(ws_config));
 
-    // Get MongoDB database instance for stores
-    let mongo_db = db.database();
-
     // Create app state (general services only)
     let state = AppState::new(
         email,
@@ -100,25 +118,47 @@ async fn main() -> std::io::Result<()> {
         config.frontend_url.clone(),
     );
 
+    // Auth stores are built on the configured backend (MongoDB or SQL). The
+    // API key validator holds the per-key rate limiter, so it is shared by
+    // all workers rather than built in each
+    let auth_state = backend.auth_state(&state);
+    let api_keys = auth_state.api_key_validator();
+
     // Run server
+    let db_for_shutdown = db.clone();
     HttpServer::new(move || {
-        let state_for_routes = state.clone();
-        let routes_mongo_db = mongo_db.clone();
+        let auth_state = auth_state.clone();
+        let api_keys = api_keys.clone();
         App::new()
             .wrap(Cors::default())
             .app_data(web::Data::new(state.clone()))
-            .configure(move |cfg| init_routes(cfg, &state_for_routes, &routes_mongo_db))
+            .configure(move |cfg| init_routes(cfg, &auth_state, api_keys))
             // Health check
             .service(health_check)
     })
     .bind((config.server_ip, config.server_port))?
     .run()
-    .await
+    .await?;
+
+    // Workers have stopped, close pooled connections cleanly
+    if let Err(e) = db_for_shutdown.close().await {
+        middleware::tracing::warn!("Failed to close database connections: {}", e);
+    }
+    Ok(())
 }
 
 #[actix_web::get("/health")]
-async fn health_check() -> impl actix_web::Responder {
-    actix_web::HttpResponse::Ok().json(serde_json::json!({
-        "status": "healthy"
-    }))
+async fn health_check(state: web::Data<AppState>) -> impl actix_web::Responder {
+    let pool = state.db.pool_stats();
+
+    match state.db.ping().await {
+        Ok(()) => actix_web::HttpResponse::Ok().json(serde_json::json!({
+            "status": "healthy",
+            "database": { "status": "up", "pool": pool }
+        })),
+        Err(e) => actix_web::HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "status": "unhealthy",
+            "database": { "status": "down", "error": e.message, "pool": pool }
+        })),
+    }
 }
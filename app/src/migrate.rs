@@ -0,0 +1,57 @@
+//! Database migrations
+//!
+//! Runs the schema migrations of every crate at startup, or on demand with
+//! `app migrate [status|up|down <version>]`. The runner comes from
+//! `Backend::migrator`, so the same commands work on MongoDB and SQL.
+
+use database::migrations::Migrator;
+use database::utils::{DbError, DbResult};
+
+/// Apply pending migrations, logging each one
+pub async fn migrate(runner: &dyn Migrator) -> DbResult<()> {
+    let applied = runner.migrate().await?;
+    for record in &applied {
+        middleware::tracing::info!(
+            "Applied migration {} ({}) in {}ms",
+            record.version,
+            record.name,
+            record.duration_ms
+        );
+    }
+    if applied.is_empty() {
+        middleware::tracing::info!("Database schema is up to date");
+    }
+    Ok(())
+}
+
+/// Handle `app migrate ...` arguments
+pub async fn run_command(runner: &dyn Migrator, args: &[String]) -> DbResult<()> {
+    match args.first().map(String::as_str) {
+        None | Some("up") => migrate(runner).await,
+        Some("status") => {
+            for status in runner.status().await? {
+                let state = match (status.applied_at, status.checksum_mismatch) {
+                    (Some(_), true) => "changed",
+                    (Some(_), false) => "applied",
+                    (None, _) => "pending",
+                };
+                println!("{:>4}  {:<8}  {}", status.version, state, status.name);
+            }
+            Ok(())
+        }
+        Some("down") => {
+            let target = args
+                .get(1)
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| DbError::invalid_config("Usage: migrate down <version>"))?;
+            for version in runner.rollback(target).await? {
+                middleware::tracing::info!("Reverted migration {}", version);
+            }
+            Ok(())
+        }
+        Some(other) => Err(DbError::invalid_config(&format!(
+            "Unknown migrate command '{}'; expected status, up or down <version>",
+            other
+        ))),
+    }
+}
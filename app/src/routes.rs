@@ -1,29 +1,21 @@
 use actix_web::web;
 
-use crate::state::AppState;
-use auth::routes::configure as auth_configure;
-use auth::routes::init as auth_init;
+use auth::routes::{AppState as AuthState, configure as auth_configure};
+use middleware::api_key::ApiKeyValidator;
 
 /// Initialize all routes
-/// Accepts MongoDB database instance (from MongoConnection.database())
+///
+/// Runs once per worker. The auth state and API key validator are built once
+/// in `main` and shared, so every worker sees the same per-key rate limits.
 pub fn init_routes(
     cfg: &mut web::ServiceConfig,
-    state: &AppState,
-    mongo_db: &mongodb::sync::Database,
+    auth_state: &AuthState,
+    api_keys: Option<web::Data<dyn ApiKeyValidator>>,
 ) {
-    // Initialize auth module with database and config
-    let auth_state = auth_init(
-        mongo_db,
-        state.jwt_secret.clone(),
-        state.jwt_expiry_minutes,
-        state.refresh_token_expiry_days,
-        state.email.clone(),
-        state.email_from.clone(),
-        state.app_name.clone(),
-        state.frontend_url.clone(),
-    );
-
     // Auth routes
     cfg.app_data(web::Data::new(auth_state.clone()));
+    if let Some(validator) = api_keys {
+        cfg.app_data(validator);
+    }
     auth_configure(cfg, auth_state.jwt_secret.clone());
 }
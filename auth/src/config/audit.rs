@@ -0,0 +1,41 @@
+//! Audit Log Configuration
+//!
+//! Loads the audit log retention policy from environment variables.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::audit::DEFAULT_AUDIT_RETENTION_DAYS;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditConfig {
+    pub retention_days: i64, // Events older than this are purged
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: DEFAULT_AUDIT_RETENTION_DAYS,
+        }
+    }
+}
+
+impl AuditConfig {
+    /// Load audit configuration from environment variables
+    ///
+    /// Expected env vars:
+    /// - AUDIT_RETENTION_DAYS (optional, defaults to 365)
+    pub fn from_env() -> Self {
+        let retention_days = std::env::var("AUDIT_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|days| *days > 0)
+            .unwrap_or(DEFAULT_AUDIT_RETENTION_DAYS);
+
+        Self { retention_days }
+    }
+
+    /// Oldest timestamp still kept
+    pub fn cutoff(&self, now: i64) -> i64 {
+        now - self.retention_days * 24 * 60 * 60
+    }
+}
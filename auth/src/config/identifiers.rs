@@ -0,0 +1,72 @@
+//! Identifier Configuration
+//!
+//! How emails and phone numbers are normalized, loaded from environment
+//! variables. Every setting is optional.
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::user_store::{IdentifierType, identify_user};
+use crate::utils::errors::{AuthError, AuthResult};
+use crate::utils::identifiers::normalize_email;
+use utils::phone;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentifierConfig {
+    pub default_region:  String, // Region for phone numbers without a country code
+    pub canonical_gmail: bool,   // Drop dots and +tags from Gmail addresses
+}
+
+impl Default for IdentifierConfig {
+    fn default() -> Self {
+        Self {
+            default_region:  "KE".to_string(),
+            canonical_gmail: false,
+        }
+    }
+}
+
+impl IdentifierConfig {
+    /// Load identifier configuration from environment variables
+    ///
+    /// Expected env vars:
+    /// - PHONE_DEFAULT_REGION (optional, ISO code, defaults to KE)
+    /// - EMAIL_CANONICAL_GMAIL (optional, defaults to false)
+    pub fn from_env() -> Self {
+        let default = Self::default();
+
+        Self {
+            default_region:  std::env::var("PHONE_DEFAULT_REGION").unwrap_or(default.default_region),
+            canonical_gmail: std::env::var("EMAIL_CANONICAL_GMAIL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default.canonical_gmail),
+        }
+    }
+
+    /// Normalized email address, for storing and lookups
+    pub fn email(&self, email: &str) -> AuthResult<String> {
+        normalize_email(email, self.canonical_gmail)
+            .map_err(|e| AuthError::invalid_email(&format!("{} ({})", email.trim(), e)))
+    }
+
+    /// Phone number in E.164 form, for storing, lookups and SMS
+    pub fn phone(&self, phone: &str) -> AuthResult<String> {
+        phone::to_e164(phone, Some(&self.default_region))
+            .map_err(|e| AuthError::invalid_phone_number(&format!("{} ({})", phone.trim(), e)))
+    }
+
+    /// Normalized login identifier (email, phone or username)
+    ///
+    /// Identifiers that don't parse are returned trimmed, so lookups simply
+    /// find nobody instead of revealing why.
+    pub fn identifier(&self, identifier: &str) -> String {
+        let identifier = identifier.trim();
+        let normalized = match identify_user(identifier) {
+            IdentifierType::Email => self.email(identifier).ok(),
+            IdentifierType::Phone => self.phone(identifier).ok(),
+            IdentifierType::Username => None,
+        };
+
+        normalized.unwrap_or_else(|| identifier.to_string())
+    }
+}
@@ -1 +1,6 @@
+pub mod audit;
+pub mod identifiers;
 pub mod oauth;
+pub mod profile;
+pub mod risk;
+pub mod webauthn;
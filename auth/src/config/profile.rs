@@ -0,0 +1,36 @@
+//! User Profile Configuration
+//!
+//! Loads the profile schema from environment variables. Without one the
+//! standard fields (avatar URL, locale, timezone, date of birth and marketing
+//! consent) are used.
+
+use crate::models::profile::ProfileSchema;
+
+impl ProfileSchema {
+    /// Load the profile schema from environment variables
+    ///
+    /// Expected env vars (first one set wins):
+    /// - PROFILE_SCHEMA_PATH (optional, JSON file)
+    /// - PROFILE_SCHEMA (optional, inline JSON)
+    ///
+    /// The JSON looks like `{"fields": [{"name": "plan", "type": "string",
+    /// "options": ["free", "pro"], "queryable": true}]}`. An invalid schema
+    /// is logged and the standard one used instead.
+    pub fn from_env() -> Self {
+        let json = match std::env::var("PROFILE_SCHEMA_PATH") {
+            Ok(path) => std::fs::read_to_string(&path)
+                .map_err(|e| crate::warn!("Failed to read profile schema from {}: {}", path, e))
+                .ok(),
+            Err(_) => std::env::var("PROFILE_SCHEMA").ok(),
+        };
+
+        match json.map(|json| Self::from_json(&json)) {
+            Some(Ok(schema)) => schema,
+            Some(Err(e)) => {
+                crate::warn!("Using the standard profile schema: {}", e.message);
+                Self::standard()
+            }
+            None => Self::standard(),
+        }
+    }
+}
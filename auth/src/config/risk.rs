@@ -0,0 +1,60 @@
+//! Login Risk Configuration
+//!
+//! Loads the thresholds used by the login risk evaluator from environment
+//! variables. Every setting is optional.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskConfig {
+    pub challenge_score:        u32,            // Scores at or above this need a second factor
+    pub deny_score:             u32,            // Scores at or above this are refused
+    pub max_travel_speed_kmh:   f64,            // Faster moves between logins are "impossible travel"
+    pub failure_window_minutes: i64,            // Window for failed-attempt velocity
+    pub max_recent_failures:    u64,            // Failures in the window before it counts as risky
+    pub ip_ranges_path:         Option<String>, // CSV of IP ranges with coordinates
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            challenge_score:        40,
+            deny_score:             90,
+            max_travel_speed_kmh:   900.0,
+            failure_window_minutes: 15,
+            max_recent_failures:    5,
+            ip_ranges_path:         None,
+        }
+    }
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse::<T>().ok())
+        .unwrap_or(default)
+}
+
+impl RiskConfig {
+    /// Load risk configuration from environment variables
+    ///
+    /// Expected env vars:
+    /// - RISK_CHALLENGE_SCORE (optional, defaults to 40)
+    /// - RISK_DENY_SCORE (optional, defaults to 90)
+    /// - RISK_MAX_TRAVEL_SPEED_KMH (optional, defaults to 900)
+    /// - RISK_FAILURE_WINDOW_MINUTES (optional, defaults to 15)
+    /// - RISK_MAX_RECENT_FAILURES (optional, defaults to 5)
+    /// - RISK_IP_RANGES_PATH (optional, impossible travel is skipped without it)
+    pub fn from_env() -> Self {
+        let default = Self::default();
+
+        Self {
+            challenge_score:        env_or("RISK_CHALLENGE_SCORE", default.challenge_score),
+            deny_score:             env_or("RISK_DENY_SCORE", default.deny_score),
+            max_travel_speed_kmh:   env_or("RISK_MAX_TRAVEL_SPEED_KMH", default.max_travel_speed_kmh),
+            failure_window_minutes: env_or("RISK_FAILURE_WINDOW_MINUTES", default.failure_window_minutes),
+            max_recent_failures:    env_or("RISK_MAX_RECENT_FAILURES", default.max_recent_failures),
+            ip_ranges_path:         std::env::var("RISK_IP_RANGES_PATH").ok(),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! WebAuthn Configuration
+//!
+//! Loads relying party settings for passkeys from environment variables.
+//! Needs: WEBAUTHN_RP_ID, WEBAUTHN_RP_NAME and WEBAUTHN_ORIGIN
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebAuthnConfig {
+    pub rp_id:                     String, // Effective domain, e.g. "example.com"
+    pub rp_name:                   String,
+    pub origin:                    String, // Full origin, e.g. "https://app.example.com"
+    pub timeout_ms:                u64,
+    pub challenge_expiry_secs:     i64,
+    pub require_user_verification: bool,
+}
+
+impl WebAuthnConfig {
+    pub fn new(
+        rp_id:   impl Into<String>,
+        rp_name: impl Into<String>,
+        origin:  impl Into<String>,
+    ) -> Self {
+        Self {
+            rp_id:                     rp_id.into(),
+            rp_name:                   rp_name.into(),
+            origin:                    origin.into(),
+            timeout_ms:                60_000,
+            challenge_expiry_secs:     300,
+            require_user_verification: false,
+        }
+    }
+
+    pub fn with_user_verification(mut self, required: bool) -> Self {
+        self.require_user_verification = required;
+        self
+    }
+
+    /// Load WebAuthn configuration from environment variables
+    ///
+    /// Expected env vars:
+    /// - WEBAUTHN_RP_ID
+    /// - WEBAUTHN_RP_NAME (defaults to the RP ID)
+    /// - WEBAUTHN_ORIGIN
+    /// - WEBAUTHN_REQUIRE_UV (optional, "true" to require user verification)
+    pub fn from_env() -> Option<Self> {
+        let rp_id   = std::env::var("WEBAUTHN_RP_ID").ok()?;
+        let origin  = std::env::var("WEBAUTHN_ORIGIN").ok()?;
+        let rp_name = std::env::var("WEBAUTHN_RP_NAME").unwrap_or_else(|_| rp_id.clone());
+        let require_uv = std::env::var("WEBAUTHN_REQUIRE_UV")
+            .map(|v| v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+
+        Some(Self::new(rp_id, rp_name, origin).with_user_verification(require_uv))
+    }
+
+    /// User verification requirement string for ceremony options
+    pub fn user_verification(&self) -> &'static str {
+        if self.require_user_verification {
+            "required"
+        } else {
+            "preferred"
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Admin handler - support tooling
+//!
+//! Impersonation lets support staff see the app as a specific user. The token
+//! is short-lived, has no refresh token, names the admin in its `act` claim
+//! and is refused on sensitive routes by `DenyImpersonation`.
+
+use actix_web::{Error, HttpRequest, HttpResponse, web};
+use chrono::Duration;
+
+use crate::handler::login_user::{access_claims, encode_access_token};
+use crate::handler::organizations::current_user_id;
+use crate::models::audit::AuditAction;
+use crate::models::session::CreateSession;
+use crate::models::user::User;
+use crate::routes::AppState;
+use crate::utils::audit::AuditEntry;
+use crate::utils::errors::AuthError;
+use crate::utils::types::{ImpersonationResponse, UserPublic};
+
+use database::utils::{DbId, parse_id};
+use middleware::jwt::{ActorClaim, JwtClaims};
+use utils::hash::hash_sha256;
+use utils::response::ApiResponse;
+
+/// Lifetime of an impersonation token
+pub const IMPERSONATION_TOKEN_MINUTES: i64 = 15;
+
+/// Resolve the caller, who must be a platform admin signed in with a session
+pub(crate) async fn require_admin(req: &HttpRequest, state: &web::Data<AppState>) -> Result<User, AuthError> {
+    let user_id = current_user_id(req, state).await?;
+
+    if req.api_key().is_some() {
+        return Err(AuthError::forbidden("Admin actions require a signed in session"));
+    }
+
+    ensure_admin(state, &user_id).await
+}
+
+/// Load a user who must be a platform admin
+pub(crate) async fn ensure_admin(state: &web::Data<AppState>, user_id: &DbId) -> Result<User, AuthError> {
+    let user = state
+        .users
+        .find_by_id(user_id)
+        .await?
+        .ok_or_else(AuthError::invalid_session)?;
+
+    if !user.is_admin {
+        return Err(AuthError::forbidden("Admin access required"));
+    }
+
+    Ok(user)
+}
+
+/// Issue a short-lived access token for a user, on behalf of an admin
+pub async fn impersonate_user(
+    state:   web::Data<AppState>,
+    user_id: web::Path<String>,
+    req:     HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let admin = match require_admin(&req, &state).await {
+        Ok(admin) => admin,
+        Err(e) => {
+            AuditEntry::failure(AuditAction::Impersonation, "not_admin").record(&state, &req).await;
+            return Err(e.into());
+        }
+    };
+
+    let target_id = parse_id(&user_id).map_err(|_| AuthError::not_found("User not found"))?;
+    let target = state
+        .users
+        .find_by_id(&target_id)
+        .await?
+        .filter(|u| u.deleted_at.is_none())
+        .ok_or_else(|| AuthError::not_found("User not found"))?;
+
+    // Admins are not impersonated, so support can't borrow a colleague's access
+    if target.id == admin.id || target.is_admin {
+        AuditEntry::failure(AuditAction::Impersonation, "target_is_admin")
+            .actor(&admin.id)
+            .target(&target.id)
+            .record(&state, &req)
+            .await;
+        return Err(AuthError::forbidden("Admins can't be impersonated").into());
+    }
+
+    if !target.is_active() {
+        AuditEntry::failure(AuditAction::Impersonation, "account_disabled")
+            .actor(&admin.id)
+            .target(&target.id)
+            .record(&state, &req)
+            .await;
+        return Err(AuthError::account_disabled().into());
+    }
+
+    let mut claims = access_claims(
+        &target.id.to_string(),
+        target.email.as_deref(),
+        IMPERSONATION_TOKEN_MINUTES,
+    );
+    claims.act = Some(ActorClaim { sub: admin.id.to_string() });
+
+    let access_token = encode_access_token(&claims, &state.jwt_secret)?;
+    let user_agent = req
+        .headers()
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    let ip_address = req.connection_info().realip_remote_addr().map(String::from);
+
+    // Protected routes require a session, this one can't be refreshed
+    state.sessions.create(CreateSession {
+        user_id:            target.id.clone(),
+        access_token_hash:  hash_sha256(&access_token),
+        refresh_token_hash: None,
+        device:             Some(format!("Impersonation by {}", admin.id)),
+        ip_address,
+        user_agent,
+        org_id:             None,
+        expires_in:         IMPERSONATION_TOKEN_MINUTES * 60,
+    }).await?;
+
+    AuditEntry::success(AuditAction::Impersonation)
+        .actor(&admin.id)
+        .target(&target.id)
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::success_data(
+        "Impersonation token issued",
+        ImpersonationResponse {
+            user:         UserPublic::from(&target),
+            access_token,
+            actor_id:     admin.id.to_string(),
+            expires_in:   chrono::Utc::now() + Duration::minutes(IMPERSONATION_TOKEN_MINUTES),
+        },
+    );
+
+    Ok(HttpResponse::Ok().json(response))
+}
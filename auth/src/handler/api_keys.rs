@@ -0,0 +1,211 @@
+//! API key handler
+//!
+//! Keys are managed by signed in users. Requests authenticated with an API key
+//! cannot create or revoke keys, so a leaked key cannot mint new ones.
+//!
+//! Scopes must come from `API_KEY_SCOPES`, and those reaching operator data
+//! (`*`, `audit:*`) can only be granted by platform admins.
+
+use actix_web::{Error, HttpRequest, HttpResponse, web};
+use std::sync::Arc;
+
+use crate::models::api_key::{API_KEY_SCOPES, CreateApiKey, is_admin_scope};
+use crate::models::audit::AuditAction;
+use crate::routes::AppState;
+use crate::service::api_key::ApiKeyService;
+use crate::store::api_key_store::ApiKeyStore;
+use crate::utils::audit::AuditEntry;
+use crate::utils::errors::AuthError;
+use crate::utils::session_validation::validate_access_token;
+use crate::utils::types::{ApiKeyCreatedResponse, ApiKeyPublic, CreateApiKeyRequest};
+
+use super::admin::require_admin;
+
+use database::utils::{DbId, parse_id};
+use middleware::jwt::JwtClaims;
+use utils::response::ApiResponse;
+
+fn api_key_store(state: &AppState) -> Result<&Arc<dyn ApiKeyStore>, AuthError> {
+    state
+        .api_keys
+        .as_ref()
+        .ok_or_else(|| AuthError::internal_error("API key store not configured"))
+}
+
+/// Resolve the signed in user, rejecting requests made with an API key
+async fn session_user_id(req: &HttpRequest, state: &web::Data<AppState>) -> Result<DbId, AuthError> {
+    let token_info = validate_access_token(req, state)
+        .await
+        .map_err(|_e| AuthError::invalid_session())?;
+
+    if req.api_key().is_some() {
+        return Err(AuthError::forbidden("API keys cannot be managed with an API key"));
+    }
+
+    if req.is_impersonated() {
+        return Err(AuthError::forbidden("API keys cannot be managed while impersonating a user"));
+    }
+
+    parse_id(&token_info.user_id).map_err(|e| AuthError::internal_error(&e.to_string()))
+}
+
+/// Trimmed, de-duplicated scopes, all taken from `API_KEY_SCOPES`
+fn parse_scopes(requested: &[String]) -> Result<Vec<String>, AuthError> {
+    let mut scopes: Vec<String> = Vec::new();
+    for scope in requested.iter().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        if !API_KEY_SCOPES.contains(&scope) {
+            return Err(AuthError::invalid_request(&format!("Unknown scope: {}", scope)));
+        }
+        if !scopes.iter().any(|s| s == scope) {
+            scopes.push(scope.to_string());
+        }
+    }
+
+    if scopes.is_empty() {
+        return Err(AuthError::invalid_request("At least one scope is required"));
+    }
+
+    Ok(scopes)
+}
+
+pub async fn create_api_key(
+    state:      web::Data<AppState>,
+    create_req: web::Json<CreateApiKeyRequest>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = session_user_id(&req, &state).await?;
+
+    let name = create_req.name.trim();
+    if name.is_empty() {
+        return Err(AuthError::invalid_request("API key name is required").into());
+    }
+
+    let scopes = parse_scopes(&create_req.scopes)?;
+    if scopes.iter().any(|scope| is_admin_scope(scope)) {
+        require_admin(&req, &state).await?;
+    }
+
+    if create_req.expires_in_days.is_some_and(|days| days <= 0) {
+        return Err(AuthError::invalid_request("Expiry must be at least one day").into());
+    }
+
+    if create_req.rate_limit_per_minute == Some(0) {
+        return Err(AuthError::invalid_request("Rate limit must be greater than zero").into());
+    }
+
+    let generated = ApiKeyService::generate_key();
+    let api_key = api_key_store(&state)?.create(CreateApiKey {
+        user_id,
+        name: name.to_string(),
+        key_prefix: generated.prefix,
+        key_hash: generated.hash,
+        scopes,
+        rate_limit_per_minute: create_req.rate_limit_per_minute,
+        expires_in: create_req.expires_in_days.map(|days| days * 24 * 60 * 60),
+    }).await?;
+
+    AuditEntry::success(AuditAction::ApiKeyCreated)
+        .target(&api_key.id)
+        .reason(&api_key.key_prefix)
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::success_data(
+        "API key created. Store it now, it will not be shown again.",
+        ApiKeyCreatedResponse {
+            key:     generated.raw,
+            api_key: ApiKeyPublic::from(api_key),
+        },
+    );
+
+    Ok(HttpResponse::Created().json(response))
+}
+
+pub async fn list_api_keys(
+    state: web::Data<AppState>,
+    req:   HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = session_user_id(&req, &state).await?;
+
+    let keys: Vec<ApiKeyPublic> = api_key_store(&state)?
+        .list_by_user(&user_id)
+        .await?
+        .into_iter()
+        .map(ApiKeyPublic::from)
+        .collect();
+
+    let response = ApiResponse::success_data("API keys retrieved", keys);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_api_key(
+    state:  web::Data<AppState>,
+    key_id: web::Path<String>,
+    req:    HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = session_user_id(&req, &state).await?;
+    let key_id = parse_id(&key_id).map_err(|_| AuthError::invalid_request("Invalid API key ID"))?;
+
+    let api_key = api_key_store(&state)?
+        .find_by_id(&key_id)
+        .await?
+        .filter(|k| k.user_id == user_id)
+        .ok_or_else(|| AuthError::not_found("API key not found"))?;
+
+    let response = ApiResponse::success_data("API key retrieved", ApiKeyPublic::from(api_key));
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn revoke_api_key(
+    state:  web::Data<AppState>,
+    key_id: web::Path<String>,
+    req:    HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = session_user_id(&req, &state).await?;
+    let key_id = parse_id(&key_id).map_err(|_| AuthError::invalid_request("Invalid API key ID"))?;
+
+    let store = api_key_store(&state)?;
+    let api_key = store
+        .find_by_id(&key_id)
+        .await?
+        .filter(|k| k.user_id == user_id)
+        .ok_or_else(|| AuthError::not_found("API key not found"))?;
+
+    store.revoke(&api_key.id).await?;
+    AuditEntry::success(AuditAction::ApiKeyRevoked)
+        .target(&api_key.id)
+        .reason(&api_key.key_prefix)
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::<()>::ok("API key revoked");
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_scopes_allows_listed_scopes_only() {
+        let parsed = parse_scopes(&scopes(&[" audit:read ", "audit:read", ""])).unwrap();
+        assert_eq!(parsed, scopes(&["audit:read"]));
+
+        assert!(parse_scopes(&scopes(&["users:delete"])).is_err());
+        assert!(parse_scopes(&scopes(&["  "])).is_err());
+    }
+
+    #[test]
+    fn test_operator_scopes_need_admin() {
+        assert!(is_admin_scope("*"));
+        assert!(is_admin_scope(crate::models::api_key::AUDIT_ADMIN_SCOPE));
+        assert!(!is_admin_scope("profile:read"));
+    }
+}
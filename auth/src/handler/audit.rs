@@ -0,0 +1,219 @@
+//! Audit log handler - query and retention
+//!
+//! The full log is for operators and is read with an API key carrying the
+//! `audit:read` scope. Organization owners and admins can read the events of
+//! their own organization with a normal session.
+
+use actix_web::{Error, HttpRequest, HttpResponse, web};
+use std::sync::Arc;
+
+use crate::models::api_key::{AUDIT_ADMIN_SCOPE, AUDIT_READ_SCOPE};
+use crate::models::audit::AuditFilter;
+use crate::routes::AppState;
+use crate::store::audit_store::AuditStore;
+use crate::utils::errors::AuthError;
+use crate::utils::session_validation::validate_access_token;
+use crate::utils::types::{AuditEventPublic, AuditEventsQuery};
+
+use super::admin::ensure_admin;
+use super::organizations::{current_user_id, org_store, require_manager, tenant_org_id};
+use database::utils::{DbId, parse_id};
+use middleware::jwt::JwtClaims;
+use utils::response::{ApiResponse, CursorMeta, ResponseMeta};
+
+fn audit_store(state: &AppState) -> Result<&Arc<dyn AuditStore>, AuthError> {
+    state
+        .audit
+        .as_ref()
+        .ok_or_else(|| AuthError::internal_error("Audit store not configured"))
+}
+
+/// Require an API key with the given scope, owned by a platform admin
+async fn require_scope(
+    req:   &HttpRequest,
+    state: &web::Data<AppState>,
+    scope: &str,
+) -> Result<(), AuthError> {
+    validate_access_token(req, state)
+        .await
+        .map_err(|_e| AuthError::invalid_session())?;
+
+    match req.api_key() {
+        Some(key) if key.has_scope(scope) => {}
+        _ => return Err(AuthError::forbidden(&format!("Requires an API key with the {} scope", scope))),
+    }
+
+    // The owner may have lost admin rights since the key was issued
+    let owner_id = current_user_id(req, state).await?;
+    ensure_admin(state, &owner_id).await?;
+
+    Ok(())
+}
+
+fn parse_filter_id(id: Option<&str>, field: &str) -> Result<Option<DbId>, AuthError> {
+    id.map(|id| {
+        parse_id(id).map_err(|_| AuthError::invalid_request(&format!("Invalid {}", field)))
+    })
+    .transpose()
+}
+
+fn build_filter(query: &AuditEventsQuery) -> Result<AuditFilter, AuthError> {
+    Ok(AuditFilter {
+        actor_id:  parse_filter_id(query.actor_id.as_deref(), "actor_id")?,
+        target_id: parse_filter_id(query.target_id.as_deref(), "target_id")?,
+        org_id:    parse_filter_id(query.org_id.as_deref(), "org_id")?,
+        action:    query.action,
+        outcome:   query.outcome,
+        reason:    query.reason.clone(),
+        from:      query.from,
+        to:        query.to,
+    })
+}
+
+async fn list_events(
+    state:  &AppState,
+    filter: &AuditFilter,
+    query:  &AuditEventsQuery,
+) -> Result<HttpResponse, AuthError> {
+    let store = audit_store(state)?;
+    let pagination = query.pagination();
+
+    let cursors = state.cursors();
+    if let Some(request) = pagination.page_request(&cursors)? {
+        let page = store.list_after(filter, &request).await?;
+        let next = page.next.as_ref().map(|cursor| cursors.encode(cursor));
+
+        let events: Vec<AuditEventPublic> =
+            page.items.into_iter().map(AuditEventPublic::from).collect();
+        let response = ApiResponse::success_data("Audit events retrieved", events)
+            .with_cursor(CursorMeta::new(pagination.per_page(), next));
+
+        return Ok(HttpResponse::Ok().json(response));
+    }
+
+    let events: Vec<AuditEventPublic> = store
+        .list(filter, pagination.offset_page(), pagination.per_page())
+        .await?
+        .into_iter()
+        .map(AuditEventPublic::from)
+        .collect();
+    let total = store.count(filter).await?;
+
+    let response = ApiResponse::success_data("Audit events retrieved", events).with_meta(
+        ResponseMeta::new(pagination.page(), pagination.per_page(), total),
+    );
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Query the full audit log
+pub async fn list_audit_events(
+    state: web::Data<AppState>,
+    query: web::Query<AuditEventsQuery>,
+    req:   HttpRequest,
+) -> Result<HttpResponse, Error> {
+    require_scope(&req, &state, AUDIT_READ_SCOPE).await?;
+
+    let filter = build_filter(&query)?;
+
+    Ok(list_events(&state, &filter, &query).await?)
+}
+
+/// Query the audit log of one organization
+pub async fn list_org_audit_events(
+    state:  web::Data<AppState>,
+    org_id: web::Path<String>,
+    query:  web::Query<AuditEventsQuery>,
+    req:    HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let org_id = tenant_org_id(&req, &org_id)?;
+
+    require_manager(org_store(&state)?, &org_id, &user_id).await?;
+
+    let mut filter = build_filter(&query)?;
+    filter.org_id = Some(org_id);
+
+    Ok(list_events(&state, &filter, &query).await?)
+}
+
+/// Delete events older than the configured retention period
+pub async fn purge_audit_events(
+    state: web::Data<AppState>,
+    req:   HttpRequest,
+) -> Result<HttpResponse, Error> {
+    require_scope(&req, &state, AUDIT_ADMIN_SCOPE).await?;
+
+    let cutoff = state.audit_config.cutoff(chrono::Utc::now().timestamp());
+    let deleted = audit_store(&state)?.delete_older_than(cutoff).await?;
+
+    let response = ApiResponse::success_data(
+        "Audit retention applied",
+        serde_json::json!({
+            "deleted": deleted,
+            "retention_days": state.audit_config.retention_days,
+        }),
+    );
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::audit::AuditOutcome;
+    use database::pagination::{Cursor, CursorCodec};
+    use database::utils::DbId;
+
+    fn query() -> AuditEventsQuery {
+        AuditEventsQuery {
+            page:      None,
+            per_page:  None,
+            cursor:    None,
+            actor_id:  None,
+            target_id: None,
+            org_id:    None,
+            action:    None,
+            outcome:   Some(AuditOutcome::Failure),
+            reason:    None,
+            from:      Some(100),
+            to:        None,
+        }
+    }
+
+    #[test]
+    fn test_build_filter() {
+        let filter = build_filter(&query()).unwrap();
+
+        assert_eq!(filter.outcome, Some(AuditOutcome::Failure));
+        assert_eq!(filter.from, Some(100));
+        assert!(filter.actor_id.is_none());
+    }
+
+    #[test]
+    fn test_build_filter_rejects_bad_id() {
+        let mut query = query();
+        query.actor_id = Some("not-an-id".to_string());
+
+        assert!(build_filter(&query).is_err());
+    }
+
+    #[test]
+    fn test_cursor_selects_keyset_paging() {
+        let codec = CursorCodec::new(b"secret");
+        let mut query = query();
+        assert!(query.pagination().page_request(&codec).unwrap().is_none());
+
+        query.cursor = Some(String::new());
+        let first = query.pagination().page_request(&codec).unwrap().unwrap();
+        assert!(first.after.is_none());
+
+        let cursor = Cursor::new(100, DbId::new_uuid());
+        query.cursor = Some(codec.encode(&cursor));
+        let next = query.pagination().page_request(&codec).unwrap().unwrap();
+        assert_eq!(next.after, Some(cursor));
+
+        query.cursor = Some("forged".to_string());
+        assert!(query.pagination().page_request(&codec).is_err());
+    }
+}
@@ -0,0 +1,356 @@
+//! Contact change handler - secure email and phone changes
+//!
+//! A change is stored as pending and a code goes to the new address. The old
+//! address gets a security notice with a revert link. The user's email or phone
+//! only changes once the code is confirmed, and the revert link stays valid for
+//! a while after that in case the account was taken over.
+
+use actix_web::{Error, HttpRequest, HttpResponse, web};
+use std::sync::Arc;
+
+use crate::config::identifiers::IdentifierConfig;
+use crate::models::audit::AuditAction;
+use crate::models::contact_change::{ContactChangeModel, CreateContactChange};
+use crate::models::user::User;
+use crate::models::verification::VerificationMedium;
+use crate::routes::AppState;
+use crate::store::contact_change_store::ContactChangeStore;
+use crate::utils::audit::AuditEntry;
+use crate::utils::errors::{AuthError, AuthErrorCode};
+use crate::utils::session_validation::validate_access_token;
+use crate::utils::types::{ContactChangeConfirm, ContactChangeRequest, ContactChangeRevert};
+
+use database::utils::{DbId, parse_id};
+use middleware::jwt::JwtClaims;
+use utils::email_templates::{EmailTemplateConfig, contact_change_code, contact_change_notice};
+use utils::hash::{generate_hex, generate_otp, hash_sha256};
+use utils::response::ApiResponse;
+use utils::sms::templates as sms_templates;
+
+const CODE_EXPIRY_SECONDS: i64 = 15 * 60; // 15 minutes
+const REVERT_EXPIRY_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days
+const MAX_CONFIRM_ATTEMPTS: i32 = 5;
+
+fn contact_change_store(state: &AppState) -> Result<&Arc<dyn ContactChangeStore>, AuthError> {
+    state
+        .contact_changes
+        .as_ref()
+        .ok_or_else(|| AuthError::internal_error("Contact change store not configured"))
+}
+
+/// Resolve the signed in user - contact details cannot be changed with an API key
+async fn session_user(req: &HttpRequest, state: &web::Data<AppState>) -> Result<User, AuthError> {
+    let token_info = validate_access_token(req, state)
+        .await
+        .map_err(|_e| AuthError::invalid_session())?;
+
+    if req.api_key().is_some() {
+        return Err(AuthError::forbidden("Contact details cannot be changed with an API key"));
+    }
+
+    let user_id = parse_id(&token_info.user_id)
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+
+    state
+        .users
+        .find_by_id(&user_id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("User not found"))
+}
+
+/// Normalize and validate a new email or phone
+fn normalize_contact(
+    identifiers: &IdentifierConfig,
+    medium:      &VerificationMedium,
+    value:       &str,
+) -> Result<String, AuthError> {
+    match medium {
+        VerificationMedium::Email => identifiers.email(value),
+        VerificationMedium::Phone => identifiers.phone(value),
+    }
+}
+
+/// Same uniqueness rules as signup
+async fn ensure_contact_available(
+    state:   &AppState,
+    user_id: &DbId,
+    medium:  &VerificationMedium,
+    value:   &str,
+) -> Result<(), AuthError> {
+    let existing = match medium {
+        VerificationMedium::Email => state.users.find_by_email(value).await?,
+        VerificationMedium::Phone => state.users.find_by_phone(value).await?,
+    };
+
+    match (existing, medium) {
+        (Some(other), VerificationMedium::Email) if &other.id != user_id => {
+            Err(AuthError::email_already_exists(value))
+        }
+        (Some(other), VerificationMedium::Phone) if &other.id != user_id => {
+            Err(AuthError::phone_already_exists(value))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn medium_label(medium: &VerificationMedium) -> &'static str {
+    match medium {
+        VerificationMedium::Email => "email",
+        VerificationMedium::Phone => "phone",
+    }
+}
+
+fn current_contact(user: &User, medium: &VerificationMedium) -> Option<String> {
+    match medium {
+        VerificationMedium::Email => user.email.clone(),
+        VerificationMedium::Phone => user.phone.clone(),
+    }
+}
+
+/// Send the confirmation code to the new address
+async fn send_code(
+    state:  &AppState,
+    change: &ContactChangeModel,
+    code:   &str,
+) -> Result<(), AuthError> {
+    match change.medium {
+        VerificationMedium::Email => {
+            let template_config = EmailTemplateConfig::new(&state.app_name, &state.frontend_url);
+            let mut email = contact_change_code::build(&template_config, code);
+            email.to = change.new_value.clone();
+            email.from = state.email_from.clone();
+
+            let _email_result = state.email.send(&email).await;
+        }
+        VerificationMedium::Phone => {
+            let sms = state
+                .sms
+                .as_ref()
+                .ok_or_else(|| AuthError::internal_error("SMS service not configured"))?;
+
+            let _sms_result = sms
+                .send(&sms_templates::verification_code(&change.new_value, code))
+                .await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Send the security notice with the revert link to the old address
+async fn send_notice(state: &AppState, change: &ContactChangeModel, revert_token: &str) {
+    let Some(old_value) = change.old_value.as_deref() else {
+        return;
+    };
+
+    let revert_link = format!(
+        "{}/account/contact/revert?token={}",
+        state.frontend_url.trim_end_matches('/'),
+        revert_token
+    );
+
+    match change.medium {
+        VerificationMedium::Email => {
+            let template_config = EmailTemplateConfig::new(&state.app_name, &state.frontend_url);
+            let mut email =
+                contact_change_notice::build(&template_config, "email address", &revert_link);
+            email.to = old_value.to_string();
+            email.from = state.email_from.clone();
+
+            let _email_result = state.email.send(&email).await;
+        }
+        VerificationMedium::Phone => {
+            if let Some(sms) = state.sms.as_ref() {
+                let _sms_result = sms
+                    .send(&sms_templates::contact_change_notice(old_value, &revert_link))
+                    .await;
+            }
+        }
+    }
+}
+
+/// Request an email or phone change - sends a code to the new address
+pub async fn request_contact_change(
+    state:      web::Data<AppState>,
+    change_req: web::Json<ContactChangeRequest>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user = session_user(&req, &state).await?;
+    let store = contact_change_store(&state)?;
+    let medium = change_req.medium.clone();
+
+    if medium == VerificationMedium::Phone && state.sms.is_none() {
+        return Err(AuthError::internal_error("SMS service not configured").into());
+    }
+
+    let new_value = normalize_contact(&state.identifiers, &medium, &change_req.new_value)?;
+    let old_value = current_contact(&user, &medium);
+
+    if old_value.as_deref() == Some(new_value.as_str()) {
+        return Err(AuthError::invalid_request("New value is the same as the current one").into());
+    }
+
+    ensure_contact_available(&state, &user.id, &medium, &new_value).await?;
+
+    // A new request replaces any earlier pending one
+    store.cancel_pending(&user.id, medium.clone()).await?;
+
+    let code = generate_otp(6);
+    let revert_token = old_value.as_ref().map(|_| generate_hex(32));
+
+    let change = store.create(CreateContactChange {
+        user_id: user.id.clone(),
+        medium,
+        old_value,
+        new_value,
+        code_hash: hash_sha256(&code),
+        revert_token_hash: revert_token.as_deref().map(hash_sha256),
+        expires_in: CODE_EXPIRY_SECONDS,
+        revert_expires_in: REVERT_EXPIRY_SECONDS,
+    }).await?;
+
+    AuditEntry::success(AuditAction::ContactChangeRequested)
+        .user(&user.id)
+        .reason(medium_label(&change.medium))
+        .record(&state, &req)
+        .await;
+
+    send_code(&state, &change, &code).await?;
+    if let Some(revert_token) = revert_token.as_deref() {
+        send_notice(&state, &change, revert_token).await;
+    }
+
+    let response = ApiResponse::<()>::ok("Confirmation code sent");
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Confirm a pending change with the code sent to the new address
+pub async fn confirm_contact_change(
+    state:       web::Data<AppState>,
+    confirm_req: web::Json<ContactChangeConfirm>,
+    req:         HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user = session_user(&req, &state).await?;
+    let store = contact_change_store(&state)?;
+
+    let change = store
+        .find_pending(&user.id, confirm_req.medium.clone())
+        .await?
+        .ok_or_else(AuthError::verification_code_expired)?;
+
+    if change.attempts >= MAX_CONFIRM_ATTEMPTS {
+        return Err(AuthError::new(
+            AuthErrorCode::TooManyAttempts,
+            "Too many attempts. Please request a new code.",
+        )
+        .into());
+    }
+
+    if hash_sha256(confirm_req.code.trim()) != change.code_hash {
+        store.increment_attempts(&change.id).await?;
+        AuditEntry::failure(AuditAction::ContactChanged, "invalid_code")
+            .user(&user.id)
+            .record(&state, &req)
+            .await;
+        return Err(AuthError::invalid_verification_code().into());
+    }
+
+    // The address may have been taken since the request was made
+    ensure_contact_available(&state, &user.id, &change.medium, &change.new_value).await?;
+
+    // The new address and the spent code land together
+    state
+        .transactions
+        .run(|| async {
+            state.users.set_contact(&user.id, &change.medium, &change.new_value).await?;
+            store.mark_confirmed(&change.id).await?;
+            Ok::<_, AuthError>(())
+        })
+        .await?;
+
+    AuditEntry::success(AuditAction::ContactChanged)
+        .user(&user.id)
+        .reason(medium_label(&change.medium))
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::<()>::ok("Contact details updated");
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Undo a change from the link sent to the old address
+///
+/// Restores the old value if the change was already confirmed and signs the
+/// user out everywhere, since the account may be compromised.
+pub async fn revert_contact_change(
+    state:      web::Data<AppState>,
+    revert_req: web::Json<ContactChangeRevert>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let store = contact_change_store(&state)?;
+
+    let change = store
+        .find_by_revert_token(&hash_sha256(revert_req.token.trim()))
+        .await?
+        .filter(|c| c.can_revert())
+        .ok_or_else(|| AuthError::invalid_request("Revert link is invalid or has expired"))?;
+
+    let restore = change.old_value.as_deref().filter(|_| change.confirmed_at.is_some());
+
+    // Someone else may have registered the old address since it was released
+    if let Some(old_value) = restore {
+        ensure_contact_available(&state, &change.user_id, &change.medium, old_value).await?;
+    }
+
+    state
+        .transactions
+        .run(|| async {
+            if let Some(old_value) = restore {
+                state.users.set_contact(&change.user_id, &change.medium, old_value).await?;
+            }
+            store.mark_reverted(&change.id).await?;
+            state.sessions.revoke_all(&change.user_id).await?;
+            Ok::<_, AuthError>(())
+        })
+        .await?;
+
+    AuditEntry::success(AuditAction::ContactChangeReverted)
+        .target(&change.user_id)
+        .reason(medium_label(&change.medium))
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::<()>::ok(
+        "Change reverted. Please sign in again and update your password.",
+    );
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_email() {
+        let identifiers = IdentifierConfig::default();
+        let email = normalize_contact(&identifiers, &VerificationMedium::Email, "  New@Example.COM ").unwrap();
+        assert_eq!(email, "new@example.com");
+
+        assert!(normalize_contact(&identifiers, &VerificationMedium::Email, "not-an-email").is_err());
+        assert!(normalize_contact(&identifiers, &VerificationMedium::Email, "@example.com").is_err());
+    }
+
+    #[test]
+    fn test_normalize_phone() {
+        let identifiers = IdentifierConfig::default();
+        let phone = normalize_contact(&identifiers, &VerificationMedium::Phone, " +254712345678 ").unwrap();
+        assert_eq!(phone, "+254712345678");
+        assert_eq!(normalize_contact(&identifiers, &VerificationMedium::Phone, "0712 345 678").unwrap(), phone);
+
+        assert!(normalize_contact(&identifiers, &VerificationMedium::Phone, "12345").is_err());
+        assert!(normalize_contact(&identifiers, &VerificationMedium::Phone, "+2547abc45678").is_err());
+    }
+}
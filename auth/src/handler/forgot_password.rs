@@ -3,10 +3,12 @@
 //! Handles password reset requests by generating a reset token
 //! and sending it via email to the user.
 
-use actix_web::{Error, HttpResponse, web};
+use actix_web::{Error, HttpRequest, HttpResponse, web};
 
+use crate::models::audit::AuditAction;
 use crate::models::reset_password::CreatePasswordResetToken;
 use crate::routes::AppState;
+use crate::utils::audit::AuditEntry;
 use crate::utils::errors::AuthError;
 use crate::utils::types::PasswordResetRequest;
 
@@ -20,11 +22,13 @@ const PASSWORD_RESET_TOKEN_EXPIRY_SECONDS: i64 = 360; // 6 minutes
 pub async fn forgot_password(
     state: web::Data<AppState>,
     reset_req: web::Json<PasswordResetRequest>,
+    req: HttpRequest,
 ) -> Result<HttpResponse, Error> {
-    let identifier = reset_req.identifier.trim();
+    let identifier = state.identifiers.identifier(&reset_req.identifier);
     let user = state
         .users
-        .find_by_identifier(identifier)
+        .find_by_identifier(&identifier)
+        .await
         .map_err(|e| AuthError::not_found(&e.to_string()))?
         .ok_or_else(|| AuthError::not_found("No account found with this creds"))?;
 
@@ -49,7 +53,8 @@ pub async fn forgot_password(
         .password_resets
         .as_ref()
         .ok_or_else(|| AuthError::internal_error("Password reset not configured"))?
-        .delete_all_for_user(&user_id);
+        .delete_all_for_user(&user_id)
+        .await;
 
     // Create new reset token
     let token_input = CreatePasswordResetToken {
@@ -63,8 +68,14 @@ pub async fn forgot_password(
         .as_ref()
         .ok_or_else(|| AuthError::internal_error("Password reset not configured"))?
         .create(token_input)
+        .await
         .map_err(|e| AuthError::internal_error(&e.to_string()))?;
 
+    AuditEntry::success(AuditAction::PasswordResetRequested)
+        .target(&user.id)
+        .record(&state, &req)
+        .await;
+
     // Build reset link
     let reset_link = format!(
         "{}/reset-password?token={}",
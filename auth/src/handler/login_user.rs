@@ -10,37 +10,75 @@
 use actix_web::{Error, HttpRequest, HttpResponse, web};
 use chrono::Duration;
 
+use crate::models::audit::AuditAction;
 use crate::models::session::{CreateRefreshToken, CreateSession, UpdateSession};
+use crate::models::user::User;
+use crate::models::verification::{CreateVerificationCode, VerificationMedium, VerificationPurpose};
 use crate::routes::AppState;
+use crate::service::risk::{self, RiskAssessment, RiskDecision};
+use crate::utils::audit::AuditEntry;
 use crate::utils::errors::AuthError;
 use crate::utils::session_validation::validate_access_token;
 use crate::utils::types::RefreshTokenRequest;
-use crate::utils::types::{SignInRequest, SignInResponse, UserPublic};
+use crate::utils::types::{
+    LoginChallengeResponse, LoginChallengeVerifyRequest, SignInRequest, SignInResponse, UserPublic,
+};
 
 use database::utils::parse_id;
 use jsonwebtoken::{EncodingKey, Header, encode};
-use middleware::jwt::Claims;
-use utils::hash::Hash;
-use utils::hash::hash_sha256;
+use middleware::jwt::{AMR_OTP, AMR_PASSWORD, Claims, JwtClaims};
+use utils::email_templates::{EmailTemplateConfig, login_code, new_sign_in};
+use utils::hash::{generate_otp, hash_sha256};
 use utils::response::ApiResponse;
 
+const LOGIN_CHALLENGE_EXPIRY_SECONDS: i64 = 10 * 60; // 10 minutes
+const MAX_CHALLENGE_ATTEMPTS: i32 = 5;
+
 pub fn generate_access_token(
     user_id:        &str,
     email:          Option<&str>,
     jwt_secret:     &str,
     expiry_minutes: i64,
 ) -> Result<String, AuthError> {
+    let claims = access_claims(user_id, email, expiry_minutes);
+    encode_access_token(&claims, jwt_secret)
+}
+
+/// Build access token claims with the standard expiry
+pub fn access_claims(user_id: &str, email: Option<&str>, expiry_minutes: i64) -> Claims {
     let now = chrono::Utc::now().timestamp();
-    let claims = Claims {
-        sub:   user_id.to_string(),
-        email: email.map(String::from),
-        exp:   now + (expiry_minutes * 60),
-        iat:   now,
-    };
+    Claims {
+        sub:    user_id.to_string(),
+        email:  email.map(String::from),
+        exp:    now + (expiry_minutes * 60),
+        iat:    now,
+        org_id: None,
+        act:    None,
+        auth_time: None,
+        amr:    Vec::new(),
+    }
+}
+
+/// Build claims for a user who just authenticated with `method` (an AMR value)
+///
+/// Step-up protected routes only accept tokens with a recent `auth_time`.
+pub fn authenticated_claims(
+    user_id:        &str,
+    email:          Option<&str>,
+    expiry_minutes: i64,
+    method:         &str,
+) -> Claims {
+    let mut claims = access_claims(user_id, email, expiry_minutes);
+    claims.auth_time = Some(claims.iat);
+    claims.amr = vec![method.to_string()];
+    claims
+}
 
+/// Sign access token claims
+pub fn encode_access_token(claims: &Claims, jwt_secret: &str) -> Result<String, AuthError> {
     encode(
         &Header::default(),
-        &claims,
+        claims,
         &EncodingKey::from_secret(jwt_secret.as_bytes()),
     )
     .map_err(|e| AuthError::internal_error(&e.to_string()))
@@ -50,41 +88,40 @@ pub fn generate_refresh_token() -> String {
     utils::hash::generate_hex(64)
 }
 
-pub async fn login_user(
-    state:     web::Data<AppState>,
-    login_req: web::Json<SignInRequest>,
-    req:       HttpRequest,
-) -> Result<HttpResponse, Error> {
-    // FUTURE: Check Redis cache first for user data to avoid DB lookup
-    // FUTURE: Use cached password hash if available
-
-    let identifier  = login_req.identifier.trim();
-    let password = &login_req.password;
-
-    let user = state
-        .users
-        .find_by_identifier(identifier)
-        .map_err(|e| AuthError::internal_error(&e.to_string()))?
-        .ok_or_else(|| AuthError::invalid_credentials())?;
-
-    let stored_hash = Hash::from_string(&user.password_hash)
-        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
-
-    let password_valid = stored_hash
-        .verify(password)
-        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
-
-    if !password_valid {
-        return Ok(HttpResponse::Unauthorized()
-            .json(AuthError::invalid_credentials().to_response::<SignInResponse>()));
-    }
-
-    let access_token = generate_access_token(
+/// Issue access and refresh tokens for an authenticated user and record the session.
+///
+/// Shared by every login method (password, passkey, ...) so sessions look the same.
+pub async fn issue_session(
+    state:  &AppState,
+    user:   &User,
+    method: &str,
+    req:    &HttpRequest,
+) -> Result<SignInResponse, AuthError> {
+    let claims = authenticated_claims(
         &user.id.to_string(),
         user.email.as_deref(),
-        &state.jwt_secret,
         state.jwt_expiry_minutes,
-    )?;
+        method,
+    );
+    issue_session_with_claims(state, user, claims, req).await
+}
+
+/// Issue a session for custom claims (e.g. with an active organization)
+///
+/// The session records the claims' `org_id` so sessions can be filtered per tenant.
+pub async fn issue_session_with_claims(
+    state:  &AppState,
+    user:   &User,
+    claims: Claims,
+    req:    &HttpRequest,
+) -> Result<SignInResponse, AuthError> {
+    let access_token = encode_access_token(&claims, &state.jwt_secret)?;
+    let org_id = claims
+        .org_id
+        .as_deref()
+        .map(parse_id)
+        .transpose()
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
 
     let refresh_token = generate_refresh_token();
     let device = req
@@ -113,6 +150,7 @@ pub async fn login_user(
             .get("user-agent")
             .and_then(|v| v.to_str().ok())
             .map(String::from),
+        org_id,
         expires_in: state.refresh_token_expiry_days * 24 * 60 * 60,
     };
 
@@ -122,25 +160,222 @@ pub async fn login_user(
         expires_in: state.refresh_token_expiry_days * 24 * 60 * 60,
     };
 
-    let _ = state.sessions.create(session_input);
-    let _ = state.sessions.create_refresh_token(refresh_input);
+    let _ = state.sessions.create(session_input).await;
+    let _ = state.sessions.create_refresh_token(refresh_input).await;
 
-    let user_public = UserPublic {
-        id:         user.id.to_string(),
-        username:   user.username.unwrap_or_default(),
-        first_name: user.first_name,
-        last_name:  user.last_name,
-        is_verified:user.is_verified,
-        created_at: user.created_at,
-    };
+    let user_public = UserPublic::from(user);
 
-    let response_data = SignInResponse {
+    Ok(SignInResponse {
         user: user_public,
         access_token,
         refresh_token: Some(refresh_token),
         expires_in: chrono::Utc::now() + Duration::seconds(state.jwt_expiry_minutes * 60),
+    })
+}
+
+pub async fn login_user(
+    state:     web::Data<AppState>,
+    login_req: web::Json<SignInRequest>,
+    req:       HttpRequest,
+) -> Result<HttpResponse, Error> {
+    // FUTURE: Check Redis cache first for user data to avoid DB lookup
+    // FUTURE: Use cached password hash if available
+
+    let user = match state
+        .user_service()
+        .authenticate(&login_req.identifier, &login_req.password)
+        .await
+    {
+        Ok(user) => user,
+        Err(failure) => {
+            let mut entry = AuditEntry::failure(AuditAction::Login, failure.reason);
+            if let Some(ref user_id) = failure.user_id {
+                entry = entry.target(user_id);
+            }
+            entry.record(&state, &req).await;
+            return Err(failure.error.into());
+        }
     };
 
+    let assessment = risk::assess_login(&state, &user, &req).await?;
+    match assessment.decision {
+        RiskDecision::Deny => {
+            AuditEntry::failure(AuditAction::Login, &format!("risk_denied:{}", assessment.reason()))
+                .target(&user.id)
+                .record(&state, &req)
+                .await;
+            return Err(AuthError::forbidden(
+                "This sign-in looks unusual and was blocked. Please try again later or contact support.",
+            )
+            .into());
+        }
+        RiskDecision::Challenge => {
+            return start_login_challenge(&state, &user, &assessment, &req).await;
+        }
+        RiskDecision::Allow => {}
+    }
+
+    let response_data = issue_session(&state, &user, AMR_PASSWORD, &req).await?;
+    AuditEntry::success(AuditAction::Login).user(&user.id).record(&state, &req).await;
+
+    if assessment.is_risky() {
+        send_sign_in_alert(&state, &user, &assessment, &req).await;
+    }
+
+    let response = ApiResponse::success_data("Login successful", response_data);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Email a one-time code and hold the sign-in until it is entered
+async fn start_login_challenge(
+    state:      &AppState,
+    user:       &User,
+    assessment: &RiskAssessment,
+    req:        &HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (Some(store), Some(email_address)) = (&state.verifications, user.email.as_deref()) else {
+        AuditEntry::failure(AuditAction::Login, "challenge_unavailable")
+            .target(&user.id)
+            .record(state, req)
+            .await;
+        return Err(AuthError::forbidden(
+            "Additional verification is required. Please sign in with a passkey.",
+        )
+        .into());
+    };
+
+    let code = generate_otp(6);
+    let challenge = store.create(CreateVerificationCode {
+        user_id:    user.id.clone(),
+        code_hash:  hash_sha256(&code),
+        medium:     VerificationMedium::Email,
+        purpose:    VerificationPurpose::TwoFactor,
+        expires_in: LOGIN_CHALLENGE_EXPIRY_SECONDS,
+    }).await?;
+
+    let template_config = EmailTemplateConfig::new(&state.app_name, &state.frontend_url);
+    let mut email = login_code::build(&template_config, &code);
+    email.to = email_address.to_string();
+    email.from = state.email_from.clone();
+    let _email_result = state.email.send(&email).await;
+
+    AuditEntry::success(AuditAction::LoginChallenged)
+        .user(&user.id)
+        .reason(&assessment.reason())
+        .record(state, req)
+        .await;
+
+    let response = ApiResponse::success_data(
+        "Additional verification required",
+        LoginChallengeResponse {
+            challenge_id: challenge.id.to_string(),
+            method:       "email_otp".to_string(),
+            expires_at:   challenge.expires_at,
+        },
+    );
+
+    Ok(HttpResponse::Accepted().json(response))
+}
+
+/// Tell the user about a successful sign-in that looked unusual
+async fn send_sign_in_alert(
+    state:      &AppState,
+    user:       &User,
+    assessment: &RiskAssessment,
+    req:        &HttpRequest,
+) {
+    let Some(email_address) = user.email.as_deref() else {
+        return;
+    };
+
+    let ip_address = req.connection_info().realip_remote_addr().map(String::from);
+    let location = match (&assessment.location, ip_address) {
+        (Some(location), Some(ip)) => format!("{} ({})", location.country, ip),
+        (None, Some(ip)) => ip,
+        (_, None) => "Unknown".to_string(),
+    };
+    let device = req
+        .headers()
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("Unknown device");
+    let security_link = format!("{}/account/security", state.frontend_url.trim_end_matches('/'));
+
+    let template_config = EmailTemplateConfig::new(&state.app_name, &state.frontend_url);
+    let mut email = new_sign_in::build(&template_config, &location, device, &security_link);
+    email.to = email_address.to_string();
+    email.from = state.email_from.clone();
+    let _email_result = state.email.send(&email).await;
+}
+
+/// Finish a challenged sign-in with the emailed code
+pub async fn verify_login_challenge(
+    state:      web::Data<AppState>,
+    verify_req: web::Json<LoginChallengeVerifyRequest>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let store = state
+        .verifications
+        .as_ref()
+        .ok_or_else(|| AuthError::internal_error("Verification store not configured"))?;
+
+    let challenge_id =
+        parse_id(&verify_req.challenge_id).map_err(|_| AuthError::invalid_verification_code())?;
+    let challenge = store
+        .find_by_id(&challenge_id)
+        .await?
+        .filter(|c| {
+            c.purpose == VerificationPurpose::TwoFactor && c.medium == VerificationMedium::Email
+        })
+        .ok_or_else(AuthError::invalid_verification_code)?;
+
+    if !challenge.is_valid() {
+        return Err(AuthError::verification_code_expired().into());
+    }
+
+    if challenge.attempts >= MAX_CHALLENGE_ATTEMPTS {
+        return Err(AuthError::too_many_attempts().into());
+    }
+
+    if hash_sha256(verify_req.code.trim()) != challenge.code_hash {
+        store.increment_attempts(&challenge.id).await?;
+        AuditEntry::failure(AuditAction::Login, "invalid_login_code")
+            .target(&challenge.user_id)
+            .record(&state, &req)
+            .await;
+        return Err(AuthError::invalid_verification_code().into());
+    }
+
+    store.verify(&challenge.id).await?;
+
+    let user = state
+        .users
+        .find_by_id(&challenge.user_id)
+        .await?
+        .filter(|u| u.is_active() && !u.is_locked())
+        .ok_or_else(AuthError::account_disabled)?;
+
+    let mut claims = authenticated_claims(
+        &user.id.to_string(),
+        user.email.as_deref(),
+        state.jwt_expiry_minutes,
+        AMR_PASSWORD,
+    );
+    claims.amr.push(AMR_OTP.to_string());
+
+    // Assessed again only to describe the sign-in in the alert
+    let assessment = risk::assess_login(&state, &user, &req).await?;
+
+    let response_data = issue_session_with_claims(&state, &user, claims, &req).await?;
+    AuditEntry::success(AuditAction::Login)
+        .user(&user.id)
+        .reason("challenge_passed")
+        .record(&state, &req)
+        .await;
+
+    send_sign_in_alert(&state, &user, &assessment, &req).await;
+
     let response = ApiResponse::success_data("Login successful", response_data);
 
     Ok(HttpResponse::Ok().json(response))
@@ -158,7 +393,9 @@ pub async fn logout_user(
     let user_id = parse_id(&token_info.user_id).map_err(|_e| AuthError::invalid_credentials())?;
 
     // Revoke all sessions for this user on logout
-    let _ = state.sessions.revoke_all(&user_id);
+    let _ = state.sessions.revoke_all(&user_id).await;
+    AuditEntry::success(AuditAction::Logout).user(&user_id).record(&state, &req).await;
+
     let response = ApiResponse::<()>::ok("Logged out successfully");
 
     Ok(HttpResponse::Ok().json(response))
@@ -183,11 +420,15 @@ pub async fn refresh_token(
     let refresh_token_model = state
         .sessions
         .find_refresh_token_by_hash(&refresh_token_hash)
+        .await
         .map_err(|e| AuthError::internal_error(&e.to_string()))?;
 
     let refresh_token_model = match refresh_token_model {
         Some(rt) => rt,
         None => {
+            AuditEntry::failure(AuditAction::TokenRefresh, "invalid_refresh_token")
+                .record(&state, &req)
+                .await;
             let response = ApiResponse::<()>::error("Invalid or expired refresh token", None);
             return Ok(HttpResponse::Unauthorized().json(response));
         }
@@ -195,6 +436,10 @@ pub async fn refresh_token(
 
     // Check expiry
     if now > refresh_token_model.expires_at || refresh_token_model.revoked {
+        AuditEntry::failure(AuditAction::TokenRefresh, "refresh_token_expired_or_revoked")
+            .user(&refresh_token_model.user_id)
+            .record(&state, &req)
+            .await;
         let response = ApiResponse::<()>::error("Refresh token has expired or been revoked", None);
         return Ok(HttpResponse::Unauthorized().json(response));
     }
@@ -203,16 +448,30 @@ pub async fn refresh_token(
     let user = state
         .users
         .find_by_id(&refresh_token_model.user_id)
+        .await
         .map_err(|e| AuthError::internal_error(&e.to_string()))?
         .ok_or_else(|| AuthError::internal_error("User not found"))?;
 
-    // Generate new access token
-    let new_access_token = generate_access_token(
+    let session = state
+        .sessions
+        .find_by_token(&token_data.token_hash)
+        .await
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?
+        .ok_or_else(AuthError::invalid_session)?;
+
+    // Generate new access token - refreshing is not authenticating, so the
+    // original auth_time and methods carry over, as does the active organization
+    let mut new_claims = access_claims(
         &user.id.to_string(),
         user.email.as_deref(),
-        &state.jwt_secret,
         state.jwt_expiry_minutes,
-    )?;
+    );
+    new_claims.org_id = session.org_id.as_ref().map(ToString::to_string);
+    if let Some(claims) = req.claims() {
+        new_claims.auth_time = claims.auth_time;
+        new_claims.amr = claims.amr;
+    }
+    let new_access_token = encode_access_token(&new_claims, &state.jwt_secret)?;
 
     // Generate new refresh token
     let new_refresh_token  = generate_refresh_token();
@@ -228,27 +487,20 @@ pub async fn refresh_token(
 
     let new_session_input = UpdateSession {
         access_token_hash,
-        refresh_token_hash: refresh_token_hash,
-        expires_at: state.jwt_expiry_minutes * 60,
+        refresh_token_hash,
+        expires_at: now + state.refresh_token_expiry_days * 24 * 60 * 60,
         is_revoked: false,
     };
 
-    let session = state
-        .sessions
-        .find_by_token(&token_data.token_hash)
-        .map_err(|e| AuthError::internal_error(&e.to_string()))?
-        .ok_or_else(|| AuthError::invalid_session())?;
+    let _ = state.sessions.update(&session.id, new_session_input).await;
 
+    // Create the new refresh token, revoke the old one and link it to the new one
     let _ = state
         .sessions
-        .create_refresh_token(new_refresh_input.clone());
-    
-    let _ = state.sessions.update(&session.id, new_session_input);
+        .replace_refresh_token(&refresh_token_model.id, new_refresh_input)
+        .await;
 
-    // Mark old refresh token as revoked and link to new one
-    let _ = state
-        .sessions
-        .replace_refresh_token(&refresh_token_model.id, new_refresh_input.clone());
+    AuditEntry::success(AuditAction::TokenRefresh).user(&user.id).record(&state, &req).await;
 
     // Return new tokens
     let response_data = serde_json::json!({
@@ -2,25 +2,41 @@
 //!
 //! Contains all HTTP request handlers for authentication operations.
 
+pub mod admin;
+pub mod api_keys;
+pub mod audit;
+pub mod contact_change;
 pub mod devices;
 pub mod forgot_password;
 pub mod login_user;
 pub mod magic_link;
 pub mod oauth;
+pub mod organizations;
+pub mod passkeys;
+pub mod reauthenticate;
 pub mod reset_password;
 pub mod sessions;
 pub mod signup_user;
 pub mod two_factor;
+pub mod user_invitations;
 pub mod users;
 
 // Re-export handlers for easier use
+pub use admin::*;
+pub use api_keys::*;
+pub use audit::*;
+pub use contact_change::*;
 pub use devices::*;
 pub use forgot_password::*;
 pub use login_user::*;
 pub use magic_link::*;
 pub use oauth::*;
+pub use organizations::*;
+pub use passkeys::*;
+pub use reauthenticate::*;
 pub use reset_password::*;
 pub use sessions::*;
 pub use signup_user::*;
 pub use two_factor::*;
+pub use user_invitations::*;
 pub use users::*;
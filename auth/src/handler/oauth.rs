@@ -11,8 +11,9 @@ use utils::response::{ApiResponse, ApiError };
 use crate::config::oauth::{OAuthConfig};
 use crate::service::OAuthService;
 use crate::handler::login_user::{
-    generate_access_token, generate_refresh_token};
+    authenticated_claims, encode_access_token, generate_refresh_token};
 
+use crate::models::audit::AuditAction;
 use crate::models::oauth::{
     CreateOAuthAccount, 
     OAuthProvider, 
@@ -22,11 +23,13 @@ use crate::models::oauth::{
 use crate::models::user::CreateUserInput;
 use crate::models::session::CreateRefreshToken;   
 use crate::models::session::CreateSession;
+use crate::utils::audit::AuditEntry;
 use crate::utils::errors::AuthError;
 use crate::routes::AppState;
 use database::utils::{generate_id, parse_id};
 
 // Import logging utilities
+use middleware::jwt::AMR_FEDERATED;
 use middleware::tracing::{info, warn};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -94,7 +97,7 @@ pub async fn oauth_redirect(
     let oauth_service = OAuthService::new(oauth_config);
 
     // Generate state token - this will be validated in callback
-    let state_token = format!("{}.{}", generate_id().to_string(),
+    let state_token = format!("{}.{}", generate_id(),
         chrono::Utc::now().timestamp()
     );
 
@@ -195,15 +198,16 @@ pub async fn oauth_callback(
             .map(String::from);
 
     // Check if OAuth account exists
-    match oauth_accounts.find_by_provider_user_id(&provider, &user_info.provider_user_id) {
+    match oauth_accounts.find_by_provider_user_id(&provider, &user_info.provider_user_id).await {
         Ok(Some(oauth_account)) => {
             // User already linked - create new session
-            let access_token   = generate_access_token(
-                &oauth_account.user_id.to_string(), 
-                user_info.email.as_deref(), 
-                &state.jwt_secret, 
-                state.jwt_expiry_minutes
-            )?;
+            let claims         = authenticated_claims(
+                &oauth_account.user_id.to_string(),
+                user_info.email.as_deref(),
+                state.jwt_expiry_minutes,
+                AMR_FEDERATED,
+            );
+            let access_token   = encode_access_token(&claims, &state.jwt_secret)?;
 
             let refresh_token      = generate_refresh_token();
             let access_token_hash  = hash_sha256(&access_token);
@@ -216,6 +220,7 @@ pub async fn oauth_callback(
                 device:             device.clone(),
                 ip_address:         ip_address.clone(),
                 user_agent:         user_agent.clone(),
+                org_id:             None,
                 expires_in:         state.jwt_expiry_minutes * 24 * 60 * 60,
             };
 
@@ -228,13 +233,21 @@ pub async fn oauth_callback(
             state
                 .sessions
                 .create(session_input)
+                .await
                 .map_err(|_| AuthError::internal_error("Failed to create session"))?;
 
             state
                 .sessions
                 .create_refresh_token(refresh_input)
+                .await
                 .map_err(|_| AuthError::internal_error("Failed to create refresh token"))?;
 
+            AuditEntry::success(AuditAction::OAuthLogin)
+                .user(&oauth_account.user_id)
+                .reason(&provider_str.to_lowercase())
+                .record(&state, &req)
+                .await;
+
             let response = ApiResponse::success_data(
                 "OAuth login successful",
                 serde_json::json!({
@@ -248,14 +261,16 @@ pub async fn oauth_callback(
         }
         Ok(None) => {
             // New OAuth user - create account
-            let user_email = user_info.email.clone().ok_or_else(|| {
+            let user_email = user_info.email.as_deref().ok_or_else(|| {
                 AuthError::invalid_request("Email not provided by OAuth provider")
             })?;
+            let user_email = state.identifiers.email(user_email)?;
 
             // Check if user with this email exists
             let existing_user = state
                 .users
                 .find_by_email(&user_email)
+                .await
                 .map_err(|_| AuthError::internal_error("Database error while checking email"))?;
 
             let user = if let Some(existing) = existing_user {
@@ -267,11 +282,13 @@ pub async fn oauth_callback(
                     email: Some(user_email.clone()),
                     password: generate_id().to_string(), // Random password for OAuth users
                     phone: None,
+                    username: None,
                 };
 
                 state
                     .users
                     .create(user_input)
+                    .await
                     .map_err(|_| AuthError::internal_error("Failed to create user"))?
             };
 
@@ -288,15 +305,17 @@ pub async fn oauth_callback(
 
             oauth_accounts
                 .create(oauth_account_input)
+                .await
                 .map_err(|_| AuthError::internal_error("Failed to create OAuth account"))?;
 
             // Create session
-            let access_token   = generate_access_token(
-                &user.id.to_string(), 
-                user_info.email.as_deref(), 
-                &state.jwt_secret, 
-                state.jwt_expiry_minutes
-            )?;
+            let claims         = authenticated_claims(
+                &user.id.to_string(),
+                user_info.email.as_deref(),
+                state.jwt_expiry_minutes,
+                AMR_FEDERATED,
+            );
+            let access_token   = encode_access_token(&claims, &state.jwt_secret)?;
 
             let refresh_token      = generate_refresh_token();
             let access_token_hash  = hash_sha256(&access_token);
@@ -309,6 +328,7 @@ pub async fn oauth_callback(
                 device: device.clone(),
                 ip_address: ip_address.clone(),
                 user_agent: user_agent.clone(),
+                org_id: None,
                 expires_in: state.jwt_expiry_minutes * 24 * 60 * 60,
             };
 
@@ -321,13 +341,26 @@ pub async fn oauth_callback(
             state
                 .sessions
                 .create_refresh_token(refresh_input)
+                .await
                 .map_err(|_| AuthError::internal_error("Failed to create refresh token"))?;
 
             state
                 .sessions
                 .create(session_input)
+                .await
                 .map_err(|_| AuthError::internal_error("Failed to create session"))?;
 
+            AuditEntry::success(AuditAction::OAuthLinked)
+                .user(&user.id)
+                .reason(&provider_str.to_lowercase())
+                .record(&state, &req)
+                .await;
+            AuditEntry::success(AuditAction::OAuthLogin)
+                .user(&user.id)
+                .reason(&provider_str.to_lowercase())
+                .record(&state, &req)
+                .await;
+
             let response = ApiResponse::success_data(
                 "OAuth account created",
                 serde_json::json!({
@@ -348,6 +381,7 @@ pub async fn link_oauth(
     state: web::Data<AppState>,
     user_id: web::Path<String>,
     req: web::Json<LinkOAuthRequest>,
+    http_req: HttpRequest,
 ) -> Result<HttpResponse, Error> {
     let user_id_str = user_id.into_inner();
     let user_id =
@@ -357,6 +391,7 @@ pub async fn link_oauth(
     let user = state
         .users
         .find_by_id(&user_id)
+        .await
         .map_err(|_| AuthError::internal_error("Database error while finding user"))?
         .ok_or_else(|| AuthError::not_found("User not found"))?;
 
@@ -366,7 +401,7 @@ pub async fn link_oauth(
         .ok_or_else(|| AuthError::internal_error("OAuth accounts store not configured"))?;
 
     // Check if OAuth account already linked for this user and provider
-    if let Ok(Some(_)) = oauth_accounts.find_by_user_and_provider(&user_id, &req.provider) {
+    if let Ok(Some(_)) = oauth_accounts.find_by_user_and_provider(&user_id, &req.provider).await {
         return Err(AuthError::invalid_request("OAuth account already linked for this provider").into());
     }
 
@@ -388,8 +423,12 @@ pub async fn link_oauth(
         .map_err(|_| AuthError::internal_error("Failed to retrieve user info from provider"))?;
 
     // Check if this OAuth account is already linked to another user
-    if let Ok(Some(existing_account)) = oauth_accounts.find_by_provider_user_id(&req.provider, &user_info.provider_user_id) {
+    if let Ok(Some(existing_account)) = oauth_accounts.find_by_provider_user_id(&req.provider, &user_info.provider_user_id).await {
         if existing_account.user_id != user_id {
+            AuditEntry::failure(AuditAction::OAuthLinked, "linked_to_another_user")
+                .target(&user_id)
+                .record(&state, &http_req)
+                .await;
             return Err(AuthError::conflict("This OAuth account is already linked to another user").into());
         }
     }
@@ -407,8 +446,15 @@ pub async fn link_oauth(
 
     oauth_accounts
         .create(oauth_account_input)
+        .await
         .map_err(|_| AuthError::internal_error("Failed to create OAuth account"))?;
 
+    AuditEntry::success(AuditAction::OAuthLinked)
+        .target(&user.id)
+        .reason(&provider_str.to_lowercase())
+        .record(&state, &http_req)
+        .await;
+
     let response = ApiResponse::success_data(
         "OAuth account linked successfully",
         serde_json::json!({
@@ -426,6 +472,7 @@ pub async fn unlink_oauth(
     state: web::Data<AppState>,
     user_id: web::Path<String>,
     req: web::Json<UnlinkOAuthRequest>,
+    http_req: HttpRequest,
 ) -> Result<HttpResponse, Error> {
     let user_id_str = user_id.into_inner();
     let user_id =
@@ -438,8 +485,15 @@ pub async fn unlink_oauth(
 
     oauth_accounts
         .delete_by_user_and_provider(&user_id, &req.provider)
+        .await
         .map_err(|_| AuthError::internal_error("Failed to delete OAuth account"))?;
 
+    AuditEntry::success(AuditAction::OAuthUnlinked)
+        .target(&user_id)
+        .reason(&format!("{:?}", req.provider).to_lowercase())
+        .record(&state, &http_req)
+        .await;
+
     let response =
         ApiResponse::success_data("OAuth account unlinked successfully", serde_json::json!({}));
 
@@ -462,6 +516,7 @@ pub async fn list_oauth_connections(
 
     let accounts = oauth_accounts
         .list_by_user(&user_id)
+        .await
         .map_err(|_| AuthError::internal_error("Failed to retrieve OAuth connections"))?;
 
     let connections: Vec<OAuthConnectionResponse> = accounts
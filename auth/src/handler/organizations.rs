@@ -0,0 +1,678 @@
+//! Organization handler - tenants, memberships and invitations
+//!
+//! Every organization route checks the caller's membership first. Requests for
+//! organizations the caller does not belong to get "not found" so tenants
+//! cannot discover each other.
+
+use actix_web::{Error, HttpRequest, HttpResponse, web};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::models::audit::AuditAction;
+use crate::models::organization::{
+    CreateMembership, CreateOrgInvitation, CreateOrganization, Membership, OrgRole,
+};
+use crate::routes::AppState;
+use crate::store::organization_store::OrganizationStore;
+use crate::utils::audit::AuditEntry;
+use crate::utils::errors::AuthError;
+use crate::utils::session_validation::validate_access_token;
+use crate::utils::types::{
+    AcceptInvitationRequest, CreateOrganizationRequest, InviteMemberRequest,
+    OrgInvitationPublic, OrgMemberResponse, OrganizationResponse, PaginationQuery,
+    UpdateMemberRoleRequest, UserPublic,
+};
+
+use super::login_user::{access_claims, issue_session_with_claims};
+use database::utils::{DbId, parse_id};
+use middleware::jwt::JwtClaims;
+use utils::email_templates::{EmailTemplateConfig, org_invitation};
+use utils::hash::{generate_hex, hash_sha256};
+use utils::response::{ApiResponse, ResponseMeta};
+
+const INVITATION_EXPIRY_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days
+
+pub(crate) fn org_store(state: &AppState) -> Result<&Arc<dyn OrganizationStore>, AuthError> {
+    state
+        .organizations
+        .as_ref()
+        .ok_or_else(|| AuthError::internal_error("Organization store not configured"))
+}
+
+pub(crate) async fn current_user_id(req: &HttpRequest, state: &web::Data<AppState>) -> Result<DbId, AuthError> {
+    let token_info = validate_access_token(req, state)
+        .await
+        .map_err(|_e| AuthError::invalid_session())?;
+
+    parse_id(&token_info.user_id).map_err(|e| AuthError::internal_error(&e.to_string()))
+}
+
+fn parse_org_id(org_id: &str) -> Result<DbId, AuthError> {
+    parse_id(org_id).map_err(|_| AuthError::not_found("Organization not found"))
+}
+
+/// Organization of a tenant-scoped route
+///
+/// A token with an active organization only reaches that organization's data;
+/// the others look the same as organizations the caller isn't a member of.
+pub(crate) fn tenant_org_id(req: &HttpRequest, org_id: &str) -> Result<DbId, AuthError> {
+    let org_id = parse_org_id(org_id)?;
+    let active = req.claims().and_then(|c| c.org_id);
+
+    if active.is_some_and(|active| active != org_id.to_string()) {
+        return Err(AuthError::not_found("Organization not found"));
+    }
+    Ok(org_id)
+}
+
+/// Membership of the caller, or "not found" for non-members
+async fn require_membership(
+    store:   &Arc<dyn OrganizationStore>,
+    org_id:  &DbId,
+    user_id: &DbId,
+) -> Result<Membership, AuthError> {
+    store
+        .find_membership(org_id, user_id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("Organization not found"))
+}
+
+/// Membership of the caller, who must be allowed to manage members
+pub(crate) async fn require_manager(
+    store:   &Arc<dyn OrganizationStore>,
+    org_id:  &DbId,
+    user_id: &DbId,
+) -> Result<Membership, AuthError> {
+    let membership = require_membership(store, org_id, user_id).await?;
+    if !membership.role.can_manage_members() {
+        return Err(AuthError::forbidden("Only organization admins can manage members"));
+    }
+    Ok(membership)
+}
+
+/// Build a URL-safe slug from an organization name
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+pub async fn create_organization(
+    state:      web::Data<AppState>,
+    create_req: web::Json<CreateOrganizationRequest>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let store = org_store(&state)?;
+
+    let name = create_req.name.trim();
+    if name.is_empty() {
+        return Err(AuthError::invalid_request("Organization name is required").into());
+    }
+
+    let slug = slugify(create_req.slug.as_deref().unwrap_or(name));
+    if slug.is_empty() {
+        return Err(AuthError::invalid_request("Organization slug is invalid").into());
+    }
+
+    if store.find_by_slug(&slug).await?.is_some() {
+        return Err(AuthError::conflict("Organization slug is already taken").into());
+    }
+
+    let org = store.create(CreateOrganization {
+        name: name.to_string(),
+        slug,
+        owner_id: user_id.clone(),
+    }).await?;
+
+    store.add_member(CreateMembership {
+        org_id:  org.id.clone(),
+        user_id: user_id.clone(),
+        role:    OrgRole::Owner,
+    }).await?;
+    state.users.add_to_org(&user_id, &org.id).await?;
+
+    AuditEntry::success(AuditAction::OrgCreated)
+        .actor(&user_id)
+        .org(&org.id)
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::success_data(
+        "Organization created",
+        OrganizationResponse {
+            id:         org.id.to_string(),
+            name:       org.name,
+            slug:       org.slug,
+            role:       OrgRole::Owner,
+            created_at: org.created_at,
+        },
+    );
+
+    Ok(HttpResponse::Created().json(response))
+}
+
+pub async fn list_organizations(
+    state: web::Data<AppState>,
+    req:   HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let store = org_store(&state)?;
+
+    let memberships = store.list_user_memberships(&user_id).await?;
+    let roles: HashMap<DbId, OrgRole> = memberships
+        .iter()
+        .map(|m| (m.org_id.clone(), m.role))
+        .collect();
+    let org_ids: Vec<DbId> = roles.keys().cloned().collect();
+
+    let orgs: Vec<OrganizationResponse> = store
+        .find_by_ids(&org_ids)
+        .await?
+        .into_iter()
+        .filter_map(|org| {
+            roles.get(&org.id).map(|role| OrganizationResponse {
+                id:         org.id.to_string(),
+                name:       org.name,
+                slug:       org.slug,
+                role:       *role,
+                created_at: org.created_at,
+            })
+        })
+        .collect();
+
+    let response = ApiResponse::success_data("Organizations retrieved", orgs);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_organization(
+    state:  web::Data<AppState>,
+    org_id: web::Path<String>,
+    req:    HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let org_id = tenant_org_id(&req, &org_id)?;
+    let store = org_store(&state)?;
+
+    let membership = require_membership(store, &org_id, &user_id).await?;
+    let org = store
+        .find_by_id(&org_id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("Organization not found"))?;
+
+    let response = ApiResponse::success_data(
+        "Organization retrieved",
+        OrganizationResponse {
+            id:         org.id.to_string(),
+            name:       org.name,
+            slug:       org.slug,
+            role:       membership.role,
+            created_at: org.created_at,
+        },
+    );
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn list_org_members(
+    state:  web::Data<AppState>,
+    org_id: web::Path<String>,
+    query:  web::Query<PaginationQuery>,
+    req:    HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let org_id = tenant_org_id(&req, &org_id)?;
+    let store = org_store(&state)?;
+
+    require_membership(store, &org_id, &user_id).await?;
+
+    let memberships: HashMap<DbId, Membership> = store
+        .list_members(&org_id)
+        .await?
+        .into_iter()
+        .map(|m| (m.user_id.clone(), m))
+        .collect();
+
+    // Users are read through the tenant-filtered store query
+    let users = state
+        .users
+        .list_by_org(&org_id, query.offset_page(), query.per_page())
+        .await?;
+    let total = state.users.count_by_org(&org_id).await?;
+
+    let members: Vec<OrgMemberResponse> = users
+        .iter()
+        .filter_map(|user| {
+            memberships.get(&user.id).map(|m| OrgMemberResponse {
+                user:      UserPublic::from(user),
+                role:      m.role,
+                joined_at: m.created_at,
+            })
+        })
+        .collect();
+
+    let response = ApiResponse::success_data("Members retrieved", members)
+        .with_meta(ResponseMeta::new(query.page(), query.per_page(), total));
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn update_org_member(
+    state:      web::Data<AppState>,
+    path:       web::Path<(String, String)>,
+    update_req: web::Json<UpdateMemberRoleRequest>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let (org_id, member_id) = path.into_inner();
+    let org_id = tenant_org_id(&req, &org_id)?;
+    let member_id = parse_id(&member_id).map_err(|_| AuthError::not_found("Member not found"))?;
+    let store = org_store(&state)?;
+
+    let caller = require_manager(store, &org_id, &user_id).await?;
+    let member = store
+        .find_membership(&org_id, &member_id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("Member not found"))?;
+
+    // Only owners can grant or take away ownership
+    if (member.role == OrgRole::Owner || update_req.role == OrgRole::Owner)
+        && caller.role != OrgRole::Owner
+    {
+        return Err(AuthError::forbidden("Only owners can change ownership").into());
+    }
+
+    if member.role == OrgRole::Owner && update_req.role != OrgRole::Owner {
+        ensure_other_owner(store, &org_id, &member_id).await?;
+    }
+
+    store.update_member_role(&org_id, &member_id, update_req.role).await?;
+    AuditEntry::success(AuditAction::OrgMemberUpdated)
+        .actor(&user_id)
+        .target(&member_id)
+        .org(&org_id)
+        .reason(update_req.role.as_str())
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::<()>::ok("Member role updated");
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn remove_org_member(
+    state: web::Data<AppState>,
+    path:  web::Path<(String, String)>,
+    req:   HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let (org_id, member_id) = path.into_inner();
+    let org_id = tenant_org_id(&req, &org_id)?;
+    let member_id = parse_id(&member_id).map_err(|_| AuthError::not_found("Member not found"))?;
+    let store = org_store(&state)?;
+
+    // Members may leave on their own; removing others needs admin rights
+    let caller = if member_id == user_id {
+        require_membership(store, &org_id, &user_id).await?
+    } else {
+        require_manager(store, &org_id, &user_id).await?
+    };
+
+    let member = store
+        .find_membership(&org_id, &member_id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("Member not found"))?;
+
+    if member.role == OrgRole::Owner {
+        if caller.role != OrgRole::Owner {
+            return Err(AuthError::forbidden("Only owners can remove an owner").into());
+        }
+        ensure_other_owner(store, &org_id, &member_id).await?;
+    }
+
+    store.remove_member(&org_id, &member_id).await?;
+    state.users.remove_from_org(&member_id, &org_id).await?;
+    AuditEntry::success(AuditAction::OrgMemberRemoved)
+        .actor(&user_id)
+        .target(&member_id)
+        .org(&org_id)
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::<()>::ok("Member removed");
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// An organization must always keep at least one owner
+async fn ensure_other_owner(
+    store:     &Arc<dyn OrganizationStore>,
+    org_id:    &DbId,
+    member_id: &DbId,
+) -> Result<(), AuthError> {
+    let has_other_owner = store
+        .list_members(org_id)
+        .await?
+        .iter()
+        .any(|m| m.role == OrgRole::Owner && &m.user_id != member_id);
+
+    if !has_other_owner {
+        return Err(AuthError::last_owner());
+    }
+    Ok(())
+}
+
+pub async fn invite_org_member(
+    state:      web::Data<AppState>,
+    org_id:     web::Path<String>,
+    invite_req: web::Json<InviteMemberRequest>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let org_id = tenant_org_id(&req, &org_id)?;
+    let store = org_store(&state)?;
+
+    let caller = require_manager(store, &org_id, &user_id).await?;
+    if invite_req.role == OrgRole::Owner && caller.role != OrgRole::Owner {
+        return Err(AuthError::forbidden("Only owners can invite owners").into());
+    }
+
+    let email = state.identifiers.email(&invite_req.email)?;
+
+    if let Some(existing) = state.users.find_by_email(&email).await? {
+        if existing.is_member_of(&org_id) {
+            return Err(AuthError::conflict("User is already a member").into());
+        }
+    }
+
+    let org = store
+        .find_by_id(&org_id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("Organization not found"))?;
+    let inviter = state
+        .users
+        .find_by_id(&user_id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("User not found"))?;
+
+    let token = generate_hex(32);
+    let invitation = store.create_invitation(CreateOrgInvitation {
+        org_id:     org_id.clone(),
+        email:      email.clone(),
+        role:       invite_req.role,
+        token_hash: hash_sha256(&token),
+        invited_by: user_id,
+        expires_in: INVITATION_EXPIRY_SECONDS,
+    }).await?;
+
+    AuditEntry::success(AuditAction::OrgInvitationSent)
+        .actor(&invitation.invited_by)
+        .target(&invitation.id)
+        .org(&org_id)
+        .reason(invitation.role.as_str())
+        .record(&state, &req)
+        .await;
+
+    let accept_link = format!(
+        "{}/orgs/invitations/accept?token={}",
+        state.frontend_url.trim_end_matches('/'),
+        token
+    );
+    let inviter_name = inviter
+        .first_name
+        .clone()
+        .or(inviter.username.clone())
+        .or(inviter.email.clone())
+        .unwrap_or_else(|| "A teammate".to_string());
+
+    let template_config = EmailTemplateConfig::new(&state.app_name, &state.frontend_url);
+    let mut invitation_email =
+        org_invitation::build(&template_config, &org.name, &inviter_name, &accept_link);
+    invitation_email.to = email;
+    invitation_email.from = state.email_from.clone();
+
+    let _email_result = state.email.send(&invitation_email).await;
+
+    let response =
+        ApiResponse::success_data("Invitation sent", OrgInvitationPublic::from(invitation));
+
+    Ok(HttpResponse::Created().json(response))
+}
+
+pub async fn list_org_invitations(
+    state:  web::Data<AppState>,
+    org_id: web::Path<String>,
+    req:    HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let org_id = tenant_org_id(&req, &org_id)?;
+    let store = org_store(&state)?;
+
+    require_manager(store, &org_id, &user_id).await?;
+
+    let invitations: Vec<OrgInvitationPublic> = store
+        .list_invitations(&org_id)
+        .await?
+        .into_iter()
+        .filter(|i| i.is_pending())
+        .map(OrgInvitationPublic::from)
+        .collect();
+
+    let response = ApiResponse::success_data("Invitations retrieved", invitations);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn revoke_org_invitation(
+    state: web::Data<AppState>,
+    path:  web::Path<(String, String)>,
+    req:   HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let (org_id, invitation_id) = path.into_inner();
+    let org_id = tenant_org_id(&req, &org_id)?;
+    let invitation_id =
+        parse_id(&invitation_id).map_err(|_| AuthError::not_found("Invitation not found"))?;
+    let store = org_store(&state)?;
+
+    require_manager(store, &org_id, &user_id).await?;
+
+    let invitation = store
+        .find_invitation(&invitation_id)
+        .await?
+        .filter(|i| i.org_id == org_id)
+        .ok_or_else(|| AuthError::not_found("Invitation not found"))?;
+
+    store.delete_invitation(&invitation.id).await?;
+    AuditEntry::success(AuditAction::OrgInvitationRevoked)
+        .actor(&user_id)
+        .target(&invitation.id)
+        .org(&org_id)
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::<()>::ok("Invitation revoked");
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Accept an invitation as the signed in user, whose email must match
+pub async fn accept_org_invitation(
+    state:      web::Data<AppState>,
+    accept_req: web::Json<AcceptInvitationRequest>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let store = org_store(&state)?;
+
+    let invitation = store
+        .find_invitation_by_token(&hash_sha256(accept_req.token.trim()))
+        .await?
+        .filter(|i| i.is_pending())
+        .ok_or_else(|| AuthError::invalid_request("Invitation is invalid or has expired"))?;
+
+    let user = state
+        .users
+        .find_by_id(&user_id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("User not found"))?;
+
+    let email_matches = user
+        .email
+        .as_deref()
+        .is_some_and(|email| email.eq_ignore_ascii_case(&invitation.email));
+    if !email_matches {
+        return Err(AuthError::forbidden("This invitation was sent to a different email").into());
+    }
+    // Anyone can sign up with an address they don't own; only a verified one proves receipt
+    if !user.is_verified {
+        return Err(AuthError::account_not_verified().into());
+    }
+
+    if store.find_membership(&invitation.org_id, &user_id).await?.is_none() {
+        store.add_member(CreateMembership {
+            org_id:  invitation.org_id.clone(),
+            user_id: user_id.clone(),
+            role:    invitation.role,
+        }).await?;
+    }
+    state.users.add_to_org(&user_id, &invitation.org_id).await?;
+    store.accept_invitation(&invitation.id).await?;
+    AuditEntry::success(AuditAction::OrgInvitationAccepted)
+        .user(&user_id)
+        .org(&invitation.org_id)
+        .reason(invitation.role.as_str())
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::success_data(
+        "Invitation accepted",
+        serde_json::json!({ "org_id": invitation.org_id.to_string(), "role": invitation.role }),
+    );
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Switch the active organization - issues new tokens carrying the `org_id` claim
+pub async fn switch_organization(
+    state:  web::Data<AppState>,
+    org_id: web::Path<String>,
+    req:    HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let token_info = validate_access_token(&req, &state)
+        .await
+        .map_err(|_e| AuthError::invalid_session())?;
+
+    // Switching issues a full session, which neither may obtain
+    if req.api_key().is_some() {
+        return Err(AuthError::forbidden("Organizations cannot be switched with an API key").into());
+    }
+    if req.is_impersonated() {
+        return Err(AuthError::forbidden("Not allowed while impersonating a user").into());
+    }
+
+    let user_id = parse_id(&token_info.user_id)
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+    let org_id = parse_org_id(&org_id)?;
+
+    require_membership(org_store(&state)?, &org_id, &user_id).await?;
+
+    let user = state
+        .users
+        .find_by_id(&user_id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("User not found"))?;
+
+    let mut claims = access_claims(
+        &user.id.to_string(),
+        user.email.as_deref(),
+        state.jwt_expiry_minutes,
+    );
+    claims.org_id = Some(org_id.to_string());
+    if let Some(current) = req.claims() {
+        claims.auth_time = current.auth_time;
+        claims.amr = current.amr;
+    }
+
+    let response_data = issue_session_with_claims(&state, &user, claims, &req).await?;
+    AuditEntry::success(AuditAction::OrgSwitched)
+        .user(&user_id)
+        .org(&org_id)
+        .record(&state, &req)
+        .await;
+
+    // The previous token belongs to the old organization context
+    if let Ok(Some(session)) = state.sessions.find_by_token(&token_info.token_hash).await {
+        let _ = state.sessions.revoke(&session.id).await;
+    }
+
+    let response = ApiResponse::success_data("Organization switched", response_data);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn list_org_sessions(
+    state:  web::Data<AppState>,
+    org_id: web::Path<String>,
+    req:    HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let org_id = tenant_org_id(&req, &org_id)?;
+
+    require_manager(org_store(&state)?, &org_id, &user_id).await?;
+
+    let sessions: Vec<serde_json::Value> = state
+        .sessions
+        .find_by_org(&org_id)
+        .await?
+        .into_iter()
+        .filter(|s| s.is_valid())
+        .map(|s| {
+            serde_json::json!({
+                "id":           s.id.to_string(),
+                "user_id":      s.user_id.to_string(),
+                "device":       s.device,
+                "ip_address":   s.ip_address,
+                "created_at":   s.created_at,
+                "last_used_at": s.last_used_at,
+            })
+        })
+        .collect();
+
+    let response = ApiResponse::success_data("Sessions retrieved", sessions);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Acme Corp"), "acme-corp");
+        assert_eq!(slugify("  Hello,  World!! "), "hello-world");
+        assert_eq!(slugify("***"), "");
+    }
+
+    #[test]
+    fn test_org_role_permissions() {
+        assert!(OrgRole::Owner.can_manage_members());
+        assert!(OrgRole::Admin.can_manage_members());
+        assert!(!OrgRole::Member.can_manage_members());
+    }
+
+    #[test]
+    fn test_org_role_serialization() {
+        assert_eq!(serde_json::to_string(&OrgRole::Admin).unwrap(), "\"admin\"");
+        let role: OrgRole = serde_json::from_str("\"owner\"").unwrap();
+        assert_eq!(role, OrgRole::Owner);
+    }
+}
@@ -0,0 +1,418 @@
+//! Passkey (WebAuthn) handler
+//!
+//! Passkeys can be used as a standalone login method or as a second factor.
+//! Every ceremony is two steps: fetch options (which stores a challenge),
+//! then submit the browser's credential for verification.
+
+use actix_web::{Error, HttpRequest, HttpResponse, web};
+use std::sync::Arc;
+
+use crate::models::audit::AuditAction;
+use crate::models::passkey::{
+    CreatePasskeyCredential, CreateWebAuthnChallenge, PasskeyLoginRequest,
+    PasskeyLoginVerifyRequest, PasskeyRegisterRequest, PasskeyRegisterVerifyRequest,
+    WebAuthnCeremony, WebAuthnChallenge,
+};
+use crate::routes::AppState;
+use crate::service::webauthn::WebAuthnService;
+use crate::store::passkey_store::PasskeyStore;
+use crate::utils::audit::AuditEntry;
+use crate::utils::errors::AuthError;
+use crate::utils::session_validation::validate_access_token;
+use crate::utils::types::PasskeyPublic;
+
+use super::login_user::issue_session;
+use super::reauthenticate::refresh_auth_time;
+use database::utils::{DbId, parse_id};
+use middleware::jwt::{AMR_PASSKEY, JwtClaims};
+use utils::response::ApiResponse;
+
+fn passkey_store(state: &AppState) -> Result<&Arc<dyn PasskeyStore>, AuthError> {
+    state
+        .passkeys
+        .as_ref()
+        .ok_or_else(|| AuthError::internal_error("Passkey store not configured"))
+}
+
+fn webauthn_service(state: &AppState) -> Result<WebAuthnService, AuthError> {
+    state
+        .webauthn
+        .clone()
+        .map(WebAuthnService::new)
+        .ok_or_else(|| AuthError::internal_error("WebAuthn is not configured"))
+}
+
+/// Issue and store a new challenge for a ceremony
+async fn issue_challenge(
+    state:    &AppState,
+    user_id:  Option<DbId>,
+    ceremony: WebAuthnCeremony,
+) -> Result<String, AuthError> {
+    let config = state
+        .webauthn
+        .as_ref()
+        .ok_or_else(|| AuthError::internal_error("WebAuthn is not configured"))?;
+
+    let challenge = WebAuthnService::generate_challenge();
+    passkey_store(state)?.create_challenge(CreateWebAuthnChallenge {
+        user_id,
+        challenge: challenge.clone(),
+        ceremony,
+        expires_in: config.challenge_expiry_secs,
+    }).await?;
+
+    Ok(challenge)
+}
+
+/// Look up and consume the challenge echoed in clientDataJSON
+///
+/// Challenges are single use: they are deleted before verification so a
+/// failed attempt cannot be retried with the same challenge.
+async fn consume_challenge(
+    state:            &AppState,
+    client_data_json: &str,
+    ceremony:         WebAuthnCeremony,
+) -> Result<WebAuthnChallenge, AuthError> {
+    let store = passkey_store(state)?;
+    let value = WebAuthnService::client_data_challenge(client_data_json)?;
+
+    let challenge = store
+        .find_challenge(&value)
+        .await?
+        .ok_or_else(|| AuthError::invalid_passkey("Unknown challenge"))?;
+
+    store.delete_challenge(&challenge.id).await?;
+
+    if challenge.ceremony != ceremony {
+        return Err(AuthError::invalid_passkey("Challenge issued for a different ceremony"));
+    }
+
+    if challenge.is_expired() {
+        return Err(AuthError::invalid_passkey("Challenge has expired"));
+    }
+
+    Ok(challenge)
+}
+
+/// Verify an assertion for a user and persist the new signature counter
+pub(crate) async fn verify_user_assertion(
+    state:      &AppState,
+    user_id:    &DbId,
+    request:    &PasskeyLoginVerifyRequest,
+    ceremony:   WebAuthnCeremony,
+) -> Result<(), AuthError> {
+    let service = webauthn_service(state)?;
+    let store = passkey_store(state)?;
+    let credential = &request.credential;
+
+    let challenge = consume_challenge(state, &credential.response.client_data_json, ceremony).await?;
+    if challenge.user_id.as_ref().is_some_and(|id| id != user_id) {
+        return Err(AuthError::invalid_passkey("Challenge issued for a different user"));
+    }
+
+    let stored = store
+        .find_by_credential_id(&credential.id)
+        .await?
+        .filter(|c| &c.user_id == user_id)
+        .ok_or_else(|| AuthError::invalid_passkey("Unknown credential"))?;
+
+    let sign_count = service.verify_assertion(credential, &challenge.challenge, &stored)?;
+    store.update_sign_count(&stored.id, sign_count).await?;
+
+    Ok(())
+}
+
+/// Start passkey registration for the signed in user
+pub async fn passkey_register_options(
+    state:        web::Data<AppState>,
+    _register_req: web::Json<PasskeyRegisterRequest>,
+    req:          HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let token_info = validate_access_token(&req, &state)
+        .await
+        .map_err(|_e| AuthError::invalid_session())?;
+
+    let user_id = parse_id(&token_info.user_id)
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+
+    let user = state
+        .users
+        .find_by_id(&user_id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("User not found"))?;
+
+    let service = webauthn_service(&state)?;
+    let existing = passkey_store(&state)?.list_by_user(&user_id).await?;
+    let challenge = issue_challenge(&state, Some(user_id), WebAuthnCeremony::Registration).await?;
+
+    let options = service.registration_options(&user, &existing, &challenge);
+    let response = ApiResponse::success_data("Passkey registration options", options);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Finish passkey registration and store the credential
+pub async fn passkey_register_verify(
+    state:        web::Data<AppState>,
+    register_req: web::Json<PasskeyRegisterVerifyRequest>,
+    req:          HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let token_info = validate_access_token(&req, &state)
+        .await
+        .map_err(|_e| AuthError::invalid_session())?;
+
+    let user_id = parse_id(&token_info.user_id)
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+
+    let service = webauthn_service(&state)?;
+    let store = passkey_store(&state)?;
+    let credential = &register_req.credential;
+
+    let challenge = consume_challenge(
+        &state,
+        &credential.response.client_data_json,
+        WebAuthnCeremony::Registration,
+    ).await?;
+    if challenge.user_id.as_ref() != Some(&user_id) {
+        return Err(AuthError::invalid_passkey("Challenge issued for a different user").into());
+    }
+
+    let verified = service.verify_registration(credential, &challenge.challenge)?;
+
+    if store.find_by_credential_id(&verified.credential_id).await?.is_some() {
+        return Err(AuthError::conflict("Passkey is already registered").into());
+    }
+
+    let passkey = store.create(CreatePasskeyCredential {
+        user_id,
+        credential_id: verified.credential_id,
+        public_key:    verified.public_key,
+        sign_count:    verified.sign_count,
+        aaguid:        Some(verified.aaguid),
+        name:          register_req.name.clone(),
+        transports:    credential.response.transports.clone(),
+    }).await?;
+
+    AuditEntry::success(AuditAction::PasskeyRegistered)
+        .user(&passkey.user_id)
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::success_data("Passkey registered", PasskeyPublic::from(passkey));
+
+    Ok(HttpResponse::Created().json(response))
+}
+
+/// Start passkey login
+///
+/// With an identifier the options list that user's passkeys; without one the
+/// browser offers any discoverable passkey for this site. Unknown identifiers
+/// get usernameless options so the endpoint does not reveal which accounts exist.
+pub async fn passkey_login_options(
+    state:     web::Data<AppState>,
+    login_req: web::Json<PasskeyLoginRequest>,
+) -> Result<HttpResponse, Error> {
+    let service = webauthn_service(&state)?;
+    let store = passkey_store(&state)?;
+
+    let user = match login_req.identifier.as_deref().map(str::trim) {
+        Some(identifier) if !identifier.is_empty() => {
+            state.users.find_by_identifier(&state.identifiers.identifier(identifier)).await?
+        }
+        _ => None,
+    };
+
+    let allowed = match &user {
+        Some(user) => store.list_by_user(&user.id).await?,
+        None => Vec::new(),
+    };
+
+    let challenge = issue_challenge(&state, None, WebAuthnCeremony::Authentication).await?;
+    let options = service.authentication_options(&allowed, &challenge);
+    let response = ApiResponse::success_data("Passkey login options", options);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Finish passkey login and issue tokens
+pub async fn passkey_login_verify(
+    state:     web::Data<AppState>,
+    login_req: web::Json<PasskeyLoginVerifyRequest>,
+    req:       HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let stored = passkey_store(&state)?
+        .find_by_credential_id(&login_req.credential.id)
+        .await?
+        .ok_or_else(|| AuthError::invalid_passkey("Unknown credential"))?;
+
+    let user = state
+        .users
+        .find_by_id(&stored.user_id)
+        .await?
+        .ok_or_else(AuthError::invalid_credentials)?;
+
+    if !user.is_active() {
+        AuditEntry::failure(AuditAction::PasskeyLogin, "account_disabled")
+            .target(&user.id)
+            .record(&state, &req)
+            .await;
+        return Err(AuthError::account_disabled().into());
+    }
+
+    if user.is_locked() {
+        AuditEntry::failure(AuditAction::PasskeyLogin, "account_locked")
+            .target(&user.id)
+            .record(&state, &req)
+            .await;
+        return Err(AuthError::account_locked().into());
+    }
+
+    if let Err(e) =
+        verify_user_assertion(&state, &user.id, &login_req, WebAuthnCeremony::Authentication).await
+    {
+        AuditEntry::failure(AuditAction::PasskeyLogin, "invalid_assertion")
+            .target(&user.id)
+            .record(&state, &req)
+            .await;
+        return Err(e.into());
+    }
+
+    let response_data = issue_session(&state, &user, AMR_PASSKEY, &req).await?;
+    AuditEntry::success(AuditAction::PasskeyLogin).user(&user.id).record(&state, &req).await;
+
+    let response = ApiResponse::success_data("Login successful", response_data);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// List passkeys for the signed in user
+pub async fn list_passkeys(
+    state: web::Data<AppState>,
+    req:   HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let token_info = validate_access_token(&req, &state)
+        .await
+        .map_err(|_e| AuthError::invalid_session())?;
+
+    let user_id = parse_id(&token_info.user_id)
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+
+    let passkeys: Vec<PasskeyPublic> = passkey_store(&state)?
+        .list_by_user(&user_id)
+        .await?
+        .into_iter()
+        .map(PasskeyPublic::from)
+        .collect();
+
+    let response = ApiResponse::success_data("Passkeys retrieved", passkeys);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Remove a passkey owned by the signed in user
+pub async fn delete_passkey(
+    state:      web::Data<AppState>,
+    passkey_id: web::Path<String>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let token_info = validate_access_token(&req, &state)
+        .await
+        .map_err(|_e| AuthError::invalid_session())?;
+
+    let user_id = parse_id(&token_info.user_id)
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+    let passkey_id = parse_id(&passkey_id)
+        .map_err(|_| AuthError::invalid_request("Invalid passkey ID"))?;
+
+    let store = passkey_store(&state)?;
+    let passkey = store
+        .find_by_id(&passkey_id)
+        .await?
+        .filter(|p| p.user_id == user_id)
+        .ok_or_else(|| AuthError::not_found("Passkey not found"))?;
+
+    store.delete(&passkey.id).await?;
+    AuditEntry::success(AuditAction::PasskeyRemoved).user(&user_id).record(&state, &req).await;
+
+    let response = ApiResponse::<()>::ok("Passkey removed");
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Start a passkey second-factor check for the signed in user
+pub async fn passkey_2fa_options(
+    state: web::Data<AppState>,
+    req:   HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let token_info = validate_access_token(&req, &state)
+        .await
+        .map_err(|_e| AuthError::invalid_session())?;
+
+    let user_id = parse_id(&token_info.user_id)
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+
+    let service = webauthn_service(&state)?;
+    let allowed = passkey_store(&state)?.list_by_user(&user_id).await?;
+    if allowed.is_empty() {
+        return Err(AuthError::not_found("No passkeys registered").into());
+    }
+
+    let challenge = issue_challenge(&state, Some(user_id), WebAuthnCeremony::TwoFactor).await?;
+    let options = service.authentication_options(&allowed, &challenge);
+    let response = ApiResponse::success_data("Passkey verification options", options);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Verify a passkey as the second factor for the signed in user
+///
+/// The session moves to a fresh access token authenticated now, with the
+/// passkey added to the session's methods, so step-up protected routes accept
+/// it and it shows as multi-factor.
+pub async fn passkey_2fa_verify(
+    state:      web::Data<AppState>,
+    verify_req: web::Json<PasskeyLoginVerifyRequest>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let token_info = validate_access_token(&req, &state)
+        .await
+        .map_err(|_e| AuthError::invalid_session())?;
+
+    if req.api_key().is_some() {
+        return Err(AuthError::forbidden("API keys cannot verify a second factor").into());
+    }
+
+    let user_id = parse_id(&token_info.user_id)
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+
+    let user = state
+        .users
+        .find_by_id(&user_id)
+        .await?
+        .filter(|u| u.is_active())
+        .ok_or_else(AuthError::invalid_session)?;
+
+    if let Err(e) = verify_user_assertion(&state, &user_id, &verify_req, WebAuthnCeremony::TwoFactor).await {
+        AuditEntry::failure(AuditAction::Reauthenticated, "invalid_passkey")
+            .user(&user.id)
+            .record(&state, &req)
+            .await;
+        return Err(e.into());
+    }
+
+    let mut amr = req.claims().map(|c| c.amr).unwrap_or_default();
+    if !amr.iter().any(|m| m == AMR_PASSKEY) {
+        amr.push(AMR_PASSKEY.to_string());
+    }
+    let refreshed = refresh_auth_time(&state, &req, &user, &token_info.token_hash, amr).await?;
+
+    AuditEntry::success(AuditAction::Reauthenticated)
+        .user(&user.id)
+        .reason("passkey_2fa")
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::success_data("Two-factor verification successful", refreshed);
+
+    Ok(HttpResponse::Ok().json(response))
+}
@@ -0,0 +1,127 @@
+//! Re-authentication handler - step-up before sensitive actions
+//!
+//! Routes wrapped in `RequireRecentAuth` refuse tokens whose `auth_time` is
+//! stale. The client proves it is still the user with the password or a
+//! passkey, and the current session is moved to a fresh access token.
+
+use actix_web::{Error, HttpRequest, HttpResponse, web};
+use chrono::Duration;
+
+use crate::handler::login_user::{authenticated_claims, encode_access_token};
+use crate::handler::passkeys::verify_user_assertion;
+use crate::models::audit::AuditAction;
+use crate::models::passkey::WebAuthnCeremony;
+use crate::models::user::User;
+use crate::routes::AppState;
+use crate::utils::audit::AuditEntry;
+use crate::utils::errors::AuthError;
+use crate::utils::session_validation::validate_access_token;
+use crate::utils::types::{ReauthenticateRequest, ReauthenticateResponse};
+
+use database::utils::parse_id;
+use middleware::jwt::{AMR_PASSKEY, AMR_PASSWORD, JwtClaims};
+use utils::hash::{Hash, hash_sha256};
+use utils::response::ApiResponse;
+
+pub async fn reauthenticate(
+    state:      web::Data<AppState>,
+    reauth_req: web::Json<ReauthenticateRequest>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let token_info = validate_access_token(&req, &state)
+        .await
+        .map_err(|_e| AuthError::invalid_session())?;
+
+    if req.api_key().is_some() {
+        return Err(AuthError::forbidden("API keys cannot re-authenticate").into());
+    }
+
+    let user_id = parse_id(&token_info.user_id)
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+
+    let user = state
+        .users
+        .find_by_id(&user_id)
+        .await?
+        .filter(|u| u.is_active())
+        .ok_or_else(AuthError::invalid_session)?;
+
+    let verified = match (&reauth_req.password, &reauth_req.passkey) {
+        (Some(password), _) => {
+            let stored_hash = Hash::from_string(&user.password_hash)
+                .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+            let valid = stored_hash
+                .verify(password)
+                .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+
+            if valid { Ok(AMR_PASSWORD) } else { Err(AuthError::invalid_credentials()) }
+        }
+        (None, Some(passkey)) => {
+            verify_user_assertion(&state, &user_id, passkey, WebAuthnCeremony::TwoFactor)
+                .await
+                .map(|_| AMR_PASSKEY)
+        }
+        (None, None) => {
+            return Err(AuthError::invalid_request("A password or passkey is required").into());
+        }
+    };
+
+    let method = match verified {
+        Ok(method) => method,
+        Err(e) => {
+            AuditEntry::failure(AuditAction::Reauthenticated, "invalid_credentials")
+                .user(&user.id)
+                .record(&state, &req)
+                .await;
+            return Err(e.into());
+        }
+    };
+
+    let refreshed = refresh_auth_time(&state, &req, &user, &token_info.token_hash, vec![method.to_string()]).await?;
+
+    AuditEntry::success(AuditAction::Reauthenticated)
+        .user(&user.id)
+        .reason(method)
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::success_data("Re-authentication successful", refreshed);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Move the current session to a fresh access token authenticated now with `amr`
+///
+/// The active organization carries over; the refresh token is left alone.
+pub(crate) async fn refresh_auth_time(
+    state:      &AppState,
+    req:        &HttpRequest,
+    user:       &User,
+    token_hash: &str,
+    amr:        Vec<String>,
+) -> Result<ReauthenticateResponse, AuthError> {
+    let session = state
+        .sessions
+        .find_by_token(token_hash)
+        .await?
+        .ok_or_else(AuthError::invalid_session)?;
+
+    let mut claims = authenticated_claims(
+        &user.id.to_string(),
+        user.email.as_deref(),
+        state.jwt_expiry_minutes,
+        AMR_PASSWORD,
+    );
+    claims.amr = amr;
+    claims.org_id = req.claims().and_then(|c| c.org_id);
+
+    let access_token = encode_access_token(&claims, &state.jwt_secret)?;
+    state.sessions.rotate_access_token(&session.id, &hash_sha256(&access_token)).await?;
+
+    Ok(ReauthenticateResponse {
+        access_token,
+        auth_time:  claims.iat,
+        amr:        claims.amr,
+        expires_in: chrono::Utc::now() + Duration::minutes(state.jwt_expiry_minutes),
+    })
+}
@@ -4,7 +4,9 @@
 
 use actix_web::{Error, HttpRequest, HttpResponse, web};
 
+use crate::models::audit::AuditAction;
 use crate::routes::AppState;
+use crate::utils::audit::AuditEntry;
 use crate::utils::errors::AuthError;
 use crate::utils::passwords::validate_strength;
 use crate::utils::session_validation::validate_access_token;
@@ -16,6 +18,10 @@ use utils::hash::Hash;
 use utils::hash::hash_sha256;
 use utils::response::ApiResponse;
 
+/// Set a new password with an emailed reset token
+///
+/// The route is public: whoever resets a password has usually lost access to
+/// the account, and the single-use token already proves who the user is.
 pub async fn reset_password(
     state: web::Data<AppState>,
     reset_req: web::Json<PasswordResetConfirm>,
@@ -26,21 +32,19 @@ pub async fn reset_password(
     let confirm_password = &reset_req.confirm_password;
     let token_hash = hash_sha256(&token);
 
-    // Validate token and session in one call
-    let _token_info = validate_access_token(&req, &state)
-        .await
-        .map_err(|_e| AuthError::invalid_session())?;
-
-    // Get password resets store
-    let password_resets = state
+    // No session needed - the reset token proves who the user is
+    let resets = state
         .password_resets
         .as_ref()
-        .ok_or_else(|| AuthError::internal_error("Password reset not configured"))?
-        .find_by_hash(&token_hash)?;
+        .ok_or_else(|| AuthError::internal_error("Password reset not configured"))?;
+    let password_resets = resets.find_by_hash(&token_hash).await?;
 
     let password_resets = match password_resets {
         Some(pr) => pr,
         None => {
+            AuditEntry::failure(AuditAction::PasswordReset, "invalid_reset_token")
+                .record(&state, &req)
+                .await;
             let response = ApiResponse::<()>::error("Invalid or expired token", None);
             return Ok(HttpResponse::Unauthorized().json(response));
         }
@@ -49,6 +53,10 @@ pub async fn reset_password(
     // check the token is valid and not expired
     let now = chrono::Utc::now().timestamp();
     if password_resets.expires_at < now {
+        AuditEntry::failure(AuditAction::PasswordReset, "reset_token_expired")
+            .target(&password_resets.user_id)
+            .record(&state, &req)
+            .await;
         return Ok(
             HttpResponse::BadRequest().json(AuthError::reset_token_expired().to_response::<()>())
         );
@@ -56,6 +64,10 @@ pub async fn reset_password(
 
     // check if the token is already used
     if password_resets.used_at != None {
+        AuditEntry::failure(AuditAction::PasswordReset, "reset_token_used")
+            .target(&password_resets.user_id)
+            .record(&state, &req)
+            .await;
         return Ok(
             HttpResponse::BadRequest().json(AuthError::invalid_reset_token().to_response::<()>())
         );
@@ -77,16 +89,21 @@ pub async fn reset_password(
         .map_err(|e| AuthError::internal_error(&e.to_string()))?
         .to_string();
 
-    let _ = state
-        .users
-        .update_password(&password_resets.user_id, &password_hash)
-        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
-
-    let _ = state
-        .password_resets
-        .as_ref()
-        .ok_or_else(|| AuthError::internal_error("Password reset not configured"))?
-        .mark_used(&password_resets.id);
+    // The new password, the spent token and the revoked sessions land together
+    state
+        .transactions
+        .run(|| async {
+            state.users.update_password(&password_resets.user_id, &password_hash).await?;
+            resets.mark_used(&password_resets.id).await?;
+            state.sessions.revoke_all(&password_resets.user_id).await?;
+            Ok::<_, AuthError>(())
+        })
+        .await?;
+
+    AuditEntry::success(AuditAction::PasswordReset)
+        .target(&password_resets.user_id)
+        .record(&state, &req)
+        .await;
 
     let response = ApiResponse::<()>::ok("Password changed successfully");
 
@@ -126,6 +143,7 @@ pub async fn change_password(
     let user = state
         .users
         .find_by_id(&uid)
+        .await
         .map_err(|e| AuthError::internal_error(&e.to_string()))?
         .ok_or_else(|| AuthError::not_found("User not found"))?;
 
@@ -138,6 +156,10 @@ pub async fn change_password(
         .map_err(|e| AuthError::internal_error(&e.to_string()))?;
 
     if !password_valid {
+        AuditEntry::failure(AuditAction::PasswordChanged, "invalid_credentials")
+            .user(&uid)
+            .record(&state, &req)
+            .await;
         return Ok(
             HttpResponse::Unauthorized().json(AuthError::invalid_credentials().to_response::<()>())
         );
@@ -152,8 +174,11 @@ pub async fn change_password(
     let _ = state
         .users
         .update_password(&uid, &password_hash)
+        .await
         .map_err(|e| AuthError::internal_error(&e.to_string()))?;
 
+    AuditEntry::success(AuditAction::PasswordChanged).user(&uid).record(&state, &req).await;
+
     let response = ApiResponse::<()>::ok("Password changed successfully");
 
     Ok(HttpResponse::Ok().json(response))
@@ -1,65 +1,45 @@
 //! Signup handler
 
-use actix_web::{Error, HttpResponse, web};
-use crate::models::user::{CreateUserInput};
+use actix_web::{Error, HttpRequest, HttpResponse, web};
+use crate::models::audit::AuditAction;
+use crate::models::verification::VerificationMedium;
 use crate::routes::AppState;
-use crate::utils::errors::AuthError;
+use crate::service::user::SignUpCode;
+use crate::utils::audit::AuditEntry;
+use crate::utils::errors::{AuthError, AuthErrorCode};
 use crate::utils::types::{
     SendVerificationRequest, 
     VerifyCodeRequest,
     SignUpResponseData,
-    SignUpRequest
+    SignUpRequest,
+    UsernameAvailabilityQuery,
+    UsernameAvailabilityResponse,
 };
+use crate::utils::usernames;
 
+use utils::email_templates::{EmailTemplateConfig, verify_email as verify_email_template};
 use utils::response::ApiResponse;
-use database::utils::parse_id;
-use utils::hash::Hash;
+use utils::sms::templates as sms_templates;
+use database::utils::{DbId, parse_id};
 
 pub async fn signup_user(
     state:      web::Data<AppState>,
     signup_req: web::Json<SignUpRequest>,
+    req:        HttpRequest,
 ) -> Result<HttpResponse, Error> {
-    
-    // Check if email already exists
-    if let Some(ref email) = signup_req.email {
-        if let Some(_existing) = state.users.find_by_email(email.as_str())? {
-            let response =
-                AuthError::email_already_exists(email.as_str()).to_response::<SignUpResponseData>();
-            return Ok(HttpResponse::Conflict().json(response));
-        }
-    }
-
-    // Check if phone already exists (if provided)
-    if let Some(ref phone) = signup_req.phone {
-        if let Some(_existing) = state.users.find_by_phone(phone.as_str())? {
-            let response =
-                AuthError::phone_already_exists(phone.as_str()).to_response::<SignUpResponseData>();
-            return Ok(HttpResponse::Conflict().json(response));
-        }
-    }
-
-    // Hash the password before storing
-    let hashed_password = Hash::argon2(&signup_req.password)
-        .map_err(|e| AuthError::internal_error(&e.to_string()))?
-        .to_string();
+    let (user, code) = state.user_service().signup(signup_req.into_inner()).await?;
 
-    // Create user input with hashed password
-    let create_input = CreateUserInput {
-        email:    signup_req.email.clone(),
-        password: hashed_password,
-        phone:    signup_req.phone.clone(),
-    };
+    AuditEntry::success(AuditAction::Signup).user(&user.id).record(&state, &req).await;
 
-    // Create the user
-    let user = state
-        .users
-        .create(create_input)
-        .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+    if let Some(code) = code {
+        send_code(&state, &user.id, code).await;
+    }
 
     // Return success response
     let response_data = SignUpResponseData {
-        user_id: user.id.to_string(),
-        email:   user.email,
+        user_id:  user.id.to_string(),
+        email:    user.email,
+        username: user.username,
     };
 
     let response = ApiResponse::success_data("User registered successfully", response_data);
@@ -67,31 +47,87 @@ pub async fn signup_user(
 
 }
 
-/// Verify email with code
+/// Check whether a username can be taken
+pub async fn check_username_availability(
+    state: web::Data<AppState>,
+    query: web::Query<UsernameAvailabilityQuery>,
+) -> Result<HttpResponse, Error> {
+    let availability = match state.user_service().claim_username(&query.username, None).await {
+        Ok(username) => UsernameAvailabilityResponse { username, available: true, reason: None },
+        Err(e) if matches!(e.code, AuthErrorCode::InvalidUsername | AuthErrorCode::UsernameAlreadyExists) => {
+            UsernameAvailabilityResponse {
+                username:  usernames::normalize(&query.username),
+                available: false,
+                reason:    Some(e.message),
+            }
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let response = ApiResponse::success_data("Username availability checked", availability);
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Verify email or phone with code
 pub async fn verify_email(
-    state:       web::Data<AppState>,
-    user_id:     web::Path<String>,
-    _verify_req: web::Json<VerifyCodeRequest>,
+    state:      web::Data<AppState>,
+    user_id:    web::Path<String>,
+    verify_req: web::Json<VerifyCodeRequest>,
+    req:        HttpRequest,
 ) -> Result<HttpResponse, Error> {
-    // Find the user
     let db_id = parse_id(&user_id).map_err(|_| AuthError::invalid_request("Invalid user ID"))?;
+    let verify_req = verify_req.into_inner();
 
-    let mut user = state
-        .users
-        .find_by_id(&db_id)?
-        .ok_or_else(|| AuthError::not_found("User not found"))?;
+    let user = state
+        .user_service()
+        .verify(&db_id, verify_req.medium, &verify_req.code)
+        .await?;
+    AuditEntry::success(AuditAction::EmailVerified).user(&user.id).record(&state, &req).await;
 
-    // For now, just mark as verified
-    user.verify_user();
-    let response: ApiResponse<()> = ApiResponse::ok("Email verified successfully");
+    let response: ApiResponse<()> = ApiResponse::ok("Verified successfully");
     Ok(HttpResponse::Ok().json(response))
 }
 
 /// Send verification code
+///
+/// Always answers the same way, so it does not reveal which accounts exist.
 pub async fn send_verification_code(
-    _state:    web::Data<AppState>,
-    _send_req: web::Json<SendVerificationRequest>,
+    state:    web::Data<AppState>,
+    send_req: web::Json<SendVerificationRequest>,
 ) -> Result<HttpResponse, Error> {
+    let service = state.user_service();
+    let user = service.find_by_identifier(&send_req.identifier).await?;
+
+    if let Some(user) = user {
+        if let Ok((address, code)) = service.start_verification(&user, send_req.medium.clone()).await {
+            send_code(&state, &user.id, SignUpCode { medium: send_req.medium.clone(), address, code }).await;
+        }
+    }
+
     let response: ApiResponse<()> = ApiResponse::ok("Verification code sent");
     Ok(HttpResponse::Ok().json(response))
-}
\ No newline at end of file
+}
+
+/// Email or text a verification code; delivery failures are not reported
+async fn send_code(state: &AppState, user_id: &DbId, code: SignUpCode) {
+    match code.medium {
+        VerificationMedium::Email => {
+            let verify_link = format!(
+                "{}/verify/{}",
+                state.frontend_url.trim_end_matches('/'),
+                user_id
+            );
+            let template_config = EmailTemplateConfig::new(&state.app_name, &state.frontend_url);
+            let mut email = verify_email_template::build(&template_config, &verify_link, Some(&code.code));
+            email.to = code.address;
+            email.from = state.email_from.clone();
+
+            let _email_result = state.email.send(&email).await;
+        }
+        VerificationMedium::Phone => {
+            if let Some(sms) = state.sms.as_ref() {
+                let _sms_result = sms.send(&sms_templates::verification_code(&code.address, &code.code)).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! User invitation handler - onboard users without public registration
+//!
+//! Platform admins who manage an organization invite a new user by email. A
+//! pending (inactive) user is created right away and the invitee receives a
+//! signed, expiring link. Accepting sets the password, verifies the user and
+//! joins the organization in one step.
+//!
+//! Links carry a nonce whose hash is stored on the invitation, so resending or
+//! revoking an invitation invalidates every link sent before.
+
+use actix_web::{Error, HttpRequest, HttpResponse, web};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::models::audit::AuditAction;
+use crate::models::organization::{CreateMembership, OrgRole};
+use crate::models::user::{CreateUserInput, UpdateUserInput};
+use crate::models::user_invitation::{CreateUserInvitation, UserInvitation};
+use crate::routes::AppState;
+use crate::store::user_invitation_store::UserInvitationStore;
+use crate::utils::audit::AuditEntry;
+use crate::utils::errors::AuthError;
+use crate::utils::passwords::validate_strength;
+use crate::utils::types::{AcceptUserInvitationRequest, InviteUserRequest, UserInvitationPublic};
+
+use super::admin::require_admin;
+use super::login_user::issue_session;
+use super::organizations::{current_user_id, org_store, require_manager, tenant_org_id};
+use database::utils::{DbId, parse_id};
+use middleware::jwt::AMR_PASSWORD;
+use utils::email_templates::{EmailTemplateConfig, account_invitation};
+use utils::hash::{Hash, generate_hex, hash_sha256};
+use utils::response::ApiResponse;
+use utils::signature::{create_signed_url, verify_signed_url};
+
+/// Frontend path of the invite link, covered by the signature
+const INVITE_LINK_PATH: &str = "/invite/accept";
+const INVITATION_EXPIRY_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days
+
+fn invitation_store(state: &AppState) -> Result<&Arc<dyn UserInvitationStore>, AuthError> {
+    state
+        .user_invitations
+        .as_ref()
+        .ok_or_else(|| AuthError::internal_error("User invitation store not configured"))
+}
+
+/// Sign and email a fresh invite link
+async fn send_invite_link(
+    state:        &AppState,
+    invitation:   &UserInvitation,
+    nonce:        &str,
+    inviter_name: &str,
+) -> Result<(), AuthError> {
+    let invitation_id = invitation.id.to_string();
+    let query = create_signed_url(
+        INVITE_LINK_PATH,
+        &[("invitation_id", &invitation_id), ("nonce", nonce)],
+        state.jwt_secret.as_bytes(),
+    )
+    .map_err(|e| AuthError::internal_error(&e.to_string()))?;
+
+    let setup_link = format!(
+        "{}{}?{}",
+        state.frontend_url.trim_end_matches('/'),
+        INVITE_LINK_PATH,
+        query
+    );
+
+    let template_config = EmailTemplateConfig::new(&state.app_name, &state.frontend_url);
+    let mut email = account_invitation::build(&template_config, inviter_name, &setup_link);
+    email.to = invitation.email.clone();
+    email.from = state.email_from.clone();
+
+    let _email_result = state.email.send(&email).await;
+
+    Ok(())
+}
+
+async fn inviter_name(state: &AppState, inviter_id: &DbId) -> Result<String, AuthError> {
+    let inviter = state.users.find_by_id(inviter_id).await?;
+
+    Ok(inviter
+        .and_then(|u| u.first_name.or(u.username).or(u.email))
+        .unwrap_or_else(|| "An administrator".to_string()))
+}
+
+pub async fn invite_user(
+    state:      web::Data<AppState>,
+    org_id:     web::Path<String>,
+    invite_req: web::Json<InviteUserRequest>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    // Pre-creating an account claims its email platform-wide
+    let user_id = require_admin(&req, &state).await?.id;
+    let org_id = tenant_org_id(&req, &org_id)?;
+
+    let caller = require_manager(org_store(&state)?, &org_id, &user_id).await?;
+    if invite_req.role == OrgRole::Owner && caller.role != OrgRole::Owner {
+        return Err(AuthError::forbidden("Only owners can invite owners").into());
+    }
+
+    let email = state.identifiers.email(&invite_req.email)?;
+
+    // Existing users join through organization invitations instead
+    if state.users.find_by_email(&email).await?.is_some() {
+        return Err(AuthError::email_already_exists(&email).into());
+    }
+
+    // The pending user gets a random password nobody knows until the invite is accepted
+    let placeholder_hash = Hash::argon2(&generate_hex(32))
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?
+        .to_string();
+
+    let pending_user = state.users.create(CreateUserInput {
+        email:    Some(email.clone()),
+        phone:    None,
+        username: None,
+        password: placeholder_hash,
+    }).await?;
+    state.users.set_active(&pending_user.id, false).await?;
+
+    if invite_req.first_name.is_some() || invite_req.last_name.is_some() {
+        state.users.update(
+            &pending_user.id,
+            UpdateUserInput {
+                username:   None,
+                first_name: invite_req.first_name.clone(),
+                last_name:  invite_req.last_name.clone(),
+                profile:    None,
+            },
+        ).await?;
+    }
+
+    let nonce = generate_hex(32);
+    let invitation = invitation_store(&state)?.create(CreateUserInvitation {
+        user_id:    pending_user.id,
+        org_id,
+        email,
+        role:       invite_req.role,
+        nonce_hash: hash_sha256(&nonce),
+        invited_by: user_id.clone(),
+        expires_in: INVITATION_EXPIRY_SECONDS,
+    }).await?;
+
+    AuditEntry::success(AuditAction::UserInvited)
+        .actor(&user_id)
+        .target(&invitation.user_id)
+        .org(&invitation.org_id)
+        .reason(invitation.role.as_str())
+        .record(&state, &req)
+        .await;
+
+    send_invite_link(&state, &invitation, &nonce, &inviter_name(&state, &user_id).await?).await?;
+
+    let response =
+        ApiResponse::success_data("Invitation sent", UserInvitationPublic::from(invitation));
+
+    Ok(HttpResponse::Created().json(response))
+}
+
+pub async fn list_user_invitations(
+    state:  web::Data<AppState>,
+    org_id: web::Path<String>,
+    req:    HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = current_user_id(&req, &state).await?;
+    let org_id = tenant_org_id(&req, &org_id)?;
+
+    require_manager(org_store(&state)?, &org_id, &user_id).await?;
+
+    let invitations: Vec<UserInvitationPublic> = invitation_store(&state)?
+        .list_pending(&org_id)
+        .await?
+        .into_iter()
+        .map(UserInvitationPublic::from)
+        .collect();
+
+    let response = ApiResponse::success_data("Invitations retrieved", invitations);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Find an invitation of the organization, managed by the caller
+async fn managed_invitation(
+    state: &web::Data<AppState>,
+    path:  (String, String),
+    req:   &HttpRequest,
+) -> Result<(DbId, UserInvitation), AuthError> {
+    let user_id = current_user_id(req, state).await?;
+    let (org_id, invitation_id) = path;
+    let org_id = tenant_org_id(req, &org_id)?;
+    let invitation_id =
+        parse_id(&invitation_id).map_err(|_| AuthError::not_found("Invitation not found"))?;
+
+    require_manager(org_store(state)?, &org_id, &user_id).await?;
+
+    let invitation = invitation_store(state)?
+        .find_by_id(&invitation_id)
+        .await?
+        .filter(|i| i.org_id == org_id)
+        .ok_or_else(|| AuthError::not_found("Invitation not found"))?;
+
+    Ok((user_id, invitation))
+}
+
+pub async fn resend_user_invitation(
+    state: web::Data<AppState>,
+    path:  web::Path<(String, String)>,
+    req:   HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (user_id, invitation) = managed_invitation(&state, path.into_inner(), &req).await?;
+
+    if invitation.accepted_at.is_some() {
+        return Err(AuthError::invalid_request("Invitation was already accepted").into());
+    }
+
+    let nonce = generate_hex(32);
+    let store = invitation_store(&state)?;
+    store.renew(&invitation.id, &hash_sha256(&nonce), INVITATION_EXPIRY_SECONDS).await?;
+
+    let invitation = store
+        .find_by_id(&invitation.id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("Invitation not found"))?;
+
+    send_invite_link(&state, &invitation, &nonce, &inviter_name(&state, &user_id).await?).await?;
+
+    let response =
+        ApiResponse::success_data("Invitation resent", UserInvitationPublic::from(invitation));
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn revoke_user_invitation(
+    state: web::Data<AppState>,
+    path:  web::Path<(String, String)>,
+    req:   HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (user_id, invitation) = managed_invitation(&state, path.into_inner(), &req).await?;
+
+    if invitation.accepted_at.is_some() {
+        return Err(AuthError::invalid_request("Invitation was already accepted").into());
+    }
+
+    // The pending user never signed in, so it goes with the invitation
+    state.users.delete(&invitation.user_id).await?;
+    invitation_store(&state)?.delete(&invitation.id).await?;
+    AuditEntry::success(AuditAction::UserInvitationRevoked)
+        .actor(&user_id)
+        .target(&invitation.user_id)
+        .org(&invitation.org_id)
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::<()>::ok("Invitation revoked");
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Accept an invitation - sets the password, verifies the user and signs them in
+pub async fn accept_user_invitation(
+    state:      web::Data<AppState>,
+    accept_req: web::Json<AcceptUserInvitationRequest>,
+    req:        HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let query = accept_req.query.trim().trim_start_matches('?');
+    let invalid_link = || AuthError::invalid_request("Invitation link is invalid or has expired");
+
+    let signature_valid = verify_signed_url(
+        INVITE_LINK_PATH,
+        query,
+        state.jwt_secret.as_bytes(),
+        INVITATION_EXPIRY_SECONDS / 60,
+    )
+    .unwrap_or(false);
+    if !signature_valid {
+        AuditEntry::failure(AuditAction::UserInvitationAccepted, "invalid_signature")
+            .record(&state, &req)
+            .await;
+        return Err(invalid_link().into());
+    }
+
+    let params = web::Query::<HashMap<String, String>>::from_query(query)
+        .map_err(|_| invalid_link())?
+        .into_inner();
+    let invitation_id = params
+        .get("invitation_id")
+        .and_then(|id| parse_id(id).ok())
+        .ok_or_else(invalid_link)?;
+    let nonce = params.get("nonce").ok_or_else(invalid_link)?;
+
+    let store = invitation_store(&state)?;
+    let invitation = store
+        .find_by_id(&invitation_id)
+        .await?
+        .filter(|i| i.is_pending() && i.nonce_hash == hash_sha256(nonce))
+        .ok_or_else(invalid_link)?;
+
+    if accept_req.password != accept_req.confirm_password {
+        return Err(AuthError::password_mismatch().into());
+    }
+    validate_strength(&accept_req.password).map_err(AuthError::weak_password)?;
+
+    let password_hash = Hash::argon2(&accept_req.password)
+        .map_err(|e| AuthError::internal_error(&e.to_string()))?
+        .to_string();
+
+    state.users.update_password(&invitation.user_id, &password_hash).await?;
+    state.users.set_verified(&invitation.user_id, true).await?;
+    state.users.set_active(&invitation.user_id, true).await?;
+
+    let orgs = org_store(&state)?;
+    if orgs.find_membership(&invitation.org_id, &invitation.user_id).await?.is_none() {
+        orgs.add_member(CreateMembership {
+            org_id:  invitation.org_id.clone(),
+            user_id: invitation.user_id.clone(),
+            role:    invitation.role,
+        }).await?;
+    }
+    state.users.add_to_org(&invitation.user_id, &invitation.org_id).await?;
+    store.mark_accepted(&invitation.id).await?;
+    AuditEntry::success(AuditAction::UserInvitationAccepted)
+        .user(&invitation.user_id)
+        .org(&invitation.org_id)
+        .record(&state, &req)
+        .await;
+
+    let user = state
+        .users
+        .find_by_id(&invitation.user_id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("User not found"))?;
+
+    let response_data = issue_session(&state, &user, AMR_PASSWORD, &req).await?;
+    let response = ApiResponse::success_data("Invitation accepted", response_data);
+
+    Ok(HttpResponse::Ok().json(response))
+}
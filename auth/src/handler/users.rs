@@ -1,34 +1,263 @@
 //! Users handler
 
-use actix_web::{Error, HttpResponse, web};
+use std::collections::HashMap;
 
+use actix_web::http::header;
+use actix_web::{Error, HttpRequest, HttpResponse, web};
+
+use crate::handler::admin::require_admin;
+use crate::handler::organizations::current_user_id;
+use crate::models::audit::AuditAction;
+use crate::models::user::{User, UserFilter};
+use crate::routes::AppState;
+use crate::service::account::{self, DELETION_GRACE_DAYS};
+use crate::utils::audit::AuditEntry;
+use crate::utils::errors::AuthError;
+use crate::utils::types::{AccountDeletionResponse, PaginationQuery, UpdateUserRequest, UserPublic};
+
+use database::utils::parse_id;
+use middleware::jwt::JwtClaims;
+use middleware::step_up::RecentAuth;
+use middleware::tracing::warn;
+use utils::email_templates::{EmailTemplateConfig, account_deletion};
+use utils::response::{ApiResponse, CursorMeta, ResponseMeta};
+
+/// Get a user - your own account, or any account for admins
 pub async fn get_user(
-    _state: web::Data<crate::routes::AppState>,
-    _user_id: web::Path<String>,
+    state:   web::Data<AppState>,
+    user_id: web::Path<String>,
+    req:     HttpRequest,
 ) -> Result<HttpResponse, Error> {
-    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Get user" })))
+    let current_id = current_user_id(&req, &state).await?;
+    if current_id.to_string() != *user_id {
+        require_admin(&req, &state).await?;
+    }
+
+    let id = parse_id(&user_id).map_err(|_| AuthError::not_found("User not found"))?;
+    let user = state
+        .users
+        .find_by_id(&id)
+        .await?
+        .filter(|u| u.deleted_at.is_none())
+        .ok_or_else(|| AuthError::not_found("User not found"))?;
+
+    let response = ApiResponse::success_data("User retrieved", UserPublic::from(&user));
+
+    Ok(HttpResponse::Ok().json(response))
 }
 
+/// Update the signed in user's name and profile
 pub async fn update_user(
-    _state: web::Data<crate::routes::AppState>,
-    _user_id: web::Path<String>,
-    _update_req: web::Json<serde_json::Value>,
+    state:      web::Data<AppState>,
+    user_id:    web::Path<String>,
+    update_req: web::Json<UpdateUserRequest>,
+    req:        HttpRequest,
 ) -> Result<HttpResponse, Error> {
-    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Update user" })))
+    let user = account_owner(&req, &state, &user_id).await?;
+    let user = state
+        .user_service()
+        .update_profile(&user.id, update_req.into_inner())
+        .await?;
+
+    let response = ApiResponse::success_data("User updated", UserPublic::from(&user));
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Resolve the signed in user, who may only manage their own account
+async fn account_owner(
+    req:     &HttpRequest,
+    state:   &web::Data<AppState>,
+    user_id: &str,
+) -> Result<User, AuthError> {
+    let current_id = current_user_id(req, state).await?;
+
+    if current_id.to_string() != user_id {
+        return Err(AuthError::forbidden("You can only manage your own account"));
+    }
+
+    state
+        .users
+        .find_by_id(&current_id)
+        .await?
+        .filter(|u| u.deleted_at.is_none())
+        .ok_or_else(|| AuthError::not_found("User not found"))
 }
 
+/// Request account deletion - purged after the grace period
 pub async fn delete_user(
-    _state: web::Data<crate::routes::AppState>,
-    _user_id: web::Path<String>,
+    state:   web::Data<AppState>,
+    user_id: web::Path<String>,
+    _recent: RecentAuth,
+    req:     HttpRequest,
 ) -> Result<HttpResponse, Error> {
-    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "Delete user" })))
+    if req.is_impersonated() {
+        return Err(AuthError::forbidden("Not allowed while impersonating a user").into());
+    }
+
+    let user = account_owner(&req, &state, &user_id).await?;
+    let due_at = account::schedule_deletion(&state, &user).await?;
+    AuditEntry::success(AuditAction::AccountDeletionScheduled)
+        .user(&user.id)
+        .record(&state, &req)
+        .await;
+
+    if let Some(to) = user.email.as_deref() {
+        let cancel_link = format!("{}/account/deletion", state.frontend_url.trim_end_matches('/'));
+        let template_config = EmailTemplateConfig::new(&state.app_name, &state.frontend_url);
+        let mut email = account_deletion::build(&template_config, DELETION_GRACE_DAYS, &cancel_link);
+        email.to = to.to_string();
+        email.from = state.email_from.clone();
+
+        let _email_result = state.email.send(&email).await;
+    }
+
+    let response = ApiResponse::success_data(
+        "Account scheduled for deletion",
+        AccountDeletionResponse { deletion_scheduled_at: due_at },
+    );
+
+    Ok(HttpResponse::Ok().json(response))
 }
 
+/// Cancel a scheduled account deletion
+pub async fn cancel_user_deletion(
+    state:   web::Data<AppState>,
+    user_id: web::Path<String>,
+    req:     HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user = account_owner(&req, &state, &user_id).await?;
+    account::cancel_deletion(&state, &user).await?;
+    AuditEntry::success(AuditAction::AccountDeletionCancelled)
+        .user(&user.id)
+        .record(&state, &req)
+        .await;
+
+    let response = ApiResponse::<()>::ok("Account deletion cancelled");
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Download everything held about the signed in user as JSON
+pub async fn export_user_data(
+    state:   web::Data<AppState>,
+    user_id: web::Path<String>,
+    req:     HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user = account_owner(&req, &state, &user_id).await?;
+    let export = account::export_user_data(&state, &user).await?;
+    AuditEntry::success(AuditAction::DataExported).user(&user.id).record(&state, &req).await;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"account-{}.json\"", user.id),
+        ))
+        .json(export))
+}
+
+/// Purge accounts whose deletion grace period has passed
+pub async fn purge_deleted_users(
+    state: web::Data<AppState>,
+    req:   HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let admin = require_admin(&req, &state).await?;
+    let report = account::purge_due(&state).await?;
+
+    for user_id in &report.purged {
+        AuditEntry::success(AuditAction::AccountPurged)
+            .actor(&admin.id)
+            .target(user_id)
+            .record(&state, &req)
+            .await;
+    }
+    for (user_id, error) in &report.failed {
+        warn!("Failed to purge user {}: {}", user_id, error);
+        AuditEntry::failure(AuditAction::AccountPurged, &error.to_string())
+            .actor(&admin.id)
+            .target(user_id)
+            .record(&state, &req)
+            .await;
+    }
+
+    let failed: Vec<String> = report.failed.iter().map(|(user_id, _)| user_id.to_string()).collect();
+    let response = ApiResponse::success_data(
+        "Scheduled deletions processed",
+        serde_json::json!({ "purged": report.purged.len(), "failed": failed }),
+    );
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Build a user list filter from query parameters
+///
+/// `is_active` and `is_verified` take `true`/`false`; `profile.<field>` filters
+/// on a queryable profile field.
+fn user_filter(state: &AppState, params: &HashMap<String, String>) -> Result<UserFilter, AuthError> {
+    let parse_bool = |name: &str| -> Result<Option<bool>, AuthError> {
+        params
+            .get(name)
+            .map(|v| {
+                v.parse::<bool>()
+                    .map_err(|_| AuthError::invalid_request(&format!("{} must be true or false", name)))
+            })
+            .transpose()
+    };
+
+    let mut filter = UserFilter {
+        is_active: parse_bool("is_active")?,
+        is_verified: parse_bool("is_verified")?,
+        ..Default::default()
+    };
+
+    for (key, raw) in params {
+        if let Some(name) = key.strip_prefix("profile.") {
+            let value = state.profile_schema.parse_filter(name, raw)?;
+            filter.profile.insert(name.to_string(), value);
+        }
+    }
+
+    Ok(filter)
+}
+
+/// List users (admins only)
 pub async fn list_users(
-    _state: web::Data<crate::routes::AppState>,
-    _query: web::Query<serde_json::Value>,
+    state: web::Data<AppState>,
+    query: web::Query<HashMap<String, String>>,
+    req:   HttpRequest,
 ) -> Result<HttpResponse, Error> {
-    Ok(HttpResponse::Ok().json(serde_json::json!({ "message": "List users" })))
+    require_admin(&req, &state).await?;
+
+    let pagination = PaginationQuery {
+        page:     query.get("page").and_then(|v| v.parse().ok()),
+        per_page: query.get("per_page").and_then(|v| v.parse().ok()),
+        cursor:   query.get("cursor").cloned(),
+    };
+    let filter = user_filter(&state, &query)?;
+
+    let cursors = state.cursors();
+    if let Some(request) = pagination.page_request(&cursors)? {
+        let page = state.users.list_after(&filter, &request).await?;
+        let next = page.next.as_ref().map(|cursor| cursors.encode(cursor));
+
+        let users: Vec<UserPublic> = page.items.iter().map(UserPublic::from).collect();
+        let response = ApiResponse::success_data("Users retrieved", users)
+            .with_cursor(CursorMeta::new(pagination.per_page(), next));
+
+        return Ok(HttpResponse::Ok().json(response));
+    }
+
+    let users = state
+        .users
+        .list(&filter, pagination.offset_page(), pagination.per_page())
+        .await?;
+    let total = state.users.count(&filter).await?;
+
+    let users: Vec<UserPublic> = users.iter().map(UserPublic::from).collect();
+    let response = ApiResponse::success_data("Users retrieved", users)
+        .with_meta(ResponseMeta::new(pagination.page(), pagination.per_page(), total));
+
+    Ok(HttpResponse::Ok().json(response))
 }
 
 pub async fn deactivate_user(
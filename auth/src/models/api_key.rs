@@ -0,0 +1,65 @@
+//! API key models
+//!
+//! API keys give machine-to-machine callers (cron jobs, partner integrations,
+//! service accounts) their own credential instead of a borrowed user JWT.
+//! Only the SHA-256 hash of a key is stored; the raw key is shown once on creation.
+
+use database::utils::DbId;
+use serde::{Deserialize, Serialize};
+use middleware::api_key::WILDCARD_SCOPE;
+
+/// Scope needed to read the full audit log
+pub const AUDIT_READ_SCOPE: &str = "audit:read";
+/// Scope needed to apply the retention policy
+pub const AUDIT_ADMIN_SCOPE: &str = "audit:admin";
+
+/// Scopes a key can be created with - one per group of routes that checks it
+pub const API_KEY_SCOPES: &[&str] = &[WILDCARD_SCOPE, AUDIT_READ_SCOPE, AUDIT_ADMIN_SCOPE];
+
+/// Whether only platform admins may grant the scope
+///
+/// Every scope so far reaches operator data, but user-level scopes added to
+/// `API_KEY_SCOPES` need not be.
+pub fn is_admin_scope(scope: &str) -> bool {
+    scope == WILDCARD_SCOPE || scope.starts_with("audit:")
+}
+
+/// API key model - stored in database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: DbId,
+    pub user_id: DbId,            // Principal the key acts as
+    pub name: String,
+    pub key_prefix: String,       // Public part of the key, e.g. "ak_1a2b3c4d"
+    pub key_hash: String,         // SHA-256 of the full key
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: Option<u32>,
+    pub expires_at: Option<i64>,  // None = never expires
+    pub last_used_at: Option<i64>,
+    pub last_used_ip: Option<String>,
+    pub is_revoked: bool,
+    pub created_at: i64,
+}
+
+impl ApiKey {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| chrono::Utc::now().timestamp() > expires_at)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        !self.is_expired() && !self.is_revoked
+    }
+}
+
+/// Create API key input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateApiKey {
+    pub user_id: DbId,
+    pub name: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: Option<u32>,
+    pub expires_in: Option<i64>, // Seconds until expiry
+}
@@ -0,0 +1,126 @@
+//! Audit event model
+
+use database::pagination::Cursor;
+use database::utils::DbId;
+use serde::{Deserialize, Serialize};
+
+/// Days audit events are kept unless configured otherwise
+pub const DEFAULT_AUDIT_RETENTION_DAYS: i64 = 365;
+
+/// Security-relevant action
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Login,
+    LoginChallenged,
+    Logout,
+    TokenRefresh,
+    Signup,
+    EmailVerified,
+    PasswordResetRequested,
+    PasswordReset,
+    PasswordChanged,
+    #[serde(rename = "oauth_login")]
+    OAuthLogin,
+    #[serde(rename = "oauth_linked")]
+    OAuthLinked,
+    #[serde(rename = "oauth_unlinked")]
+    OAuthUnlinked,
+    PasskeyRegistered,
+    PasskeyLogin,
+    PasskeyRemoved,
+    ApiKeyCreated,
+    ApiKeyRevoked,
+    ContactChangeRequested,
+    ContactChanged,
+    ContactChangeReverted,
+    OrgCreated,
+    OrgSwitched,
+    OrgMemberUpdated,
+    OrgMemberRemoved,
+    OrgInvitationSent,
+    OrgInvitationRevoked,
+    OrgInvitationAccepted,
+    UserInvited,
+    UserInvitationRevoked,
+    UserInvitationAccepted,
+    AccountDeletionScheduled,
+    AccountDeletionCancelled,
+    AccountPurged,
+    DataExported,
+    Impersonation,
+    Reauthenticated,
+}
+
+/// Whether the attempted action went through
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+/// Audit event - append-only record of an auth action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub id: DbId,
+    pub action: AuditAction,
+    pub outcome: AuditOutcome,
+    pub actor_id: Option<DbId>,  // Who performed the action
+    pub target_id: Option<DbId>, // Who or what it was performed on
+    pub impersonator_id: Option<DbId>, // Admin acting through an impersonation token
+    pub org_id: Option<DbId>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub reason: Option<String>, // Failure reason or extra context
+    pub created_at: i64,
+}
+
+impl AuditEvent {
+    /// Position of this event in list order
+    pub fn cursor(&self) -> Cursor {
+        Cursor::new(self.created_at, &self.id)
+    }
+}
+
+/// Create audit event input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAuditEvent {
+    pub action: AuditAction,
+    pub outcome: AuditOutcome,
+    pub actor_id: Option<DbId>,
+    pub target_id: Option<DbId>,
+    pub impersonator_id: Option<DbId>,
+    pub org_id: Option<DbId>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// Audit event filter - unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub actor_id: Option<DbId>,
+    pub target_id: Option<DbId>,
+    pub org_id: Option<DbId>,
+    pub action: Option<AuditAction>,
+    pub outcome: Option<AuditOutcome>,
+    pub reason: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_names() {
+        let names: Vec<String> = [AuditAction::Login, AuditAction::OAuthLinked, AuditAction::ApiKeyCreated]
+            .iter()
+            .map(|a| serde_json::to_string(a).unwrap())
+            .collect();
+
+        assert_eq!(names, ["\"login\"", "\"oauth_linked\"", "\"api_key_created\""]);
+    }
+}
@@ -0,0 +1,56 @@
+//! Contact change models
+//!
+//! A pending change of a user's email or phone. The user's contact is only
+//! replaced once the code sent to the new address is confirmed.
+
+use database::utils::DbId;
+use serde::{Deserialize, Serialize};
+
+use super::verification::VerificationMedium;
+
+/// Contact change request - stored while the new address is unconfirmed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactChangeModel {
+    pub id: DbId,
+    pub user_id: DbId,
+    pub medium: VerificationMedium,
+    pub old_value: Option<String>,
+    pub new_value: String,
+    pub code_hash: String,                 // SHA-256 of the code sent to the new address
+    pub revert_token_hash: Option<String>, // SHA-256 of the token sent to the old address
+    pub attempts: i32,
+    pub created_at: i64,
+    pub expires_at: i64,        // Confirmation deadline
+    pub revert_expires_at: i64, // Revert deadline, also after confirmation
+    pub confirmed_at: Option<i64>,
+    pub reverted_at: Option<i64>,
+}
+
+impl ContactChangeModel {
+    pub fn is_expired(&self) -> bool {
+        chrono::Utc::now().timestamp() > self.expires_at
+    }
+
+    /// Waiting for the code from the new address
+    pub fn is_pending(&self) -> bool {
+        self.confirmed_at.is_none() && self.reverted_at.is_none() && !self.is_expired()
+    }
+
+    /// Can still be undone from the old address
+    pub fn can_revert(&self) -> bool {
+        self.reverted_at.is_none() && chrono::Utc::now().timestamp() <= self.revert_expires_at
+    }
+}
+
+/// Create contact change input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateContactChange {
+    pub user_id: DbId,
+    pub medium: VerificationMedium,
+    pub old_value: Option<String>,
+    pub new_value: String,
+    pub code_hash: String,
+    pub revert_token_hash: Option<String>,
+    pub expires_in: i64,
+    pub revert_expires_in: i64,
+}
@@ -1,9 +1,16 @@
 //! Authentication models
 
+pub mod api_key;
+pub mod audit;
+pub mod contact_change;
 pub mod magic_link;
 pub mod oauth;
+pub mod organization;
+pub mod passkey;
+pub mod profile;
 pub mod reset_password;
 pub mod session;
 pub mod two_factor;
 pub mod user;
+pub mod user_invitation;
 pub mod verification;
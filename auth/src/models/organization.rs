@@ -0,0 +1,101 @@
+//! Organization (tenant) models
+
+use database::utils::DbId;
+use serde::{Deserialize, Serialize};
+
+/// Organization - a tenant sharing the deployment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Organization {
+    pub id: DbId,
+    pub name: String,
+    pub slug: String,
+    pub owner_id: DbId,
+    pub created_at: i64,
+    pub updated_at: Option<i64>,
+}
+
+/// Create organization input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateOrganization {
+    pub name: String,
+    pub slug: String,
+    pub owner_id: DbId,
+}
+
+/// Role of a member within an organization
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum OrgRole {
+    Member,
+    Admin,
+    Owner,
+}
+
+impl OrgRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrgRole::Member => "member",
+            OrgRole::Admin => "admin",
+            OrgRole::Owner => "owner",
+        }
+    }
+
+    /// Admins and owners can invite, remove and change roles of members
+    pub fn can_manage_members(&self) -> bool {
+        *self >= OrgRole::Admin
+    }
+}
+
+/// Membership - links a user to an organization with a role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Membership {
+    pub id: DbId,
+    pub org_id: DbId,
+    pub user_id: DbId,
+    pub role: OrgRole,
+    pub created_at: i64,
+    pub updated_at: Option<i64>,
+}
+
+/// Create membership input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMembership {
+    pub org_id: DbId,
+    pub user_id: DbId,
+    pub role: OrgRole,
+}
+
+/// Organization invitation - sent by email, accepted by the invitee
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrgInvitation {
+    pub id: DbId,
+    pub org_id: DbId,
+    pub email: String,
+    pub role: OrgRole,
+    pub token_hash: String, // SHA-256 of the token sent by email
+    pub invited_by: DbId,
+    pub expires_at: i64,
+    pub accepted_at: Option<i64>,
+    pub created_at: i64,
+}
+
+impl OrgInvitation {
+    pub fn is_expired(&self) -> bool {
+        chrono::Utc::now().timestamp() > self.expires_at
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.accepted_at.is_none() && !self.is_expired()
+    }
+}
+
+/// Create invitation input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateOrgInvitation {
+    pub org_id: DbId,
+    pub email: String,
+    pub role: OrgRole,
+    pub token_hash: String,
+    pub invited_by: DbId,
+    pub expires_in: i64, // Usually 7 days
+}
@@ -0,0 +1,202 @@
+//! Passkey (WebAuthn) models
+
+use database::utils::DbId;
+use serde::{Deserialize, Serialize};
+
+/// Passkey credential - a WebAuthn public key registered to a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasskeyCredential {
+    pub id: DbId,
+    pub user_id: DbId,
+    pub credential_id: String, // base64url credential id from the authenticator
+    pub public_key: String,    // base64url SEC1 encoded P-256 public key
+    pub sign_count: u32,
+    pub aaguid: Option<String>,
+    pub name: Option<String>,
+    pub transports: Vec<String>,
+    pub created_at: i64,
+    pub last_used_at: Option<i64>,
+}
+
+/// Create passkey credential input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePasskeyCredential {
+    pub user_id: DbId,
+    pub credential_id: String,
+    pub public_key: String,
+    pub sign_count: u32,
+    pub aaguid: Option<String>,
+    pub name: Option<String>,
+    pub transports: Vec<String>,
+}
+
+/// WebAuthn ceremony type
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebAuthnCeremony {
+    Registration,
+    Authentication,
+    TwoFactor,
+}
+
+/// WebAuthn challenge - issued with ceremony options, consumed on verification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebAuthnChallenge {
+    pub id: DbId,
+    pub user_id: Option<DbId>, // None for usernameless login
+    pub challenge: String,     // base64url random bytes
+    pub ceremony: WebAuthnCeremony,
+    pub expires_at: i64,
+    pub created_at: i64,
+}
+
+impl WebAuthnChallenge {
+    pub fn is_expired(&self) -> bool {
+        chrono::Utc::now().timestamp() > self.expires_at
+    }
+}
+
+/// Create WebAuthn challenge input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWebAuthnChallenge {
+    pub user_id: Option<DbId>,
+    pub challenge: String,
+    pub ceremony: WebAuthnCeremony,
+    pub expires_in: i64, // Usually 5 minutes
+}
+
+// ============================================
+// Ceremony options (sent to the browser)
+// ============================================
+
+/// Relying party entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelyingParty {
+    pub id: String,
+    pub name: String,
+}
+
+/// User entity for registration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyUserEntity {
+    pub id: String, // base64url user handle
+    pub name: String,
+    pub display_name: String,
+}
+
+/// Supported public key algorithm
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicKeyCredentialParameters {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub alg: i64,
+}
+
+/// Credential descriptor (used for allow/exclude lists)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicKeyCredentialDescriptor {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transports: Vec<String>,
+}
+
+/// Authenticator selection criteria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorSelection {
+    pub resident_key: String,
+    pub user_verification: String,
+}
+
+/// Registration options (`navigator.credentials.create`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyRegistrationOptions {
+    pub rp: RelyingParty,
+    pub user: PasskeyUserEntity,
+    pub challenge: String,
+    pub pub_key_cred_params: Vec<PublicKeyCredentialParameters>,
+    pub timeout: u64,
+    pub attestation: String,
+    pub authenticator_selection: AuthenticatorSelection,
+    pub exclude_credentials: Vec<PublicKeyCredentialDescriptor>,
+}
+
+/// Authentication options (`navigator.credentials.get`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyAuthenticationOptions {
+    pub challenge: String,
+    pub timeout: u64,
+    pub rp_id: String,
+    pub allow_credentials: Vec<PublicKeyCredentialDescriptor>,
+    pub user_verification: String,
+}
+
+// ============================================
+// Ceremony results (sent back by the browser)
+// ============================================
+
+/// Attestation response from the authenticator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponse {
+    pub client_data_json: String,   // base64url
+    pub attestation_object: String, // base64url CBOR
+    #[serde(default)]
+    pub transports: Vec<String>,
+}
+
+/// Registration credential (`PublicKeyCredential` with attestation)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationCredential {
+    pub id: String,
+    pub response: AttestationResponse,
+}
+
+/// Assertion response from the authenticator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResponse {
+    pub client_data_json: String,   // base64url
+    pub authenticator_data: String, // base64url
+    pub signature: String,          // base64url DER ECDSA signature
+    pub user_handle: Option<String>,
+}
+
+/// Authentication credential (`PublicKeyCredential` with assertion)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticationCredential {
+    pub id: String,
+    pub response: AssertionResponse,
+}
+
+/// Start passkey registration request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasskeyRegisterRequest {
+    pub name: Option<String>,
+}
+
+/// Finish passkey registration request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasskeyRegisterVerifyRequest {
+    pub name: Option<String>,
+    pub credential: RegistrationCredential,
+}
+
+/// Start passkey login request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasskeyLoginRequest {
+    pub identifier: Option<String>, // None for usernameless (discoverable) login
+}
+
+/// Finish passkey login request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasskeyLoginVerifyRequest {
+    pub credential: AuthenticationCredential,
+}
@@ -0,0 +1,322 @@
+//! User profile models
+//!
+//! Products keep their own profile data (avatar, locale, ...) in the user's
+//! `profile` document. Which fields exist, their types and which can be used
+//! in user list filters is declared in a `ProfileSchema`, loaded from config.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::utils::errors::{AuthError, AuthResult};
+
+/// Profile document - field name to value
+pub type Profile = serde_json::Map<String, Value>;
+
+const DEFAULT_MAX_LENGTH: usize = 255;
+
+/// Type of a profile field
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileFieldType {
+    String,
+    Url,
+    Email,
+    Locale,   // BCP 47 tag, e.g. "en-GB"
+    Timezone, // IANA name, e.g. "Africa/Nairobi"
+    Date,     // "YYYY-MM-DD"
+    Boolean,
+    Integer,
+}
+
+/// One declared profile field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileField {
+    pub name:       String,
+    #[serde(rename = "type")]
+    pub field_type: ProfileFieldType,
+    /// Allowed values, for string fields
+    #[serde(default)]
+    pub options:    Vec<String>,
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    /// Whether user lists can be filtered on this field
+    #[serde(default)]
+    pub queryable:  bool,
+}
+
+impl ProfileField {
+    pub fn new(name: &str, field_type: ProfileFieldType) -> Self {
+        Self {
+            name: name.to_string(),
+            field_type,
+            options: Vec::new(),
+            max_length: None,
+            queryable: false,
+        }
+    }
+
+    pub fn queryable(mut self) -> Self {
+        self.queryable = true;
+        self
+    }
+
+    fn check_string(&self, value: &Value) -> Result<String, String> {
+        let text = value.as_str().ok_or("expected a string")?.trim();
+        let max_length = self.max_length.unwrap_or(DEFAULT_MAX_LENGTH);
+
+        if text.is_empty() {
+            return Err("must not be empty".to_string());
+        }
+        if text.chars().count() > max_length {
+            return Err(format!("must be at most {} characters", max_length));
+        }
+        if !self.options.is_empty() && !self.options.iter().any(|o| o == text) {
+            return Err(format!("must be one of {}", self.options.join(", ")));
+        }
+
+        Ok(text.to_string())
+    }
+
+    /// Check a value and return it normalized
+    pub fn validate(&self, value: &Value) -> Result<Value, String> {
+        match self.field_type {
+            ProfileFieldType::Boolean => value
+                .as_bool()
+                .map(Value::Bool)
+                .ok_or_else(|| "expected true or false".to_string()),
+            ProfileFieldType::Integer => value
+                .as_i64()
+                .map(Value::from)
+                .ok_or_else(|| "expected an integer".to_string()),
+            ProfileFieldType::String => self.check_string(value).map(Value::String),
+            ProfileFieldType::Url => {
+                let url = self.check_string(value)?;
+                let valid = (url.starts_with("https://") || url.starts_with("http://"))
+                    && !url.contains(char::is_whitespace);
+                if valid { Ok(Value::String(url)) } else { Err("expected an http(s) URL".to_string()) }
+            }
+            ProfileFieldType::Email => {
+                let email = self.check_string(value)?.to_lowercase();
+                let valid = email
+                    .split_once('@')
+                    .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'));
+                if valid { Ok(Value::String(email)) } else { Err("expected an email address".to_string()) }
+            }
+            ProfileFieldType::Locale => {
+                let locale = self.check_string(value)?;
+                let mut parts = locale.split('-');
+                let language = parts.next().unwrap_or_default();
+                let valid = (2..=3).contains(&language.len())
+                    && language.chars().all(|c| c.is_ascii_alphabetic())
+                    && parts.all(|p| {
+                        (2..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric())
+                    });
+                if valid { Ok(Value::String(locale)) } else { Err("expected a locale like en-GB".to_string()) }
+            }
+            ProfileFieldType::Timezone => {
+                let zone = self.check_string(value)?;
+                let valid = zone == "UTC"
+                    || (zone.contains('/')
+                        && zone.split('/').all(|p| {
+                            !p.is_empty()
+                                && p.chars().all(|c| c.is_ascii_alphanumeric() || "_+-".contains(c))
+                        }));
+                if valid { Ok(Value::String(zone)) } else { Err("expected a timezone like Africa/Nairobi".to_string()) }
+            }
+            ProfileFieldType::Date => {
+                let date = self.check_string(value)?;
+                chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                    .map(|_| Value::String(date))
+                    .map_err(|_| "expected a date as YYYY-MM-DD".to_string())
+            }
+        }
+    }
+
+    /// Parse a filter value given as text (query strings)
+    pub fn parse_filter(&self, raw: &str) -> Result<Value, String> {
+        let value = match self.field_type {
+            ProfileFieldType::Boolean => match raw {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => return Err("expected true or false".to_string()),
+            },
+            ProfileFieldType::Integer => raw
+                .parse::<i64>()
+                .map(Value::from)
+                .map_err(|_| "expected an integer".to_string())?,
+            _ => Value::String(raw.to_string()),
+        };
+
+        self.validate(&value)
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && name.len() <= 64
+}
+
+/// Declared profile fields
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileSchema {
+    pub fields: Vec<ProfileField>,
+}
+
+impl ProfileSchema {
+    /// Common fields, used when no schema is configured
+    pub fn standard() -> Self {
+        Self {
+            fields: vec![
+                ProfileField::new("avatar_url", ProfileFieldType::Url),
+                ProfileField::new("locale", ProfileFieldType::Locale).queryable(),
+                ProfileField::new("timezone", ProfileFieldType::Timezone).queryable(),
+                ProfileField::new("date_of_birth", ProfileFieldType::Date),
+                ProfileField::new("marketing_consent", ProfileFieldType::Boolean).queryable(),
+            ],
+        }
+    }
+
+    /// Parse a schema from JSON and check it
+    pub fn from_json(json: &str) -> AuthResult<Self> {
+        let schema: Self = serde_json::from_str(json)
+            .map_err(|e| AuthError::internal_error(&format!("Invalid profile schema: {}", e)))?;
+        schema.check()?;
+
+        Ok(schema)
+    }
+
+    /// Field names must be unique snake_case identifiers (they become document keys)
+    pub fn check(&self) -> AuthResult<()> {
+        for (index, field) in self.fields.iter().enumerate() {
+            if !is_valid_name(&field.name) {
+                return Err(AuthError::internal_error(&format!(
+                    "Invalid profile field name: {}",
+                    field.name
+                )));
+            }
+            if self.fields[..index].iter().any(|f| f.name == field.name) {
+                return Err(AuthError::internal_error(&format!(
+                    "Duplicate profile field: {}",
+                    field.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn field(&self, name: &str) -> Option<&ProfileField> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Validate profile changes - `null` removes a field
+    ///
+    /// Returns the normalized changes.
+    pub fn validate_changes(&self, changes: &Profile) -> AuthResult<Profile> {
+        changes
+            .iter()
+            .map(|(name, value)| {
+                let field = self.field(name).ok_or_else(|| {
+                    AuthError::invalid_request(&format!("Unknown profile field: {}", name))
+                })?;
+
+                let value = match value {
+                    Value::Null => Value::Null,
+                    value => field.validate(value).map_err(|e| {
+                        AuthError::invalid_request(&format!("Invalid profile field {}: {}", name, e))
+                    })?,
+                };
+
+                Ok((name.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Parse a list filter on a queryable field
+    pub fn parse_filter(&self, name: &str, raw: &str) -> AuthResult<Value> {
+        let field = self
+            .field(name)
+            .filter(|f| f.queryable)
+            .ok_or_else(|| {
+                AuthError::invalid_request(&format!("Can't filter on profile field: {}", name))
+            })?;
+
+        field.parse_filter(raw).map_err(|e| {
+            AuthError::invalid_request(&format!("Invalid filter for profile field {}: {}", name, e))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn changes(value: Value) -> Profile {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn test_validate_changes() {
+        let schema = ProfileSchema::standard();
+        let normalized = schema
+            .validate_changes(&changes(json!({
+                "avatar_url": " https://cdn.example.com/a.png ",
+                "locale": "en-GB",
+                "timezone": "Africa/Nairobi",
+                "marketing_consent": true,
+                "date_of_birth": null,
+            })))
+            .unwrap();
+
+        assert_eq!(normalized["avatar_url"], "https://cdn.example.com/a.png");
+        assert_eq!(normalized["date_of_birth"], Value::Null);
+    }
+
+    #[test]
+    fn test_rejects_unknown_field() {
+        let schema = ProfileSchema::standard();
+        assert!(schema.validate_changes(&changes(json!({ "avatar": "x" }))).is_err());
+    }
+
+    #[test]
+    fn test_rejects_bad_values() {
+        let schema = ProfileSchema::standard();
+
+        for bad in [
+            json!({ "avatar_url": "javascript:alert(1)" }),
+            json!({ "locale": "english" }),
+            json!({ "timezone": "Nairobi" }),
+            json!({ "date_of_birth": "01/04/1990" }),
+            json!({ "marketing_consent": "yes" }),
+        ] {
+            assert!(schema.validate_changes(&changes(bad.clone())).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        let schema = ProfileSchema::standard();
+
+        assert_eq!(schema.parse_filter("marketing_consent", "true").unwrap(), Value::Bool(true));
+        assert_eq!(schema.parse_filter("locale", "sw-KE").unwrap(), "sw-KE");
+        // Declared but not queryable
+        assert!(schema.parse_filter("date_of_birth", "1990-04-01").is_err());
+    }
+
+    #[test]
+    fn test_schema_from_json() {
+        let schema = ProfileSchema::from_json(
+            r#"{ "fields": [{ "name": "plan", "type": "string", "options": ["free", "pro"], "queryable": true }] }"#,
+        )
+        .unwrap();
+        assert!(schema.parse_filter("plan", "pro").is_ok());
+        assert!(schema.parse_filter("plan", "gold").is_err());
+
+        let duplicate = r#"{ "fields": [{ "name": "a", "type": "string" }, { "name": "a", "type": "url" }] }"#;
+        assert!(ProfileSchema::from_json(duplicate).is_err());
+        assert!(ProfileSchema::from_json(r#"{ "fields": [{ "name": "$where", "type": "string" }] }"#).is_err());
+    }
+}
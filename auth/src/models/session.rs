@@ -13,6 +13,8 @@ pub struct SessionModel {
     pub device: Option<String>,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
+    #[serde(default)]
+    pub org_id: Option<DbId>, // Active organization the session was issued for
     pub created_at: i64,
     pub expires_at: i64,
     pub last_used_at: i64,
@@ -42,6 +44,7 @@ pub struct CreateSession {
     pub device: Option<String>,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
+    pub org_id: Option<DbId>,
     pub expires_in: i64,
 }
 
@@ -11,6 +11,7 @@ pub enum TwoFactorMethod {
     Sms,    // SMS verification codes
     Email,  // Email verification codes
     Backup, // Backup codes
+    Passkey, // WebAuthn passkey assertion
 }
 
 /// Two-factor authentication method - stores 2FA configuration for a user
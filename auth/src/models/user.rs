@@ -1,17 +1,24 @@
 //! User model
 use chrono::{DateTime, Utc, Duration};
-use database::utils::ObjectId;
+use database::pagination::Cursor;
+use database::utils::DbId;
 use serde::{Deserialize, Serialize};
 
+use crate::models::profile::Profile;
+
 /// User model - stored in database
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
-    pub id:             ObjectId,
+    pub id:             DbId,
     pub email:          Option<String>,
     pub password_hash:  String,
 
     pub phone:          Option<String>,
+    /// Normalized username, see `utils::usernames`
     pub username:       Option<String>,
+    /// Confusable skeleton of the username - unique index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username_key:   Option<String>,
     #[serde(rename =    "firstName")]
     pub first_name:     Option<String>,
      #[serde(rename =   "lastName")]
@@ -27,9 +34,33 @@ pub struct User {
     pub created_at:     DateTime<Utc>,
     pub updated_at:     Option<DateTime<Utc>,>,
     pub last_login:     Option<DateTime<Utc>,>,
+
+    /// Organizations the user belongs to (mirrors memberships for tenant filtering)
+    #[serde(default)]
+    pub org_ids:        Vec<DbId>,
+
+    /// When the account will be purged (deletion requested, grace period running)
+    #[serde(default)]
+    pub deletion_scheduled_at: Option<DateTime<Utc>>,
+    /// When the account was purged - the record is kept anonymized
+    #[serde(default)]
+    pub deleted_at:     Option<DateTime<Utc>>,
+
+    /// Platform admin (support staff) - granted out of band, never through the API
+    #[serde(default)]
+    pub is_admin:       bool,
+
+    /// Product-specific fields, validated against the configured `ProfileSchema`
+    #[serde(default)]
+    pub profile:        Profile,
 }
 
 impl User {
+    /// Position of this user in list order
+    pub fn cursor(&self) -> Cursor {
+        Cursor::new(self.created_at.timestamp(), &self.id)
+    }
+
     pub fn is_locked(&self) -> bool {
         if let Some(locked_until) = self.locked_until {
             return locked_until > chrono::Utc::now();
@@ -61,6 +92,14 @@ impl User {
         }
     }
 
+    pub fn is_deletion_scheduled(&self) -> bool {
+        self.deletion_scheduled_at.is_some()
+    }
+
+    pub fn is_member_of(&self, org_id: &DbId) -> bool {
+        self.org_ids.contains(org_id)
+    }
+
     pub fn reset_failed_attempts(&mut self) {
         self.login_attempts = 0;
         self.locked_until = None;
@@ -73,15 +112,29 @@ impl User {
 pub struct CreateUserInput {
     pub email:    Option<String>,
     pub phone:    Option<String>,
+    /// Already validated and normalized
+    pub username: Option<String>,
     pub password: String,
 }
 
 /// User update input
+///
+/// Email and phone are not updatable here - they change through the
+/// confirmed change-contact flow.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateUserInput {
-    pub email:      Option<String>,
-    pub phone:      Option<String>,
     pub username:   Option<String>,
     pub first_name: Option<String>,
     pub last_name:  Option<String>,
+    /// Validated profile changes - `null` removes a field
+    pub profile:    Option<Profile>,
+}
+
+/// User list filters, all optional - deleted users are never listed
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter {
+    pub is_active:   Option<bool>,
+    pub is_verified: Option<bool>,
+    /// Exact matches on queryable profile fields
+    pub profile:     Profile,
 }
@@ -0,0 +1,47 @@
+//! User invitation model
+//!
+//! An invitation onboards a new user without public registration. The invitee
+//! gets a signed link, sets a password and is verified in one step.
+
+use database::utils::DbId;
+use serde::{Deserialize, Serialize};
+
+use super::organization::OrgRole;
+
+/// User invitation - links a pending user to the organization that invited them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserInvitation {
+    pub id: DbId,
+    pub user_id: DbId, // Pending user created with the invitation
+    pub org_id: DbId,
+    pub email: String,
+    pub role: OrgRole,
+    pub nonce_hash: String, // SHA-256 of the nonce in the current link
+    pub invited_by: DbId,
+    pub expires_at: i64,
+    pub accepted_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: Option<i64>,
+}
+
+impl UserInvitation {
+    pub fn is_expired(&self) -> bool {
+        chrono::Utc::now().timestamp() > self.expires_at
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.accepted_at.is_none() && !self.is_expired()
+    }
+}
+
+/// Create user invitation input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateUserInvitation {
+    pub user_id: DbId,
+    pub org_id: DbId,
+    pub email: String,
+    pub role: OrgRole,
+    pub nonce_hash: String,
+    pub invited_by: DbId,
+    pub expires_in: i64, // Usually 7 days
+}
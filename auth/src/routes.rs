@@ -1,21 +1,48 @@
 //! Authentication routes
 
+use crate::config::audit::AuditConfig;
+use crate::config::identifiers::IdentifierConfig;
+use crate::config::risk::RiskConfig;
+use crate::config::webauthn::WebAuthnConfig;
+use crate::models::profile::ProfileSchema;
+use crate::service::api_key::ApiKeyService;
+use crate::service::risk::{DefaultRiskEvaluator, RiskEvaluator};
+use crate::service::user::UserService;
+use crate::store::api_key_store::ApiKeyStore;
+use crate::store::audit_store::AuditStore;
+use crate::store::contact_change_store::ContactChangeStore;
 use crate::store::oauth_account_store::OAuthAccountStore;
+use crate::store::organization_store::OrganizationStore;
+use crate::store::passkey_store::PasskeyStore;
 use crate::store::password_reset_store::PasswordResetStore;
 use crate::store::session_store::SessionStore;
+use crate::store::user_invitation_store::UserInvitationStore;
 use crate::store::user_store::UserStore;
 use crate::store::verification_store::VerificationStore;
 use actix_web::web;
 
+use database::pagination::CursorCodec;
+use database::transaction::Transactions;
+use middleware::api_key::ApiKeyValidator;
+use middleware::impersonation::DenyImpersonation;
 use middleware::jwt::JwtMiddleware;
+use middleware::step_up::RequireRecentAuth;
 use std::sync::Arc;
 use utils::email::EmailService;
+use utils::sms::SmsService;
 
 // Internal imports for store initialization
+use crate::store::database::MongoApiKeyStore;
+use crate::store::database::MongoAuditStore;
+use crate::store::database::MongoContactChangeStore;
 use crate::store::database::MongoPasswordResetStore;
 use crate::store::database::MongoSessionStore;
 use crate::store::database::MongoVerificationStore;
 use crate::store::database::MongoOAuthAccountStore;
+use crate::store::database::MongoPasskeyStore;
+use crate::store::database::MongoOrganizationStore;
+use crate::store::database::MongoUserInvitationStore;
+use crate::store::database::collections;
 
 /// Application state for authentication handlers
 pub struct AppState {
@@ -24,13 +51,27 @@ pub struct AppState {
     pub password_resets: Option<Arc<dyn PasswordResetStore>>,
     pub verifications:   Option<Arc<dyn VerificationStore>>,
     pub oauth_accounts:  Option<Arc<dyn OAuthAccountStore>>,
+    pub passkeys:        Option<Arc<dyn PasskeyStore>>,
+    pub webauthn:        Option<WebAuthnConfig>,
+    pub api_keys:        Option<Arc<dyn ApiKeyStore>>,
+    pub organizations:   Option<Arc<dyn OrganizationStore>>,
+    pub user_invitations: Option<Arc<dyn UserInvitationStore>>,
+    pub contact_changes: Option<Arc<dyn ContactChangeStore>>,
+    pub audit:           Option<Arc<dyn AuditStore>>,
+    pub audit_config:    AuditConfig,
+    pub risk:            Option<Arc<dyn RiskEvaluator>>,
+    pub profile_schema:  ProfileSchema,
+    pub identifiers:     IdentifierConfig,
     pub jwt_secret:      String,
     pub jwt_expiry_minutes: i64,
     pub refresh_token_expiry_days: i64,
     pub email:           Arc<EmailService>,
+    pub sms:             Option<Arc<SmsService>>,
     pub email_from:      String,
     pub app_name:        String,
     pub frontend_url:    String,
+    /// Units of work for multi-step flows; disabled unless the backend has them
+    pub transactions:    Transactions,
 }
 
 impl Clone for AppState {
@@ -41,13 +82,26 @@ impl Clone for AppState {
             password_resets: self.password_resets.clone(),
             verifications: self.verifications.clone(),
             oauth_accounts: self.oauth_accounts.clone(),
+            passkeys: self.passkeys.clone(),
+            webauthn: self.webauthn.clone(),
+            api_keys: self.api_keys.clone(),
+            organizations: self.organizations.clone(),
+            user_invitations: self.user_invitations.clone(),
+            contact_changes: self.contact_changes.clone(),
+            audit: self.audit.clone(),
+            audit_config: self.audit_config.clone(),
+            risk: self.risk.clone(),
+            profile_schema: self.profile_schema.clone(),
+            identifiers: self.identifiers.clone(),
             jwt_secret: self.jwt_secret.clone(),
             jwt_expiry_minutes: self.jwt_expiry_minutes,
             refresh_token_expiry_days: self.refresh_token_expiry_days,
             email: self.email.clone(),
+            sms: self.sms.clone(),
             email_from: self.email_from.clone(),
             app_name: self.app_name.clone(),
             frontend_url: self.frontend_url.clone(),
+            transactions: self.transactions.clone(),
         }
     }
 }
@@ -57,36 +111,105 @@ impl AppState {
         panic!("AppState must be initialized with store implementations")
     }
 
-    /// Create AppState with all required fields
+    /// Create AppState over the required stores and the optional ones given
+    ///
+    /// Configuration starts from defaults (no WebAuthn or risk evaluation,
+    /// the standard profile schema); set it with the `with_*` methods.
     pub fn with_jwt(
-        users:           Arc<dyn UserStore>,
-        sessions:        Arc<dyn SessionStore>,
-        password_resets: Option<Arc<dyn PasswordResetStore>>,
-        verifications:   Option<Arc<dyn VerificationStore>>,
-        oauth_accounts:  Option<Arc<dyn OAuthAccountStore>>,
-        jwt_secret:      String,
-        jwt_expiry_minutes: i64,
-        refresh_token_expiry_days: i64,
-        email:           Arc<EmailService>,
-        email_from:      String,
-        app_name:        String,
-        frontend_url:    String,
+        users:    Arc<dyn UserStore>,
+        sessions: Arc<dyn SessionStore>,
+        stores:   OptionalStores,
+        settings: AuthSettings,
     ) -> Self {
         Self {
             users,
             sessions,
-            password_resets,
-            verifications,
-            oauth_accounts,
-            jwt_secret,
-            jwt_expiry_minutes,
-            refresh_token_expiry_days,
-            email,
-            email_from,
-            app_name,
-            frontend_url,
+            password_resets: stores.password_resets,
+            verifications: stores.verifications,
+            oauth_accounts: stores.oauth_accounts,
+            passkeys: stores.passkeys,
+            webauthn: None,
+            api_keys: stores.api_keys,
+            organizations: stores.organizations,
+            user_invitations: stores.user_invitations,
+            contact_changes: stores.contact_changes,
+            audit: stores.audit,
+            audit_config: AuditConfig::default(),
+            risk: None,
+            profile_schema: ProfileSchema::standard(),
+            identifiers: IdentifierConfig::default(),
+            jwt_secret: settings.jwt_secret,
+            jwt_expiry_minutes: settings.jwt_expiry_minutes,
+            refresh_token_expiry_days: settings.refresh_token_expiry_days,
+            email: settings.email,
+            sms: settings.sms,
+            email_from: settings.email_from,
+            app_name: settings.app_name,
+            frontend_url: settings.frontend_url,
+            // Only the Mongo backend turns these on; see `init_sql`
+            transactions: Transactions::disabled(),
         }
     }
+
+    /// Enable passkeys with this relying party
+    pub fn with_webauthn(mut self, webauthn: Option<WebAuthnConfig>) -> Self {
+        self.webauthn = webauthn;
+        self
+    }
+
+    pub fn with_audit_config(mut self, audit_config: AuditConfig) -> Self {
+        self.audit_config = audit_config;
+        self
+    }
+
+    /// Score sign-ins with this evaluator
+    pub fn with_risk(mut self, risk: Arc<dyn RiskEvaluator>) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    pub fn with_profile_schema(mut self, profile_schema: ProfileSchema) -> Self {
+        self.profile_schema = profile_schema;
+        self
+    }
+
+    pub fn with_identifiers(mut self, identifiers: IdentifierConfig) -> Self {
+        self.identifiers = identifiers;
+        self
+    }
+
+    /// Run multi-step flows as units of work
+    pub fn with_transactions(mut self, transactions: Transactions) -> Self {
+        self.transactions = transactions;
+        self
+    }
+
+    /// User flows (signup, login, verification, profile) over this state's stores
+    pub fn user_service(&self) -> UserService {
+        let service = UserService::new(self.users.clone())
+            .with_identifiers(self.identifiers.clone())
+            .with_profile_schema(self.profile_schema.clone())
+            .with_transactions(self.transactions.clone());
+
+        match &self.verifications {
+            Some(verifications) => service.with_verifications(verifications.clone()),
+            None => service,
+        }
+    }
+
+    /// Signs list cursors with the JWT secret
+    pub fn cursors(&self) -> CursorCodec {
+        CursorCodec::new(self.jwt_secret.as_bytes())
+    }
+
+    /// API key validator for `JwtMiddleware`, to be registered as app data
+    pub fn api_key_validator(&self) -> Option<web::Data<dyn ApiKeyValidator>> {
+        self.api_keys.clone().map(|store| {
+            let validator: Arc<dyn ApiKeyValidator> =
+                Arc::new(ApiKeyService::new(store, self.users.clone()));
+            web::Data::from(validator)
+        })
+    }
 }
 
 /// Initialize auth module with database and configuration
@@ -98,52 +221,172 @@ impl AppState {
 /// This function creates all auth-specific stores internally and returns
 /// the auth AppState, encapsulating all auth dependencies.
 pub fn init(
-    mongo_db: &mongodb::sync::Database,
+    mongo_db: &mongodb::Database,
     users: Arc<dyn UserStore>,
     jwt_secret: String,
     jwt_expiry_minutes: i64,
     refresh_token_expiry_days: i64,
     email: Arc<EmailService>,
+    sms: Option<Arc<SmsService>>,
     email_from: String,
     app_name: String,
     frontend_url: String,
 ) -> AppState {
     // Create auth store instances (except users which is passed in)
-    let sessions = Arc::new(MongoSessionStore::new(
-        mongo_db.collection("sessions"),
-        mongo_db.collection("refresh_tokens"),
-    )) as Arc<dyn SessionStore>;
-
-    let verifications = Arc::new(MongoVerificationStore::new(
-        mongo_db.collection("verification_codes"),
-    )) as Arc<dyn VerificationStore>;
-
-    let password_resets = Arc::new(MongoPasswordResetStore::new(
-        mongo_db.collection("password_reset_tokens"),
-    )) as Arc<dyn PasswordResetStore>;
-
-    let oauth_accounts = Arc::new(MongoOAuthAccountStore::new(
-        mongo_db.collection("oauth_accounts"),
-    )) as Arc<dyn OAuthAccountStore>;
-
-    // Create auth AppState with provided dependencies
-    AppState::with_jwt(
+    let stores = Stores {
         users,
-        sessions,
-        Some(password_resets),
-        Some(verifications),
-        Some(oauth_accounts),
+        sessions: Arc::new(MongoSessionStore::new(
+            mongo_db.collection(collections::SESSIONS),
+            mongo_db.collection(collections::REFRESH_TOKENS),
+        )),
+        verifications: Arc::new(MongoVerificationStore::new(
+            mongo_db.collection(collections::VERIFICATION_CODES),
+        )),
+        password_resets: Arc::new(MongoPasswordResetStore::new(
+            mongo_db.collection(collections::PASSWORD_RESET_TOKENS),
+        )),
+        oauth_accounts: Arc::new(MongoOAuthAccountStore::new(
+            mongo_db.collection(collections::OAUTH_ACCOUNTS),
+        )),
+        passkeys: Arc::new(MongoPasskeyStore::new(
+            mongo_db.collection(collections::PASSKEYS),
+            mongo_db.collection(collections::WEBAUTHN_CHALLENGES),
+        )),
+        api_keys: Arc::new(MongoApiKeyStore::new(
+            mongo_db.collection(collections::API_KEYS),
+        )),
+        organizations: Arc::new(MongoOrganizationStore::new(
+            mongo_db.collection(collections::ORGANIZATIONS),
+            mongo_db.collection(collections::ORG_MEMBERSHIPS),
+            mongo_db.collection(collections::ORG_INVITATIONS),
+        )),
+        user_invitations: Arc::new(MongoUserInvitationStore::new(
+            mongo_db.collection(collections::USER_INVITATIONS),
+        )),
+        contact_changes: Arc::new(MongoContactChangeStore::new(
+            mongo_db.collection(collections::CONTACT_CHANGES),
+        )),
+        audit: Arc::new(MongoAuditStore::new(
+            mongo_db.collection(collections::AUDIT_EVENTS),
+        )),
+    };
+
+    stores.into_state(AuthSettings {
         jwt_secret,
         jwt_expiry_minutes,
         refresh_token_expiry_days,
         email,
+        sms,
         email_from,
         app_name,
         frontend_url,
-    )
+    })
+}
+
+/// Token lifetimes and messaging for the auth module
+pub struct AuthSettings {
+    pub jwt_secret:                String,
+    pub jwt_expiry_minutes:        i64,
+    pub refresh_token_expiry_days: i64,
+    pub email:                     Arc<EmailService>,
+    pub sms:                       Option<Arc<SmsService>>,
+    pub email_from:                String,
+    pub app_name:                  String,
+    pub frontend_url:              String,
+}
+
+/// Stores behind optional features; a feature without its store answers
+/// with an error
+#[derive(Clone, Default)]
+pub struct OptionalStores {
+    pub password_resets:  Option<Arc<dyn PasswordResetStore>>,
+    pub verifications:    Option<Arc<dyn VerificationStore>>,
+    pub oauth_accounts:   Option<Arc<dyn OAuthAccountStore>>,
+    pub passkeys:         Option<Arc<dyn PasskeyStore>>,
+    pub api_keys:         Option<Arc<dyn ApiKeyStore>>,
+    pub organizations:    Option<Arc<dyn OrganizationStore>>,
+    pub user_invitations: Option<Arc<dyn UserInvitationStore>>,
+    pub contact_changes:  Option<Arc<dyn ContactChangeStore>>,
+    pub audit:            Option<Arc<dyn AuditStore>>,
+}
+
+/// Initialize auth module on a SQL database
+///
+/// Like `init`, with every store (users included) backed by the SQL
+/// connection. The schema comes from `store::sql::migrations::migrations()`.
+///
+/// SQL stores don't join units of work yet, so transactions stay disabled
+/// and multi-step flows (password reset, contact changes, signup) run as
+/// separate writes.
+#[cfg(feature = "sql")]
+pub fn init_sql(db: &database::sql::SqlConnection, settings: AuthSettings) -> AppState {
+    use crate::store::sql::*;
+
+    let pool = db.pool();
+    let stores = Stores {
+        users: Arc::new(SqlUserStore::new(pool.clone(), db.dialect())),
+        sessions: Arc::new(SqlSessionStore::new(pool.clone())),
+        verifications: Arc::new(SqlVerificationStore::new(pool.clone())),
+        password_resets: Arc::new(SqlPasswordResetStore::new(pool.clone())),
+        oauth_accounts: Arc::new(SqlOAuthAccountStore::new(pool.clone())),
+        passkeys: Arc::new(SqlPasskeyStore::new(pool.clone())),
+        api_keys: Arc::new(SqlApiKeyStore::new(pool.clone())),
+        organizations: Arc::new(SqlOrganizationStore::new(pool.clone())),
+        user_invitations: Arc::new(SqlUserInvitationStore::new(pool.clone())),
+        contact_changes: Arc::new(SqlContactChangeStore::new(pool.clone())),
+        audit: Arc::new(SqlAuditStore::new(pool.clone())),
+    };
+
+    stores.into_state(settings)
+}
+
+/// One store of each kind, from the same backend
+struct Stores {
+    users:            Arc<dyn UserStore>,
+    sessions:         Arc<dyn SessionStore>,
+    verifications:    Arc<dyn VerificationStore>,
+    password_resets:  Arc<dyn PasswordResetStore>,
+    oauth_accounts:   Arc<dyn OAuthAccountStore>,
+    passkeys:         Arc<dyn PasskeyStore>,
+    api_keys:         Arc<dyn ApiKeyStore>,
+    organizations:    Arc<dyn OrganizationStore>,
+    user_invitations: Arc<dyn UserInvitationStore>,
+    contact_changes:  Arc<dyn ContactChangeStore>,
+    audit:            Arc<dyn AuditStore>,
+}
+
+impl Stores {
+    /// Auth AppState over these stores, with settings from the environment
+    fn into_state(self, settings: AuthSettings) -> AppState {
+        let risk = Arc::new(DefaultRiskEvaluator::from_config(RiskConfig::from_env()))
+            as Arc<dyn RiskEvaluator>;
+
+        let stores = OptionalStores {
+            password_resets:  Some(self.password_resets),
+            verifications:    Some(self.verifications),
+            oauth_accounts:   Some(self.oauth_accounts),
+            passkeys:         Some(self.passkeys),
+            api_keys:         Some(self.api_keys),
+            organizations:    Some(self.organizations),
+            user_invitations: Some(self.user_invitations),
+            contact_changes:  Some(self.contact_changes),
+            audit:            Some(self.audit),
+        };
+
+        AppState::with_jwt(self.users, self.sessions, stores, settings)
+            .with_webauthn(WebAuthnConfig::from_env())
+            .with_audit_config(AuditConfig::from_env())
+            .with_risk(risk)
+            .with_profile_schema(ProfileSchema::from_env())
+            .with_identifiers(IdentifierConfig::from_env())
+    }
 }
 
 /// Configure all authentication routes
+///
+/// Only routes that check key scopes (the audit log) accept API keys, when
+/// `AppState::api_key_validator()` is registered as app data. Every other
+/// protected route requires a signed in session.
 pub fn configure(cfg: &mut web::ServiceConfig, jwt_secret: String) {
     let secret = jwt_secret;
 
@@ -151,7 +394,15 @@ pub fn configure(cfg: &mut web::ServiceConfig, jwt_secret: String) {
         web::scope("/auth")
             // Public routes (no auth required)
             .route("/login", web::post().to(crate::handler::login_user))
+            .route(
+                "/login/challenge",
+                web::post().to(crate::handler::verify_login_challenge),
+            )
             .route("/register", web::post().to(crate::handler::signup_user))
+            .route(
+                "/username/availability",
+                web::get().to(crate::handler::check_username_availability),
+            )
             .route(
                 "/verify/{user_id}",
                 web::post().to(crate::handler::verify_email),
@@ -176,28 +427,73 @@ pub fn configure(cfg: &mut web::ServiceConfig, jwt_secret: String) {
                 "/oauth/{provider}/callback",
                 web::get().to(crate::handler::oauth_callback),
             )
+            .route(
+                "/passkey/login/options",
+                web::post().to(crate::handler::passkey_login_options),
+            )
+            .route(
+                "/passkey/login/verify",
+                web::post().to(crate::handler::passkey_login_verify),
+            )
+            .route(
+                "/contact/revert",
+                web::post().to(crate::handler::revert_contact_change),
+            )
+            .route(
+                "/invitations/accept",
+                web::post().to(crate::handler::accept_user_invitation),
+            )
             
+            // Routes open to API keys, each checking the key's scope.
+            // Registered before `/pt` so they match first
+            .service(
+                web::scope("/pt/audit-events")
+                    .wrap(JwtMiddleware::from_secret(secret.clone()))
+                    .route("", web::get().to(crate::handler::list_audit_events))
+                    .route("/purge", web::post().to(crate::handler::purge_audit_events)),
+            )
+
             // Protected routes (auth required) - different path prefix
             // pt = protected - requires JWT auth
             .service(
                 web::scope("/pt")
-                    .wrap(JwtMiddleware::from_secret(secret))
+                    .wrap(JwtMiddleware::from_secret(secret).without_api_keys())
                     .route(
                         "/refresh-token",
                         web::post().to(crate::handler::refresh_token),
                     )
                     .route("/logout", web::post().to(crate::handler::logout_user))
-                    .route(
-                        "/change-password",
-                        web::post().to(crate::handler::change_password),
+                    .service(
+                        web::resource("/reauthenticate")
+                            .wrap(DenyImpersonation)
+                            .route(web::post().to(crate::handler::reauthenticate)),
                     )
-                    .route(
-                        "/oauth/link/{user_id}",
-                        web::post().to(crate::handler::link_oauth),
+                    .service(
+                        web::resource("/change-password")
+                            .wrap(DenyImpersonation)
+                            .wrap(RequireRecentAuth::default())
+                            .route(web::post().to(crate::handler::change_password)),
                     )
-                    .route(
-                        "/oauth/unlink/{user_id}",
-                        web::post().to(crate::handler::unlink_oauth),
+                    .service(
+                        web::resource("/contact/change")
+                            .wrap(DenyImpersonation)
+                            .route(web::post().to(crate::handler::request_contact_change)),
+                    )
+                    .service(
+                        web::resource("/contact/confirm")
+                            .wrap(DenyImpersonation)
+                            .route(web::post().to(crate::handler::confirm_contact_change)),
+                    )
+                    .service(
+                        web::resource("/oauth/link/{user_id}")
+                            .wrap(DenyImpersonation)
+                            .route(web::post().to(crate::handler::link_oauth)),
+                    )
+                    .service(
+                        web::resource("/oauth/unlink/{user_id}")
+                            .wrap(DenyImpersonation)
+                            .wrap(RequireRecentAuth::default())
+                            .route(web::post().to(crate::handler::unlink_oauth)),
                     )
                     .route(
                         "/oauth/connections/{user_id}",
@@ -207,6 +503,103 @@ pub fn configure(cfg: &mut web::ServiceConfig, jwt_secret: String) {
                         "/2fa/status/{user_id}",
                         web::get().to(crate::handler::get_2fa_status),
                     )
+                    .route(
+                        "/2fa/passkey/options",
+                        web::post().to(crate::handler::passkey_2fa_options),
+                    )
+                    .service(
+                        web::resource("/2fa/passkey/verify")
+                            .wrap(DenyImpersonation)
+                            .route(web::post().to(crate::handler::passkey_2fa_verify)),
+                    )
+                    .route("/api-keys", web::post().to(crate::handler::create_api_key))
+                    .route("/api-keys", web::get().to(crate::handler::list_api_keys))
+                    .route(
+                        "/api-keys/{key_id}",
+                        web::get().to(crate::handler::get_api_key),
+                    )
+                    .route(
+                        "/api-keys/{key_id}",
+                        web::delete().to(crate::handler::revoke_api_key),
+                    )
+                    .route("/orgs", web::post().to(crate::handler::create_organization))
+                    .route("/orgs", web::get().to(crate::handler::list_organizations))
+                    .route(
+                        "/orgs/invitations/accept",
+                        web::post().to(crate::handler::accept_org_invitation),
+                    )
+                    .route(
+                        "/orgs/{org_id}",
+                        web::get().to(crate::handler::get_organization),
+                    )
+                    .route(
+                        "/orgs/{org_id}/members",
+                        web::get().to(crate::handler::list_org_members),
+                    )
+                    .route(
+                        "/orgs/{org_id}/members/{user_id}",
+                        web::put().to(crate::handler::update_org_member),
+                    )
+                    .route(
+                        "/orgs/{org_id}/members/{user_id}",
+                        web::delete().to(crate::handler::remove_org_member),
+                    )
+                    .route(
+                        "/orgs/{org_id}/invitations",
+                        web::post().to(crate::handler::invite_org_member),
+                    )
+                    .route(
+                        "/orgs/{org_id}/invitations",
+                        web::get().to(crate::handler::list_org_invitations),
+                    )
+                    .route(
+                        "/orgs/{org_id}/invitations/{invitation_id}",
+                        web::delete().to(crate::handler::revoke_org_invitation),
+                    )
+                    .route(
+                        "/orgs/{org_id}/switch",
+                        web::post().to(crate::handler::switch_organization),
+                    )
+                    .route(
+                        "/orgs/{org_id}/sessions",
+                        web::get().to(crate::handler::list_org_sessions),
+                    )
+                    .route(
+                        "/orgs/{org_id}/audit-events",
+                        web::get().to(crate::handler::list_org_audit_events),
+                    )
+                    .route(
+                        "/orgs/{org_id}/users/invite",
+                        web::post().to(crate::handler::invite_user),
+                    )
+                    .route(
+                        "/orgs/{org_id}/users/invitations",
+                        web::get().to(crate::handler::list_user_invitations),
+                    )
+                    .route(
+                        "/orgs/{org_id}/users/invitations/{invitation_id}/resend",
+                        web::post().to(crate::handler::resend_user_invitation),
+                    )
+                    .route(
+                        "/orgs/{org_id}/users/invitations/{invitation_id}",
+                        web::delete().to(crate::handler::revoke_user_invitation),
+                    )
+                    .route("/passkeys", web::get().to(crate::handler::list_passkeys))
+                    .service(
+                        web::resource("/passkeys/register/options")
+                            .wrap(DenyImpersonation)
+                            .route(web::post().to(crate::handler::passkey_register_options)),
+                    )
+                    .service(
+                        web::resource("/passkeys/register/verify")
+                            .wrap(DenyImpersonation)
+                            .route(web::post().to(crate::handler::passkey_register_verify)),
+                    )
+                    .service(
+                        web::resource("/passkeys/{passkey_id}")
+                            .wrap(DenyImpersonation)
+                            .route(web::delete().to(crate::handler::delete_passkey)),
+                    )
                     
                     // Unimplimented Routes - these are for future features and may not be fully implemented yet
                     .route(
@@ -217,13 +610,16 @@ pub fn configure(cfg: &mut web::ServiceConfig, jwt_secret: String) {
                         "/magic/verify",
                         web::post().to(crate::handler::verify_magic_link),
                     )
-                    .route(
-                        "/2fa/enable/{user_id}",
-                        web::post().to(crate::handler::enable_2fa),
+                    .service(
+                        web::resource("/2fa/enable/{user_id}")
+                            .wrap(DenyImpersonation)
+                            .route(web::post().to(crate::handler::enable_2fa)),
                     )
-                    .route(
-                        "/2fa/disable/{user_id}",
-                        web::post().to(crate::handler::disable_2fa),
+                    .service(
+                        web::resource("/2fa/disable/{user_id}")
+                            .wrap(DenyImpersonation)
+                            .wrap(RequireRecentAuth::default())
+                            .route(web::post().to(crate::handler::disable_2fa)),
                     )
                     .route(
                         "/2fa/verify/{user_id}",
@@ -289,6 +685,15 @@ pub fn configure(cfg: &mut web::ServiceConfig, jwt_secret: String) {
                         "/devices/{user_id}/revoke-all",
                         web::post().to(crate::handler::revoke_all_devices),
                     )
+                    .service(
+                        web::resource("/admin/users/{user_id}/impersonate")
+                            .wrap(DenyImpersonation)
+                            .route(web::post().to(crate::handler::impersonate_user)),
+                    )
+                    .route(
+                        "/users/deletions/purge",
+                        web::post().to(crate::handler::purge_deleted_users),
+                    )
                     .route("/users/{user_id}", web::get().to(crate::handler::get_user))
                     .route(
                         "/users/{user_id}",
@@ -306,6 +711,14 @@ pub fn configure(cfg: &mut web::ServiceConfig, jwt_secret: String) {
                     .route(
                         "/users/{user_id}/deactivate",
                         web::post().to(crate::handler::deactivate_user),
+                    )
+                    .route(
+                        "/users/{user_id}/deletion/cancel",
+                        web::post().to(crate::handler::cancel_user_deletion),
+                    )
+                    .route(
+                        "/users/{user_id}/export",
+                        web::get().to(crate::handler::export_user_data),
                     ),
             ),
     );
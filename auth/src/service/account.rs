@@ -0,0 +1,198 @@
+//! Account Service
+//!
+//! Account deletion and personal data export. Deletion is requested first and
+//! only carried out once the grace period has passed, so a user (or someone
+//! who took over their account) can't wipe it in one click. Purging removes
+//! the user's records from every auth store and anonymizes the user document,
+//! which is kept so references from retained records stay valid. Audit events
+//! are kept as well, minus the IP address and user agent.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::organization::OrgRole;
+use crate::models::user::User;
+use crate::routes::AppState;
+use crate::utils::errors::{AuthError, AuthResult};
+use crate::utils::types::AccountExport;
+use database::utils::DbId;
+
+/// Days between a deletion request and the purge
+pub const DELETION_GRACE_DAYS: i64 = 30;
+
+/// When an account whose deletion is requested now will be purged
+pub fn deletion_due_at(requested_at: DateTime<Utc>) -> DateTime<Utc> {
+    requested_at + Duration::days(DELETION_GRACE_DAYS)
+}
+
+/// Outcome of a purge run
+#[derive(Debug, Default)]
+pub struct PurgeReport {
+    /// Users purged in this run
+    pub purged: Vec<DbId>,
+    /// Users that could not be purged, left for the next run
+    pub failed: Vec<(DbId, AuthError)>,
+}
+
+/// Schedule deletion, sign the user out everywhere and revoke their API keys
+pub async fn schedule_deletion(state: &AppState, user: &User) -> AuthResult<DateTime<Utc>> {
+    if user.deleted_at.is_some() {
+        return Err(AuthError::not_found("User not found"));
+    }
+    ensure_not_last_owner(state, &user.id).await?;
+
+    // Re-requesting keeps the original date rather than extending it
+    let due_at = user
+        .deletion_scheduled_at
+        .unwrap_or_else(|| deletion_due_at(Utc::now()));
+
+    state.users.schedule_deletion(&user.id, Some(due_at)).await?;
+    state.sessions.revoke_all(&user.id).await?;
+    if let Some(store) = &state.api_keys {
+        store.revoke_all_for_user(&user.id).await?;
+    }
+
+    Ok(due_at)
+}
+
+/// Cancel a scheduled deletion
+pub async fn cancel_deletion(state: &AppState, user: &User) -> AuthResult<()> {
+    if !user.is_deletion_scheduled() {
+        return Err(AuthError::invalid_request("Account deletion is not scheduled"));
+    }
+
+    state.users.schedule_deletion(&user.id, None).await
+}
+
+/// A user who is the last owner of an organization can't be deleted until
+/// ownership is handed over
+async fn ensure_not_last_owner(state: &AppState, user_id: &DbId) -> AuthResult<()> {
+    let Some(store) = &state.organizations else {
+        return Ok(());
+    };
+
+    for membership in store.list_user_memberships(user_id).await? {
+        if membership.role != OrgRole::Owner {
+            continue;
+        }
+        let has_other_owner = store
+            .list_members(&membership.org_id)
+            .await?
+            .iter()
+            .any(|m| m.role == OrgRole::Owner && &m.user_id != user_id);
+        if !has_other_owner {
+            return Err(AuthError::last_owner());
+        }
+    }
+    Ok(())
+}
+
+/// Remove everything held about a user and anonymize the user record
+pub async fn purge_user(state: &AppState, user_id: &DbId) -> AuthResult<()> {
+    let user = state
+        .users
+        .find_by_id(user_id)
+        .await?
+        .ok_or_else(|| AuthError::not_found("User not found"))?;
+
+    // Ownership may have changed during the grace period; check before anything is removed
+    ensure_not_last_owner(state, user_id).await?;
+
+    state.sessions.delete_all_for_user(user_id).await?;
+
+    if let Some(store) = &state.password_resets {
+        store.delete_all_for_user(user_id).await?;
+    }
+    if let Some(store) = &state.verifications {
+        store.delete_all_for_user(user_id).await?;
+    }
+    if let Some(store) = &state.oauth_accounts {
+        store.delete_by_user(user_id).await?;
+    }
+    if let Some(store) = &state.passkeys {
+        store.delete_all_for_user(user_id).await?;
+    }
+    if let Some(store) = &state.api_keys {
+        store.delete_all_for_user(user_id).await?;
+    }
+    if let Some(store) = &state.contact_changes {
+        store.delete_all_for_user(user_id).await?;
+    }
+    if let Some(store) = &state.user_invitations {
+        store.delete_all_for_user(user_id).await?;
+    }
+    if let Some(store) = &state.organizations {
+        for membership in store.list_user_memberships(user_id).await? {
+            store.remove_member(&membership.org_id, user_id).await?;
+        }
+        if let Some(email) = user.email.as_deref() {
+            store.delete_invitations_for_email(email).await?;
+        }
+    }
+    if let Some(store) = &state.audit {
+        store.anonymize_user(user_id).await?;
+    }
+
+    state.users.anonymize(user_id).await
+}
+
+/// Purge every account whose grace period has passed
+///
+/// A failure doesn't stop the run: the user stays scheduled and is retried
+/// on the next run, and the failure is reported alongside the purged IDs.
+pub async fn purge_due(state: &AppState) -> AuthResult<PurgeReport> {
+    let mut report = PurgeReport::default();
+
+    for user in state.users.list_due_for_deletion(Utc::now()).await? {
+        match purge_user(state, &user.id).await {
+            Ok(()) => report.purged.push(user.id),
+            Err(e) => report.failed.push((user.id, e)),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Bundle everything held about a user
+pub async fn export_user_data(state: &AppState, user: &User) -> AuthResult<AccountExport> {
+    let sessions = state.sessions.find_by_user_id(&user.id).await?;
+
+    let oauth_accounts = match &state.oauth_accounts {
+        Some(store) => store.list_by_user(&user.id).await?,
+        None => Vec::new(),
+    };
+    let passkeys = match &state.passkeys {
+        Some(store) => store.list_by_user(&user.id).await?,
+        None => Vec::new(),
+    };
+    let api_keys = match &state.api_keys {
+        Some(store) => store.list_by_user(&user.id).await?,
+        None => Vec::new(),
+    };
+    let memberships = match &state.organizations {
+        Some(store) => store.list_user_memberships(&user.id).await?,
+        None => Vec::new(),
+    };
+
+    Ok(AccountExport {
+        exported_at:    Utc::now(),
+        profile:        user.into(),
+        sessions:       sessions.into_iter().map(Into::into).collect(),
+        oauth_accounts: oauth_accounts.into_iter().map(Into::into).collect(),
+        passkeys:       passkeys.into_iter().map(Into::into).collect(),
+        api_keys:       api_keys.into_iter().map(Into::into).collect(),
+        memberships:    memberships.into_iter().map(Into::into).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deletion_due_at() {
+        let requested_at = Utc::now();
+        let due_at = deletion_due_at(requested_at);
+
+        assert_eq!((due_at - requested_at).num_days(), DELETION_GRACE_DAYS);
+    }
+}
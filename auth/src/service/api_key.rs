@@ -0,0 +1,272 @@
+//! API Key Service
+//!
+//! Generates API keys and validates them for `middleware::jwt::JwtMiddleware`.
+//! A validated key produces the same `Claims` principal as a JWT login, with
+//! the key's scopes attached as `middleware::api_key::ApiKeyInfo`.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use middleware::api_key::{API_KEY_PREFIX, ApiKeyInfo, ApiKeyPrincipal, ApiKeyValidator};
+use middleware::jwt::Claims;
+use middleware::rate_limit::{RateLimitConfig, RateLimiter};
+use middleware::token_validation::TokenValidationError;
+use utils::hash::{generate_hex, hash_sha256};
+
+use crate::store::api_key_store::ApiKeyStore;
+use crate::store::user_store::UserStore;
+
+/// Requests per minute for keys without their own limit
+pub const DEFAULT_RATE_LIMIT_PER_MINUTE: u32 = 60;
+
+/// Freshly generated key - `raw` is only ever shown to the caller once
+#[derive(Debug, Clone)]
+pub struct GeneratedApiKey {
+    pub raw: String,
+    pub prefix: String,
+    pub hash: String,
+}
+
+/// API key service
+pub struct ApiKeyService {
+    store: Arc<dyn ApiKeyStore>,
+    users: Arc<dyn UserStore>,
+    limiter: RateLimiter,
+}
+
+impl ApiKeyService {
+    /// Create new API key service backed by a store, checking key owners in `users`
+    pub fn new(store: Arc<dyn ApiKeyStore>, users: Arc<dyn UserStore>) -> Self {
+        Self {
+            store,
+            users,
+            limiter: RateLimiter::new(RateLimitConfig::new(DEFAULT_RATE_LIMIT_PER_MINUTE, 60)),
+        }
+    }
+
+    /// Generate a new key in the form `ak_<8 hex>_<48 hex>`
+    pub fn generate_key() -> GeneratedApiKey {
+        let prefix = format!("{}{}", API_KEY_PREFIX, generate_hex(4));
+        let raw = format!("{}_{}", prefix, generate_hex(24));
+        let hash = hash_sha256(&raw);
+
+        GeneratedApiKey { raw, prefix, hash }
+    }
+}
+
+#[async_trait]
+impl ApiKeyValidator for ApiKeyService {
+    async fn validate(
+        &self,
+        raw_key: &str,
+        ip_address: Option<&str>,
+    ) -> Result<ApiKeyPrincipal, TokenValidationError> {
+        let key = self
+            .store
+            .find_by_hash(&hash_sha256(raw_key))
+            .await
+            .map_err(|_| TokenValidationError::internal_error("Failed to validate API key"))?
+            .ok_or_else(|| TokenValidationError::unauthorized("Invalid API key"))?;
+
+        if key.is_revoked {
+            return Err(TokenValidationError::unauthorized("API key has been revoked"));
+        }
+
+        if key.is_expired() {
+            return Err(TokenValidationError::unauthorized("API key has expired"));
+        }
+
+        // A key acts as its owner, so it stops working with the owner's account
+        let owner = self
+            .users
+            .find_by_id(&key.user_id)
+            .await
+            .map_err(|_| TokenValidationError::internal_error("Failed to validate API key"))?;
+        let owner_active = owner.is_some_and(|user| {
+            user.is_active && user.deleted_at.is_none() && !user.is_deletion_scheduled()
+        });
+        if !owner_active {
+            return Err(TokenValidationError::unauthorized("API key owner is not active"));
+        }
+
+        let limit = key.rate_limit_per_minute.unwrap_or(DEFAULT_RATE_LIMIT_PER_MINUTE);
+        if !self.limiter.check_with_limit(&key.id.to_string(), limit) {
+            return Err(TokenValidationError::too_many_requests(
+                "API key rate limit exceeded",
+            ));
+        }
+
+        // Usage tracking is best effort and must not fail the request
+        let _ = self.store.touch(&key.id, ip_address).await;
+
+        let now = chrono::Utc::now().timestamp();
+        Ok(ApiKeyPrincipal {
+            claims: Claims {
+                sub:   key.user_id.to_string(),
+                email: None,
+                exp:   now + 60, // Principal only lives for the current request
+                iat:   now,
+                org_id: None,
+                act:    None,
+                auth_time: None,
+                amr:    Vec::new(),
+            },
+            info: ApiKeyInfo {
+                key_id: key.id.to_string(),
+                scopes: key.scopes,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::api_key::{ApiKey, CreateApiKey};
+    use crate::models::user::CreateUserInput;
+    use crate::store::memory::MemoryUserStore;
+    use crate::utils::errors::AuthResult;
+    use database::utils::{DbId, generate_id};
+    use std::sync::Mutex;
+
+    /// Minimal store holding a single key
+    struct SingleKeyStore {
+        key: Mutex<ApiKey>,
+    }
+
+    #[async_trait]
+    impl ApiKeyStore for SingleKeyStore {
+        async fn create(&self, _input: CreateApiKey) -> AuthResult<ApiKey> {
+            Ok(self.key.lock().unwrap().clone())
+        }
+
+        async fn find_by_id(&self, _id: &DbId) -> AuthResult<Option<ApiKey>> {
+            Ok(Some(self.key.lock().unwrap().clone()))
+        }
+
+        async fn find_by_hash(&self, key_hash: &str) -> AuthResult<Option<ApiKey>> {
+            let key = self.key.lock().unwrap();
+            Ok((key.key_hash == key_hash).then(|| key.clone()))
+        }
+
+        async fn list_by_user(&self, _user_id: &DbId) -> AuthResult<Vec<ApiKey>> {
+            Ok(vec![self.key.lock().unwrap().clone()])
+        }
+
+        async fn touch(&self, _id: &DbId, ip_address: Option<&str>) -> AuthResult<()> {
+            let mut key = self.key.lock().unwrap();
+            key.last_used_at = Some(chrono::Utc::now().timestamp());
+            key.last_used_ip = ip_address.map(String::from);
+            Ok(())
+        }
+
+        async fn revoke(&self, _id: &DbId) -> AuthResult<()> {
+            self.key.lock().unwrap().is_revoked = true;
+            Ok(())
+        }
+
+        async fn revoke_all_for_user(&self, _user_id: &DbId) -> AuthResult<u64> {
+            self.key.lock().unwrap().is_revoked = true;
+            Ok(1)
+        }
+
+        async fn delete_all_for_user(&self, _user_id: &DbId) -> AuthResult<u64> {
+            Ok(0)
+        }
+    }
+
+    async fn service_with_key(
+        generated: &GeneratedApiKey,
+        rate_limit: Option<u32>,
+    ) -> (ApiKeyService, Arc<SingleKeyStore>, Arc<MemoryUserStore>) {
+        let users = Arc::new(MemoryUserStore::new());
+        let owner = users.create(CreateUserInput {
+            email:    Some("cron@example.com".to_string()),
+            phone:    None,
+            username: None,
+            password: "hash".to_string(),
+        }).await.unwrap();
+
+        let store = Arc::new(SingleKeyStore {
+            key: Mutex::new(ApiKey {
+                id: generate_id(),
+                user_id: owner.id,
+                name: "cron".to_string(),
+                key_prefix: generated.prefix.clone(),
+                key_hash: generated.hash.clone(),
+                scopes: vec!["payments:read".to_string()],
+                rate_limit_per_minute: rate_limit,
+                expires_at: None,
+                last_used_at: None,
+                last_used_ip: None,
+                is_revoked: false,
+                created_at: chrono::Utc::now().timestamp(),
+            }),
+        });
+
+        (ApiKeyService::new(store.clone(), users.clone()), store, users)
+    }
+
+    #[test]
+    fn test_generate_key_format() {
+        let key = ApiKeyService::generate_key();
+
+        assert!(key.raw.starts_with(&key.prefix));
+        assert!(key.prefix.starts_with(API_KEY_PREFIX));
+        assert_eq!(key.prefix.len(), API_KEY_PREFIX.len() + 8);
+        assert_eq!(key.hash, hash_sha256(&key.raw));
+    }
+
+    #[actix_web::test]
+    async fn test_validate_produces_claims_principal() {
+        let generated = ApiKeyService::generate_key();
+        let (service, store, _users) = service_with_key(&generated, None).await;
+
+        let principal = service.validate(&generated.raw, Some("10.0.0.1")).await.unwrap();
+        let key = store.key.lock().unwrap().clone();
+
+        assert_eq!(principal.claims.sub, key.user_id.to_string());
+        assert!(principal.info.has_scope("payments:read"));
+        assert_eq!(key.last_used_ip.as_deref(), Some("10.0.0.1"));
+    }
+
+    #[actix_web::test]
+    async fn test_validate_rejects_unknown_and_revoked() {
+        let generated = ApiKeyService::generate_key();
+        let (service, store, _users) = service_with_key(&generated, None).await;
+
+        assert!(service.validate("ak_00000000_unknown", None).await.is_err());
+
+        store.key.lock().unwrap().is_revoked = true;
+        let err = service.validate(&generated.raw, None).await.unwrap_err();
+        assert_eq!(err.status_code, 401);
+    }
+
+    #[actix_web::test]
+    async fn test_validate_enforces_rate_limit() {
+        let generated = ApiKeyService::generate_key();
+        let (service, _store, _users) = service_with_key(&generated, Some(1)).await;
+
+        let mut results = Vec::new();
+        for _ in 0..5 {
+            results.push(service.validate(&generated.raw, None).await);
+        }
+        let limited = results.iter().filter(|r| matches!(r, Err(e) if e.status_code == 429));
+
+        assert!(limited.count() > 0);
+    }
+
+    #[actix_web::test]
+    async fn test_validate_rejects_keys_of_inactive_owners() {
+        let generated = ApiKeyService::generate_key();
+        let (service, store, users) = service_with_key(&generated, None).await;
+        let owner_id = store.key.lock().unwrap().user_id.clone();
+
+        users.schedule_deletion(&owner_id, Some(chrono::Utc::now())).await.unwrap();
+        let err = service.validate(&generated.raw, None).await.unwrap_err();
+        assert_eq!(err.status_code, 401);
+
+        users.schedule_deletion(&owner_id, None).await.unwrap();
+        assert!(service.validate(&generated.raw, None).await.is_ok());
+    }
+}
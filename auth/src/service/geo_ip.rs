@@ -0,0 +1,186 @@
+//! IP Geolocation
+//!
+//! A local IP-range database for rough login locations - no external lookups.
+//! The source is a CSV file with one range per line:
+//!
+//! ```text
+//! start_ip,end_ip,country,latitude,longitude
+//! 41.89.0.0,41.89.255.255,KE,-1.2864,36.8172
+//! ```
+//!
+//! IPv4 and IPv6 ranges can be mixed. Lines starting with `#` are skipped.
+
+use std::net::IpAddr;
+
+use crate::utils::errors::{AuthError, AuthResult};
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Approximate location of an IP address
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoLocation {
+    pub country:   String,
+    pub latitude:  f64,
+    pub longitude: f64,
+}
+
+impl GeoLocation {
+    /// Great-circle distance in kilometres (haversine)
+    pub fn distance_km(&self, other: &GeoLocation) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.longitude - self.longitude).to_radians();
+
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpRange {
+    start:    u128,
+    end:      u128,
+    location: GeoLocation,
+}
+
+/// Sorted IP ranges with their locations
+#[derive(Debug, Clone, Default)]
+pub struct IpRangeDatabase {
+    ranges: Vec<IpRange>,
+}
+
+/// IPv4 addresses are mapped into the IPv6 space so both share one ordering
+fn ip_key(ip: IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(v4) => u128::from(v4.to_ipv6_mapped()),
+        IpAddr::V6(v6) => u128::from(v6),
+    }
+}
+
+fn parse_ip(value: &str, line: usize) -> AuthResult<u128> {
+    value
+        .trim()
+        .parse::<IpAddr>()
+        .map(ip_key)
+        .map_err(|_| AuthError::internal_error(&format!("Invalid IP on line {}: {}", line, value)))
+}
+
+fn parse_coordinate(value: &str, line: usize) -> AuthResult<f64> {
+    value
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| AuthError::internal_error(&format!("Invalid coordinate on line {}", line)))
+}
+
+impl IpRangeDatabase {
+    /// Parse the CSV ranges
+    pub fn from_csv(data: &str) -> AuthResult<Self> {
+        let mut ranges = Vec::new();
+
+        for (index, line) in data.lines().enumerate() {
+            let line_no = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split(',').collect();
+            if fields.len() != 5 {
+                return Err(AuthError::internal_error(&format!(
+                    "Expected 5 fields on line {}",
+                    line_no
+                )));
+            }
+
+            let start = parse_ip(fields[0], line_no)?;
+            let end = parse_ip(fields[1], line_no)?;
+            if end < start {
+                return Err(AuthError::internal_error(&format!(
+                    "Range end before start on line {}",
+                    line_no
+                )));
+            }
+
+            ranges.push(IpRange {
+                start,
+                end,
+                location: GeoLocation {
+                    country:   fields[2].trim().to_string(),
+                    latitude:  parse_coordinate(fields[3], line_no)?,
+                    longitude: parse_coordinate(fields[4], line_no)?,
+                },
+            });
+        }
+
+        ranges.sort_by_key(|r| r.start);
+
+        Ok(Self { ranges })
+    }
+
+    /// Load the CSV ranges from a file
+    pub fn load(path: &str) -> AuthResult<Self> {
+        let data = std::fs::read_to_string(path).map_err(|e| {
+            AuthError::internal_error(&format!("Failed to read IP ranges from {}: {}", path, e))
+        })?;
+
+        Self::from_csv(&data)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Location of an IP address, if it falls in a known range
+    pub fn lookup(&self, ip: &str) -> Option<&GeoLocation> {
+        let key = ip_key(ip.trim().parse::<IpAddr>().ok()?);
+
+        // Last range starting at or before the address
+        let index = self.ranges.partition_point(|r| r.start <= key).checked_sub(1)?;
+        let range = &self.ranges[index];
+
+        (key <= range.end).then_some(&range.location)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RANGES: &str = "\
+# start,end,country,lat,lon
+41.89.0.0,41.89.255.255,KE,-1.2864,36.8172
+81.2.69.0,81.2.69.255,GB,51.5074,-0.1278
+2001:db8::,2001:db8::ffff,US,40.7128,-74.0060
+";
+
+    #[test]
+    fn test_lookup() {
+        let db = IpRangeDatabase::from_csv(RANGES).unwrap();
+
+        assert_eq!(db.len(), 3);
+        assert_eq!(db.lookup("41.89.12.1").map(|l| l.country.as_str()), Some("KE"));
+        assert_eq!(db.lookup("81.2.69.160").map(|l| l.country.as_str()), Some("GB"));
+        assert_eq!(db.lookup("2001:db8::1").map(|l| l.country.as_str()), Some("US"));
+        assert!(db.lookup("8.8.8.8").is_none());
+        assert!(db.lookup("not-an-ip").is_none());
+    }
+
+    #[test]
+    fn test_rejects_malformed_line() {
+        assert!(IpRangeDatabase::from_csv("41.89.0.0,41.89.255.255,KE").is_err());
+        assert!(IpRangeDatabase::from_csv("41.89.255.255,41.89.0.0,KE,0,0").is_err());
+    }
+
+    #[test]
+    fn test_distance_km() {
+        let nairobi = GeoLocation { country: "KE".into(), latitude: -1.2864, longitude: 36.8172 };
+        let london = GeoLocation { country: "GB".into(), latitude: 51.5074, longitude: -0.1278 };
+
+        let distance = nairobi.distance_km(&london);
+        assert!((6700.0..6900.0).contains(&distance), "{}", distance);
+    }
+}
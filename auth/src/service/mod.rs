@@ -3,8 +3,17 @@
 //! This module contains services that handle the core business logic
 //! for authentication flows, OAuth integration, and account management.
 
+pub mod account;
+pub mod api_key;
+pub mod geo_ip;
 pub mod oauth;
+pub mod risk;
 pub mod user;
+pub mod webauthn;
 
+pub use account::DELETION_GRACE_DAYS;
+pub use api_key::ApiKeyService;
 pub use oauth::OAuthService;
+pub use risk::{DefaultRiskEvaluator, RiskEvaluator};
 pub use user::UserService;
+pub use webauthn::WebAuthnService;
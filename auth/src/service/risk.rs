@@ -0,0 +1,371 @@
+//! Login Risk Service
+//!
+//! Scores a password login that already succeeded against what is known about
+//! the user: the IP addresses and user agents of their previous sessions, how
+//! far they would have travelled since the last one, and recent failed
+//! attempts. The score maps to allow, challenge (second factor first) or deny.
+//!
+//! The evaluator is pluggable through `RiskEvaluator`; `assess_login` gathers
+//! the context from the stores and runs the one configured in `AppState`.
+
+use actix_web::HttpRequest;
+use serde::Serialize;
+
+use crate::config::risk::RiskConfig;
+use crate::models::audit::{AuditAction, AuditFilter, AuditOutcome};
+use crate::models::session::SessionModel;
+use crate::models::user::User;
+use crate::routes::AppState;
+use crate::service::geo_ip::{GeoLocation, IpRangeDatabase};
+use crate::service::user::BAD_PASSWORD_REASON;
+use crate::utils::errors::AuthResult;
+
+/// What to do with a login
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskDecision {
+    Allow,
+    Challenge,
+    Deny,
+}
+
+/// Something unusual about a login
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "signal")]
+pub enum RiskSignal {
+    NewIpAddress,
+    NewUserAgent,
+    ImpossibleTravel { distance_km: u64, speed_kmh: u64 },
+    FailedAttempts { count: u64 },
+}
+
+impl RiskSignal {
+    /// Contribution to the risk score
+    pub fn score(&self) -> u32 {
+        match self {
+            RiskSignal::NewIpAddress => 25,
+            RiskSignal::NewUserAgent => 15,
+            RiskSignal::ImpossibleTravel { .. } => 50,
+            RiskSignal::FailedAttempts { .. } => 30,
+        }
+    }
+
+    /// Short machine-readable name (audit reasons)
+    pub fn code(&self) -> &'static str {
+        match self {
+            RiskSignal::NewIpAddress => "new_ip_address",
+            RiskSignal::NewUserAgent => "new_user_agent",
+            RiskSignal::ImpossibleTravel { .. } => "impossible_travel",
+            RiskSignal::FailedAttempts { .. } => "failed_attempts",
+        }
+    }
+}
+
+/// Outcome of a risk evaluation
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskAssessment {
+    pub decision: RiskDecision,
+    pub score:    u32,
+    pub signals:  Vec<RiskSignal>,
+    #[serde(skip)]
+    pub location: Option<GeoLocation>,
+}
+
+impl RiskAssessment {
+    /// Nothing unusual was seen
+    pub fn allow() -> Self {
+        Self {
+            decision: RiskDecision::Allow,
+            score:    0,
+            signals:  Vec::new(),
+            location: None,
+        }
+    }
+
+    /// Whether the user should hear about this login
+    pub fn is_risky(&self) -> bool {
+        !self.signals.is_empty()
+    }
+
+    /// Signal codes joined for an audit reason
+    pub fn reason(&self) -> String {
+        self.signals.iter().map(RiskSignal::code).collect::<Vec<_>>().join(",")
+    }
+}
+
+/// Everything known about a login attempt
+#[derive(Debug, Clone)]
+pub struct LoginAttempt<'a> {
+    pub ip_address:        Option<&'a str>,
+    pub user_agent:        Option<&'a str>,
+    pub at:                i64,
+    pub previous_sessions: &'a [SessionModel],
+    pub recent_failures:   u64,
+}
+
+/// Risk evaluator trait - implement this for custom login policies
+pub trait RiskEvaluator: Send + Sync {
+    /// Score a login whose password was correct
+    fn evaluate(&self, attempt: &LoginAttempt) -> RiskAssessment;
+
+    /// How far back failed attempts are counted, in seconds
+    fn failure_window_seconds(&self) -> i64 {
+        RiskConfig::default().failure_window_minutes * 60
+    }
+}
+
+/// Default evaluator - IP, user agent, impossible travel and failure velocity
+pub struct DefaultRiskEvaluator {
+    config:    RiskConfig,
+    ip_ranges: IpRangeDatabase,
+}
+
+impl DefaultRiskEvaluator {
+    pub fn new(config: RiskConfig, ip_ranges: IpRangeDatabase) -> Self {
+        Self { config, ip_ranges }
+    }
+
+    /// Build from config, loading the IP ranges if a path is set
+    ///
+    /// A missing or broken range file disables impossible-travel checks
+    /// instead of failing startup.
+    pub fn from_config(config: RiskConfig) -> Self {
+        let ip_ranges = match config.ip_ranges_path.as_deref() {
+            Some(path) => IpRangeDatabase::load(path).unwrap_or_else(|e| {
+                crate::warn!("Impossible-travel checks disabled: {}", e.message);
+                IpRangeDatabase::default()
+            }),
+            None => IpRangeDatabase::default(),
+        };
+
+        Self::new(config, ip_ranges)
+    }
+
+    fn impossible_travel(
+        &self,
+        attempt:  &LoginAttempt,
+        location: &GeoLocation,
+    ) -> Option<RiskSignal> {
+        // Most recent earlier session whose IP we can place
+        let (previous, previous_location) = attempt
+            .previous_sessions
+            .iter()
+            .filter(|s| s.created_at <= attempt.at)
+            .filter_map(|s| {
+                let ip = s.ip_address.as_deref()?;
+                Some((s, self.ip_ranges.lookup(ip)?))
+            })
+            .max_by_key(|(s, _)| s.created_at)?;
+
+        let distance_km = previous_location.distance_km(location);
+        // Nearby ranges are often the same city, so short hops never count
+        if distance_km < 100.0 {
+            return None;
+        }
+
+        let hours = ((attempt.at - previous.created_at) as f64 / 3600.0).max(1.0 / 60.0);
+        let speed_kmh = distance_km / hours;
+
+        (speed_kmh > self.config.max_travel_speed_kmh).then_some(RiskSignal::ImpossibleTravel {
+            distance_km: distance_km.round() as u64,
+            speed_kmh:   speed_kmh.round() as u64,
+        })
+    }
+}
+
+impl RiskEvaluator for DefaultRiskEvaluator {
+    fn evaluate(&self, attempt: &LoginAttempt) -> RiskAssessment {
+        let mut signals = Vec::new();
+        let location = attempt
+            .ip_address
+            .and_then(|ip| self.ip_ranges.lookup(ip))
+            .cloned();
+
+        // A first login has nothing to compare against
+        if !attempt.previous_sessions.is_empty() {
+            let known_ip = attempt.previous_sessions.iter().any(|s| {
+                attempt.ip_address.is_some() && s.ip_address.as_deref() == attempt.ip_address
+            });
+            if !known_ip {
+                signals.push(RiskSignal::NewIpAddress);
+            }
+
+            let known_agent = attempt.previous_sessions.iter().any(|s| {
+                attempt.user_agent.is_some() && s.user_agent.as_deref() == attempt.user_agent
+            });
+            if !known_agent {
+                signals.push(RiskSignal::NewUserAgent);
+            }
+
+            if let Some(signal) = location.as_ref().and_then(|l| self.impossible_travel(attempt, l)) {
+                signals.push(signal);
+            }
+        }
+
+        if attempt.recent_failures >= self.config.max_recent_failures {
+            signals.push(RiskSignal::FailedAttempts { count: attempt.recent_failures });
+        }
+
+        let score: u32 = signals.iter().map(RiskSignal::score).sum();
+        let decision = if score >= self.config.deny_score {
+            RiskDecision::Deny
+        } else if score >= self.config.challenge_score {
+            RiskDecision::Challenge
+        } else {
+            RiskDecision::Allow
+        };
+
+        RiskAssessment { decision, score, signals, location }
+    }
+
+    fn failure_window_seconds(&self) -> i64 {
+        self.config.failure_window_minutes * 60
+    }
+}
+
+/// Wrong passwords for the user within the window
+///
+/// Only credential failures count: refusals the login handler records itself
+/// (risk denials, wrong challenge codes) would otherwise feed the next score.
+async fn recent_failures(state: &AppState, user: &User, since: i64) -> AuthResult<u64> {
+    match &state.audit {
+        Some(store) => store.count(&AuditFilter {
+            target_id: Some(user.id.clone()),
+            action: Some(AuditAction::Login),
+            outcome: Some(AuditOutcome::Failure),
+            reason: Some(BAD_PASSWORD_REASON.to_string()),
+            from: Some(since),
+            ..AuditFilter::default()
+        }).await,
+        // Without an audit log only the lockout counter is available
+        None => Ok(user.login_attempts.max(0) as u64),
+    }
+}
+
+/// Evaluate a login whose credentials were correct
+///
+/// Always allows when no evaluator is configured.
+pub async fn assess_login(state: &AppState, user: &User, req: &HttpRequest) -> AuthResult<RiskAssessment> {
+    let Some(evaluator) = state.risk.as_ref() else {
+        return Ok(RiskAssessment::allow());
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let previous_sessions = state.sessions.find_by_user_id(&user.id).await?;
+    let recent_failures = recent_failures(state, user, now - evaluator.failure_window_seconds()).await?;
+
+    let connection_info = req.connection_info();
+    let user_agent = req.headers().get("user-agent").and_then(|v| v.to_str().ok());
+
+    Ok(evaluator.evaluate(&LoginAttempt {
+        ip_address: connection_info.realip_remote_addr(),
+        user_agent,
+        at: now,
+        previous_sessions: &previous_sessions,
+        recent_failures,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use database::utils::generate_id;
+
+    const RANGES: &str = "\
+41.89.0.0,41.89.255.255,KE,-1.2864,36.8172
+81.2.69.0,81.2.69.255,GB,51.5074,-0.1278
+";
+
+    fn evaluator() -> DefaultRiskEvaluator {
+        DefaultRiskEvaluator::new(
+            RiskConfig::default(),
+            IpRangeDatabase::from_csv(RANGES).unwrap(),
+        )
+    }
+
+    fn session(ip: &str, user_agent: &str, created_at: i64) -> SessionModel {
+        SessionModel {
+            id:                 generate_id(),
+            user_id:            generate_id(),
+            access_token_hash:  String::new(),
+            refresh_token_hash: None,
+            device:             None,
+            ip_address:         Some(ip.to_string()),
+            user_agent:         Some(user_agent.to_string()),
+            org_id:             None,
+            created_at,
+            expires_at:         created_at + 3600,
+            last_used_at:       created_at,
+            is_revoked:         false,
+        }
+    }
+
+    fn attempt<'a>(ip: &'a str, user_agent: &'a str, at: i64, sessions: &'a [SessionModel]) -> LoginAttempt<'a> {
+        LoginAttempt {
+            ip_address: Some(ip),
+            user_agent: Some(user_agent),
+            at,
+            previous_sessions: sessions,
+            recent_failures: 0,
+        }
+    }
+
+    #[test]
+    fn test_known_device_is_allowed() {
+        let sessions = [session("41.89.1.1", "Firefox", 1_000)];
+        let assessment = evaluator().evaluate(&attempt("41.89.1.1", "Firefox", 90_000, &sessions));
+
+        assert_eq!(assessment.decision, RiskDecision::Allow);
+        assert!(!assessment.is_risky());
+    }
+
+    #[test]
+    fn test_first_login_is_allowed() {
+        let assessment = evaluator().evaluate(&attempt("41.89.1.1", "Firefox", 1_000, &[]));
+
+        assert_eq!(assessment.decision, RiskDecision::Allow);
+    }
+
+    #[test]
+    fn test_new_ip_and_agent_is_challenged() {
+        let sessions = [session("41.89.1.1", "Firefox", 1_000)];
+        let assessment = evaluator().evaluate(&attempt("41.89.7.7", "curl", 90_000, &sessions));
+
+        assert_eq!(assessment.signals, [RiskSignal::NewIpAddress, RiskSignal::NewUserAgent]);
+        assert_eq!(assessment.decision, RiskDecision::Challenge);
+    }
+
+    #[test]
+    fn test_impossible_travel() {
+        // Nairobi to London in an hour
+        let sessions = [session("41.89.1.1", "Firefox", 1_000)];
+        let assessment = evaluator().evaluate(&attempt("81.2.69.10", "Firefox", 4_600, &sessions));
+
+        assert!(matches!(assessment.signals[1], RiskSignal::ImpossibleTravel { .. }));
+        assert_eq!(assessment.decision, RiskDecision::Challenge);
+
+        // A day later is plausible
+        let assessment = evaluator().evaluate(&attempt("81.2.69.10", "Firefox", 90_000, &sessions));
+        assert_eq!(assessment.signals, [RiskSignal::NewIpAddress]);
+    }
+
+    #[test]
+    fn test_travel_from_new_device_is_denied() {
+        let sessions = [session("41.89.1.1", "Firefox", 1_000)];
+        let assessment = evaluator().evaluate(&attempt("81.2.69.10", "curl", 4_600, &sessions));
+
+        assert_eq!(assessment.decision, RiskDecision::Deny);
+    }
+
+    #[test]
+    fn test_failures_are_flagged() {
+        let sessions = [session("41.89.1.1", "Firefox", 1_000)];
+        let mut attempt = attempt("41.89.1.1", "Firefox", 90_000, &sessions);
+        attempt.recent_failures = 8;
+
+        let assessment = evaluator().evaluate(&attempt);
+        assert_eq!(assessment.signals, [RiskSignal::FailedAttempts { count: 8 }]);
+        assert_eq!(assessment.decision, RiskDecision::Allow);
+        assert!(assessment.is_risky());
+    }
+}
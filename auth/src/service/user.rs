@@ -2,57 +2,349 @@
 //!
 //! Provides business logic for user operations including
 //! user management, validation, and account operations.
+//!
+//! The methods are transport independent - HTTP handlers, gRPC and CLI
+//! front-ends call the same flows and map `AuthError` themselves. Anything
+//! tied to a request (audit entries, risk checks, sessions, sending email)
+//! stays with the caller.
+
+use std::sync::Arc;
 
-use crate::store::user_store::{UserStore};
-use crate::utils::errors::AuthError;
+use crate::config::identifiers::IdentifierConfig;
+use crate::models::profile::ProfileSchema;
+use crate::models::user::{CreateUserInput, UpdateUserInput, User};
+use crate::models::verification::{CreateVerificationCode, VerificationMedium, VerificationPurpose};
+use crate::store::user_store::UserStore;
+use crate::store::verification_store::VerificationStore;
+use crate::utils::errors::{AuthError, AuthResult};
+use crate::utils::types::{SignUpRequest, UpdateUserRequest};
+use crate::utils::usernames;
+use database::transaction::Transactions;
 use database::utils::DbId;
+use utils::hash::{Hash, generate_otp, hash_sha256};
+
+const VERIFICATION_CODE_EXPIRY_SECONDS: i64 = 15 * 60; // 15 minutes
+const MAX_VERIFICATION_ATTEMPTS: i32 = 5;
+
+/// Audit reason for a wrong password - the failure risk checks count
+pub const BAD_PASSWORD_REASON: &str = "invalid_credentials";
+
+/// Why a sign-in was refused
+///
+/// `reason` and `user_id` are for the audit log; callers return `error`.
+#[derive(Debug)]
+pub struct LoginFailure {
+    pub reason:  &'static str,
+    pub user_id: Option<DbId>,
+    pub error:   AuthError,
+}
+
+impl LoginFailure {
+    fn new(reason: &'static str, user_id: Option<&DbId>, error: AuthError) -> Self {
+        Self { reason, user_id: user_id.cloned(), error }
+    }
+}
+
+impl From<LoginFailure> for AuthError {
+    fn from(failure: LoginFailure) -> Self {
+        failure.error
+    }
+}
 
 /// User service for handling user business logic
-pub struct UserService<T: UserStore> {
-    store: T,
+pub struct UserService<T: UserStore + ?Sized = dyn UserStore> {
+    store:          Arc<T>,
+    verifications:  Option<Arc<dyn VerificationStore>>,
+    identifiers:    IdentifierConfig,
+    profile_schema: ProfileSchema,
+    transactions:   Transactions,
+}
+
+/// Verification code created at signup, for the caller to send
+pub struct SignUpCode {
+    pub medium:  VerificationMedium,
+    pub address: String,
+    pub code:    String,
 }
 
-impl<T: UserStore> UserService<T> {
-    /// Create a new user service
-    pub fn new(store: T) -> Self {
-        Self { store }
+impl<T: UserStore + ?Sized> UserService<T> {
+    /// Create a new user service with the default identifier rules and the
+    /// standard profile schema
+    pub fn new(store: Arc<T>) -> Self {
+        Self {
+            store,
+            verifications: None,
+            identifiers: IdentifierConfig::default(),
+            profile_schema: ProfileSchema::standard(),
+            transactions: Transactions::disabled(),
+        }
+    }
+
+    pub fn with_verifications(mut self, verifications: Arc<dyn VerificationStore>) -> Self {
+        self.verifications = Some(verifications);
+        self
+    }
+
+    pub fn with_identifiers(mut self, identifiers: IdentifierConfig) -> Self {
+        self.identifiers = identifiers;
+        self
+    }
+
+    pub fn with_profile_schema(mut self, profile_schema: ProfileSchema) -> Self {
+        self.profile_schema = profile_schema;
+        self
+    }
+
+    pub fn with_transactions(mut self, transactions: Transactions) -> Self {
+        self.transactions = transactions;
+        self
     }
 
     /// Check if a user exists by email
-    pub fn email_exists(&self, email: &str) -> Result<bool, AuthError> {
-        let user = self.store.find_by_email(email)?;
+    pub async fn email_exists(&self, email: &str) -> Result<bool, AuthError> {
+        let user = self.store.find_by_email(&self.identifiers.email(email)?).await?;
         Ok(user.is_some())
     }
 
     /// Check if a user exists by phone
-    pub fn phone_exists(&self, phone: &str) -> Result<bool, AuthError> {
-        let user = self.store.find_by_phone(phone)?;
+    pub async fn phone_exists(&self, phone: &str) -> Result<bool, AuthError> {
+        let user = self.store.find_by_phone(&self.identifiers.phone(phone)?).await?;
         Ok(user.is_some())
     }
 
     /// Check if a user exists by username
-    pub fn username_exists(&self, username: &str) -> Result<bool, AuthError> {
-        let user = self.store.find_by_username(username)?;
+    pub async fn username_exists(&self, username: &str) -> Result<bool, AuthError> {
+        let user = self.store.find_by_username(username).await?;
         Ok(user.is_some())
     }
 
     /// Check if a user exists by any identifier (email, phone, or username)
-    pub fn user_exists(&self, identifier: &str) -> Result<bool, AuthError> {
-        let user = self.store.find_by_identifier(identifier)?;
+    pub async fn user_exists(&self, identifier: &str) -> Result<bool, AuthError> {
+        let user = self.find_by_identifier(identifier).await?;
         Ok(user.is_some())
     }
 
     /// Check if a user exists by ID
-    pub fn user_exists_by_id(&self, user_id: &DbId) -> Result<bool, AuthError> {
-        let user = self.store.find_by_id(user_id)?;
+    pub async fn user_exists_by_id(&self, user_id: &DbId) -> Result<bool, AuthError> {
+        let user = self.store.find_by_id(user_id).await?;
         Ok(user.is_some())
     }
 
-}
+    /// Find a user by email, phone or username (normalized first)
+    pub async fn find_by_identifier(&self, identifier: &str) -> AuthResult<Option<User>> {
+        let user = self.store.find_by_identifier(&self.identifiers.identifier(identifier)).await?;
+        Ok(user.filter(|u| u.deleted_at.is_none()))
+    }
 
-impl<T: UserStore> Default for UserService<T> {
-    fn default() -> Self {
-        // This is a placeholder - in practice, you'd need to inject the store
-        panic!("UserService cannot be created without a store. Use UserService::new(store) instead.")
+    /// Find a user that has not been deleted
+    pub async fn find_user(&self, user_id: &DbId) -> AuthResult<User> {
+        self.store
+            .find_by_id(user_id)
+            .await?
+            .filter(|u| u.deleted_at.is_none())
+            .ok_or_else(|| AuthError::not_found("User not found"))
     }
-}
\ No newline at end of file
+
+    /// Validate a username and make sure nobody else holds it (or a lookalike)
+    ///
+    /// Returns the normalized username to store.
+    pub async fn claim_username(&self, username: &str, owner: Option<&DbId>) -> AuthResult<String> {
+        let normalized = usernames::validate(username).map_err(AuthError::invalid_username)?;
+
+        if let Some(existing) = self.store.find_by_username(&normalized).await? {
+            if Some(&existing.id) != owner {
+                return Err(AuthError::username_already_exists(&normalized));
+            }
+        }
+
+        Ok(normalized)
+    }
+
+    /// Register a new user
+    ///
+    /// With a verification store configured, the code confirming the email
+    /// (or else the phone) is created in the same unit of work as the user.
+    pub async fn signup(&self, input: SignUpRequest) -> AuthResult<(User, Option<SignUpCode>)> {
+        // Normalize identifiers so one address or number can't hold two accounts
+        let email = input.email.as_deref().map(|e| self.identifiers.email(e)).transpose()?;
+        let phone = input.phone.as_deref().map(|p| self.identifiers.phone(p)).transpose()?;
+
+        if let Some(ref email) = email {
+            if self.store.find_by_email(email).await?.is_some() {
+                return Err(AuthError::email_already_exists(email));
+            }
+        }
+
+        if let Some(ref phone) = phone {
+            if self.store.find_by_phone(phone).await?.is_some() {
+                return Err(AuthError::phone_already_exists(phone));
+            }
+        }
+
+        let username = match input.username.as_deref() {
+            Some(username) => Some(self.claim_username(username, None).await?),
+            None => None,
+        };
+
+        let password = Hash::argon2(&input.password)
+            .map_err(|e| AuthError::internal_error(&e.to_string()))?
+            .to_string();
+
+        let medium = if email.is_some() { VerificationMedium::Email } else { VerificationMedium::Phone };
+        let input = CreateUserInput { email, phone, username, password };
+
+        self.transactions
+            .run(|| async {
+                let user = self.store.create(input.clone()).await?;
+                if self.verifications.is_none() || (user.email.is_none() && user.phone.is_none()) {
+                    return Ok((user, None));
+                }
+
+                let (address, code) = self.start_verification(&user, medium.clone()).await?;
+                let code = SignUpCode { medium: medium.clone(), address, code };
+                Ok((user, Some(code)))
+            })
+            .await
+    }
+
+    /// Check a password sign-in
+    ///
+    /// Unknown users and wrong passwords get the same error.
+    pub async fn authenticate(&self, identifier: &str, password: &str) -> Result<User, LoginFailure> {
+        let identifier = self.identifiers.identifier(identifier);
+
+        let user = match self.store.find_by_identifier(&identifier).await {
+            Ok(Some(user)) => user,
+            Ok(None) => {
+                return Err(LoginFailure::new("unknown_user", None, AuthError::invalid_credentials()));
+            }
+            Err(e) => return Err(LoginFailure::new("lookup_failed", None, e)),
+        };
+
+        // Deactivated users and invitees who have not accepted yet cannot sign in
+        if !user.is_active() {
+            return Err(LoginFailure::new("account_disabled", Some(&user.id), AuthError::account_disabled()));
+        }
+
+        if user.is_locked() {
+            return Err(LoginFailure::new("account_locked", Some(&user.id), AuthError::account_locked()));
+        }
+
+        let password_valid = Hash::from_string(&user.password_hash)
+            .and_then(|hash| hash.verify(password))
+            .map_err(|e| {
+                LoginFailure::new("hash_error", Some(&user.id), AuthError::internal_error(&e.to_string()))
+            })?;
+
+        if !password_valid {
+            // Counting is best effort; the sign-in fails either way
+            let _ = self.store.record_failed_login(&user.id).await;
+            return Err(LoginFailure::new(
+                BAD_PASSWORD_REASON,
+                Some(&user.id),
+                AuthError::invalid_credentials(),
+            ));
+        }
+
+        // The returned user keeps the count so the risk check can still see it
+        if user.login_attempts > 0 {
+            let _ = self.store.reset_login_attempts(&user.id).await;
+        }
+
+        Ok(user)
+    }
+
+    fn verification_store(&self) -> AuthResult<&Arc<dyn VerificationStore>> {
+        self.verifications
+            .as_ref()
+            .ok_or_else(|| AuthError::internal_error("Verification store not configured"))
+    }
+
+    /// Create a code confirming the user's email or phone
+    ///
+    /// Returns the address to send it to and the plain code. Earlier codes
+    /// stop working.
+    pub async fn start_verification(
+        &self,
+        user:   &User,
+        medium: VerificationMedium,
+    ) -> AuthResult<(String, String)> {
+        let store = self.verification_store()?;
+
+        if user.is_verified {
+            return Err(AuthError::already_verified());
+        }
+
+        let address = match medium {
+            VerificationMedium::Email => user.email.clone(),
+            VerificationMedium::Phone => user.phone.clone(),
+        }
+        .ok_or_else(|| AuthError::invalid_request("No address to verify"))?;
+
+        if let Some(previous) = store.find_valid_code(&user.id, medium.clone(), VerificationPurpose::SignUp).await? {
+            store.delete(&previous.id).await?;
+        }
+
+        let code = generate_otp(6);
+        store.create(CreateVerificationCode {
+            user_id:    user.id.clone(),
+            code_hash:  hash_sha256(&code),
+            medium,
+            purpose:    VerificationPurpose::SignUp,
+            expires_in: VERIFICATION_CODE_EXPIRY_SECONDS,
+        }).await?;
+
+        Ok((address, code))
+    }
+
+    /// Check a verification code and mark the user verified
+    pub async fn verify(&self, user_id: &DbId, medium: VerificationMedium, code: &str) -> AuthResult<User> {
+        let store = self.verification_store()?;
+        let user = self.find_user(user_id).await?;
+
+        if user.is_verified {
+            return Err(AuthError::already_verified());
+        }
+
+        let challenge = store
+            .find_valid_code(&user.id, medium, VerificationPurpose::SignUp)
+            .await?
+            .ok_or_else(AuthError::verification_code_expired)?;
+
+        if challenge.attempts >= MAX_VERIFICATION_ATTEMPTS {
+            return Err(AuthError::too_many_attempts());
+        }
+
+        if hash_sha256(code.trim()) != challenge.code_hash {
+            store.increment_attempts(&challenge.id).await?;
+            return Err(AuthError::invalid_verification_code());
+        }
+
+        store.verify(&challenge.id).await?;
+        self.store.set_verified(&user.id, true).await?;
+
+        self.find_user(&user.id).await
+    }
+
+    /// Update names, username and profile fields
+    pub async fn update_profile(&self, user_id: &DbId, changes: UpdateUserRequest) -> AuthResult<User> {
+        let profile = changes
+            .profile
+            .map(|profile| self.profile_schema.validate_changes(&profile))
+            .transpose()?;
+        let username = match changes.username {
+            Some(username) => Some(self.claim_username(&username, Some(user_id)).await?),
+            None => None,
+        };
+
+        self.store.update(
+            user_id,
+            UpdateUserInput {
+                username,
+                first_name: changes.first_name,
+                last_name: changes.last_name,
+                profile,
+            },
+        ).await
+    }
+}
@@ -0,0 +1,683 @@
+//! WebAuthn Service
+//!
+//! Builds passkey ceremony options and verifies attestation (registration)
+//! and assertion (authentication) responses from the browser.
+//!
+//! Only ES256 (ECDSA P-256 with SHA-256) credentials are supported. Attestation
+//! is requested as "none", so "none" and self-attested "packed" statements are
+//! accepted; attestation certificate chains are not evaluated.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL;
+use ciborium::value::Value;
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::config::webauthn::WebAuthnConfig;
+use crate::models::passkey::{
+    AuthenticationCredential, AuthenticatorSelection, PasskeyAuthenticationOptions,
+    PasskeyCredential, PasskeyRegistrationOptions, PasskeyUserEntity,
+    PublicKeyCredentialDescriptor, PublicKeyCredentialParameters, RegistrationCredential,
+    RelyingParty,
+};
+use crate::models::user::User;
+use crate::utils::errors::{AuthError, AuthResult};
+
+/// COSE algorithm identifier for ES256
+pub const COSE_ALG_ES256: i64 = -7;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+/// Client data collected by the browser
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClientData {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub challenge: String,
+    pub origin: String,
+}
+
+/// Parsed authenticator data
+#[derive(Debug, Clone)]
+pub struct AuthenticatorData {
+    pub rp_id_hash: [u8; 32],
+    pub flags: u8,
+    pub sign_count: u32,
+    pub attested_credential: Option<AttestedCredential>,
+}
+
+/// Credential data attached to authenticator data during registration
+#[derive(Debug, Clone)]
+pub struct AttestedCredential {
+    pub aaguid: [u8; 16],
+    pub credential_id: Vec<u8>,
+    pub public_key: Vec<u8>, // SEC1 uncompressed point
+}
+
+/// Result of a successful registration ceremony
+#[derive(Debug, Clone)]
+pub struct VerifiedRegistration {
+    pub credential_id: String, // base64url
+    pub public_key: String,    // base64url SEC1
+    pub sign_count: u32,
+    pub aaguid: String,
+}
+
+/// WebAuthn service for passkey ceremonies
+#[derive(Clone)]
+pub struct WebAuthnService {
+    config: WebAuthnConfig,
+}
+
+impl WebAuthnService {
+    /// Create new WebAuthn service with configuration
+    pub fn new(config: WebAuthnConfig) -> Self {
+        Self { config }
+    }
+
+    /// Get the relying party configuration
+    pub fn config(&self) -> &WebAuthnConfig {
+        &self.config
+    }
+
+    /// Generate a random base64url challenge (32 bytes)
+    pub fn generate_challenge() -> String {
+        use rand::RngCore;
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        BASE64URL.encode(bytes)
+    }
+
+    /// Build registration options for a user
+    pub fn registration_options(
+        &self,
+        user: &User,
+        existing: &[PasskeyCredential],
+        challenge: &str,
+    ) -> PasskeyRegistrationOptions {
+        let name = user
+            .username
+            .clone()
+            .or_else(|| user.email.clone())
+            .or_else(|| user.phone.clone())
+            .unwrap_or_else(|| user.id.to_string());
+
+        let display_name = match (&user.first_name, &user.last_name) {
+            (Some(first), Some(last)) => format!("{} {}", first, last),
+            (Some(first), None) => first.clone(),
+            _ => name.clone(),
+        };
+
+        PasskeyRegistrationOptions {
+            rp: RelyingParty {
+                id:   self.config.rp_id.clone(),
+                name: self.config.rp_name.clone(),
+            },
+            user: PasskeyUserEntity {
+                id: BASE64URL.encode(user.id.to_bytes()),
+                name,
+                display_name,
+            },
+            challenge: challenge.to_string(),
+            pub_key_cred_params: vec![PublicKeyCredentialParameters {
+                kind: "public-key".to_string(),
+                alg:  COSE_ALG_ES256,
+            }],
+            timeout: self.config.timeout_ms,
+            attestation: "none".to_string(),
+            authenticator_selection: AuthenticatorSelection {
+                resident_key:      "preferred".to_string(),
+                user_verification: self.config.user_verification().to_string(),
+            },
+            exclude_credentials: existing.iter().map(Self::descriptor).collect(),
+        }
+    }
+
+    /// Build authentication options, optionally restricted to known credentials
+    pub fn authentication_options(
+        &self,
+        allowed: &[PasskeyCredential],
+        challenge: &str,
+    ) -> PasskeyAuthenticationOptions {
+        PasskeyAuthenticationOptions {
+            challenge: challenge.to_string(),
+            timeout: self.config.timeout_ms,
+            rp_id: self.config.rp_id.clone(),
+            allow_credentials: allowed.iter().map(Self::descriptor).collect(),
+            user_verification: self.config.user_verification().to_string(),
+        }
+    }
+
+    /// Read the challenge echoed in a base64url clientDataJSON, used to look up the stored challenge
+    pub fn client_data_challenge(client_data_json: &str) -> AuthResult<String> {
+        Ok(Self::parse_client_data(client_data_json)?.challenge)
+    }
+
+    /// Verify a registration (attestation) response
+    pub fn verify_registration(
+        &self,
+        credential: &RegistrationCredential,
+        expected_challenge: &str,
+    ) -> AuthResult<VerifiedRegistration> {
+        let client_data_raw = decode(&credential.response.client_data_json, "clientDataJSON")?;
+        let client_data = Self::parse_client_data(&credential.response.client_data_json)?;
+        self.check_client_data(&client_data, "webauthn.create", expected_challenge)?;
+
+        let attestation_raw = decode(&credential.response.attestation_object, "attestationObject")?;
+        let attestation: Value = ciborium::de::from_reader(attestation_raw.as_slice())
+            .map_err(|_| AuthError::invalid_passkey("Malformed attestation object"))?;
+
+        let fmt = map_get_text(&attestation, "fmt")
+            .and_then(|v| v.as_text().map(String::from))
+            .ok_or_else(|| AuthError::invalid_passkey("Attestation format missing"))?;
+        let auth_data_raw = map_get_text(&attestation, "authData")
+            .and_then(|v| v.as_bytes().cloned())
+            .ok_or_else(|| AuthError::invalid_passkey("Authenticator data missing"))?;
+
+        let auth_data = Self::parse_authenticator_data(&auth_data_raw)?;
+        self.check_authenticator_data(&auth_data)?;
+
+        let attested = auth_data
+            .attested_credential
+            .clone()
+            .ok_or_else(|| AuthError::invalid_passkey("No credential in attestation"))?;
+
+        let credential_id = BASE64URL.encode(&attested.credential_id);
+        if credential_id != credential.id {
+            return Err(AuthError::invalid_passkey("Credential ID mismatch"));
+        }
+
+        match fmt.as_str() {
+            "none" => {}
+            "packed" => {
+                let att_stmt = map_get_text(&attestation, "attStmt")
+                    .ok_or_else(|| AuthError::invalid_passkey("Attestation statement missing"))?;
+
+                if map_get_text(att_stmt, "x5c").is_some() {
+                    return Err(AuthError::invalid_passkey(
+                        "Certificate attestation is not supported",
+                    ));
+                }
+
+                let alg = map_get_text(att_stmt, "alg")
+                    .and_then(value_to_i64)
+                    .ok_or_else(|| AuthError::invalid_passkey("Attestation algorithm missing"))?;
+                if alg != COSE_ALG_ES256 {
+                    return Err(AuthError::invalid_passkey("Unsupported attestation algorithm"));
+                }
+
+                let sig = map_get_text(att_stmt, "sig")
+                    .and_then(|v| v.as_bytes().cloned())
+                    .ok_or_else(|| AuthError::invalid_passkey("Attestation signature missing"))?;
+
+                verify_signature(&attested.public_key, &auth_data_raw, &client_data_raw, &sig)?;
+            }
+            other => {
+                return Err(AuthError::invalid_passkey(&format!(
+                    "Unsupported attestation format: {}",
+                    other
+                )));
+            }
+        }
+
+        Ok(VerifiedRegistration {
+            credential_id,
+            public_key: BASE64URL.encode(&attested.public_key),
+            sign_count: auth_data.sign_count,
+            aaguid: attested.aaguid.iter().map(|b| format!("{:02x}", b)).collect(),
+        })
+    }
+
+    /// Verify an authentication (assertion) response against a stored credential
+    ///
+    /// Returns the new signature counter to persist.
+    pub fn verify_assertion(
+        &self,
+        credential: &AuthenticationCredential,
+        expected_challenge: &str,
+        stored: &PasskeyCredential,
+    ) -> AuthResult<u32> {
+        if credential.id != stored.credential_id {
+            return Err(AuthError::invalid_passkey("Credential ID mismatch"));
+        }
+
+        let client_data_raw = decode(&credential.response.client_data_json, "clientDataJSON")?;
+        let client_data = Self::parse_client_data(&credential.response.client_data_json)?;
+        self.check_client_data(&client_data, "webauthn.get", expected_challenge)?;
+
+        let auth_data_raw = decode(&credential.response.authenticator_data, "authenticatorData")?;
+        let auth_data = Self::parse_authenticator_data(&auth_data_raw)?;
+        self.check_authenticator_data(&auth_data)?;
+
+        let public_key = decode(&stored.public_key, "public key")?;
+        let signature = decode(&credential.response.signature, "signature")?;
+        verify_signature(&public_key, &auth_data_raw, &client_data_raw, &signature)?;
+
+        // A counter that does not increase indicates a cloned authenticator.
+        // Authenticators that do not implement counters always report zero.
+        if (auth_data.sign_count != 0 || stored.sign_count != 0)
+            && auth_data.sign_count <= stored.sign_count
+        {
+            return Err(AuthError::invalid_passkey(
+                "Signature counter did not increase; possible cloned authenticator",
+            ));
+        }
+
+        Ok(auth_data.sign_count)
+    }
+
+    /// Parse base64url clientDataJSON
+    pub fn parse_client_data(client_data_json: &str) -> AuthResult<ClientData> {
+        let raw = decode(client_data_json, "clientDataJSON")?;
+        serde_json::from_slice(&raw).map_err(|_| AuthError::invalid_passkey("Malformed client data"))
+    }
+
+    /// Parse raw authenticator data
+    pub fn parse_authenticator_data(data: &[u8]) -> AuthResult<AuthenticatorData> {
+        if data.len() < 37 {
+            return Err(AuthError::invalid_passkey("Authenticator data too short"));
+        }
+
+        let mut rp_id_hash = [0u8; 32];
+        rp_id_hash.copy_from_slice(&data[0..32]);
+        let flags = data[32];
+        let sign_count = u32::from_be_bytes([data[33], data[34], data[35], data[36]]);
+
+        let attested_credential = if flags & FLAG_ATTESTED_CREDENTIAL != 0 {
+            let rest = &data[37..];
+            if rest.len() < 18 {
+                return Err(AuthError::invalid_passkey("Attested credential data too short"));
+            }
+
+            let mut aaguid = [0u8; 16];
+            aaguid.copy_from_slice(&rest[0..16]);
+            let id_len = u16::from_be_bytes([rest[16], rest[17]]) as usize;
+            if rest.len() < 18 + id_len {
+                return Err(AuthError::invalid_passkey("Credential ID truncated"));
+            }
+
+            let credential_id = rest[18..18 + id_len].to_vec();
+            let mut cose_key = &rest[18 + id_len..];
+            let key: Value = ciborium::de::from_reader(&mut cose_key)
+                .map_err(|_| AuthError::invalid_passkey("Malformed credential public key"))?;
+
+            Some(AttestedCredential {
+                aaguid,
+                credential_id,
+                public_key: cose_key_to_sec1(&key)?,
+            })
+        } else {
+            None
+        };
+
+        Ok(AuthenticatorData {
+            rp_id_hash,
+            flags,
+            sign_count,
+            attested_credential,
+        })
+    }
+
+    fn check_client_data(
+        &self,
+        client_data: &ClientData,
+        expected_type: &str,
+        expected_challenge: &str,
+    ) -> AuthResult<()> {
+        if client_data.kind != expected_type {
+            return Err(AuthError::invalid_passkey("Unexpected ceremony type"));
+        }
+        if client_data.challenge != expected_challenge {
+            return Err(AuthError::invalid_passkey("Challenge mismatch"));
+        }
+        if client_data.origin.trim_end_matches('/') != self.config.origin.trim_end_matches('/') {
+            return Err(AuthError::invalid_passkey("Origin mismatch"));
+        }
+        Ok(())
+    }
+
+    fn check_authenticator_data(&self, auth_data: &AuthenticatorData) -> AuthResult<()> {
+        let expected_hash = Sha256::digest(self.config.rp_id.as_bytes());
+        if auth_data.rp_id_hash[..] != expected_hash[..] {
+            return Err(AuthError::invalid_passkey("Relying party ID mismatch"));
+        }
+        if auth_data.flags & FLAG_USER_PRESENT == 0 {
+            return Err(AuthError::invalid_passkey("User presence not confirmed"));
+        }
+        if self.config.require_user_verification && auth_data.flags & FLAG_USER_VERIFIED == 0 {
+            return Err(AuthError::invalid_passkey("User verification required"));
+        }
+        Ok(())
+    }
+
+    fn descriptor(credential: &PasskeyCredential) -> PublicKeyCredentialDescriptor {
+        PublicKeyCredentialDescriptor {
+            kind: "public-key".to_string(),
+            id: credential.credential_id.clone(),
+            transports: credential.transports.clone(),
+        }
+    }
+}
+
+fn decode(value: &str, field: &str) -> AuthResult<Vec<u8>> {
+    BASE64URL
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| AuthError::invalid_passkey(&format!("Invalid base64url in {}", field)))
+}
+
+/// Verify an ES256 signature over `authenticatorData || SHA-256(clientDataJSON)`
+fn verify_signature(
+    public_key: &[u8],
+    auth_data: &[u8],
+    client_data: &[u8],
+    signature: &[u8],
+) -> AuthResult<()> {
+    let key = VerifyingKey::from_sec1_bytes(public_key)
+        .map_err(|_| AuthError::invalid_passkey("Invalid credential public key"))?;
+    let signature = Signature::from_der(signature)
+        .map_err(|_| AuthError::invalid_passkey("Malformed signature"))?;
+
+    let mut signed = auth_data.to_vec();
+    signed.extend_from_slice(&Sha256::digest(client_data));
+
+    key.verify(&signed, &signature)
+        .map_err(|_| AuthError::invalid_passkey("Signature verification failed"))
+}
+
+/// Convert a COSE EC2 P-256 key to a SEC1 uncompressed point
+fn cose_key_to_sec1(key: &Value) -> AuthResult<Vec<u8>> {
+    let kty = map_get_int(key, 1).and_then(value_to_i64);
+    let alg = map_get_int(key, 3).and_then(value_to_i64);
+    let crv = map_get_int(key, -1).and_then(value_to_i64);
+
+    if kty != Some(2) || crv != Some(1) || alg.is_some_and(|a| a != COSE_ALG_ES256) {
+        return Err(AuthError::invalid_passkey("Only ES256 passkeys are supported"));
+    }
+
+    let x = map_get_int(key, -2).and_then(|v| v.as_bytes());
+    let y = map_get_int(key, -3).and_then(|v| v.as_bytes());
+
+    match (x, y) {
+        (Some(x), Some(y)) if x.len() == 32 && y.len() == 32 => {
+            let mut point = Vec::with_capacity(65);
+            point.push(0x04);
+            point.extend_from_slice(x);
+            point.extend_from_slice(y);
+            Ok(point)
+        }
+        _ => Err(AuthError::invalid_passkey("Invalid credential public key coordinates")),
+    }
+}
+
+fn map_get_text<'a>(map: &'a Value, key: &str) -> Option<&'a Value> {
+    map.as_map()?
+        .iter()
+        .find(|(k, _)| k.as_text() == Some(key))
+        .map(|(_, v)| v)
+}
+
+fn map_get_int(map: &Value, key: i64) -> Option<&Value> {
+    map.as_map()?
+        .iter()
+        .find(|(k, _)| value_to_i64(k) == Some(key))
+        .map(|(_, v)| v)
+}
+
+fn value_to_i64(value: &Value) -> Option<i64> {
+    value.as_integer().and_then(|i| i64::try_from(i).ok())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use database::utils::generate_id;
+    use p256::ecdsa::{SigningKey, signature::Signer};
+
+    use crate::models::passkey::{AssertionResponse, AttestationResponse};
+
+    pub(crate) const RP_ID: &str = "example.com";
+    pub(crate) const ORIGIN: &str = "https://example.com";
+
+    /// Software authenticator used as a test fixture
+    pub(crate) struct SoftAuthenticator {
+        key: SigningKey,
+        credential_id: Vec<u8>,
+        counter: u32,
+    }
+
+    impl SoftAuthenticator {
+        pub(crate) fn new() -> Self {
+            use rand::RngCore;
+            let mut secret = [0u8; 32];
+            rand::thread_rng().fill_bytes(&mut secret);
+            secret[0] = 1; // keep the scalar well below the curve order
+
+            let mut credential_id = vec![0u8; 16];
+            rand::thread_rng().fill_bytes(&mut credential_id);
+
+            Self {
+                key: SigningKey::from_slice(&secret).unwrap(),
+                credential_id,
+                counter: 0,
+            }
+        }
+
+        fn credential_id(&self) -> String {
+            BASE64URL.encode(&self.credential_id)
+        }
+
+        fn cose_key(&self) -> Vec<u8> {
+            let point = self.key.verifying_key().to_encoded_point(false);
+            let key = Value::Map(vec![
+                (Value::from(1), Value::from(2)),
+                (Value::from(3), Value::from(COSE_ALG_ES256)),
+                (Value::from(-1), Value::from(1)),
+                (Value::from(-2), Value::Bytes(point.x().unwrap().to_vec())),
+                (Value::from(-3), Value::Bytes(point.y().unwrap().to_vec())),
+            ]);
+            let mut out = Vec::new();
+            ciborium::ser::into_writer(&key, &mut out).unwrap();
+            out
+        }
+
+        fn auth_data(&self, rp_id: &str, flags: u8, attested: bool) -> Vec<u8> {
+            let mut data = Sha256::digest(rp_id.as_bytes()).to_vec();
+            data.push(flags);
+            data.extend_from_slice(&self.counter.to_be_bytes());
+            if attested {
+                data.extend_from_slice(&[0u8; 16]);
+                data.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+                data.extend_from_slice(&self.credential_id);
+                data.extend_from_slice(&self.cose_key());
+            }
+            data
+        }
+
+        fn client_data(kind: &str, challenge: &str, origin: &str) -> Vec<u8> {
+            serde_json::json!({ "type": kind, "challenge": challenge, "origin": origin })
+                .to_string()
+                .into_bytes()
+        }
+
+        fn sign(&self, auth_data: &[u8], client_data: &[u8]) -> Vec<u8> {
+            let mut signed = auth_data.to_vec();
+            signed.extend_from_slice(&Sha256::digest(client_data));
+            let signature: Signature = self.key.sign(&signed);
+            signature.to_der().as_bytes().to_vec()
+        }
+
+        pub(crate) fn register(&self, challenge: &str, fmt: &str) -> RegistrationCredential {
+            let client_data = Self::client_data("webauthn.create", challenge, ORIGIN);
+            let auth_data = self.auth_data(RP_ID, FLAG_USER_PRESENT | FLAG_ATTESTED_CREDENTIAL, true);
+
+            let att_stmt = if fmt == "packed" {
+                Value::Map(vec![
+                    (Value::from("alg"), Value::from(COSE_ALG_ES256)),
+                    (Value::from("sig"), Value::Bytes(self.sign(&auth_data, &client_data))),
+                ])
+            } else {
+                Value::Map(vec![])
+            };
+
+            let attestation = Value::Map(vec![
+                (Value::from("fmt"), Value::from(fmt)),
+                (Value::from("attStmt"), att_stmt),
+                (Value::from("authData"), Value::Bytes(auth_data)),
+            ]);
+            let mut attestation_object = Vec::new();
+            ciborium::ser::into_writer(&attestation, &mut attestation_object).unwrap();
+
+            RegistrationCredential {
+                id: self.credential_id(),
+                response: AttestationResponse {
+                    client_data_json: BASE64URL.encode(client_data),
+                    attestation_object: BASE64URL.encode(attestation_object),
+                    transports: vec!["internal".to_string()],
+                },
+            }
+        }
+
+        pub(crate) fn assert(&mut self, challenge: &str, origin: &str) -> AuthenticationCredential {
+            self.counter += 1;
+            let client_data = Self::client_data("webauthn.get", challenge, origin);
+            let auth_data = self.auth_data(RP_ID, FLAG_USER_PRESENT | FLAG_USER_VERIFIED, false);
+
+            AuthenticationCredential {
+                id: self.credential_id(),
+                response: AssertionResponse {
+                    client_data_json: BASE64URL.encode(&client_data),
+                    authenticator_data: BASE64URL.encode(&auth_data),
+                    signature: BASE64URL.encode(self.sign(&auth_data, &client_data)),
+                    user_handle: None,
+                },
+            }
+        }
+    }
+
+    fn service() -> WebAuthnService {
+        WebAuthnService::new(WebAuthnConfig::new(RP_ID, "Example", ORIGIN))
+    }
+
+    fn stored(registration: &VerifiedRegistration) -> PasskeyCredential {
+        PasskeyCredential {
+            id: generate_id(),
+            user_id: generate_id(),
+            credential_id: registration.credential_id.clone(),
+            public_key: registration.public_key.clone(),
+            sign_count: registration.sign_count,
+            aaguid: Some(registration.aaguid.clone()),
+            name: None,
+            transports: vec![],
+            created_at: 0,
+            last_used_at: None,
+        }
+    }
+
+    #[test]
+    fn test_register_and_authenticate() {
+        let service = service();
+        let mut authenticator = SoftAuthenticator::new();
+
+        let challenge = WebAuthnService::generate_challenge();
+        let registration = service
+            .verify_registration(&authenticator.register(&challenge, "none"), &challenge)
+            .expect("registration should verify");
+        assert_eq!(registration.credential_id, authenticator.credential_id());
+        assert_eq!(registration.sign_count, 0);
+
+        let challenge = WebAuthnService::generate_challenge();
+        let assertion = authenticator.assert(&challenge, ORIGIN);
+        let count = service
+            .verify_assertion(&assertion, &challenge, &stored(&registration))
+            .expect("assertion should verify");
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_packed_self_attestation() {
+        let service = service();
+        let authenticator = SoftAuthenticator::new();
+
+        let challenge = WebAuthnService::generate_challenge();
+        let result =
+            service.verify_registration(&authenticator.register(&challenge, "packed"), &challenge);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_registration_challenge_mismatch() {
+        let service = service();
+        let authenticator = SoftAuthenticator::new();
+
+        let challenge = WebAuthnService::generate_challenge();
+        let other = WebAuthnService::generate_challenge();
+        let result = service.verify_registration(&authenticator.register(&challenge, "none"), &other);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_assertion_wrong_origin() {
+        let service = service();
+        let mut authenticator = SoftAuthenticator::new();
+
+        let challenge = WebAuthnService::generate_challenge();
+        let registration = service
+            .verify_registration(&authenticator.register(&challenge, "none"), &challenge)
+            .unwrap();
+
+        let challenge = WebAuthnService::generate_challenge();
+        let assertion = authenticator.assert(&challenge, "https://evil.example.net");
+        let result = service.verify_assertion(&assertion, &challenge, &stored(&registration));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_assertion_tampered_signature() {
+        let service = service();
+        let mut authenticator = SoftAuthenticator::new();
+
+        let challenge = WebAuthnService::generate_challenge();
+        let registration = service
+            .verify_registration(&authenticator.register(&challenge, "none"), &challenge)
+            .unwrap();
+
+        let challenge = WebAuthnService::generate_challenge();
+        let mut assertion = authenticator.assert(&challenge, ORIGIN);
+        let other = SoftAuthenticator::new();
+        let auth_data = decode(&assertion.response.authenticator_data, "authData").unwrap();
+        let client_data = decode(&assertion.response.client_data_json, "clientData").unwrap();
+        assertion.response.signature = BASE64URL.encode(other.sign(&auth_data, &client_data));
+
+        let result = service.verify_assertion(&assertion, &challenge, &stored(&registration));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_assertion_counter_replay() {
+        let service = service();
+        let mut authenticator = SoftAuthenticator::new();
+
+        let challenge = WebAuthnService::generate_challenge();
+        let registration = service
+            .verify_registration(&authenticator.register(&challenge, "none"), &challenge)
+            .unwrap();
+        let mut credential = stored(&registration);
+        credential.sign_count = 5;
+
+        let challenge = WebAuthnService::generate_challenge();
+        let assertion = authenticator.assert(&challenge, ORIGIN);
+        let result = service.verify_assertion(&assertion, &challenge, &credential);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_client_data_challenge() {
+        let client_data = SoftAuthenticator::client_data("webauthn.get", "abc", ORIGIN);
+        let challenge = WebAuthnService::client_data_challenge(&BASE64URL.encode(client_data));
+        assert_eq!(challenge.unwrap(), "abc");
+    }
+}
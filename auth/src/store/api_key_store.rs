@@ -0,0 +1,36 @@
+//! API key store module
+//!
+//! Provides a generic API key store that works with any database.
+
+use async_trait::async_trait;
+use crate::models::api_key::{ApiKey, CreateApiKey};
+use crate::utils::errors::AuthResult;
+use database::utils::DbId;
+
+/// API key store trait - implement this for each database
+#[async_trait]
+pub trait ApiKeyStore: Send + Sync {
+    /// Create a new API key
+    async fn create(&self, input: CreateApiKey) -> AuthResult<ApiKey>;
+
+    /// Find API key by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<ApiKey>>;
+
+    /// Find API key by key hash
+    async fn find_by_hash(&self, key_hash: &str) -> AuthResult<Option<ApiKey>>;
+
+    /// List all API keys for a user
+    async fn list_by_user(&self, user_id: &DbId) -> AuthResult<Vec<ApiKey>>;
+
+    /// Record that a key was used
+    async fn touch(&self, id: &DbId, ip_address: Option<&str>) -> AuthResult<()>;
+
+    /// Revoke an API key
+    async fn revoke(&self, id: &DbId) -> AuthResult<()>;
+
+    /// Revoke all API keys for a user
+    async fn revoke_all_for_user(&self, user_id: &DbId) -> AuthResult<u64>;
+
+    /// Delete all API keys of a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64>;
+}
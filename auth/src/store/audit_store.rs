@@ -0,0 +1,32 @@
+//! Audit store module
+//!
+//! Provides a generic store for the security audit log.
+
+use async_trait::async_trait;
+use crate::models::audit::{AuditEvent, AuditFilter, CreateAuditEvent};
+use crate::utils::errors::AuthResult;
+use database::pagination::{Page, PageRequest};
+use database::utils::DbId;
+
+/// Audit store trait - implement this for each database
+#[async_trait]
+pub trait AuditStore: Send + Sync {
+    /// Append an event
+    async fn record(&self, input: CreateAuditEvent) -> AuthResult<AuditEvent>;
+
+    /// List events matching a filter, newest first (with pagination)
+    async fn list(&self, filter: &AuditFilter, page: u32, limit: u32) -> AuthResult<Vec<AuditEvent>>;
+
+    /// List events matching a filter, newest first, continuing after a cursor
+    async fn list_after(&self, filter: &AuditFilter, page: &PageRequest) -> AuthResult<Page<AuditEvent>>;
+
+    /// Count events matching a filter
+    async fn count(&self, filter: &AuditFilter) -> AuthResult<u64>;
+
+    /// Delete events created before a timestamp (retention)
+    async fn delete_older_than(&self, before: i64) -> AuthResult<u64>;
+
+    /// Clear the IP address and user agent of events a user took part in,
+    /// returns how many events were changed (account purge)
+    async fn anonymize_user(&self, user_id: &DbId) -> AuthResult<u64>;
+}
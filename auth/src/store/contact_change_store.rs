@@ -0,0 +1,41 @@
+//! Contact change store module
+//!
+//! Provides a generic store for pending email and phone changes.
+
+use async_trait::async_trait;
+use crate::models::contact_change::{ContactChangeModel, CreateContactChange};
+use crate::models::verification::VerificationMedium;
+use crate::utils::errors::AuthResult;
+use database::utils::DbId;
+
+/// Contact change store trait - implement this for each database
+#[async_trait]
+pub trait ContactChangeStore: Send + Sync {
+    /// Create a new contact change request
+    async fn create(&self, input: CreateContactChange) -> AuthResult<ContactChangeModel>;
+
+    /// Find the pending (unconfirmed, unexpired) request of a user for a medium
+    async fn find_pending(
+        &self,
+        user_id: &DbId,
+        medium: VerificationMedium,
+    ) -> AuthResult<Option<ContactChangeModel>>;
+
+    /// Find request by revert token hash
+    async fn find_by_revert_token(&self, token_hash: &str) -> AuthResult<Option<ContactChangeModel>>;
+
+    /// Increment failed confirmation attempts
+    async fn increment_attempts(&self, id: &DbId) -> AuthResult<()>;
+
+    /// Mark request as confirmed
+    async fn mark_confirmed(&self, id: &DbId) -> AuthResult<()>;
+
+    /// Mark request as reverted
+    async fn mark_reverted(&self, id: &DbId) -> AuthResult<()>;
+
+    /// Cancel pending requests of a user for a medium
+    async fn cancel_pending(&self, user_id: &DbId, medium: VerificationMedium) -> AuthResult<u64>;
+
+    /// Delete all requests of a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64>;
+}
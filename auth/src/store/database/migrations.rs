@@ -0,0 +1,21 @@
+//! Schema migrations for the auth collections
+//!
+//! Register these with `database::migrations::MigrationRunner`. Never edit a
+//! migration once it has shipped; add a new version instead.
+
+use mongodb::bson::{Bson, Document};
+
+use super::collections::USERS;
+use database::migrations::{Migration, Step, StepMigration};
+
+/// All auth migrations, oldest first
+pub fn migrations() -> Vec<Box<dyn Migration>> {
+    vec![Box::new(
+        // Users created before organizations, admin flags and profiles
+        StepMigration::new(1, "backfill_user_defaults")
+            .with_step(Step::backfill(USERS, "login_attempts", 0))
+            .with_step(Step::backfill(USERS, "org_ids", Bson::Array(Vec::new())))
+            .with_step(Step::backfill(USERS, "is_admin", false))
+            .with_step(Step::backfill(USERS, "profile", Document::new())),
+    )]
+}
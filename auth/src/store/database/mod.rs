@@ -2,14 +2,75 @@
 //!
 //! Provides concrete implementations of the store traits using MongoDB.
 
+pub mod migrations;
+pub mod mongo_api_key_store;
+pub mod mongo_audit_store;
+pub mod mongo_contact_change_store;
 pub mod mongo_oauth_account_store;
+pub mod mongo_organization_store;
+pub mod mongo_passkey_store;
 pub mod mongo_password_reset_store;
 pub mod mongo_session_store;
+pub mod mongo_user_invitation_store;
 pub mod mongo_user_store;
 pub mod mongo_verification_store;
 
+pub use mongo_api_key_store::MongoApiKeyStore;
+pub use mongo_audit_store::MongoAuditStore;
+pub use mongo_contact_change_store::MongoContactChangeStore;
 pub use mongo_oauth_account_store::MongoOAuthAccountStore;
+pub use mongo_organization_store::MongoOrganizationStore;
+pub use mongo_passkey_store::MongoPasskeyStore;
 pub use mongo_password_reset_store::MongoPasswordResetStore;
 pub use mongo_session_store::MongoSessionStore;
+pub use mongo_user_invitation_store::MongoUserInvitationStore;
 pub use mongo_user_store::MongoUserStore;
 pub use mongo_verification_store::MongoVerificationStore;
+
+use database::indexes::IndexReport;
+use mongodb::Database;
+
+/// Collection names used by the auth stores
+pub mod collections {
+    pub const USERS: &str = "users";
+    pub const SESSIONS: &str = "sessions";
+    pub const REFRESH_TOKENS: &str = "refresh_tokens";
+    pub const VERIFICATION_CODES: &str = "verification_codes";
+    pub const PASSWORD_RESET_TOKENS: &str = "password_reset_tokens";
+    pub const OAUTH_ACCOUNTS: &str = "oauth_accounts";
+    pub const PASSKEYS: &str = "passkeys";
+    pub const WEBAUTHN_CHALLENGES: &str = "webauthn_challenges";
+    pub const API_KEYS: &str = "api_keys";
+    pub const ORGANIZATIONS: &str = "organizations";
+    pub const ORG_MEMBERSHIPS: &str = "org_memberships";
+    pub const ORG_INVITATIONS: &str = "org_invitations";
+    pub const USER_INVITATIONS: &str = "user_invitations";
+    pub const CONTACT_CHANGES: &str = "contact_changes";
+    pub const AUDIT_EVENTS: &str = "audit_events";
+}
+
+/// Create the indexes of every auth collection - run once at startup
+pub async fn ensure_indexes(db: &Database) -> Vec<IndexReport> {
+    use collections::*;
+
+    [
+        MongoUserStore::new(db.collection(USERS)).ensure_indexes().await,
+        MongoSessionStore::new(db.collection(SESSIONS), db.collection(REFRESH_TOKENS)).ensure_indexes().await,
+        MongoVerificationStore::new(db.collection(VERIFICATION_CODES)).ensure_indexes().await,
+        MongoPasswordResetStore::new(db.collection(PASSWORD_RESET_TOKENS)).ensure_indexes().await,
+        MongoOAuthAccountStore::new(db.collection(OAUTH_ACCOUNTS)).ensure_indexes().await,
+        MongoPasskeyStore::new(db.collection(PASSKEYS), db.collection(WEBAUTHN_CHALLENGES)).ensure_indexes().await,
+        MongoApiKeyStore::new(db.collection(API_KEYS)).ensure_indexes().await,
+        MongoOrganizationStore::new(
+            db.collection(ORGANIZATIONS),
+            db.collection(ORG_MEMBERSHIPS),
+            db.collection(ORG_INVITATIONS),
+        )
+        .ensure_indexes()
+        .await,
+        MongoUserInvitationStore::new(db.collection(USER_INVITATIONS)).ensure_indexes().await,
+        MongoContactChangeStore::new(db.collection(CONTACT_CHANGES)).ensure_indexes().await,
+        MongoAuditStore::new(db.collection(AUDIT_EVENTS)).ensure_indexes().await,
+    ]
+    .concat()
+}
@@ -0,0 +1,107 @@
+//! MongoDB API Key Store Implementation
+
+use async_trait::async_trait;
+use mongodb::bson::doc;
+use mongodb::Collection;
+
+use crate::models::api_key::{ApiKey, CreateApiKey};
+use crate::store::api_key_store::ApiKeyStore;
+use crate::utils::errors::AuthResult;
+use database::utils::{DbId, generate_id};
+use database::indexes::{IndexReport, IndexSpec, ensure_indexes};
+use database::repository::{Filter, Repository, Update};
+
+/// MongoDB implementation of ApiKeyStore
+pub struct MongoApiKeyStore {
+    keys: Repository<ApiKey>,
+}
+
+impl MongoApiKeyStore {
+    /// Create a new MongoApiKeyStore
+    pub fn new(collection: Collection<ApiKey>) -> Self {
+        Self { keys: Repository::new(collection) }
+    }
+
+    /// Create the indexes backing this store's lookups
+    pub async fn ensure_indexes(&self) -> Vec<IndexReport> {
+        vec![
+            ensure_indexes(
+                self.keys.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("key_hash_unique", doc! { "key_hash": 1 }).unique(),
+                    IndexSpec::new("user_id", doc! { "user_id": 1 }),
+                ],
+            )
+            .await,
+        ]
+    }
+}
+
+#[async_trait]
+impl ApiKeyStore for MongoApiKeyStore {
+    /// Create a new API key
+    async fn create(&self, input: CreateApiKey) -> AuthResult<ApiKey> {
+        let now = chrono::Utc::now().timestamp();
+
+        let api_key = ApiKey {
+            id: generate_id(),
+            user_id: input.user_id,
+            name: input.name,
+            key_prefix: input.key_prefix,
+            key_hash: input.key_hash,
+            scopes: input.scopes,
+            rate_limit_per_minute: input.rate_limit_per_minute,
+            expires_at: input.expires_in.map(|secs| now + secs),
+            last_used_at: None,
+            last_used_ip: None,
+            is_revoked: false,
+            created_at: now,
+        };
+
+        self.keys.insert(&api_key).await?;
+        Ok(api_key)
+    }
+
+    /// Find API key by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<ApiKey>> {
+        Ok(self.keys.find_by_id(id).await?)
+    }
+
+    /// Find API key by key hash
+    async fn find_by_hash(&self, key_hash: &str) -> AuthResult<Option<ApiKey>> {
+        Ok(self.keys.find_one(Filter::new().eq("key_hash", key_hash)).await?)
+    }
+
+    /// List all API keys for a user
+    async fn list_by_user(&self, user_id: &DbId) -> AuthResult<Vec<ApiKey>> {
+        Ok(self.keys.find(Filter::new().eq("user_id", user_id.to_bson()), None).await?)
+    }
+
+    /// Record that a key was used
+    async fn touch(&self, id: &DbId, ip_address: Option<&str>) -> AuthResult<()> {
+        let update = Update::new()
+            .set("last_used_at", chrono::Utc::now().timestamp())
+            .set("last_used_ip", ip_address);
+
+        self.keys.update_by_id(id, update).await?;
+        Ok(())
+    }
+
+    /// Revoke an API key
+    async fn revoke(&self, id: &DbId) -> AuthResult<()> {
+        self.keys.update_by_id(id, Update::new().set("is_revoked", true)).await?;
+        Ok(())
+    }
+
+    /// Revoke all API keys for a user
+    async fn revoke_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let filter = Filter::new().eq("user_id", user_id.to_bson());
+        Ok(self.keys.update_many(filter, Update::new().set("is_revoked", true)).await?)
+    }
+
+    /// Delete all API keys of a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        Ok(self.keys.delete_many(Filter::new().eq("user_id", user_id.to_bson())).await?)
+    }
+}
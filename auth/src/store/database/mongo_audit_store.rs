@@ -0,0 +1,148 @@
+//! MongoDB Audit Store Implementation
+
+use async_trait::async_trait;
+use mongodb::bson::{Bson, doc};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+
+use crate::models::audit::{AuditEvent, AuditFilter, CreateAuditEvent};
+use crate::store::audit_store::AuditStore;
+use crate::utils::errors::{AuthError, AuthResult};
+use database::pagination::{Page, PageRequest};
+use database::utils::{DbId, generate_id, parse_id};
+use database::indexes::{IndexReport, IndexSpec, ensure_indexes};
+use database::repository::{Filter, Repository, Update, to_bson};
+
+/// MongoDB implementation of AuditStore
+pub struct MongoAuditStore {
+    events: Repository<AuditEvent>,
+}
+
+impl MongoAuditStore {
+    /// Create a new MongoAuditStore
+    pub fn new(collection: Collection<AuditEvent>) -> Self {
+        Self { events: Repository::new(collection) }
+    }
+
+    /// Create the indexes backing this store's lookups
+    pub async fn ensure_indexes(&self) -> Vec<IndexReport> {
+        vec![
+            ensure_indexes(
+                self.events.collection(),
+                &[
+                    IndexSpec::new("created_at", doc! { "created_at": -1 }),
+                    IndexSpec::new("actor_id_created_at", doc! { "actor_id": 1, "created_at": -1 }),
+                    IndexSpec::new("target_id_created_at", doc! { "target_id": 1, "created_at": -1 }),
+                    IndexSpec::new("org_id_created_at", doc! { "org_id": 1, "created_at": -1 }),
+                ],
+            )
+            .await,
+        ]
+    }
+}
+
+fn query(filter: &AuditFilter) -> AuthResult<Filter> {
+    let mut query = Filter::new();
+
+    if let Some(actor_id) = &filter.actor_id {
+        query = query.eq("actor_id", actor_id.to_bson());
+    }
+    if let Some(target_id) = &filter.target_id {
+        query = query.eq("target_id", target_id.to_bson());
+    }
+    if let Some(org_id) = &filter.org_id {
+        query = query.eq("org_id", org_id.to_bson());
+    }
+    if let Some(action) = &filter.action {
+        query = query.eq("action", to_bson(action)?);
+    }
+    if let Some(outcome) = &filter.outcome {
+        query = query.eq("outcome", to_bson(outcome)?);
+    }
+    if let Some(reason) = &filter.reason {
+        query = query.eq("reason", reason.as_str());
+    }
+    if let Some(from) = filter.from {
+        query = query.gte("created_at", from);
+    }
+    if let Some(to) = filter.to {
+        query = query.lte("created_at", to);
+    }
+
+    Ok(query)
+}
+
+#[async_trait]
+impl AuditStore for MongoAuditStore {
+    /// Append an event
+    async fn record(&self, input: CreateAuditEvent) -> AuthResult<AuditEvent> {
+        let event = AuditEvent {
+            id: generate_id(),
+            action: input.action,
+            outcome: input.outcome,
+            actor_id: input.actor_id,
+            target_id: input.target_id,
+            impersonator_id: input.impersonator_id,
+            org_id: input.org_id,
+            ip_address: input.ip_address,
+            user_agent: input.user_agent,
+            reason: input.reason,
+            created_at: chrono::Utc::now().timestamp(),
+        };
+
+        self.events.insert(&event).await?;
+        Ok(event)
+    }
+
+    /// List events matching a filter, newest first
+    async fn list(&self, filter: &AuditFilter, page: u32, limit: u32) -> AuthResult<Vec<AuditEvent>> {
+        let options = FindOptions::builder()
+            .sort(doc! { "created_at": -1 })
+            .skip(page as u64 * limit as u64)
+            .limit(limit as i64)
+            .build();
+
+        Ok(self.events.find(query(filter)?, options).await?)
+    }
+
+    /// List events after a cursor, newest first
+    async fn list_after(&self, filter: &AuditFilter, page: &PageRequest) -> AuthResult<Page<AuditEvent>> {
+        let mut query = query(filter)?;
+        if let Some(after) = &page.after {
+            let id = parse_id(&after.id).map_err(|_| AuthError::invalid_request("Invalid cursor"))?;
+            query = query.any_of([
+                Filter::new().lt("created_at", after.created_at),
+                Filter::new().eq("created_at", after.created_at).lt("id", id.to_bson()),
+            ]);
+        }
+
+        let options = FindOptions::builder()
+            .sort(doc! { "created_at": -1, "id": -1 })
+            .limit(page.fetch_limit())
+            .build();
+
+        let events = self.events.find(query, options).await?;
+        Ok(Page::from_rows(events, page, AuditEvent::cursor))
+    }
+
+    /// Count events matching a filter
+    async fn count(&self, filter: &AuditFilter) -> AuthResult<u64> {
+        Ok(self.events.count(query(filter)?).await?)
+    }
+
+    /// Delete events created before a timestamp
+    async fn delete_older_than(&self, before: i64) -> AuthResult<u64> {
+        Ok(self.events.delete_many(Filter::new().lt("created_at", before)).await?)
+    }
+
+    /// Clear the IP address and user agent of a user's events
+    async fn anonymize_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let filter = Filter::new().any_of([
+            Filter::new().eq("actor_id", user_id.to_bson()),
+            Filter::new().eq("target_id", user_id.to_bson()),
+        ]);
+        let update = Update::new().set("ip_address", Bson::Null).set("user_agent", Bson::Null);
+
+        Ok(self.events.update_many(filter, update).await?)
+    }
+}
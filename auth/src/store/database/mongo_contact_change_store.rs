@@ -0,0 +1,122 @@
+//! MongoDB Contact Change Store Implementation
+
+use async_trait::async_trait;
+use mongodb::bson::{Bson, doc};
+use mongodb::Collection;
+
+use crate::models::contact_change::{ContactChangeModel, CreateContactChange};
+use crate::models::verification::VerificationMedium;
+use crate::store::contact_change_store::ContactChangeStore;
+use crate::utils::errors::AuthResult;
+use database::utils::{DbId, generate_id};
+use database::indexes::{IndexReport, IndexSpec, ensure_indexes};
+use database::repository::{Filter, Repository, Update, to_bson};
+
+/// MongoDB implementation of ContactChangeStore
+pub struct MongoContactChangeStore {
+    changes: Repository<ContactChangeModel>,
+}
+
+impl MongoContactChangeStore {
+    /// Create a new MongoContactChangeStore
+    pub fn new(collection: Collection<ContactChangeModel>) -> Self {
+        Self { changes: Repository::new(collection) }
+    }
+
+    /// Create the indexes backing this store's lookups
+    pub async fn ensure_indexes(&self) -> Vec<IndexReport> {
+        vec![
+            ensure_indexes(
+                self.changes.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("user_id_medium", doc! { "user_id": 1, "medium": 1 }),
+                    IndexSpec::new("revert_token_hash", doc! { "revert_token_hash": 1 }),
+                ],
+            )
+            .await,
+        ]
+    }
+}
+
+/// Requests of a user for a medium
+fn user_medium(user_id: &DbId, medium: &VerificationMedium) -> AuthResult<Filter> {
+    Ok(Filter::new().eq("user_id", user_id.to_bson()).eq("medium", to_bson(medium)?))
+}
+
+#[async_trait]
+impl ContactChangeStore for MongoContactChangeStore {
+    /// Create a new contact change request
+    async fn create(&self, input: CreateContactChange) -> AuthResult<ContactChangeModel> {
+        let now = chrono::Utc::now().timestamp();
+
+        let change = ContactChangeModel {
+            id: generate_id(),
+            user_id: input.user_id,
+            medium: input.medium,
+            old_value: input.old_value,
+            new_value: input.new_value,
+            code_hash: input.code_hash,
+            revert_token_hash: input.revert_token_hash,
+            attempts: 0,
+            created_at: now,
+            expires_at: now + input.expires_in,
+            revert_expires_at: now + input.revert_expires_in,
+            confirmed_at: None,
+            reverted_at: None,
+        };
+
+        self.changes.insert(&change).await?;
+        Ok(change)
+    }
+
+    /// Find the pending request of a user for a medium
+    async fn find_pending(
+        &self,
+        user_id: &DbId,
+        medium: VerificationMedium,
+    ) -> AuthResult<Option<ContactChangeModel>> {
+        let filter = user_medium(user_id, &medium)?
+            .gt("expires_at", chrono::Utc::now().timestamp())
+            .eq("confirmed_at", Bson::Null)
+            .eq("reverted_at", Bson::Null);
+
+        Ok(self.changes.find_one(filter).await?)
+    }
+
+    /// Find request by revert token hash
+    async fn find_by_revert_token(&self, token_hash: &str) -> AuthResult<Option<ContactChangeModel>> {
+        Ok(self.changes.find_one(Filter::new().eq("revert_token_hash", token_hash)).await?)
+    }
+
+    /// Increment failed confirmation attempts
+    async fn increment_attempts(&self, id: &DbId) -> AuthResult<()> {
+        self.changes.update_by_id(id, Update::new().inc("attempts", 1)).await?;
+        Ok(())
+    }
+
+    /// Mark request as confirmed
+    async fn mark_confirmed(&self, id: &DbId) -> AuthResult<()> {
+        let update = Update::new().set("confirmed_at", chrono::Utc::now().timestamp());
+        self.changes.update_by_id(id, update).await?;
+        Ok(())
+    }
+
+    /// Mark request as reverted
+    async fn mark_reverted(&self, id: &DbId) -> AuthResult<()> {
+        let update = Update::new().set("reverted_at", chrono::Utc::now().timestamp());
+        self.changes.update_by_id(id, update).await?;
+        Ok(())
+    }
+
+    /// Cancel pending requests of a user for a medium
+    async fn cancel_pending(&self, user_id: &DbId, medium: VerificationMedium) -> AuthResult<u64> {
+        let filter = user_medium(user_id, &medium)?.eq("confirmed_at", Bson::Null);
+        Ok(self.changes.delete_many(filter).await?)
+    }
+
+    /// Delete all requests of a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        Ok(self.changes.delete_many(Filter::new().eq("user_id", user_id.to_bson())).await?)
+    }
+}
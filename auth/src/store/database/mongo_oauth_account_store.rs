@@ -1,32 +1,56 @@
 //! MongoDB OAuth Account Store Implementation
 
-use mongodb::bson::{doc, oid};
-use mongodb::sync::Collection;
+use async_trait::async_trait;
+use mongodb::bson::doc;
+use mongodb::Collection;
 
 use crate::models::oauth::{CreateOAuthAccount, OAuthAccount, OAuthProvider};
 use crate::store::oauth_account_store::OAuthAccountStore;
 use crate::utils::errors::AuthError;
 use database::utils::{DbId, generate_id};
+use database::indexes::{IndexReport, IndexSpec, ensure_indexes};
+use database::repository::{Filter, Repository, Update, to_bson};
 
 /// MongoDB implementation of OAuthAccountStore
 pub struct MongoOAuthAccountStore {
-    collection: Collection<OAuthAccount>,
+    accounts: Repository<OAuthAccount>,
 }
 
 impl MongoOAuthAccountStore {
     /// Create a new MongoOAuthAccountStore
     pub fn new(collection: Collection<OAuthAccount>) -> Self {
-        Self { collection }
+        Self { accounts: Repository::new(collection) }
+    }
+
+    /// Create the indexes backing this store's lookups
+    pub async fn ensure_indexes(&self) -> Vec<IndexReport> {
+        vec![
+            ensure_indexes(
+                self.accounts.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("provider_user_unique", doc! { "provider": 1, "provider_user_id": 1 }).unique(),
+                    IndexSpec::new("user_id_provider", doc! { "user_id": 1, "provider": 1 }),
+                ],
+            )
+            .await,
+        ]
     }
 
     /// Get the MongoDB collection
     pub fn collection(&self) -> &Collection<OAuthAccount> {
-        &self.collection
+        self.accounts.collection()
     }
 }
 
+/// Account of a user at a provider
+fn user_provider(user_id: &DbId, provider: &OAuthProvider) -> Result<Filter, AuthError> {
+    Ok(Filter::new().eq("user_id", user_id.to_bson()).eq("provider", to_bson(provider)?))
+}
+
+#[async_trait]
 impl OAuthAccountStore for MongoOAuthAccountStore {
-    fn create(&self, account: CreateOAuthAccount) -> Result<OAuthAccount, AuthError> {
+    async fn create(&self, account: CreateOAuthAccount) -> Result<OAuthAccount, AuthError> {
         let id = generate_id();
         let now = chrono::Utc::now().timestamp();
 
@@ -45,154 +69,69 @@ impl OAuthAccountStore for MongoOAuthAccountStore {
             updated_at: None,
         };
 
-        self.collection
-            .insert_one(&oauth_account, None)
-            .map_err(|e| {
-                AuthError::internal_error(&format!("Failed to create OAuth account: {}", e))
-            })?;
-
+        self.accounts.insert(&oauth_account).await?;
         Ok(oauth_account)
     }
 
-    fn find_by_id(&self, id: &DbId) -> Result<Option<OAuthAccount>, AuthError> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "_id": bson_oid };
-
-        self.collection
-            .find_one(filter, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to find OAuth account: {}", e)))
+    async fn find_by_id(&self, id: &DbId) -> Result<Option<OAuthAccount>, AuthError> {
+        Ok(self.accounts.find_by_id(id).await?)
     }
 
-    fn find_by_user_and_provider(
+    async fn find_by_user_and_provider(
         &self,
         user_id: &DbId,
         provider: &OAuthProvider,
     ) -> Result<Option<OAuthAccount>, AuthError> {
-        let user_oid = oid::ObjectId::from_bytes(user_id.as_bytes().clone());
-        let provider_str = serde_json::to_value(provider)
-            .ok()
-            .and_then(|v| v.as_str().map(String::from))
-            .unwrap_or_else(|| format!("{:?}", provider));
-
-        let filter = doc! {
-            "user_id": user_oid,
-            "provider": provider_str,
-        };
-
-        self.collection
-            .find_one(filter, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to find OAuth account: {}", e)))
+        Ok(self.accounts.find_one(user_provider(user_id, provider)?).await?)
     }
 
-    fn find_by_provider_user_id(
+    async fn find_by_provider_user_id(
         &self,
         provider: &OAuthProvider,
         provider_user_id: &str,
     ) -> Result<Option<OAuthAccount>, AuthError> {
-        let provider_str = serde_json::to_value(provider)
-            .ok()
-            .and_then(|v| v.as_str().map(String::from))
-            .unwrap_or_else(|| format!("{:?}", provider));
-
-        let filter = doc! {
-            "provider": provider_str,
-            "provider_user_id": provider_user_id,
-        };
+        let filter = Filter::new()
+            .eq("provider", to_bson(provider)?)
+            .eq("provider_user_id", provider_user_id);
 
-        self.collection
-            .find_one(filter, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to find OAuth account: {}", e)))
+        Ok(self.accounts.find_one(filter).await?)
     }
 
-    fn list_by_user(&self, user_id: &DbId) -> Result<Vec<OAuthAccount>, AuthError> {
-        let user_oid = oid::ObjectId::from_bytes(user_id.as_bytes().clone());
-        let filter = doc! { "user_id": user_oid };
-
-        let mut cursor = self.collection.find(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to list OAuth accounts: {}", e))
-        })?;
-
-        let mut accounts = Vec::new();
-        while cursor.advance().map_err(|e| {
-            AuthError::internal_error(&format!("Failed to iterate OAuth accounts: {}", e))
-        })? {
-            let account = cursor.deserialize_current().map_err(|e| {
-                AuthError::internal_error(&format!("Failed to deserialize OAuth account: {}", e))
-            })?;
-            accounts.push(account);
-        }
-
-        Ok(accounts)
+    async fn list_by_user(&self, user_id: &DbId) -> Result<Vec<OAuthAccount>, AuthError> {
+        Ok(self.accounts.find(Filter::new().eq("user_id", user_id.to_bson()), None).await?)
     }
 
-    fn update(&self, id: &DbId, account: &OAuthAccount) -> Result<OAuthAccount, AuthError> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "_id": bson_oid };
-
+    async fn update(&self, id: &DbId, account: &OAuthAccount) -> Result<OAuthAccount, AuthError> {
         let mut updated_account = account.clone();
         updated_account.updated_at = Some(chrono::Utc::now().timestamp());
 
-        let update = doc! {
-            "$set": {
-                "access_token": &updated_account.access_token,
-                "refresh_token": &updated_account.refresh_token,
-                "expires_at": updated_account.expires_at,
-                "scope": &updated_account.scope,
-                "updated_at": updated_account.updated_at,
-            }
-        };
-
-        self.collection
-            .update_one(filter, update, None)
-            .map_err(|e| {
-                AuthError::internal_error(&format!("Failed to update OAuth account: {}", e))
-            })?;
+        let update = Update::new()
+            .set("access_token", updated_account.access_token.as_deref())
+            .set("refresh_token", updated_account.refresh_token.as_deref())
+            .set("expires_at", updated_account.expires_at)
+            .set("scope", updated_account.scope.as_deref())
+            .set("updated_at", updated_account.updated_at);
 
+        self.accounts.update_by_id(id, update).await?;
         Ok(updated_account)
     }
 
-    fn delete(&self, id: &DbId) -> Result<(), AuthError> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "_id": bson_oid };
-
-        self.collection.delete_one(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to delete OAuth account: {}", e))
-        })?;
-
+    async fn delete(&self, id: &DbId) -> Result<(), AuthError> {
+        self.accounts.delete_by_id(id).await?;
         Ok(())
     }
 
-    fn delete_by_user(&self, user_id: &DbId) -> Result<(), AuthError> {
-        let user_oid = oid::ObjectId::from_bytes(user_id.as_bytes().clone());
-        let filter = doc! { "user_id": user_oid };
-
-        self.collection.delete_many(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to delete OAuth accounts: {}", e))
-        })?;
-
+    async fn delete_by_user(&self, user_id: &DbId) -> Result<(), AuthError> {
+        self.accounts.delete_many(Filter::new().eq("user_id", user_id.to_bson())).await?;
         Ok(())
     }
 
-    fn delete_by_user_and_provider(
+    async fn delete_by_user_and_provider(
         &self,
         user_id: &DbId,
         provider: &OAuthProvider,
     ) -> Result<(), AuthError> {
-        let user_oid = oid::ObjectId::from_bytes(user_id.as_bytes().clone());
-        let provider_str = serde_json::to_value(provider)
-            .ok()
-            .and_then(|v| v.as_str().map(String::from))
-            .unwrap_or_else(|| format!("{:?}", provider));
-
-        let filter = doc! {
-            "user_id": user_oid,
-            "provider": provider_str,
-        };
-
-        self.collection.delete_one(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to delete OAuth account: {}", e))
-        })?;
-
+        self.accounts.delete_one(user_provider(user_id, provider)?).await?;
         Ok(())
     }
 }
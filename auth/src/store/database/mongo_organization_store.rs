@@ -0,0 +1,230 @@
+//! MongoDB Organization Store Implementation
+
+use async_trait::async_trait;
+use mongodb::bson::doc;
+use mongodb::Collection;
+
+use crate::models::organization::{
+    CreateMembership, CreateOrgInvitation, CreateOrganization, Membership, OrgInvitation,
+    OrgRole, Organization,
+};
+use crate::store::organization_store::OrganizationStore;
+use crate::utils::errors::{AuthError, AuthResult};
+use database::utils::{DbId, generate_id};
+use database::indexes::{IndexReport, IndexSpec, ensure_indexes};
+use database::repository::{Filter, Repository, Update, to_bson};
+
+/// MongoDB implementation of OrganizationStore
+pub struct MongoOrganizationStore {
+    orgs: Repository<Organization>,
+    memberships: Repository<Membership>,
+    invitations: Repository<OrgInvitation>,
+}
+
+impl MongoOrganizationStore {
+    /// Create a new MongoOrganizationStore
+    pub fn new(
+        org_collection: Collection<Organization>,
+        membership_collection: Collection<Membership>,
+        invitation_collection: Collection<OrgInvitation>,
+    ) -> Self {
+        Self {
+            orgs: Repository::new(org_collection),
+            memberships: Repository::new(membership_collection),
+            invitations: Repository::new(invitation_collection),
+        }
+    }
+
+    /// Create the indexes backing this store's lookups
+    pub async fn ensure_indexes(&self) -> Vec<IndexReport> {
+        vec![
+            ensure_indexes(
+                self.orgs.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("slug_unique", doc! { "slug": 1 }).unique(),
+                ],
+            )
+            .await,
+            ensure_indexes(
+                self.memberships.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("org_user_unique", doc! { "org_id": 1, "user_id": 1 }).unique(),
+                    IndexSpec::new("user_id", doc! { "user_id": 1 }),
+                ],
+            )
+            .await,
+            ensure_indexes(
+                self.invitations.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("token_hash_unique", doc! { "token_hash": 1 }).unique(),
+                    IndexSpec::new("org_id", doc! { "org_id": 1 }),
+                    IndexSpec::new("email", doc! { "email": 1 }),
+                ],
+            )
+            .await,
+        ]
+    }
+}
+
+/// Membership of a user in an organization
+fn member(org_id: &DbId, user_id: &DbId) -> Filter {
+    Filter::new().eq("org_id", org_id.to_bson()).eq("user_id", user_id.to_bson())
+}
+
+#[async_trait]
+impl OrganizationStore for MongoOrganizationStore {
+    /// Create a new organization
+    async fn create(&self, input: CreateOrganization) -> AuthResult<Organization> {
+        let org = Organization {
+            id: generate_id(),
+            name: input.name,
+            slug: input.slug,
+            owner_id: input.owner_id,
+            created_at: chrono::Utc::now().timestamp(),
+            updated_at: None,
+        };
+
+        self.orgs.insert(&org).await?;
+        Ok(org)
+    }
+
+    /// Find organization by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<Organization>> {
+        Ok(self.orgs.find_by_id(id).await?)
+    }
+
+    /// Find organization by slug
+    async fn find_by_slug(&self, slug: &str) -> AuthResult<Option<Organization>> {
+        Ok(self.orgs.find_one(Filter::new().eq("slug", slug)).await?)
+    }
+
+    /// Find organizations by IDs
+    async fn find_by_ids(&self, ids: &[DbId]) -> AuthResult<Vec<Organization>> {
+        let filter = Filter::new().is_in("id", ids.iter().map(DbId::to_bson));
+        Ok(self.orgs.find(filter, None).await?)
+    }
+
+    /// Delete an organization with its memberships and invitations
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        let in_org = Filter::new().eq("org_id", id.to_bson());
+
+        self.memberships.delete_many(in_org.clone()).await?;
+        self.invitations.delete_many(in_org).await?;
+        self.orgs.delete_by_id(id).await?;
+
+        Ok(())
+    }
+
+    /// Add a member
+    async fn add_member(&self, input: CreateMembership) -> AuthResult<Membership> {
+        let membership = Membership {
+            id: generate_id(),
+            org_id: input.org_id,
+            user_id: input.user_id,
+            role: input.role,
+            created_at: chrono::Utc::now().timestamp(),
+            updated_at: None,
+        };
+
+        self.memberships.insert(&membership).await?;
+        Ok(membership)
+    }
+
+    /// Find a user's membership in an organization
+    async fn find_membership(&self, org_id: &DbId, user_id: &DbId) -> AuthResult<Option<Membership>> {
+        Ok(self.memberships.find_one(member(org_id, user_id)).await?)
+    }
+
+    /// List members of an organization
+    async fn list_members(&self, org_id: &DbId) -> AuthResult<Vec<Membership>> {
+        Ok(self.memberships.find(Filter::new().eq("org_id", org_id.to_bson()), None).await?)
+    }
+
+    /// List memberships of a user
+    async fn list_user_memberships(&self, user_id: &DbId) -> AuthResult<Vec<Membership>> {
+        Ok(self.memberships.find(Filter::new().eq("user_id", user_id.to_bson()), None).await?)
+    }
+
+    /// Change a member's role
+    async fn update_member_role(&self, org_id: &DbId, user_id: &DbId, role: OrgRole) -> AuthResult<()> {
+        let update = Update::new()
+            .set("role", to_bson(&role)?)
+            .set("updated_at", chrono::Utc::now().timestamp());
+
+        if !self.memberships.update_one(member(org_id, user_id), update).await? {
+            return Err(AuthError::not_found("Member not found"));
+        }
+
+        Ok(())
+    }
+
+    /// Remove a member, unless it is the last owner
+    async fn remove_member(&self, org_id: &DbId, user_id: &DbId) -> AuthResult<()> {
+        let members = self.list_members(org_id).await?;
+        let is_owner = |m: &&Membership| m.role == OrgRole::Owner;
+
+        let removes_owner = members.iter().filter(is_owner).any(|m| &m.user_id == user_id);
+        if removes_owner && !members.iter().filter(is_owner).any(|m| &m.user_id != user_id) {
+            return Err(AuthError::last_owner());
+        }
+
+        self.memberships.delete_one(member(org_id, user_id)).await?;
+        Ok(())
+    }
+
+    /// Create an invitation
+    async fn create_invitation(&self, input: CreateOrgInvitation) -> AuthResult<OrgInvitation> {
+        let now = chrono::Utc::now().timestamp();
+
+        let invitation = OrgInvitation {
+            id: generate_id(),
+            org_id: input.org_id,
+            email: input.email,
+            role: input.role,
+            token_hash: input.token_hash,
+            invited_by: input.invited_by,
+            expires_at: now + input.expires_in,
+            accepted_at: None,
+            created_at: now,
+        };
+
+        self.invitations.insert(&invitation).await?;
+        Ok(invitation)
+    }
+
+    /// Find invitation by ID
+    async fn find_invitation(&self, id: &DbId) -> AuthResult<Option<OrgInvitation>> {
+        Ok(self.invitations.find_by_id(id).await?)
+    }
+
+    /// Find invitation by token hash
+    async fn find_invitation_by_token(&self, token_hash: &str) -> AuthResult<Option<OrgInvitation>> {
+        Ok(self.invitations.find_one(Filter::new().eq("token_hash", token_hash)).await?)
+    }
+
+    /// List invitations of an organization
+    async fn list_invitations(&self, org_id: &DbId) -> AuthResult<Vec<OrgInvitation>> {
+        Ok(self.invitations.find(Filter::new().eq("org_id", org_id.to_bson()), None).await?)
+    }
+
+    /// Mark invitation as accepted
+    async fn accept_invitation(&self, id: &DbId) -> AuthResult<()> {
+        let update = Update::new().set("accepted_at", chrono::Utc::now().timestamp());
+        self.invitations.update_by_id(id, update).await?;
+        Ok(())
+    }
+
+    /// Delete an invitation
+    async fn delete_invitation(&self, id: &DbId) -> AuthResult<()> {
+        self.invitations.delete_by_id(id).await?;
+        Ok(())
+    }
+
+    /// Delete all invitations sent to an email address
+    async fn delete_invitations_for_email(&self, email: &str) -> AuthResult<u64> {
+        Ok(self.invitations.delete_many(Filter::new().eq("email", email)).await?)
+    }
+}
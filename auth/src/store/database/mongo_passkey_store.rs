@@ -0,0 +1,149 @@
+//! MongoDB Passkey Store Implementation
+
+use async_trait::async_trait;
+use mongodb::bson::doc;
+use mongodb::Collection;
+
+use crate::models::passkey::{
+    CreatePasskeyCredential, CreateWebAuthnChallenge, PasskeyCredential, WebAuthnChallenge,
+};
+use crate::store::passkey_store::PasskeyStore;
+use crate::utils::errors::AuthResult;
+use database::utils::{DbId, generate_id};
+use database::indexes::{IndexReport, IndexSpec, ensure_indexes};
+use database::repository::{Filter, Repository, Update};
+
+/// MongoDB implementation of PasskeyStore
+pub struct MongoPasskeyStore {
+    credentials: Repository<PasskeyCredential>,
+    challenges: Repository<WebAuthnChallenge>,
+}
+
+impl MongoPasskeyStore {
+    /// Create a new MongoPasskeyStore
+    pub fn new(
+        credential_collection: Collection<PasskeyCredential>,
+        challenge_collection: Collection<WebAuthnChallenge>,
+    ) -> Self {
+        Self {
+            credentials: Repository::new(credential_collection),
+            challenges: Repository::new(challenge_collection),
+        }
+    }
+
+    /// Create the indexes backing this store's lookups
+    pub async fn ensure_indexes(&self) -> Vec<IndexReport> {
+        vec![
+            ensure_indexes(
+                self.credentials.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("credential_id_unique", doc! { "credential_id": 1 }).unique(),
+                    IndexSpec::new("user_id", doc! { "user_id": 1 }),
+                ],
+            )
+            .await,
+            ensure_indexes(
+                self.challenges.collection(),
+                &[
+                    IndexSpec::new("challenge", doc! { "challenge": 1 }),
+                ],
+            )
+            .await,
+        ]
+    }
+}
+
+#[async_trait]
+impl PasskeyStore for MongoPasskeyStore {
+    /// Register a new passkey credential
+    async fn create(&self, input: CreatePasskeyCredential) -> AuthResult<PasskeyCredential> {
+        let now = chrono::Utc::now().timestamp();
+
+        let credential = PasskeyCredential {
+            id: generate_id(),
+            user_id: input.user_id,
+            credential_id: input.credential_id,
+            public_key: input.public_key,
+            sign_count: input.sign_count,
+            aaguid: input.aaguid,
+            name: input.name,
+            transports: input.transports,
+            created_at: now,
+            last_used_at: None,
+        };
+
+        self.credentials.insert(&credential).await?;
+        Ok(credential)
+    }
+
+    /// Find passkey by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<PasskeyCredential>> {
+        Ok(self.credentials.find_by_id(id).await?)
+    }
+
+    /// Find passkey by authenticator credential ID
+    async fn find_by_credential_id(&self, credential_id: &str) -> AuthResult<Option<PasskeyCredential>> {
+        Ok(self.credentials.find_one(Filter::new().eq("credential_id", credential_id)).await?)
+    }
+
+    /// List all passkeys for a user
+    async fn list_by_user(&self, user_id: &DbId) -> AuthResult<Vec<PasskeyCredential>> {
+        Ok(self.credentials.find(Filter::new().eq("user_id", user_id.to_bson()), None).await?)
+    }
+
+    /// Store the new signature counter after a successful assertion
+    async fn update_sign_count(&self, id: &DbId, sign_count: u32) -> AuthResult<()> {
+        let update = Update::new()
+            .set("sign_count", i64::from(sign_count))
+            .set("last_used_at", chrono::Utc::now().timestamp());
+
+        self.credentials.update_by_id(id, update).await?;
+        Ok(())
+    }
+
+    /// Delete a passkey
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        self.credentials.delete_by_id(id).await?;
+        Ok(())
+    }
+
+    /// Delete all passkeys for a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        Ok(self.credentials.delete_many(Filter::new().eq("user_id", user_id.to_bson())).await?)
+    }
+
+    /// Create a ceremony challenge
+    async fn create_challenge(&self, input: CreateWebAuthnChallenge) -> AuthResult<WebAuthnChallenge> {
+        let now = chrono::Utc::now().timestamp();
+
+        let challenge = WebAuthnChallenge {
+            id: generate_id(),
+            user_id: input.user_id,
+            challenge: input.challenge,
+            ceremony: input.ceremony,
+            expires_at: now + input.expires_in,
+            created_at: now,
+        };
+
+        self.challenges.insert(&challenge).await?;
+        Ok(challenge)
+    }
+
+    /// Find challenge by its base64url value
+    async fn find_challenge(&self, challenge: &str) -> AuthResult<Option<WebAuthnChallenge>> {
+        Ok(self.challenges.find_one(Filter::new().eq("challenge", challenge)).await?)
+    }
+
+    /// Delete a challenge
+    async fn delete_challenge(&self, id: &DbId) -> AuthResult<()> {
+        self.challenges.delete_by_id(id).await?;
+        Ok(())
+    }
+
+    /// Cleanup expired challenges
+    async fn cleanup_expired_challenges(&self) -> AuthResult<u64> {
+        let expired = Filter::new().lt("expires_at", chrono::Utc::now().timestamp());
+        Ok(self.challenges.delete_many(expired).await?)
+    }
+}
@@ -1,28 +1,48 @@
 //! MongoDB Password Reset Store Implementation
 
-use mongodb::bson::{doc, oid};
-use mongodb::sync::Collection;
+use async_trait::async_trait;
+use mongodb::bson::{Bson, doc};
+use mongodb::Collection;
 
 use crate::models::reset_password::{CreatePasswordResetToken, PasswordResetTokenModel};
 use crate::store::password_reset_store::PasswordResetStore;
-use crate::utils::errors::{AuthError, AuthResult};
+use crate::utils::errors::AuthResult;
 use database::utils::{DbId, generate_id};
+use database::indexes::{IndexReport, IndexSpec, ensure_indexes};
+use database::repository::{Filter, Repository, Update};
 
 /// MongoDB implementation of PasswordResetStore
 pub struct MongoPasswordResetStore {
-    collection: Collection<PasswordResetTokenModel>,
+    tokens: Repository<PasswordResetTokenModel>,
 }
 
 impl MongoPasswordResetStore {
     /// Create a new MongoPasswordResetStore
     pub fn new(collection: Collection<PasswordResetTokenModel>) -> Self {
-        Self { collection }
+        Self { tokens: Repository::new(collection) }
+    }
+
+    /// Create the indexes backing this store's lookups
+    pub async fn ensure_indexes(&self) -> Vec<IndexReport> {
+        vec![
+            ensure_indexes(
+                self.tokens.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("token_hash_unique", doc! { "token_hash": 1 }).unique(),
+                    IndexSpec::new("user_id", doc! { "user_id": 1 }),
+                    IndexSpec::ttl(),
+                ],
+            )
+            .await,
+        ]
     }
 }
 
+#[async_trait]
 impl PasswordResetStore for MongoPasswordResetStore {
     /// Create a new password reset token
-    fn create(&self, input: CreatePasswordResetToken) -> AuthResult<PasswordResetTokenModel> {
+    async fn create(&self, input: CreatePasswordResetToken) -> AuthResult<PasswordResetTokenModel> {
         let id = generate_id();
         let now = chrono::Utc::now().timestamp();
 
@@ -35,100 +55,51 @@ impl PasswordResetStore for MongoPasswordResetStore {
             used_at: None,
         };
 
-        self.collection.insert_one(&token, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to create password reset token: {}", e))
-        })?;
-
+        self.tokens.insert_expiring(&token, token.expires_at).await?;
         Ok(token)
     }
 
     /// Find password reset token by ID
-    fn find_by_id(&self, id: &DbId) -> AuthResult<Option<PasswordResetTokenModel>> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-
-        let result = self.collection.find_one(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to find password reset token: {}", e))
-        })?;
-
-        Ok(result)
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<PasswordResetTokenModel>> {
+        Ok(self.tokens.find_by_id(id).await?)
     }
 
     /// Find valid password reset token by user_id
-    fn find_valid_token(&self, user_id: &DbId) -> AuthResult<Option<PasswordResetTokenModel>> {
-        let now = chrono::Utc::now().timestamp();
-        let filter = doc! {
-            "user_id": user_id.to_string(),
-            "expires_at": { "$gt": now },
-            "used_at": null
-        };
-
-        let result = self.collection.find_one(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to find valid password reset token: {}", e))
-        })?;
+    async fn find_valid_token(&self, user_id: &DbId) -> AuthResult<Option<PasswordResetTokenModel>> {
+        let filter = Filter::new()
+            .eq("user_id", user_id.to_bson())
+            .gt("expires_at", chrono::Utc::now().timestamp())
+            .eq("used_at", Bson::Null);
 
-        Ok(result)
+        Ok(self.tokens.find_one(filter).await?)
     }
 
     /// Find token by hash
-    fn find_by_hash(&self, token_hash: &str) -> AuthResult<Option<PasswordResetTokenModel>> {
-        let filter = doc! { "token_hash": token_hash };
-
-        let result = self.collection.find_one(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to find password reset token: {}", e))
-        })?;
-
-        Ok(result)
+    async fn find_by_hash(&self, token_hash: &str) -> AuthResult<Option<PasswordResetTokenModel>> {
+        Ok(self.tokens.find_one(Filter::new().eq("token_hash", token_hash)).await?)
     }
 
     /// Mark token as used
-    fn mark_used(&self, id: &DbId) -> AuthResult<()> {
-        let now = chrono::Utc::now().timestamp();
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-        let update = doc! { "$set": { "used_at": now }};
-
-        self.collection
-            .update_one(filter, update, None)
-            .map_err(|e| {
-                AuthError::internal_error(&format!("Failed to mark token as used: {}", e))
-            })?;
-
+    async fn mark_used(&self, id: &DbId) -> AuthResult<()> {
+        let update = Update::new().set("used_at", chrono::Utc::now().timestamp());
+        self.tokens.update_by_id(id, update).await?;
         Ok(())
     }
 
     /// Delete/expire a token
-    fn delete(&self, id: &DbId) -> AuthResult<()> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-
-        self.collection.delete_one(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to delete password reset token: {}", e))
-        })?;
-
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        self.tokens.delete_by_id(id).await?;
         Ok(())
     }
 
     /// Delete all tokens for a user
-    fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
-        let filter = doc! { "user_id": user_id.to_string() };
-
-        let result = self.collection.delete_many(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to delete password reset tokens: {}", e))
-        })?;
-
-        Ok(result.deleted_count)
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        Ok(self.tokens.delete_many(Filter::new().eq("user_id", user_id.to_bson())).await?)
     }
 
     /// Cleanup expired tokens
-    fn cleanup_expired(&self) -> AuthResult<u64> {
-        let now = chrono::Utc::now().timestamp();
-        let filter = doc! { "expires_at": { "$lt": now }};
-
-        let result = self.collection.delete_many(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to cleanup expired tokens: {}", e))
-        })?;
-
-        Ok(result.deleted_count)
+    async fn cleanup_expired(&self) -> AuthResult<u64> {
+        let expired = Filter::new().lt("expires_at", chrono::Utc::now().timestamp());
+        Ok(self.tokens.delete_many(expired).await?)
     }
 }
@@ -1,19 +1,22 @@
 //! MongoDB Session Store Implementation
 
-use mongodb::bson::{doc, oid};
-use mongodb::sync::Collection;
+use async_trait::async_trait;
+use mongodb::bson::doc;
+use mongodb::Collection;
 
 use crate::models::session::{
     CreateRefreshToken, CreateSession, RefreshTokenModel, SessionModel, UpdateSession,
 };
 use crate::store::session_store::SessionStore;
-use crate::utils::errors::{AuthError, AuthResult};
+use crate::utils::errors::AuthResult;
 use database::utils::{DbId, generate_id};
+use database::indexes::{IndexReport, IndexSpec, TTL_FIELD, ensure_indexes, ttl_date};
+use database::repository::{Filter, Repository, Update};
 
 /// MongoDB implementation of SessionStore
 pub struct MongoSessionStore {
-    session_collection: Collection<SessionModel>,
-    refresh_token_collection: Collection<RefreshTokenModel>,
+    sessions: Repository<SessionModel>,
+    refresh_tokens: Repository<RefreshTokenModel>,
 }
 
 impl MongoSessionStore {
@@ -23,15 +26,43 @@ impl MongoSessionStore {
         refresh_token_collection: Collection<RefreshTokenModel>,
     ) -> Self {
         Self {
-            session_collection,
-            refresh_token_collection,
+            sessions: Repository::new(session_collection),
+            refresh_tokens: Repository::new(refresh_token_collection),
         }
     }
+
+    /// Create the indexes backing this store's lookups
+    pub async fn ensure_indexes(&self) -> Vec<IndexReport> {
+        vec![
+            ensure_indexes(
+                self.sessions.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("access_token_hash", doc! { "access_token_hash": 1 }),
+                    IndexSpec::new("user_id", doc! { "user_id": 1 }),
+                    IndexSpec::new("org_id", doc! { "org_id": 1 }),
+                    IndexSpec::ttl(),
+                ],
+            )
+            .await,
+            ensure_indexes(
+                self.refresh_tokens.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("token_hash_unique", doc! { "token_hash": 1 }).unique(),
+                    IndexSpec::new("user_id", doc! { "user_id": 1 }),
+                    IndexSpec::ttl(),
+                ],
+            )
+            .await,
+        ]
+    }
 }
 
+#[async_trait]
 impl SessionStore for MongoSessionStore {
     /// Create a new session
-    fn create(&self, input: CreateSession) -> AuthResult<SessionModel> {
+    async fn create(&self, input: CreateSession) -> AuthResult<SessionModel> {
         let id = generate_id();
         let now = chrono::Utc::now().timestamp();
 
@@ -43,123 +74,90 @@ impl SessionStore for MongoSessionStore {
             device: input.device,
             ip_address: input.ip_address,
             user_agent: input.user_agent,
+            org_id: input.org_id,
             created_at: now,
             expires_at: now + input.expires_in,
             last_used_at: now,
             is_revoked: false,
         };
 
-        self.session_collection
-            .insert_one(&session, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to create session: {}", e)))?;
-
+        self.sessions.insert_expiring(&session, session.expires_at).await?;
         Ok(session)
     }
 
     /// Find session by ID
-    fn find_by_id(&self, id: &DbId) -> AuthResult<Option<SessionModel>> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-
-        let result = self
-            .session_collection
-            .find_one(filter, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to find session: {}", e)))?;
-
-        Ok(result)
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<SessionModel>> {
+        Ok(self.sessions.find_by_id(id).await?)
     }
 
     /// Find session by access token hash
-    fn find_by_token(&self, token_hash: &str) -> AuthResult<Option<SessionModel>> {
-        let filter = doc! { "access_token_hash": token_hash };
-
-        let result = self
-            .session_collection
-            .find_one(filter, None)
-            .map_err(|e| {
-                AuthError::internal_error(&format!("Failed to find session by token: {}", e))
-            })?;
-
-        Ok(result)
+    async fn find_by_token(&self, token_hash: &str) -> AuthResult<Option<SessionModel>> {
+        Ok(self.sessions.find_one(Filter::new().eq("access_token_hash", token_hash)).await?)
     }
 
     /// Find all sessions for a user
-    fn find_by_user_id(&self, user_id: &DbId) -> AuthResult<Vec<SessionModel>> {
-        let filter = doc! { "user_id": user_id.to_string() };
-
-        let cursor = self
-            .session_collection
-            .find(filter, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to find sessions: {}", e)))?;
-
-        let sessions: Vec<SessionModel> = cursor.filter_map(|r| r.ok()).collect();
+    async fn find_by_user_id(&self, user_id: &DbId) -> AuthResult<Vec<SessionModel>> {
+        Ok(self.sessions.find(Filter::new().eq("user_id", user_id.to_bson()), None).await?)
+    }
 
-        Ok(sessions)
+    /// Find all sessions issued for an organization
+    async fn find_by_org(&self, org_id: &DbId) -> AuthResult<Vec<SessionModel>> {
+        Ok(self.sessions.find(Filter::new().eq("org_id", org_id.to_bson()), None).await?)
     }
 
     /// Update session
-    fn update(&self, id: &DbId, session: UpdateSession) -> AuthResult<UpdateSession> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-        let update = doc! { "$set": {
-            "access_token_hash": session.access_token_hash.clone(),
-            "refresh_token_hash": session.refresh_token_hash.clone(),
-            "expires_at": session.expires_at,
-            "is_revoked": session.is_revoked
-        }};
-
-        let _ = self
-            .session_collection
-            .find_one_and_update(filter, update, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to update session: {}", e)))?
-            .ok_or_else(|| AuthError::not_found("Session not found"));
-
+    async fn update(&self, id: &DbId, session: UpdateSession) -> AuthResult<UpdateSession> {
+        let update = Update::new()
+            .set("access_token_hash", session.access_token_hash.clone())
+            .set("refresh_token_hash", session.refresh_token_hash.clone())
+            .set("expires_at", session.expires_at)
+            .set(TTL_FIELD, ttl_date(session.expires_at))
+            .set("is_revoked", session.is_revoked);
+
+        self.sessions.update_by_id(id, update).await?;
         Ok(session)
     }
 
-    /// Revoke a session
-    fn revoke(&self, id: &DbId) -> AuthResult<()> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "_id": bson_oid };
-        let update = doc! { "$set": { "is_revoked": true }};
+    /// Point a session at a newly issued access token
+    async fn rotate_access_token(&self, id: &DbId, access_token_hash: &str) -> AuthResult<()> {
+        let update = Update::new()
+            .set("access_token_hash", access_token_hash)
+            .set("last_used_at", chrono::Utc::now().timestamp());
 
-        self.session_collection
-            .update_one(filter, update, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to revoke session: {}", e)))?;
+        self.sessions.update_by_id(id, update).await?;
+        Ok(())
+    }
 
+    /// Revoke a session
+    async fn revoke(&self, id: &DbId) -> AuthResult<()> {
+        self.sessions.update_by_id(id, Update::new().set("is_revoked", true)).await?;
         Ok(())
     }
 
     /// Revoke all sessions for a user
-    fn revoke_all(&self, user_id: &DbId) -> AuthResult<u64> {
-        let filter = doc! { "user_id": user_id.to_string() };
-        let update = doc! { "$set": { "is_revoked": true }};
+    async fn revoke_all(&self, user_id: &DbId) -> AuthResult<u64> {
+        let filter = Filter::new().eq("user_id", user_id.to_bson());
+        Ok(self.sessions.update_many(filter, Update::new().set("is_revoked", true)).await?)
+    }
 
-        let result = self
-            .session_collection
-            .update_many(filter, update, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to revoke sessions: {}", e)))?;
+    /// Delete all sessions and refresh tokens of a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let owned = Filter::new().eq("user_id", user_id.to_bson());
 
-        Ok(result.modified_count)
+        let sessions = self.sessions.delete_many(owned.clone()).await?;
+        self.refresh_tokens.delete_many(owned).await?;
+
+        Ok(sessions)
     }
 
     /// Delete expired sessions
-    fn cleanup_expired(&self) -> AuthResult<u64> {
-        let now = chrono::Utc::now().timestamp();
-        let filter = doc! { "expires_at": { "$lt": now }};
-
-        let result = self
-            .session_collection
-            .delete_many(filter, None)
-            .map_err(|e| {
-                AuthError::internal_error(&format!("Failed to cleanup sessions: {}", e))
-            })?;
-
-        Ok(result.deleted_count)
+    async fn cleanup_expired(&self) -> AuthResult<u64> {
+        let expired = Filter::new().lt("expires_at", chrono::Utc::now().timestamp());
+        Ok(self.sessions.delete_many(expired).await?)
     }
 
     /// Create refresh token
-    fn create_refresh_token(&self, input: CreateRefreshToken) -> AuthResult<RefreshTokenModel> {
+    async fn create_refresh_token(&self, input: CreateRefreshToken) -> AuthResult<RefreshTokenModel> {
         let id = generate_id();
         let now = chrono::Utc::now().timestamp();
 
@@ -174,80 +172,47 @@ impl SessionStore for MongoSessionStore {
             replaced_by: None,
         };
 
-        self.refresh_token_collection
-            .insert_one(&token, None)
-            .map_err(|e| {
-                AuthError::internal_error(&format!("Failed to create refresh token: {}", e))
-            })?;
-
+        self.refresh_tokens.insert_expiring(&token, token.expires_at).await?;
         Ok(token)
     }
 
     /// Find refresh token by ID
-    fn find_refresh_token(&self, id: &DbId) -> AuthResult<Option<RefreshTokenModel>> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-
-        let result = self
-            .refresh_token_collection
-            .find_one(filter, None)
-            .map_err(|e| {
-                AuthError::internal_error(&format!("Failed to find refresh token: {}", e))
-            })?;
-
-        Ok(result)
+    async fn find_refresh_token(&self, id: &DbId) -> AuthResult<Option<RefreshTokenModel>> {
+        Ok(self.refresh_tokens.find_by_id(id).await?)
     }
 
     /// Find refresh token by hash
-    fn find_refresh_token_by_hash(
+    async fn find_refresh_token_by_hash(
         &self,
         token_hash: &str,
     ) -> AuthResult<Option<RefreshTokenModel>> {
-        let filter = doc! { "token_hash": token_hash };
-
-        let result = self
-            .refresh_token_collection
-            .find_one(filter, None)
-            .map_err(|e| {
-                AuthError::internal_error(&format!("Failed to find refresh token: {}", e))
-            })?;
-
-        Ok(result)
+        Ok(self.refresh_tokens.find_one(Filter::new().eq("token_hash", token_hash)).await?)
     }
 
     /// Revoke refresh token
-    fn revoke_refresh_token(&self, id: &DbId) -> AuthResult<()> {
-        let now = chrono::Utc::now().timestamp();
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-        let update = doc! { "$set": { "revoked": true, "revoked_at": now }};
-
-        self.refresh_token_collection
-            .update_one(filter, update, None)
-            .map_err(|e| {
-                AuthError::internal_error(&format!("Failed to revoke refresh token: {}", e))
-            })?;
+    async fn revoke_refresh_token(&self, id: &DbId) -> AuthResult<()> {
+        let update = Update::new()
+            .set("revoked", true)
+            .set("revoked_at", chrono::Utc::now().timestamp());
 
+        self.refresh_tokens.update_by_id(id, update).await?;
         Ok(())
     }
 
     /// Replace refresh token (for rotation)
-    fn replace_refresh_token(
+    async fn replace_refresh_token(
         &self,
         old_id: &DbId,
         new_token: CreateRefreshToken,
     ) -> AuthResult<()> {
         // Revoke old token
-        self.revoke_refresh_token(old_id)?;
+        self.revoke_refresh_token(old_id).await?;
 
-        // Create new token
-        let input = CreateRefreshToken {
-            user_id: new_token.user_id.clone(),
-            token_hash: new_token.token_hash.clone(),
-            expires_in: new_token.expires_in - chrono::Utc::now().timestamp(),
-        };
+        // Create new token and link the old one to it
+        let token = self.create_refresh_token(new_token).await?;
 
-        self.create_refresh_token(input)?;
+        let update = Update::new().set("replaced_by", token.id.to_string());
+        self.refresh_tokens.update_by_id(old_id, update).await?;
 
         Ok(())
     }
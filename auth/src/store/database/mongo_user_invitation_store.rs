@@ -0,0 +1,113 @@
+//! MongoDB User Invitation Store Implementation
+
+use async_trait::async_trait;
+use mongodb::bson::{Bson, doc};
+use mongodb::Collection;
+
+use crate::models::user_invitation::{CreateUserInvitation, UserInvitation};
+use crate::store::user_invitation_store::UserInvitationStore;
+use crate::utils::errors::{AuthError, AuthResult};
+use database::utils::{DbId, generate_id};
+use database::indexes::{IndexReport, IndexSpec, ensure_indexes};
+use database::repository::{Filter, Repository, Update};
+
+/// MongoDB implementation of UserInvitationStore
+pub struct MongoUserInvitationStore {
+    invitations: Repository<UserInvitation>,
+}
+
+impl MongoUserInvitationStore {
+    /// Create a new MongoUserInvitationStore
+    pub fn new(collection: Collection<UserInvitation>) -> Self {
+        Self { invitations: Repository::new(collection) }
+    }
+
+    /// Create the indexes backing this store's lookups
+    pub async fn ensure_indexes(&self) -> Vec<IndexReport> {
+        vec![
+            ensure_indexes(
+                self.invitations.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("org_id", doc! { "org_id": 1 }),
+                    IndexSpec::new("user_id", doc! { "user_id": 1 }),
+                ],
+            )
+            .await,
+        ]
+    }
+}
+
+#[async_trait]
+impl UserInvitationStore for MongoUserInvitationStore {
+    /// Create a new invitation
+    async fn create(&self, input: CreateUserInvitation) -> AuthResult<UserInvitation> {
+        let now = chrono::Utc::now().timestamp();
+
+        let invitation = UserInvitation {
+            id: generate_id(),
+            user_id: input.user_id,
+            org_id: input.org_id,
+            email: input.email,
+            role: input.role,
+            nonce_hash: input.nonce_hash,
+            invited_by: input.invited_by,
+            expires_at: now + input.expires_in,
+            accepted_at: None,
+            created_at: now,
+            updated_at: None,
+        };
+
+        self.invitations.insert(&invitation).await?;
+        Ok(invitation)
+    }
+
+    /// Find invitation by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<UserInvitation>> {
+        Ok(self.invitations.find_by_id(id).await?)
+    }
+
+    /// List pending invitations of an organization
+    async fn list_pending(&self, org_id: &DbId) -> AuthResult<Vec<UserInvitation>> {
+        let filter = Filter::new()
+            .eq("org_id", org_id.to_bson())
+            .eq("accepted_at", Bson::Null)
+            .gt("expires_at", chrono::Utc::now().timestamp());
+
+        Ok(self.invitations.find(filter, None).await?)
+    }
+
+    /// Replace the link nonce and extend the expiry
+    async fn renew(&self, id: &DbId, nonce_hash: &str, expires_in: i64) -> AuthResult<()> {
+        let now = chrono::Utc::now().timestamp();
+        let update = Update::new()
+            .set("nonce_hash", nonce_hash)
+            .set("expires_at", now + expires_in)
+            .set("updated_at", now);
+
+        if !self.invitations.update_by_id(id, update).await? {
+            return Err(AuthError::not_found("Invitation not found"));
+        }
+
+        Ok(())
+    }
+
+    /// Mark invitation as accepted
+    async fn mark_accepted(&self, id: &DbId) -> AuthResult<()> {
+        let now = chrono::Utc::now().timestamp();
+        let update = Update::new().set("accepted_at", now).set("updated_at", now);
+        self.invitations.update_by_id(id, update).await?;
+        Ok(())
+    }
+
+    /// Delete an invitation
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        self.invitations.delete_by_id(id).await?;
+        Ok(())
+    }
+
+    /// Delete invitations addressed to a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        Ok(self.invitations.delete_many(Filter::new().eq("user_id", user_id.to_bson())).await?)
+    }
+}
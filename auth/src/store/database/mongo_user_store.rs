@@ -1,33 +1,121 @@
 //! MongoDB User Store Implementation
 
-use mongodb::bson::{doc, oid};
-use mongodb::sync::Collection;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use mongodb::bson::{self, Bson, doc};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
 
-use crate::models::user::{CreateUserInput, UpdateUserInput, User};
+use crate::models::user::{CreateUserInput, UpdateUserInput, User, UserFilter};
 use crate::store::user_store::{IdentifierType, UserStore, identify_user};
+use crate::models::verification::VerificationMedium;
 use crate::utils::errors::{AuthError, AuthResult};
-use database::utils::{DbId, generate_id};
+use crate::utils::usernames;
+use database::pagination::{Page, PageRequest};
+use database::utils::{DbError, DbId, generate_id, parse_id};
+use database::indexes::{IndexReport, IndexSpec, ensure_indexes};
+use database::repository::{Filter, Repository, Update, to_bson};
 
 /// MongoDB implementation of UserStore
 pub struct MongoUserStore {
-    collection: Collection<User>,
+    users: Repository<User>,
+}
+
+/// Encode a time like `User`'s chrono timestamps
+fn time_bson(time: &DateTime<Utc>) -> Bson {
+    bson::to_bson(time).unwrap_or(Bson::Null)
+}
+
+/// Error naming the identifier a write collided with, or the error as is
+fn identifier_taken(
+    error: DbError,
+    email: Option<&str>,
+    phone: Option<&str>,
+    username: Option<&str>,
+) -> AuthError {
+    let taken = match error.duplicate_index() {
+        Some("email_unique") => email.map(AuthError::email_already_exists),
+        Some("phone_unique") => phone.map(AuthError::phone_already_exists),
+        Some("username_key_unique") => username.map(AuthError::username_already_exists),
+        _ => None,
+    };
+
+    taken.unwrap_or_else(|| error.into())
+}
+
+/// Query for a user list filter
+fn query(filter: &UserFilter) -> AuthResult<Filter> {
+    let mut query = Filter::new().not_deleted();
+
+    if let Some(is_active) = filter.is_active {
+        query = query.eq("isActive", is_active);
+    }
+    if let Some(is_verified) = filter.is_verified {
+        query = query.eq("isVerified", is_verified);
+    }
+    for (name, value) in &filter.profile {
+        query = query.eq(&format!("profile.{}", name), to_bson(value)?);
+    }
+
+    Ok(query)
+}
+
+/// Current time encoded like `User`'s chrono timestamps
+fn now_bson() -> Bson {
+    time_bson(&chrono::Utc::now())
 }
 
 impl MongoUserStore {
     /// Create a new MongoUserStore
     pub fn new(collection: Collection<User>) -> Self {
-        Self { collection }
+        Self { users: Repository::new(collection) }
+    }
+
+    /// Create the indexes backing this store's lookups
+    ///
+    /// Email, phone and username are unique when set. Username uniqueness is
+    /// on the confusable skeleton, so lookalike names collide.
+    pub async fn ensure_indexes(&self) -> Vec<IndexReport> {
+        vec![
+            ensure_indexes(
+                self.users.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("email_unique", doc! { "email": 1 }).unique_when_set("email"),
+                    IndexSpec::new("phone_unique", doc! { "phone": 1 }).unique_when_set("phone"),
+                    IndexSpec::new("username_key_unique", doc! { "username_key": 1 })
+                        .unique_when_set("username_key"),
+                    IndexSpec::new("org_ids", doc! { "org_ids": 1 }),
+                ],
+            )
+            .await,
+        ]
     }
 
     /// Get the MongoDB collection
     pub fn collection(&self) -> &Collection<User> {
-        &self.collection
+        self.users.collection()
+    }
+
+    /// Set a boolean status field on a user
+    async fn set_flag(&self, id: &DbId, field: &str, value: bool) -> AuthResult<()> {
+        self.update_existing(id, Update::new().set(field, value)).await
+    }
+
+    /// Apply an update to an existing user, stamping `updated_at`
+    async fn update_existing(&self, id: &DbId, update: Update) -> AuthResult<()> {
+        if !self.users.update_by_id(id, update.set("updated_at", now_bson())).await? {
+            return Err(AuthError::not_found("User not found"));
+        }
+
+        Ok(())
     }
 }
 
+#[async_trait]
 impl UserStore for MongoUserStore {
     /// Create a new user
-    fn create(&self, input: CreateUserInput) -> AuthResult<User> {
+    async fn create(&self, input: CreateUserInput) -> AuthResult<User> {
         // Generate ID
         let id = generate_id();
         let now = chrono::Utc::now();
@@ -38,7 +126,8 @@ impl UserStore for MongoUserStore {
             email:          input.email.clone(),
             password_hash:  input.password,
             phone:          input.phone.clone(),
-            username:       None,
+            username_key:   input.username.as_deref().map(usernames::skeleton),
+            username:       input.username,
             first_name:     None,
             last_name:      None,
             is_active:      true,
@@ -48,175 +137,242 @@ impl UserStore for MongoUserStore {
             created_at:     now,
             updated_at:     None,
             last_login:     None,
+            org_ids:        Vec::new(),
+            deletion_scheduled_at: None,
+            deleted_at:     None,
+            is_admin:       false,
+            profile:        Default::default(),
         };
 
         // Insert into database
-        self.collection
-            .insert_one(&user, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to create user: {}", e)))?;
+        self.users.insert(&user).await.map_err(|e| {
+            identifier_taken(e, user.email.as_deref(), user.phone.as_deref(), user.username.as_deref())
+        })?;
 
         // Return created user
         Ok(user)
     }
 
     /// Find user by ID
-    fn find_by_id(&self, id: &DbId) -> AuthResult<Option<User>> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-
-        let result = self
-            .collection
-            .find_one(filter, None)
-            .map_err(|_e| AuthError::internal_error(&format!("Failed to find user")))?;
-
-        Ok(result)
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<User>> {
+        Ok(self.users.find_by_id(id).await?)
     }
 
     /// Find user by email
-    fn find_by_email(&self, email: &str) -> AuthResult<Option<User>> {
-        let filter = doc! { "email": email };
-
-        let result = self
-            .collection
-            .find_one(filter, None)
-            .map_err(|_e| AuthError::internal_error(&format!("Failed to find user by email")))?;
-
-        Ok(result)
+    async fn find_by_email(&self, email: &str) -> AuthResult<Option<User>> {
+        Ok(self.users.find_one(Filter::new().eq("email", email)).await?)
     }
 
     /// Find user by phone
-    fn find_by_phone(&self, phone: &str) -> AuthResult<Option<User>> {
-        let filter = doc! { "phone": phone };
-
-        let result = self
-            .collection
-            .find_one(filter, None)
-            .map_err(|_e| AuthError::internal_error(&format!("Failed to find user by phone")))?;
-
-        Ok(result)
+    async fn find_by_phone(&self, phone: &str) -> AuthResult<Option<User>> {
+        Ok(self.users.find_one(Filter::new().eq("phone", phone)).await?)
     }
 
-    /// Find user by username
-    fn find_by_username(&self, username: &str) -> AuthResult<Option<User>> {
-        let filter = doc! { "username": username };
-
-        let result = self
-            .collection
-            .find_one(filter, None)
-            .map_err(|_e| AuthError::internal_error(&format!("Failed to find user by username")))?;
+    /// Find user by username - matches case-insensitively and across lookalikes
+    async fn find_by_username(&self, username: &str) -> AuthResult<Option<User>> {
+        let normalized = usernames::normalize(username);
+        let filter = Filter::new().eq("username_key", usernames::skeleton(&normalized));
 
-        Ok(result)
+        Ok(self.users.find_one(filter).await?)
     }
 
     /// Find user by any identifier (email, phone, or username)
-    fn find_by_identifier(&self, identifier: &str) -> AuthResult<Option<User>> {
+    async fn find_by_identifier(&self, identifier: &str) -> AuthResult<Option<User>> {
         match identify_user(identifier) {
-            IdentifierType::Email => self.find_by_email(identifier),
-            IdentifierType::Phone => self.find_by_phone(identifier),
-            IdentifierType::Username => self.find_by_username(identifier),
+            IdentifierType::Email => self.find_by_email(identifier).await,
+            IdentifierType::Phone => self.find_by_phone(identifier).await,
+            IdentifierType::Username => self.find_by_username(identifier).await,
         }
     }
 
     /// Update user
-    fn update(&self, id: &DbId, input: UpdateUserInput) -> AuthResult<User> {
-        // Build update document
-        let mut update_doc = doc! { "$set": {} };
-        let set = update_doc.get_document_mut("$set").unwrap();
+    async fn update(&self, id: &DbId, input: UpdateUserInput) -> AuthResult<User> {
+        let mut update = Update::new().set("updated_at", now_bson());
 
-        if let Some(ref email) = input.email {
-            set.insert("email", email);
-        }
-        if let Some(ref phone) = input.phone {
-            set.insert("phone", phone);
-        }
         if let Some(ref username) = input.username {
-            set.insert("username", username);
+            update = update
+                .set("username", username)
+                .set("username_key", usernames::skeleton(username));
         }
         if let Some(ref first_name) = input.first_name {
-            set.insert("first_name", first_name);
+            update = update.set("firstName", first_name);
         }
         if let Some(ref last_name) = input.last_name {
-            set.insert("last_name", last_name);
+            update = update.set("lastName", last_name);
         }
 
-
-        // Add updated_at timestamp
-        set.insert("updated_at", chrono::Utc::now().timestamp());
-
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
+        // Profile changes touch single keys so other fields are kept
+        for (name, value) in input.profile.iter().flatten() {
+            let key = format!("profile.{}", name);
+            update = if value.is_null() {
+                update.unset(&key)
+            } else {
+                update.set(&key, to_bson(value)?)
+            };
+        }
 
         let result = self
-            .collection
-            .find_one_and_update(filter, update_doc, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to update user: {}", e)))?;
+            .users
+            .find_one_and_update(Filter::by_id(id), update)
+            .await
+            .map_err(|e| identifier_taken(e, None, None, input.username.as_deref()))?;
 
         result.ok_or_else(|| AuthError::not_found("User not found"))
     }
 
     /// Update user password
-    fn update_password(&self, id: &DbId, password_hash: &str) -> AuthResult<()> {
-        let now = chrono::Utc::now().timestamp();
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-        let update = doc! {
-            "$set": {
-                "password_hash": password_hash,
-                "updated_at": now
-            }
+    async fn update_password(&self, id: &DbId, password_hash: &str) -> AuthResult<()> {
+        self.update_existing(id, Update::new().set("password_hash", password_hash))
+            .await
+    }
+
+    /// Replace a confirmed email or phone
+    async fn set_contact(&self, id: &DbId, medium: &VerificationMedium, value: &str) -> AuthResult<()> {
+        let field = match medium {
+            VerificationMedium::Email => "email",
+            VerificationMedium::Phone => "phone",
         };
+        let update = Update::new().set(field, value).set("updated_at", now_bson());
 
-        let result = self
-            .collection
-            .update_one(filter, update, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to update password: {}", e)))?;
+        let matched = self
+            .users
+            .update_by_id(id, update)
+            .await
+            .map_err(|e| identifier_taken(e, Some(value), Some(value), None))?;
 
-        if result.modified_count == 0 {
+        if !matched {
             return Err(AuthError::not_found("User not found"));
         }
 
         Ok(())
     }
 
-    /// Delete user
-    fn delete(&self, id: &DbId) -> AuthResult<()> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
+    /// Activate or deactivate user
+    async fn set_active(&self, id: &DbId, is_active: bool) -> AuthResult<()> {
+        self.set_flag(id, "isActive", is_active).await
+    }
 
-        self.collection
-            .delete_one(filter, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to delete user: {}", e)))?;
+    /// Mark user as verified or unverified
+    async fn set_verified(&self, id: &DbId, is_verified: bool) -> AuthResult<()> {
+        self.set_flag(id, "isVerified", is_verified).await
+    }
+
+    /// Count a failed password attempt
+    async fn record_failed_login(&self, id: &DbId) -> AuthResult<()> {
+        self.update_existing(id, Update::new().inc("login_attempts", 1)).await
+    }
+
+    /// Clear the failed attempt count
+    async fn reset_login_attempts(&self, id: &DbId) -> AuthResult<()> {
+        self.update_existing(id, Update::new().set("login_attempts", 0)).await
+    }
 
+    /// Delete user
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        self.users.delete_by_id(id).await?;
         Ok(())
     }
 
+    /// Schedule the account for deletion, or cancel with `None`
+    async fn schedule_deletion(&self, id: &DbId, at: Option<DateTime<Utc>>) -> AuthResult<()> {
+        let scheduled_at = at.as_ref().map(time_bson).unwrap_or(Bson::Null);
+        self.update_existing(id, Update::new().set("deletion_scheduled_at", scheduled_at))
+            .await
+    }
+
+    /// List users whose scheduled deletion is due
+    async fn list_due_for_deletion(&self, now: DateTime<Utc>) -> AuthResult<Vec<User>> {
+        // Timestamps are RFC 3339 strings in UTC, so they compare in order
+        let filter = Filter::new()
+            .ne("deletion_scheduled_at", Bson::Null)
+            .lte("deletion_scheduled_at", time_bson(&now))
+            .not_deleted();
+
+        Ok(self.users.find(filter, None).await?)
+    }
+
+    /// Strip personal data and mark the user deleted
+    async fn anonymize(&self, id: &DbId) -> AuthResult<()> {
+        let update = Update::new()
+            .set("email", Bson::Null)
+            .set("phone", Bson::Null)
+            .set("username", Bson::Null)
+            .set("username_key", Bson::Null)
+            .set("firstName", Bson::Null)
+            .set("lastName", Bson::Null)
+            .set("profile", doc! {})
+            .set("password_hash", "")
+            .set("isActive", false)
+            .set("isVerified", false)
+            .set("login_attempts", 0)
+            .set("locked_until", Bson::Null)
+            .set("last_login", Bson::Null)
+            .set("org_ids", Bson::Array(Vec::new()))
+            .set("deletion_scheduled_at", Bson::Null)
+            .set("deleted_at", now_bson());
+
+        self.update_existing(id, update).await
+    }
+
     /// List all users (with pagination)
-    fn list(&self, page: u32, limit: u32) -> AuthResult<Vec<User>> {
-        let skip = (page * limit) as usize;
-        let limit = limit as usize;
-        let filter = doc! {};
+    async fn list(&self, filter: &UserFilter, page: u32, limit: u32) -> AuthResult<Vec<User>> {
+        let options = FindOptions::builder()
+            .skip(u64::from(page) * u64::from(limit))
+            .limit(i64::from(limit))
+            .build();
 
-        let cursor = self
-            .collection
-            .find(filter, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to list users: {}", e)))?;
+        Ok(self.users.find(query(filter)?, options).await?)
+    }
 
-        let users: Vec<User> = cursor
-            .skip(skip)
-            .take(limit)
-            .filter_map(|r| r.ok())
-            .collect();
+    /// List users after a cursor
+    ///
+    /// Ordered on `id` alone: the ObjectIds this store assigns grow with
+    /// creation time, and the unique `id` index serves the range.
+    async fn list_after(&self, filter: &UserFilter, page: &PageRequest) -> AuthResult<Page<User>> {
+        let mut query = query(filter)?;
+        if let Some(after) = &page.after {
+            let id = parse_id(&after.id).map_err(|_| AuthError::invalid_request("Invalid cursor"))?;
+            query = query.gt("id", id.to_bson());
+        }
+
+        let options = FindOptions::builder()
+            .sort(doc! { "id": 1 })
+            .limit(page.fetch_limit())
+            .build();
+
+        let users = self.users.find(query, options).await?;
+        Ok(Page::from_rows(users, page, User::cursor))
+    }
 
-        Ok(users)
+    /// Count users matching the filter
+    async fn count(&self, filter: &UserFilter) -> AuthResult<u64> {
+        Ok(self.users.count(query(filter)?).await?)
     }
 
-    /// Count total users
-    fn count(&self) -> AuthResult<u64> {
-        let count = self
-            .collection
-            .count_documents(doc! {}, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to count users: {}", e)))?;
+    /// List users belonging to an organization (with pagination)
+    async fn list_by_org(&self, org_id: &DbId, page: u32, limit: u32) -> AuthResult<Vec<User>> {
+        let options = FindOptions::builder()
+            .skip(u64::from(page) * u64::from(limit))
+            .limit(i64::from(limit))
+            .build();
 
-        Ok(count)
+        Ok(self.users.find(Filter::new().eq("org_ids", org_id.to_bson()), options).await?)
+    }
+
+    /// Count users belonging to an organization
+    async fn count_by_org(&self, org_id: &DbId) -> AuthResult<u64> {
+        Ok(self.users.count(Filter::new().eq("org_ids", org_id.to_bson())).await?)
+    }
+
+    /// Add user to an organization
+    async fn add_to_org(&self, id: &DbId, org_id: &DbId) -> AuthResult<()> {
+        self.users.update_by_id(id, Update::new().add_to_set("org_ids", org_id.to_bson())).await?;
+        Ok(())
+    }
+
+    /// Remove user from an organization
+    async fn remove_from_org(&self, id: &DbId, org_id: &DbId) -> AuthResult<()> {
+        self.users.update_by_id(id, Update::new().pull("org_ids", org_id.to_bson())).await?;
+        Ok(())
     }
 }
@@ -1,30 +1,49 @@
 //! MongoDB Verification Store Implementation
 
-use mongodb::bson::{doc, oid};
-use mongodb::sync::Collection;
+use async_trait::async_trait;
+use mongodb::bson::{Bson, doc};
+use mongodb::Collection;
 
 use crate::models::verification::{
     CreateVerificationCode, VerificationCodeModel, VerificationMedium, VerificationPurpose,
 };
 use crate::store::verification_store::VerificationStore;
-use crate::utils::errors::{AuthError, AuthResult};
+use crate::utils::errors::AuthResult;
 use database::utils::{DbId, generate_id};
+use database::indexes::{IndexReport, IndexSpec, ensure_indexes};
+use database::repository::{Filter, Repository, Update, to_bson};
 
 /// MongoDB implementation of VerificationStore
 pub struct MongoVerificationStore {
-    collection: Collection<VerificationCodeModel>,
+    codes: Repository<VerificationCodeModel>,
 }
 
 impl MongoVerificationStore {
     /// Create a new MongoVerificationStore
     pub fn new(collection: Collection<VerificationCodeModel>) -> Self {
-        Self { collection }
+        Self { codes: Repository::new(collection) }
+    }
+
+    /// Create the indexes backing this store's lookups
+    pub async fn ensure_indexes(&self) -> Vec<IndexReport> {
+        vec![
+            ensure_indexes(
+                self.codes.collection(),
+                &[
+                    IndexSpec::new("id_unique", doc! { "id": 1 }).unique(),
+                    IndexSpec::new("user_medium_purpose", doc! { "user_id": 1, "medium": 1, "purpose": 1 }),
+                    IndexSpec::ttl(),
+                ],
+            )
+            .await,
+        ]
     }
 }
 
+#[async_trait]
 impl VerificationStore for MongoVerificationStore {
     /// Create a new verification code
-    fn create(&self, input: CreateVerificationCode) -> AuthResult<VerificationCodeModel> {
+    async fn create(&self, input: CreateVerificationCode) -> AuthResult<VerificationCodeModel> {
         let id = generate_id();
         let now = chrono::Utc::now().timestamp();
 
@@ -40,110 +59,60 @@ impl VerificationStore for MongoVerificationStore {
             verified_at: None,
         };
 
-        self.collection.insert_one(&code, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to create verification code: {}", e))
-        })?;
-
+        self.codes.insert_expiring(&code, code.expires_at).await?;
         Ok(code)
     }
 
     /// Find verification code by ID
-    fn find_by_id(&self, id: &DbId) -> AuthResult<Option<VerificationCodeModel>> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-
-        let result = self.collection.find_one(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to find verification code: {}", e))
-        })?;
-
-        Ok(result)
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<VerificationCodeModel>> {
+        Ok(self.codes.find_by_id(id).await?)
     }
 
     /// Find valid verification code by user_id, medium, and purpose
-    fn find_valid_code(
+    async fn find_valid_code(
         &self,
         user_id: &DbId,
         medium: VerificationMedium,
         purpose: VerificationPurpose,
     ) -> AuthResult<Option<VerificationCodeModel>> {
-        let now = chrono::Utc::now().timestamp();
-        let filter = doc! {
-            "user_id": user_id.to_string(),
-            "medium": format!("{:?}", medium).to_lowercase(),
-            "purpose": format!("{:?}", purpose).to_lowercase(),
-            "expires_at": { "$gt": now },
-            "verified_at": null
-        };
-
-        let result = self.collection.find_one(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to find valid verification code: {}", e))
-        })?;
-
-        Ok(result)
+        // Enums are stored under their serde names ("sign_up", not "signup")
+        let filter = Filter::new()
+            .eq("user_id", user_id.to_bson())
+            .eq("medium", to_bson(&medium)?)
+            .eq("purpose", to_bson(&purpose)?)
+            .gt("expires_at", chrono::Utc::now().timestamp())
+            .eq("verified_at", Bson::Null);
+
+        Ok(self.codes.find_one(filter).await?)
     }
 
     /// Verify a code (mark as verified)
-    fn verify(&self, id: &DbId) -> AuthResult<()> {
-        let now = chrono::Utc::now().timestamp();
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-        let update = doc! { "$set": { "verified_at": now }};
-
-        self.collection
-            .update_one(filter, update, None)
-            .map_err(|e| AuthError::internal_error(&format!("Failed to verify code: {}", e)))?;
-
+    async fn verify(&self, id: &DbId) -> AuthResult<()> {
+        let update = Update::new().set("verified_at", chrono::Utc::now().timestamp());
+        self.codes.update_by_id(id, update).await?;
         Ok(())
     }
 
     /// Increment failed attempts
-    fn increment_attempts(&self, id: &DbId) -> AuthResult<()> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-        let update = doc! { "$inc": { "attempts": 1 }};
-
-        self.collection
-            .update_one(filter, update, None)
-            .map_err(|e| {
-                AuthError::internal_error(&format!("Failed to increment attempts: {}", e))
-            })?;
-
+    async fn increment_attempts(&self, id: &DbId) -> AuthResult<()> {
+        self.codes.update_by_id(id, Update::new().inc("attempts", 1)).await?;
         Ok(())
     }
 
     /// Delete/expire a code
-    fn delete(&self, id: &DbId) -> AuthResult<()> {
-        let bson_oid = oid::ObjectId::from_bytes(id.as_bytes().clone());
-        let filter = doc! { "id": bson_oid };
-
-        self.collection.delete_one(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to delete verification code: {}", e))
-        })?;
-
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        self.codes.delete_by_id(id).await?;
         Ok(())
     }
 
     /// Delete all codes for a user
-    fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
-        let bson_oid = oid::ObjectId::from_bytes(user_id.as_bytes().clone());
-        let filter = doc! { "user_id": bson_oid };
-
-        let result = self.collection.delete_many(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to delete verification codes: {}", e))
-        })?;
-
-        Ok(result.deleted_count)
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        Ok(self.codes.delete_many(Filter::new().eq("user_id", user_id.to_bson())).await?)
     }
 
     /// Cleanup expired codes
-    fn cleanup_expired(&self) -> AuthResult<u64> {
-        let now = chrono::Utc::now().timestamp();
-        let filter = doc! { "expires_at": { "$lt": now }};
-
-        let result = self.collection.delete_many(filter, None).map_err(|e| {
-            AuthError::internal_error(&format!("Failed to cleanup expired codes: {}", e))
-        })?;
-
-        Ok(result.deleted_count)
+    async fn cleanup_expired(&self) -> AuthResult<u64> {
+        let expired = Filter::new().lt("expires_at", chrono::Utc::now().timestamp());
+        Ok(self.codes.delete_many(expired).await?)
     }
 }
@@ -0,0 +1,105 @@
+//! In-memory API Key Store Implementation
+
+use async_trait::async_trait;
+use std::sync::RwLock;
+
+use super::{Table, read, write};
+use crate::models::api_key::{ApiKey, CreateApiKey};
+use crate::store::api_key_store::ApiKeyStore;
+use crate::utils::errors::{AuthError, AuthResult};
+use database::utils::{DbId, generate_id};
+
+/// In-memory implementation of ApiKeyStore
+///
+/// Key hashes are unique, as with the unique index of the other stores.
+#[derive(Default)]
+pub struct MemoryApiKeyStore {
+    keys: RwLock<Table<ApiKey>>,
+}
+
+impl MemoryApiKeyStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ApiKeyStore for MemoryApiKeyStore {
+    async fn create(&self, input: CreateApiKey) -> AuthResult<ApiKey> {
+        let now = chrono::Utc::now().timestamp();
+        let key = ApiKey {
+            id: generate_id(),
+            user_id: input.user_id,
+            name: input.name,
+            key_prefix: input.key_prefix,
+            key_hash: input.key_hash,
+            scopes: input.scopes,
+            rate_limit_per_minute: input.rate_limit_per_minute,
+            expires_at: input.expires_in.map(|expires_in| now + expires_in),
+            last_used_at: None,
+            last_used_ip: None,
+            is_revoked: false,
+            created_at: now,
+        };
+
+        let mut keys = write(&self.keys)?;
+        if keys.values().any(|k| k.key_hash == key.key_hash) {
+            return Err(AuthError::conflict("API key already exists"));
+        }
+        keys.insert(key.id.clone(), key.clone());
+
+        Ok(key)
+    }
+
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<ApiKey>> {
+        Ok(read(&self.keys)?.get(id).cloned())
+    }
+
+    async fn find_by_hash(&self, key_hash: &str) -> AuthResult<Option<ApiKey>> {
+        Ok(read(&self.keys)?.values().find(|k| k.key_hash == key_hash).cloned())
+    }
+
+    async fn list_by_user(&self, user_id: &DbId) -> AuthResult<Vec<ApiKey>> {
+        let mut keys: Vec<ApiKey> = read(&self.keys)?
+            .values()
+            .filter(|k| &k.user_id == user_id)
+            .cloned()
+            .collect();
+        keys.sort_by_key(|k| k.created_at);
+        Ok(keys)
+    }
+
+    async fn touch(&self, id: &DbId, ip_address: Option<&str>) -> AuthResult<()> {
+        if let Some(key) = write(&self.keys)?.get_mut(id) {
+            key.last_used_at = Some(chrono::Utc::now().timestamp());
+            key.last_used_ip = ip_address.map(String::from);
+        }
+        Ok(())
+    }
+
+    async fn revoke(&self, id: &DbId) -> AuthResult<()> {
+        if let Some(key) = write(&self.keys)?.get_mut(id) {
+            key.is_revoked = true;
+        }
+        Ok(())
+    }
+
+    async fn revoke_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let mut revoked = 0;
+        for key in write(&self.keys)?.values_mut() {
+            if &key.user_id == user_id && !key.is_revoked {
+                key.is_revoked = true;
+                revoked += 1;
+            }
+        }
+        Ok(revoked)
+    }
+
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let mut keys = write(&self.keys)?;
+        let before = keys.len();
+        keys.retain(|_, k| &k.user_id != user_id);
+        Ok((before - keys.len()) as u64)
+    }
+}
@@ -0,0 +1,113 @@
+//! In-memory Audit Store Implementation
+
+use async_trait::async_trait;
+use std::cmp::Reverse;
+use std::sync::RwLock;
+
+use super::{read, write};
+use crate::models::audit::{AuditEvent, AuditFilter, CreateAuditEvent};
+use crate::store::audit_store::AuditStore;
+use crate::utils::errors::AuthResult;
+use database::pagination::{Page, PageRequest};
+use database::utils::{DbId, generate_id};
+
+/// In-memory implementation of AuditStore
+#[derive(Default)]
+pub struct MemoryAuditStore {
+    events: RwLock<Vec<AuditEvent>>,
+}
+
+impl MemoryAuditStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matching events, newest first
+    fn matching(&self, filter: &AuditFilter) -> AuthResult<Vec<AuditEvent>> {
+        let mut events: Vec<AuditEvent> = read(&self.events)?
+            .iter()
+            .filter(|e| matches(e, filter))
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| Reverse(e.cursor()));
+        Ok(events)
+    }
+}
+
+fn matches(event: &AuditEvent, filter: &AuditFilter) -> bool {
+    filter.actor_id.as_ref().is_none_or(|id| event.actor_id.as_ref() == Some(id))
+        && filter.target_id.as_ref().is_none_or(|id| event.target_id.as_ref() == Some(id))
+        && filter.org_id.as_ref().is_none_or(|id| event.org_id.as_ref() == Some(id))
+        && filter.action.as_ref().is_none_or(|action| &event.action == action)
+        && filter.outcome.as_ref().is_none_or(|outcome| &event.outcome == outcome)
+        && filter.reason.as_ref().is_none_or(|reason| event.reason.as_ref() == Some(reason))
+        && filter.from.is_none_or(|from| event.created_at >= from)
+        && filter.to.is_none_or(|to| event.created_at <= to)
+}
+
+#[async_trait]
+impl AuditStore for MemoryAuditStore {
+    async fn record(&self, input: CreateAuditEvent) -> AuthResult<AuditEvent> {
+        let event = AuditEvent {
+            id: generate_id(),
+            action: input.action,
+            outcome: input.outcome,
+            actor_id: input.actor_id,
+            target_id: input.target_id,
+            impersonator_id: input.impersonator_id,
+            org_id: input.org_id,
+            ip_address: input.ip_address,
+            user_agent: input.user_agent,
+            reason: input.reason,
+            created_at: chrono::Utc::now().timestamp(),
+        };
+
+        write(&self.events)?.push(event.clone());
+        Ok(event)
+    }
+
+    async fn list(&self, filter: &AuditFilter, page: u32, limit: u32) -> AuthResult<Vec<AuditEvent>> {
+        Ok(self
+            .matching(filter)?
+            .into_iter()
+            .skip(page as usize * limit as usize)
+            .take(limit as usize)
+            .collect())
+    }
+
+    async fn list_after(&self, filter: &AuditFilter, page: &PageRequest) -> AuthResult<Page<AuditEvent>> {
+        let rows: Vec<AuditEvent> = self
+            .matching(filter)?
+            .into_iter()
+            .filter(|e| page.after.as_ref().is_none_or(|after| e.cursor() < *after))
+            .take(page.fetch_limit() as usize)
+            .collect();
+
+        Ok(Page::from_rows(rows, page, AuditEvent::cursor))
+    }
+
+    async fn count(&self, filter: &AuditFilter) -> AuthResult<u64> {
+        Ok(read(&self.events)?.iter().filter(|e| matches(e, filter)).count() as u64)
+    }
+
+    async fn delete_older_than(&self, before: i64) -> AuthResult<u64> {
+        let mut events = write(&self.events)?;
+        let count = events.len();
+        events.retain(|e| e.created_at >= before);
+        Ok((count - events.len()) as u64)
+    }
+
+    async fn anonymize_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let mut anonymized = 0;
+        for event in write(&self.events)?.iter_mut() {
+            let involved = event.actor_id.as_ref() == Some(user_id) || event.target_id.as_ref() == Some(user_id);
+            if involved && (event.ip_address.is_some() || event.user_agent.is_some()) {
+                event.ip_address = None;
+                event.user_agent = None;
+                anonymized += 1;
+            }
+        }
+        Ok(anonymized)
+    }
+}
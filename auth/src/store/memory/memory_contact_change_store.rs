@@ -0,0 +1,102 @@
+//! In-memory Contact Change Store Implementation
+
+use async_trait::async_trait;
+use std::sync::RwLock;
+
+use super::{Table, read, write};
+use crate::models::contact_change::{ContactChangeModel, CreateContactChange};
+use crate::models::verification::VerificationMedium;
+use crate::store::contact_change_store::ContactChangeStore;
+use crate::utils::errors::AuthResult;
+use database::utils::{DbId, generate_id};
+
+/// In-memory implementation of ContactChangeStore
+#[derive(Default)]
+pub struct MemoryContactChangeStore {
+    changes: RwLock<Table<ContactChangeModel>>,
+}
+
+impl MemoryContactChangeStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn set(&self, id: &DbId, apply: impl FnOnce(&mut ContactChangeModel)) -> AuthResult<()> {
+        if let Some(change) = write(&self.changes)?.get_mut(id) {
+            apply(change);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ContactChangeStore for MemoryContactChangeStore {
+    async fn create(&self, input: CreateContactChange) -> AuthResult<ContactChangeModel> {
+        let now = chrono::Utc::now().timestamp();
+        let change = ContactChangeModel {
+            id: generate_id(),
+            user_id: input.user_id,
+            medium: input.medium,
+            old_value: input.old_value,
+            new_value: input.new_value,
+            code_hash: input.code_hash,
+            revert_token_hash: input.revert_token_hash,
+            attempts: 0,
+            created_at: now,
+            expires_at: now + input.expires_in,
+            revert_expires_at: now + input.revert_expires_in,
+            confirmed_at: None,
+            reverted_at: None,
+        };
+
+        write(&self.changes)?.insert(change.id.clone(), change.clone());
+        Ok(change)
+    }
+
+    async fn find_pending(
+        &self,
+        user_id: &DbId,
+        medium: VerificationMedium,
+    ) -> AuthResult<Option<ContactChangeModel>> {
+        // The newest request wins, as with the other stores
+        Ok(read(&self.changes)?
+            .values()
+            .filter(|c| &c.user_id == user_id && c.medium == medium && c.is_pending())
+            .max_by_key(|c| c.created_at)
+            .cloned())
+    }
+
+    async fn find_by_revert_token(&self, token_hash: &str) -> AuthResult<Option<ContactChangeModel>> {
+        Ok(read(&self.changes)?
+            .values()
+            .find(|c| c.revert_token_hash.as_deref() == Some(token_hash))
+            .cloned())
+    }
+
+    async fn increment_attempts(&self, id: &DbId) -> AuthResult<()> {
+        self.set(id, |change| change.attempts += 1)
+    }
+
+    async fn mark_confirmed(&self, id: &DbId) -> AuthResult<()> {
+        self.set(id, |change| change.confirmed_at = Some(chrono::Utc::now().timestamp()))
+    }
+
+    async fn mark_reverted(&self, id: &DbId) -> AuthResult<()> {
+        self.set(id, |change| change.reverted_at = Some(chrono::Utc::now().timestamp()))
+    }
+
+    async fn cancel_pending(&self, user_id: &DbId, medium: VerificationMedium) -> AuthResult<u64> {
+        let mut changes = write(&self.changes)?;
+        let before = changes.len();
+        changes.retain(|_, c| !(&c.user_id == user_id && c.medium == medium && c.confirmed_at.is_none()));
+        Ok((before - changes.len()) as u64)
+    }
+
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let mut changes = write(&self.changes)?;
+        let before = changes.len();
+        changes.retain(|_, c| &c.user_id != user_id);
+        Ok((before - changes.len()) as u64)
+    }
+}
@@ -0,0 +1,130 @@
+//! In-memory OAuth Account Store Implementation
+
+use async_trait::async_trait;
+use std::sync::RwLock;
+
+use super::{Table, read, write};
+use crate::models::oauth::{CreateOAuthAccount, OAuthAccount, OAuthProvider};
+use crate::store::oauth_account_store::OAuthAccountStore;
+use crate::utils::errors::AuthError;
+use database::utils::{DbId, generate_id};
+
+/// In-memory implementation of OAuthAccountStore
+///
+/// A provider account can be linked to one user only.
+#[derive(Default)]
+pub struct MemoryOAuthAccountStore {
+    accounts: RwLock<Table<OAuthAccount>>,
+}
+
+impl MemoryOAuthAccountStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn find(&self, predicate: impl Fn(&OAuthAccount) -> bool) -> Result<Option<OAuthAccount>, AuthError> {
+        Ok(read(&self.accounts)?.values().find(|a| predicate(a)).cloned())
+    }
+}
+
+#[async_trait]
+impl OAuthAccountStore for MemoryOAuthAccountStore {
+    async fn create(&self, account: CreateOAuthAccount) -> Result<OAuthAccount, AuthError> {
+        let now = chrono::Utc::now().timestamp();
+
+        let oauth_account = OAuthAccount {
+            id: generate_id(),
+            user_id: account.user_id,
+            provider: account.provider,
+            provider_user_id: account.provider_user_id,
+            access_token: account.access_token,
+            refresh_token: account.refresh_token,
+            expires_at: account.expires_in.map(|expires_in| now + expires_in),
+            scope: account.scope,
+            created_at: now,
+            updated_at: None,
+        };
+
+        let mut accounts = write(&self.accounts)?;
+        let linked = accounts.values().any(|a| {
+            a.provider == oauth_account.provider && a.provider_user_id == oauth_account.provider_user_id
+        });
+        if linked {
+            return Err(AuthError::conflict("OAuth account is already linked"));
+        }
+        accounts.insert(oauth_account.id.clone(), oauth_account.clone());
+
+        Ok(oauth_account)
+    }
+
+    async fn find_by_id(&self, id: &DbId) -> Result<Option<OAuthAccount>, AuthError> {
+        Ok(read(&self.accounts)?.get(id).cloned())
+    }
+
+    async fn find_by_user_and_provider(
+        &self,
+        user_id: &DbId,
+        provider: &OAuthProvider,
+    ) -> Result<Option<OAuthAccount>, AuthError> {
+        self.find(|a| &a.user_id == user_id && &a.provider == provider)
+    }
+
+    async fn find_by_provider_user_id(
+        &self,
+        provider: &OAuthProvider,
+        provider_user_id: &str,
+    ) -> Result<Option<OAuthAccount>, AuthError> {
+        self.find(|a| &a.provider == provider && a.provider_user_id == provider_user_id)
+    }
+
+    async fn list_by_user(&self, user_id: &DbId) -> Result<Vec<OAuthAccount>, AuthError> {
+        Ok(read(&self.accounts)?
+            .values()
+            .filter(|a| &a.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn update(&self, id: &DbId, account: &OAuthAccount) -> Result<OAuthAccount, AuthError> {
+        let mut updated_account = account.clone();
+        updated_account.updated_at = Some(chrono::Utc::now().timestamp());
+
+        // Only the tokens change, as with MongoDB
+        if let Some(stored) = write(&self.accounts)?.get_mut(id) {
+            stored.access_token = updated_account.access_token.clone();
+            stored.refresh_token = updated_account.refresh_token.clone();
+            stored.expires_at = updated_account.expires_at;
+            stored.scope = updated_account.scope.clone();
+            stored.updated_at = updated_account.updated_at;
+        }
+
+        Ok(updated_account)
+    }
+
+    async fn delete(&self, id: &DbId) -> Result<(), AuthError> {
+        write(&self.accounts)?.remove(id);
+        Ok(())
+    }
+
+    async fn delete_by_user(&self, user_id: &DbId) -> Result<(), AuthError> {
+        write(&self.accounts)?.retain(|_, a| &a.user_id != user_id);
+        Ok(())
+    }
+
+    async fn delete_by_user_and_provider(
+        &self,
+        user_id: &DbId,
+        provider: &OAuthProvider,
+    ) -> Result<(), AuthError> {
+        let mut accounts = write(&self.accounts)?;
+        let id = accounts
+            .values()
+            .find(|a| &a.user_id == user_id && &a.provider == provider)
+            .map(|a| a.id.clone());
+        if let Some(id) = id {
+            accounts.remove(&id);
+        }
+        Ok(())
+    }
+}
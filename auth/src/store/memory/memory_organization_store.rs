@@ -0,0 +1,246 @@
+//! In-memory Organization Store Implementation
+
+use async_trait::async_trait;
+use std::sync::RwLock;
+
+use super::{Table, read, write};
+use crate::models::organization::{
+    CreateMembership, CreateOrgInvitation, CreateOrganization, Membership, OrgInvitation,
+    OrgRole, Organization,
+};
+use crate::store::organization_store::OrganizationStore;
+use crate::utils::errors::{AuthError, AuthResult};
+use database::utils::{DbId, generate_id};
+
+/// In-memory implementation of OrganizationStore
+///
+/// Slugs are unique, and a user is a member of an organization at most once.
+#[derive(Default)]
+pub struct MemoryOrganizationStore {
+    organizations: RwLock<Table<Organization>>,
+    memberships:   RwLock<Table<Membership>>,
+    invitations:   RwLock<Table<OrgInvitation>>,
+}
+
+impl MemoryOrganizationStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn memberships(&self, predicate: impl Fn(&Membership) -> bool) -> AuthResult<Vec<Membership>> {
+        let mut memberships: Vec<Membership> = read(&self.memberships)?
+            .values()
+            .filter(|m| predicate(m))
+            .cloned()
+            .collect();
+        memberships.sort_by_key(|m| m.created_at);
+        Ok(memberships)
+    }
+}
+
+#[async_trait]
+impl OrganizationStore for MemoryOrganizationStore {
+    async fn create(&self, input: CreateOrganization) -> AuthResult<Organization> {
+        let org = Organization {
+            id: generate_id(),
+            name: input.name,
+            slug: input.slug,
+            owner_id: input.owner_id,
+            created_at: chrono::Utc::now().timestamp(),
+            updated_at: None,
+        };
+
+        let mut organizations = write(&self.organizations)?;
+        if organizations.values().any(|o| o.slug == org.slug) {
+            return Err(AuthError::conflict("Organization slug is already taken"));
+        }
+        organizations.insert(org.id.clone(), org.clone());
+
+        Ok(org)
+    }
+
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<Organization>> {
+        Ok(read(&self.organizations)?.get(id).cloned())
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> AuthResult<Option<Organization>> {
+        Ok(read(&self.organizations)?.values().find(|o| o.slug == slug).cloned())
+    }
+
+    async fn find_by_ids(&self, ids: &[DbId]) -> AuthResult<Vec<Organization>> {
+        let mut organizations: Vec<Organization> = read(&self.organizations)?
+            .values()
+            .filter(|o| ids.contains(&o.id))
+            .cloned()
+            .collect();
+        organizations.sort_by_key(|o| o.created_at);
+        Ok(organizations)
+    }
+
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        write(&self.memberships)?.retain(|_, m| &m.org_id != id);
+        write(&self.invitations)?.retain(|_, i| &i.org_id != id);
+        write(&self.organizations)?.remove(id);
+        Ok(())
+    }
+
+    async fn add_member(&self, input: CreateMembership) -> AuthResult<Membership> {
+        let membership = Membership {
+            id: generate_id(),
+            org_id: input.org_id,
+            user_id: input.user_id,
+            role: input.role,
+            created_at: chrono::Utc::now().timestamp(),
+            updated_at: None,
+        };
+
+        let mut memberships = write(&self.memberships)?;
+        let exists = memberships
+            .values()
+            .any(|m| m.org_id == membership.org_id && m.user_id == membership.user_id);
+        if exists {
+            return Err(AuthError::conflict("User is already a member"));
+        }
+        memberships.insert(membership.id.clone(), membership.clone());
+
+        Ok(membership)
+    }
+
+    async fn find_membership(&self, org_id: &DbId, user_id: &DbId) -> AuthResult<Option<Membership>> {
+        Ok(read(&self.memberships)?
+            .values()
+            .find(|m| &m.org_id == org_id && &m.user_id == user_id)
+            .cloned())
+    }
+
+    async fn list_members(&self, org_id: &DbId) -> AuthResult<Vec<Membership>> {
+        self.memberships(|m| &m.org_id == org_id)
+    }
+
+    async fn list_user_memberships(&self, user_id: &DbId) -> AuthResult<Vec<Membership>> {
+        self.memberships(|m| &m.user_id == user_id)
+    }
+
+    async fn update_member_role(&self, org_id: &DbId, user_id: &DbId, role: OrgRole) -> AuthResult<()> {
+        let mut memberships = write(&self.memberships)?;
+        let membership = memberships
+            .values_mut()
+            .find(|m| &m.org_id == org_id && &m.user_id == user_id)
+            .ok_or_else(|| AuthError::not_found("Member not found"))?;
+
+        membership.role = role;
+        membership.updated_at = Some(chrono::Utc::now().timestamp());
+        Ok(())
+    }
+
+    async fn remove_member(&self, org_id: &DbId, user_id: &DbId) -> AuthResult<()> {
+        let mut memberships = write(&self.memberships)?;
+        let is_owner = |m: &Membership| &m.org_id == org_id && m.role == OrgRole::Owner;
+
+        let removes_owner = memberships.values().any(|m| is_owner(m) && &m.user_id == user_id);
+        if removes_owner && !memberships.values().any(|m| is_owner(m) && &m.user_id != user_id) {
+            return Err(AuthError::last_owner());
+        }
+
+        memberships.retain(|_, m| !(&m.org_id == org_id && &m.user_id == user_id));
+        Ok(())
+    }
+
+    async fn create_invitation(&self, input: CreateOrgInvitation) -> AuthResult<OrgInvitation> {
+        let now = chrono::Utc::now().timestamp();
+        let invitation = OrgInvitation {
+            id: generate_id(),
+            org_id: input.org_id,
+            email: input.email,
+            role: input.role,
+            token_hash: input.token_hash,
+            invited_by: input.invited_by,
+            expires_at: now + input.expires_in,
+            accepted_at: None,
+            created_at: now,
+        };
+
+        write(&self.invitations)?.insert(invitation.id.clone(), invitation.clone());
+        Ok(invitation)
+    }
+
+    async fn find_invitation(&self, id: &DbId) -> AuthResult<Option<OrgInvitation>> {
+        Ok(read(&self.invitations)?.get(id).cloned())
+    }
+
+    async fn find_invitation_by_token(&self, token_hash: &str) -> AuthResult<Option<OrgInvitation>> {
+        Ok(read(&self.invitations)?
+            .values()
+            .find(|i| i.token_hash == token_hash)
+            .cloned())
+    }
+
+    async fn list_invitations(&self, org_id: &DbId) -> AuthResult<Vec<OrgInvitation>> {
+        let mut invitations: Vec<OrgInvitation> = read(&self.invitations)?
+            .values()
+            .filter(|i| &i.org_id == org_id)
+            .cloned()
+            .collect();
+        invitations.sort_by_key(|i| i.created_at);
+        Ok(invitations)
+    }
+
+    async fn accept_invitation(&self, id: &DbId) -> AuthResult<()> {
+        if let Some(invitation) = write(&self.invitations)?.get_mut(id) {
+            invitation.accepted_at = Some(chrono::Utc::now().timestamp());
+        }
+        Ok(())
+    }
+
+    async fn delete_invitation(&self, id: &DbId) -> AuthResult<()> {
+        write(&self.invitations)?.remove(id);
+        Ok(())
+    }
+
+    async fn delete_invitations_for_email(&self, email: &str) -> AuthResult<u64> {
+        let mut invitations = write(&self.invitations)?;
+        let before = invitations.len();
+        invitations.retain(|_, i| i.email != email);
+        Ok((before - invitations.len()) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_memberships_are_unique_and_go_with_the_organization() {
+        let store = MemoryOrganizationStore::new();
+        let owner_id = generate_id();
+        let org = store.create(CreateOrganization {
+            name:     "Acme".to_string(),
+            slug:     "acme".to_string(),
+            owner_id: owner_id.clone(),
+        }).await.unwrap();
+
+        let member = CreateMembership { org_id: org.id.clone(), user_id: owner_id.clone(), role: OrgRole::Owner };
+        store.add_member(member.clone()).await.unwrap();
+        assert!(store.add_member(member).await.is_err());
+
+        store.delete(&org.id).await.unwrap();
+        assert!(store.list_user_memberships(&owner_id).await.unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_last_owner_cannot_be_removed() {
+        let store = MemoryOrganizationStore::new();
+        let org_id = generate_id();
+        let (owner_id, other_id) = (generate_id(), generate_id());
+        let member = |user_id: &DbId, role| CreateMembership { org_id: org_id.clone(), user_id: user_id.clone(), role };
+
+        store.add_member(member(&owner_id, OrgRole::Owner)).await.unwrap();
+        store.add_member(member(&other_id, OrgRole::Member)).await.unwrap();
+        assert!(store.remove_member(&org_id, &owner_id).await.is_err());
+
+        store.update_member_role(&org_id, &other_id, OrgRole::Owner).await.unwrap();
+        store.remove_member(&org_id, &owner_id).await.unwrap();
+        assert_eq!(store.list_members(&org_id).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,129 @@
+//! In-memory Passkey Store Implementation
+
+use async_trait::async_trait;
+use std::sync::RwLock;
+
+use super::{Table, read, write};
+use crate::models::passkey::{
+    CreatePasskeyCredential, CreateWebAuthnChallenge, PasskeyCredential, WebAuthnChallenge,
+};
+use crate::store::passkey_store::PasskeyStore;
+use crate::utils::errors::{AuthError, AuthResult};
+use database::utils::{DbId, generate_id};
+
+/// In-memory implementation of PasskeyStore
+///
+/// A credential id can be registered once.
+#[derive(Default)]
+pub struct MemoryPasskeyStore {
+    credentials: RwLock<Table<PasskeyCredential>>,
+    challenges:  RwLock<Table<WebAuthnChallenge>>,
+}
+
+impl MemoryPasskeyStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PasskeyStore for MemoryPasskeyStore {
+    async fn create(&self, input: CreatePasskeyCredential) -> AuthResult<PasskeyCredential> {
+        let credential = PasskeyCredential {
+            id: generate_id(),
+            user_id: input.user_id,
+            credential_id: input.credential_id,
+            public_key: input.public_key,
+            sign_count: input.sign_count,
+            aaguid: input.aaguid,
+            name: input.name,
+            transports: input.transports,
+            created_at: chrono::Utc::now().timestamp(),
+            last_used_at: None,
+        };
+
+        let mut credentials = write(&self.credentials)?;
+        if credentials.values().any(|c| c.credential_id == credential.credential_id) {
+            return Err(AuthError::conflict("Passkey is already registered"));
+        }
+        credentials.insert(credential.id.clone(), credential.clone());
+
+        Ok(credential)
+    }
+
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<PasskeyCredential>> {
+        Ok(read(&self.credentials)?.get(id).cloned())
+    }
+
+    async fn find_by_credential_id(&self, credential_id: &str) -> AuthResult<Option<PasskeyCredential>> {
+        Ok(read(&self.credentials)?
+            .values()
+            .find(|c| c.credential_id == credential_id)
+            .cloned())
+    }
+
+    async fn list_by_user(&self, user_id: &DbId) -> AuthResult<Vec<PasskeyCredential>> {
+        let mut credentials: Vec<PasskeyCredential> = read(&self.credentials)?
+            .values()
+            .filter(|c| &c.user_id == user_id)
+            .cloned()
+            .collect();
+        credentials.sort_by_key(|c| c.created_at);
+        Ok(credentials)
+    }
+
+    async fn update_sign_count(&self, id: &DbId, sign_count: u32) -> AuthResult<()> {
+        if let Some(credential) = write(&self.credentials)?.get_mut(id) {
+            credential.sign_count = sign_count;
+            credential.last_used_at = Some(chrono::Utc::now().timestamp());
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        write(&self.credentials)?.remove(id);
+        Ok(())
+    }
+
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let mut credentials = write(&self.credentials)?;
+        let before = credentials.len();
+        credentials.retain(|_, c| &c.user_id != user_id);
+        Ok((before - credentials.len()) as u64)
+    }
+
+    async fn create_challenge(&self, input: CreateWebAuthnChallenge) -> AuthResult<WebAuthnChallenge> {
+        let now = chrono::Utc::now().timestamp();
+        let challenge = WebAuthnChallenge {
+            id: generate_id(),
+            user_id: input.user_id,
+            challenge: input.challenge,
+            ceremony: input.ceremony,
+            expires_at: now + input.expires_in,
+            created_at: now,
+        };
+
+        write(&self.challenges)?.insert(challenge.id.clone(), challenge.clone());
+        Ok(challenge)
+    }
+
+    async fn find_challenge(&self, challenge: &str) -> AuthResult<Option<WebAuthnChallenge>> {
+        Ok(read(&self.challenges)?
+            .values()
+            .find(|c| c.challenge == challenge)
+            .cloned())
+    }
+
+    async fn delete_challenge(&self, id: &DbId) -> AuthResult<()> {
+        write(&self.challenges)?.remove(id);
+        Ok(())
+    }
+
+    async fn cleanup_expired_challenges(&self) -> AuthResult<u64> {
+        let mut challenges = write(&self.challenges)?;
+        let before = challenges.len();
+        challenges.retain(|_, c| !c.is_expired());
+        Ok((before - challenges.len()) as u64)
+    }
+}
@@ -0,0 +1,95 @@
+//! In-memory Password Reset Store Implementation
+
+use async_trait::async_trait;
+use std::sync::RwLock;
+
+use super::{Table, read, write};
+use crate::models::reset_password::{CreatePasswordResetToken, PasswordResetTokenModel};
+use crate::store::password_reset_store::PasswordResetStore;
+use crate::utils::errors::AuthResult;
+use database::utils::{DbId, generate_id};
+
+/// In-memory implementation of PasswordResetStore
+#[derive(Default)]
+pub struct MemoryPasswordResetStore {
+    tokens: RwLock<Table<PasswordResetTokenModel>>,
+}
+
+impl MemoryPasswordResetStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn find(&self, predicate: impl Fn(&PasswordResetTokenModel) -> bool) -> AuthResult<Option<PasswordResetTokenModel>> {
+        Ok(read(&self.tokens)?.values().find(|t| predicate(t)).cloned())
+    }
+
+    fn remove_where(&self, predicate: impl Fn(&PasswordResetTokenModel) -> bool) -> AuthResult<u64> {
+        let mut tokens = write(&self.tokens)?;
+        let before = tokens.len();
+        tokens.retain(|_, t| !predicate(t));
+
+        Ok((before - tokens.len()) as u64)
+    }
+}
+
+#[async_trait]
+impl PasswordResetStore for MemoryPasswordResetStore {
+    /// Create a new password reset token
+    async fn create(&self, input: CreatePasswordResetToken) -> AuthResult<PasswordResetTokenModel> {
+        let now = chrono::Utc::now().timestamp();
+
+        let token = PasswordResetTokenModel {
+            id: generate_id(),
+            user_id: input.user_id,
+            token_hash: input.token_hash,
+            created_at: now,
+            expires_at: now + input.expires_in,
+            used_at: None,
+        };
+
+        write(&self.tokens)?.insert(token.id.clone(), token.clone());
+
+        Ok(token)
+    }
+
+    /// Find password reset token by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<PasswordResetTokenModel>> {
+        Ok(read(&self.tokens)?.get(id).cloned())
+    }
+
+    /// Find valid password reset token by user_id
+    async fn find_valid_token(&self, user_id: &DbId) -> AuthResult<Option<PasswordResetTokenModel>> {
+        self.find(|t| &t.user_id == user_id && t.is_valid())
+    }
+
+    /// Find token by hash
+    async fn find_by_hash(&self, token_hash: &str) -> AuthResult<Option<PasswordResetTokenModel>> {
+        self.find(|t| t.token_hash == token_hash)
+    }
+
+    /// Mark token as used
+    async fn mark_used(&self, id: &DbId) -> AuthResult<()> {
+        if let Some(token) = write(&self.tokens)?.get_mut(id) {
+            token.used_at = Some(chrono::Utc::now().timestamp());
+        }
+        Ok(())
+    }
+
+    /// Delete/expire a token
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        write(&self.tokens)?.remove(id);
+        Ok(())
+    }
+
+    /// Delete all tokens for a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        self.remove_where(|t| &t.user_id == user_id)
+    }
+
+    /// Cleanup expired tokens
+    async fn cleanup_expired(&self) -> AuthResult<u64> {
+        self.remove_where(PasswordResetTokenModel::is_expired)
+    }
+}
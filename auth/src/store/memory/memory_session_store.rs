@@ -0,0 +1,261 @@
+//! In-memory Session Store Implementation
+
+use async_trait::async_trait;
+use std::sync::RwLock;
+
+use super::{Table, read, write};
+use crate::models::session::{
+    CreateRefreshToken, CreateSession, RefreshTokenModel, SessionModel, UpdateSession,
+};
+use crate::store::session_store::SessionStore;
+use crate::utils::errors::{AuthError, AuthResult};
+use database::utils::{DbId, generate_id};
+
+/// In-memory implementation of SessionStore
+#[derive(Default)]
+pub struct MemorySessionStore {
+    sessions:       RwLock<Table<SessionModel>>,
+    refresh_tokens: RwLock<Table<RefreshTokenModel>>,
+}
+
+impl MemorySessionStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn find_session(&self, predicate: impl Fn(&SessionModel) -> bool) -> AuthResult<Vec<SessionModel>> {
+        Ok(read(&self.sessions)?.values().filter(|s| predicate(s)).cloned().collect())
+    }
+}
+
+/// Build a refresh token record like `MongoSessionStore` does
+fn refresh_token(input: CreateRefreshToken) -> RefreshTokenModel {
+    let now = chrono::Utc::now().timestamp();
+
+    RefreshTokenModel {
+        id: generate_id(),
+        user_id: input.user_id,
+        token_hash: input.token_hash,
+        expires_at: now + input.expires_in,
+        created_at: now,
+        revoked: false,
+        revoked_at: None,
+        replaced_by: None,
+    }
+}
+
+fn revoke(token: &mut RefreshTokenModel) {
+    token.revoked = true;
+    token.revoked_at = Some(chrono::Utc::now().timestamp());
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionStore {
+    /// Create a new session
+    async fn create(&self, input: CreateSession) -> AuthResult<SessionModel> {
+        let now = chrono::Utc::now().timestamp();
+
+        let session = SessionModel {
+            id: generate_id(),
+            user_id: input.user_id,
+            access_token_hash: input.access_token_hash,
+            refresh_token_hash: input.refresh_token_hash,
+            device: input.device,
+            ip_address: input.ip_address,
+            user_agent: input.user_agent,
+            org_id: input.org_id,
+            created_at: now,
+            expires_at: now + input.expires_in,
+            last_used_at: now,
+            is_revoked: false,
+        };
+
+        write(&self.sessions)?.insert(session.id.clone(), session.clone());
+
+        Ok(session)
+    }
+
+    /// Find session by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<SessionModel>> {
+        Ok(read(&self.sessions)?.get(id).cloned())
+    }
+
+    /// Find session by access token hash
+    async fn find_by_token(&self, token_hash: &str) -> AuthResult<Option<SessionModel>> {
+        Ok(self.find_session(|s| s.access_token_hash == token_hash)?.into_iter().next())
+    }
+
+    /// Find all sessions for a user
+    async fn find_by_user_id(&self, user_id: &DbId) -> AuthResult<Vec<SessionModel>> {
+        self.find_session(|s| &s.user_id == user_id)
+    }
+
+    /// Find all sessions issued for an organization
+    async fn find_by_org(&self, org_id: &DbId) -> AuthResult<Vec<SessionModel>> {
+        self.find_session(|s| s.org_id.as_ref() == Some(org_id))
+    }
+
+    /// Update session
+    async fn update(&self, id: &DbId, session: UpdateSession) -> AuthResult<UpdateSession> {
+        let mut sessions = write(&self.sessions)?;
+        let stored = sessions.get_mut(id).ok_or_else(|| AuthError::not_found("Session not found"))?;
+
+        stored.access_token_hash = session.access_token_hash.clone();
+        stored.refresh_token_hash = Some(session.refresh_token_hash.clone());
+        stored.expires_at = session.expires_at;
+        stored.is_revoked = session.is_revoked;
+
+        Ok(session)
+    }
+
+    /// Point a session at a newly issued access token
+    async fn rotate_access_token(&self, id: &DbId, access_token_hash: &str) -> AuthResult<()> {
+        if let Some(session) = write(&self.sessions)?.get_mut(id) {
+            session.access_token_hash = access_token_hash.to_string();
+            session.update_last_used();
+        }
+        Ok(())
+    }
+
+    /// Revoke a session
+    async fn revoke(&self, id: &DbId) -> AuthResult<()> {
+        if let Some(session) = write(&self.sessions)?.get_mut(id) {
+            session.is_revoked = true;
+        }
+        Ok(())
+    }
+
+    /// Revoke all sessions for a user
+    async fn revoke_all(&self, user_id: &DbId) -> AuthResult<u64> {
+        let mut revoked = 0;
+        for session in write(&self.sessions)?.values_mut() {
+            if &session.user_id == user_id && !session.is_revoked {
+                session.is_revoked = true;
+                revoked += 1;
+            }
+        }
+        Ok(revoked)
+    }
+
+    /// Delete all sessions and refresh tokens of a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let mut sessions = write(&self.sessions)?;
+        let before = sessions.len();
+        sessions.retain(|_, s| &s.user_id != user_id);
+
+        write(&self.refresh_tokens)?.retain(|_, t| &t.user_id != user_id);
+
+        Ok((before - sessions.len()) as u64)
+    }
+
+    /// Delete expired sessions
+    async fn cleanup_expired(&self) -> AuthResult<u64> {
+        let now = chrono::Utc::now().timestamp();
+        let mut sessions = write(&self.sessions)?;
+        let before = sessions.len();
+        sessions.retain(|_, s| s.expires_at >= now);
+
+        Ok((before - sessions.len()) as u64)
+    }
+
+    /// Create refresh token
+    async fn create_refresh_token(&self, input: CreateRefreshToken) -> AuthResult<RefreshTokenModel> {
+        let token = refresh_token(input);
+        write(&self.refresh_tokens)?.insert(token.id.clone(), token.clone());
+        Ok(token)
+    }
+
+    /// Find refresh token by ID
+    async fn find_refresh_token(&self, id: &DbId) -> AuthResult<Option<RefreshTokenModel>> {
+        Ok(read(&self.refresh_tokens)?.get(id).cloned())
+    }
+
+    /// Find refresh token by hash
+    async fn find_refresh_token_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> AuthResult<Option<RefreshTokenModel>> {
+        Ok(read(&self.refresh_tokens)?
+            .values()
+            .find(|t| t.token_hash == token_hash)
+            .cloned())
+    }
+
+    /// Revoke refresh token
+    async fn revoke_refresh_token(&self, id: &DbId) -> AuthResult<()> {
+        if let Some(token) = write(&self.refresh_tokens)?.get_mut(id) {
+            revoke(token);
+        }
+        Ok(())
+    }
+
+    /// Replace refresh token (for rotation)
+    async fn replace_refresh_token(
+        &self,
+        old_id: &DbId,
+        new_token: CreateRefreshToken,
+    ) -> AuthResult<()> {
+        let mut tokens = write(&self.refresh_tokens)?;
+        let token = refresh_token(new_token);
+
+        if let Some(old) = tokens.get_mut(old_id) {
+            revoke(old);
+            old.replaced_by = Some(token.id.to_string());
+        }
+        tokens.insert(token.id.clone(), token);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(user_id: DbId, expires_in: i64) -> CreateSession {
+        CreateSession {
+            user_id,
+            access_token_hash: generate_id().to_string(),
+            refresh_token_hash: None,
+            device: None,
+            ip_address: None,
+            user_agent: None,
+            org_id: None,
+            expires_in,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_revoke_and_cleanup() {
+        let store = MemorySessionStore::new();
+        let user_id = generate_id();
+        let active = store.create(session(user_id.clone(), 3600)).await.unwrap();
+        store.create(session(user_id.clone(), -10)).await.unwrap();
+
+        assert_eq!(store.cleanup_expired().await.unwrap(), 1);
+        assert_eq!(store.revoke_all(&user_id).await.unwrap(), 1);
+        assert!(!store.find_by_id(&active.id).await.unwrap().unwrap().is_valid());
+    }
+
+    #[actix_web::test]
+    async fn test_replace_refresh_token_links_rotation() {
+        let store = MemorySessionStore::new();
+        let user_id = generate_id();
+        let old = store
+            .create_refresh_token(CreateRefreshToken { user_id: user_id.clone(), token_hash: "old".into(), expires_in: 60 })
+            .await
+            .unwrap();
+
+        store
+            .replace_refresh_token(&old.id, CreateRefreshToken { user_id, token_hash: "new".into(), expires_in: 60 })
+            .await
+            .unwrap();
+
+        let old = store.find_refresh_token(&old.id).await.unwrap().unwrap();
+        let new = store.find_refresh_token_by_hash("new").await.unwrap().unwrap();
+        assert!(!old.is_valid());
+        assert_eq!(old.replaced_by, Some(new.id.to_string()));
+        assert!(new.is_valid());
+    }
+}
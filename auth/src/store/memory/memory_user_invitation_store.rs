@@ -0,0 +1,91 @@
+//! In-memory User Invitation Store Implementation
+
+use async_trait::async_trait;
+use std::sync::RwLock;
+
+use super::{Table, read, write};
+use crate::models::user_invitation::{CreateUserInvitation, UserInvitation};
+use crate::store::user_invitation_store::UserInvitationStore;
+use crate::utils::errors::AuthResult;
+use database::utils::{DbId, generate_id};
+
+/// In-memory implementation of UserInvitationStore
+#[derive(Default)]
+pub struct MemoryUserInvitationStore {
+    invitations: RwLock<Table<UserInvitation>>,
+}
+
+impl MemoryUserInvitationStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl UserInvitationStore for MemoryUserInvitationStore {
+    async fn create(&self, input: CreateUserInvitation) -> AuthResult<UserInvitation> {
+        let now = chrono::Utc::now().timestamp();
+        let invitation = UserInvitation {
+            id: generate_id(),
+            user_id: input.user_id,
+            org_id: input.org_id,
+            email: input.email,
+            role: input.role,
+            nonce_hash: input.nonce_hash,
+            invited_by: input.invited_by,
+            expires_at: now + input.expires_in,
+            accepted_at: None,
+            created_at: now,
+            updated_at: None,
+        };
+
+        write(&self.invitations)?.insert(invitation.id.clone(), invitation.clone());
+        Ok(invitation)
+    }
+
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<UserInvitation>> {
+        Ok(read(&self.invitations)?.get(id).cloned())
+    }
+
+    async fn list_pending(&self, org_id: &DbId) -> AuthResult<Vec<UserInvitation>> {
+        let mut invitations: Vec<UserInvitation> = read(&self.invitations)?
+            .values()
+            .filter(|i| &i.org_id == org_id && i.is_pending())
+            .cloned()
+            .collect();
+        invitations.sort_by_key(|i| i.created_at);
+        Ok(invitations)
+    }
+
+    async fn renew(&self, id: &DbId, nonce_hash: &str, expires_in: i64) -> AuthResult<()> {
+        let now = chrono::Utc::now().timestamp();
+        if let Some(invitation) = write(&self.invitations)?.get_mut(id) {
+            invitation.nonce_hash = nonce_hash.to_string();
+            invitation.expires_at = now + expires_in;
+            invitation.updated_at = Some(now);
+        }
+        Ok(())
+    }
+
+    async fn mark_accepted(&self, id: &DbId) -> AuthResult<()> {
+        let now = chrono::Utc::now().timestamp();
+        if let Some(invitation) = write(&self.invitations)?.get_mut(id) {
+            invitation.accepted_at = Some(now);
+            invitation.updated_at = Some(now);
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        write(&self.invitations)?.remove(id);
+        Ok(())
+    }
+
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let mut invitations = write(&self.invitations)?;
+        let before = invitations.len();
+        invitations.retain(|_, i| &i.user_id != user_id);
+        Ok((before - invitations.len()) as u64)
+    }
+}
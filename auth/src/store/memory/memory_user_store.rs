@@ -0,0 +1,404 @@
+//! In-memory User Store Implementation
+
+use async_trait::async_trait;
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+
+use super::{Table, read, write};
+use crate::models::user::{CreateUserInput, UpdateUserInput, User, UserFilter};
+use crate::models::verification::VerificationMedium;
+use crate::store::user_store::{IdentifierType, UserStore, identify_user};
+use crate::utils::errors::{AuthError, AuthResult};
+use crate::utils::usernames;
+use database::pagination::{Page, PageRequest};
+use database::utils::{DbId, generate_id};
+
+/// In-memory implementation of UserStore
+///
+/// Emails, phone numbers and username skeletons are unique, like the
+/// MongoDB unique index on `username_key`.
+#[derive(Default)]
+pub struct MemoryUserStore {
+    users: RwLock<Table<User>>,
+}
+
+/// Whether a user matches a list filter (deleted users never do)
+fn matches(user: &User, filter: &UserFilter) -> bool {
+    user.deleted_at.is_none()
+        && filter.is_active.is_none_or(|is_active| user.is_active == is_active)
+        && filter.is_verified.is_none_or(|is_verified| user.is_verified == is_verified)
+        && filter.profile.iter().all(|(name, value)| match user.profile.get(name) {
+            // Array fields match any element, as in MongoDB
+            Some(stored @ serde_json::Value::Array(items)) => items.contains(value) || stored == value,
+            Some(stored) => stored == value,
+            None => value.is_null(),
+        })
+}
+
+/// Make sure no other user holds the same email, phone or username
+fn check_unique(users: &Table<User>, candidate: &User) -> AuthResult<()> {
+    for user in users.values().filter(|u| u.id != candidate.id) {
+        if let Some(ref email) = candidate.email {
+            if user.email.as_ref() == Some(email) {
+                return Err(AuthError::email_already_exists(email));
+            }
+        }
+        if let Some(ref phone) = candidate.phone {
+            if user.phone.as_ref() == Some(phone) {
+                return Err(AuthError::phone_already_exists(phone));
+            }
+        }
+        if candidate.username_key.is_some() && user.username_key == candidate.username_key {
+            let username = candidate.username.as_deref().unwrap_or_default();
+            return Err(AuthError::username_already_exists(username));
+        }
+    }
+
+    Ok(())
+}
+
+/// One page of users in creation order (ids are time ordered)
+fn page<'a>(users: impl Iterator<Item = &'a User>, page: u32, limit: u32) -> Vec<User> {
+    let mut users: Vec<&User> = users.collect();
+    users.sort_by(|a, b| a.id.cmp(&b.id));
+
+    users
+        .into_iter()
+        .skip((page * limit) as usize)
+        .take(limit as usize)
+        .cloned()
+        .collect()
+}
+
+impl MemoryUserStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Change a stored user, failing when it does not exist
+    fn modify<R>(&self, id: &DbId, change: impl FnOnce(&mut User) -> R) -> AuthResult<R> {
+        let mut users = write(&self.users)?;
+        let user = users.get_mut(id).ok_or_else(|| AuthError::not_found("User not found"))?;
+        let result = change(user);
+        user.updated_at = Some(Utc::now());
+        Ok(result)
+    }
+
+    fn find(&self, predicate: impl Fn(&User) -> bool) -> AuthResult<Option<User>> {
+        Ok(read(&self.users)?.values().find(|u| predicate(u)).cloned())
+    }
+}
+
+#[async_trait]
+impl UserStore for MemoryUserStore {
+    /// Create a new user
+    async fn create(&self, input: CreateUserInput) -> AuthResult<User> {
+        let user = User {
+            id:             generate_id(),
+            email:          input.email,
+            password_hash:  input.password,
+            phone:          input.phone,
+            username_key:   input.username.as_deref().map(usernames::skeleton),
+            username:       input.username,
+            first_name:     None,
+            last_name:      None,
+            is_active:      true,
+            is_verified:    false,
+            login_attempts: 0,
+            locked_until:   None,
+            created_at:     Utc::now(),
+            updated_at:     None,
+            last_login:     None,
+            org_ids:        Vec::new(),
+            deletion_scheduled_at: None,
+            deleted_at:     None,
+            is_admin:       false,
+            profile:        Default::default(),
+        };
+
+        let mut users = write(&self.users)?;
+        check_unique(&users, &user)?;
+        users.insert(user.id.clone(), user.clone());
+
+        Ok(user)
+    }
+
+    /// Find user by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<User>> {
+        Ok(read(&self.users)?.get(id).cloned())
+    }
+
+    /// Find user by email
+    async fn find_by_email(&self, email: &str) -> AuthResult<Option<User>> {
+        self.find(|u| u.email.as_deref() == Some(email))
+    }
+
+    /// Find user by phone
+    async fn find_by_phone(&self, phone: &str) -> AuthResult<Option<User>> {
+        self.find(|u| u.phone.as_deref() == Some(phone))
+    }
+
+    /// Find user by username - matches case-insensitively and across lookalikes
+    async fn find_by_username(&self, username: &str) -> AuthResult<Option<User>> {
+        let key = usernames::skeleton(&usernames::normalize(username));
+        self.find(|u| u.username_key.as_deref() == Some(key.as_str()))
+    }
+
+    /// Find user by any identifier (email, phone, or username)
+    async fn find_by_identifier(&self, identifier: &str) -> AuthResult<Option<User>> {
+        match identify_user(identifier) {
+            IdentifierType::Email => self.find_by_email(identifier).await,
+            IdentifierType::Phone => self.find_by_phone(identifier).await,
+            IdentifierType::Username => self.find_by_username(identifier).await,
+        }
+    }
+
+    /// Update user
+    async fn update(&self, id: &DbId, input: UpdateUserInput) -> AuthResult<User> {
+        let mut users = write(&self.users)?;
+        let mut user = users
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AuthError::not_found("User not found"))?;
+
+        if let Some(username) = input.username {
+            user.username_key = Some(usernames::skeleton(&username));
+            user.username = Some(username);
+        }
+        if let Some(first_name) = input.first_name {
+            user.first_name = Some(first_name);
+        }
+        if let Some(last_name) = input.last_name {
+            user.last_name = Some(last_name);
+        }
+
+        // Profile changes touch single keys so other fields are kept
+        for (name, value) in input.profile.into_iter().flatten() {
+            if value.is_null() {
+                user.profile.remove(&name);
+            } else {
+                user.profile.insert(name, value);
+            }
+        }
+        user.updated_at = Some(Utc::now());
+
+        check_unique(&users, &user)?;
+        users.insert(user.id.clone(), user.clone());
+
+        Ok(user)
+    }
+
+    /// Update user password
+    async fn update_password(&self, id: &DbId, password_hash: &str) -> AuthResult<()> {
+        self.modify(id, |user| user.password_hash = password_hash.to_string())
+    }
+
+    /// Replace a confirmed email or phone
+    async fn set_contact(&self, id: &DbId, medium: &VerificationMedium, value: &str) -> AuthResult<()> {
+        let mut users = write(&self.users)?;
+        let mut user = users
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AuthError::not_found("User not found"))?;
+
+        match medium {
+            VerificationMedium::Email => user.email = Some(value.to_string()),
+            VerificationMedium::Phone => user.phone = Some(value.to_string()),
+        }
+        user.updated_at = Some(Utc::now());
+
+        check_unique(&users, &user)?;
+        users.insert(user.id.clone(), user);
+
+        Ok(())
+    }
+
+    /// Activate or deactivate user
+    async fn set_active(&self, id: &DbId, is_active: bool) -> AuthResult<()> {
+        self.modify(id, |user| user.is_active = is_active)
+    }
+
+    /// Mark user as verified or unverified
+    async fn set_verified(&self, id: &DbId, is_verified: bool) -> AuthResult<()> {
+        self.modify(id, |user| user.is_verified = is_verified)
+    }
+
+    /// Count a failed password attempt
+    async fn record_failed_login(&self, id: &DbId) -> AuthResult<()> {
+        self.modify(id, |user| user.login_attempts += 1)
+    }
+
+    /// Clear the failed attempt count
+    async fn reset_login_attempts(&self, id: &DbId) -> AuthResult<()> {
+        self.modify(id, |user| user.login_attempts = 0)
+    }
+
+    /// Delete user
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        write(&self.users)?.remove(id);
+        Ok(())
+    }
+
+    /// Schedule the account for deletion, or cancel with `None`
+    async fn schedule_deletion(&self, id: &DbId, at: Option<DateTime<Utc>>) -> AuthResult<()> {
+        self.modify(id, |user| user.deletion_scheduled_at = at)
+    }
+
+    /// List users whose scheduled deletion is due
+    async fn list_due_for_deletion(&self, now: DateTime<Utc>) -> AuthResult<Vec<User>> {
+        Ok(read(&self.users)?
+            .values()
+            .filter(|u| u.deleted_at.is_none() && u.deletion_scheduled_at.is_some_and(|at| at <= now))
+            .cloned()
+            .collect())
+    }
+
+    /// Strip personal data and mark the user deleted
+    async fn anonymize(&self, id: &DbId) -> AuthResult<()> {
+        self.modify(id, |user| {
+            user.email = None;
+            user.phone = None;
+            user.username = None;
+            user.username_key = None;
+            user.first_name = None;
+            user.last_name = None;
+            user.profile = Default::default();
+            user.password_hash = String::new();
+            user.is_active = false;
+            user.is_verified = false;
+            user.login_attempts = 0;
+            user.locked_until = None;
+            user.last_login = None;
+            user.org_ids = Vec::new();
+            user.deletion_scheduled_at = None;
+            user.deleted_at = Some(Utc::now());
+        })
+    }
+
+    /// List all users (with pagination)
+    async fn list(&self, filter: &UserFilter, page_number: u32, limit: u32) -> AuthResult<Vec<User>> {
+        let users = read(&self.users)?;
+        Ok(page(users.values().filter(|u| matches(u, filter)), page_number, limit))
+    }
+
+    /// List users after a cursor
+    async fn list_after(&self, filter: &UserFilter, page: &PageRequest) -> AuthResult<Page<User>> {
+        let users = read(&self.users)?;
+        let mut rows: Vec<User> = users
+            .values()
+            .filter(|u| matches(u, filter))
+            .filter(|u| page.after.as_ref().is_none_or(|after| u.cursor() > *after))
+            .cloned()
+            .collect();
+        rows.sort_by_key(User::cursor);
+        rows.truncate(page.fetch_limit() as usize);
+
+        Ok(Page::from_rows(rows, page, User::cursor))
+    }
+
+    /// Count users matching the filter
+    async fn count(&self, filter: &UserFilter) -> AuthResult<u64> {
+        Ok(read(&self.users)?.values().filter(|u| matches(u, filter)).count() as u64)
+    }
+
+    /// List users belonging to an organization (with pagination)
+    async fn list_by_org(&self, org_id: &DbId, page_number: u32, limit: u32) -> AuthResult<Vec<User>> {
+        let users = read(&self.users)?;
+        Ok(page(users.values().filter(|u| u.org_ids.contains(org_id)), page_number, limit))
+    }
+
+    /// Count users belonging to an organization
+    async fn count_by_org(&self, org_id: &DbId) -> AuthResult<u64> {
+        Ok(read(&self.users)?.values().filter(|u| u.org_ids.contains(org_id)).count() as u64)
+    }
+
+    /// Add user to an organization
+    async fn add_to_org(&self, id: &DbId, org_id: &DbId) -> AuthResult<()> {
+        if let Some(user) = write(&self.users)?.get_mut(id) {
+            if !user.org_ids.contains(org_id) {
+                user.org_ids.push(org_id.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove user from an organization
+    async fn remove_from_org(&self, id: &DbId, org_id: &DbId) -> AuthResult<()> {
+        if let Some(user) = write(&self.users)?.get_mut(id) {
+            user.org_ids.retain(|org| org != org_id);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::errors::AuthErrorCode;
+
+    fn input(email: Option<&str>, username: Option<&str>) -> CreateUserInput {
+        CreateUserInput {
+            email:    email.map(String::from),
+            phone:    None,
+            username: username.map(String::from),
+            password: "hash".to_string(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_rejects_duplicate_identifiers() {
+        let store = MemoryUserStore::new();
+        store.create(input(Some("amani@example.com"), Some("amani"))).await.unwrap();
+
+        let err = store.create(input(Some("amani@example.com"), None)).await.unwrap_err();
+        assert!(matches!(err.code, AuthErrorCode::EmailAlreadyExists));
+
+        // Cyrillic "а" shares the skeleton of the Latin name
+        let err = store.create(input(None, Some("аmani"))).await.unwrap_err();
+        assert!(matches!(err.code, AuthErrorCode::UsernameAlreadyExists));
+    }
+
+    #[actix_web::test]
+    async fn test_list_skips_deleted_users() {
+        let store = MemoryUserStore::new();
+        let kept = store.create(input(Some("a@example.com"), None)).await.unwrap();
+        let gone = store.create(input(Some("b@example.com"), None)).await.unwrap();
+        store.anonymize(&gone.id).await.unwrap();
+
+        let users = store.list(&UserFilter::default(), 0, 10).await.unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].id, kept.id);
+        assert_eq!(store.count(&UserFilter::default()).await.unwrap(), 1);
+
+        // Anonymized users free their email
+        assert!(store.create(input(Some("b@example.com"), None)).await.is_ok());
+    }
+
+    #[actix_web::test]
+    async fn test_list_after_walks_every_user_once() {
+        let store = MemoryUserStore::new();
+        let mut created = Vec::new();
+        for n in 0..5 {
+            let user = store.create(input(Some(&format!("{}@example.com", n)), None)).await.unwrap();
+            created.push(user.id);
+        }
+
+        let mut seen = Vec::new();
+        let mut request = PageRequest::first(2);
+        loop {
+            let page = store.list_after(&UserFilter::default(), &request).await.unwrap();
+            assert!(page.items.len() <= 2);
+            seen.extend(page.items.iter().map(|u| u.id.clone()));
+            match page.next {
+                Some(cursor) => request = PageRequest::after(cursor, 2),
+                None => break,
+            }
+        }
+
+        seen.sort();
+        created.sort();
+        assert_eq!(seen, created);
+    }
+}
@@ -0,0 +1,110 @@
+//! In-memory Verification Store Implementation
+
+use async_trait::async_trait;
+use std::sync::RwLock;
+
+use super::{Table, read, write};
+use crate::models::verification::{
+    CreateVerificationCode, VerificationCodeModel, VerificationMedium, VerificationPurpose,
+};
+use crate::store::verification_store::VerificationStore;
+use crate::utils::errors::AuthResult;
+use database::utils::{DbId, generate_id};
+
+/// In-memory implementation of VerificationStore
+#[derive(Default)]
+pub struct MemoryVerificationStore {
+    codes: RwLock<Table<VerificationCodeModel>>,
+}
+
+impl MemoryVerificationStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn modify(&self, id: &DbId, change: impl FnOnce(&mut VerificationCodeModel)) -> AuthResult<()> {
+        if let Some(code) = write(&self.codes)?.get_mut(id) {
+            change(code);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl VerificationStore for MemoryVerificationStore {
+    /// Create a new verification code
+    async fn create(&self, input: CreateVerificationCode) -> AuthResult<VerificationCodeModel> {
+        let now = chrono::Utc::now().timestamp();
+
+        let code = VerificationCodeModel {
+            id: generate_id(),
+            user_id: input.user_id,
+            code_hash: input.code_hash,
+            medium: input.medium,
+            purpose: input.purpose,
+            attempts: 0,
+            created_at: now,
+            expires_at: now + input.expires_in,
+            verified_at: None,
+        };
+
+        write(&self.codes)?.insert(code.id.clone(), code.clone());
+
+        Ok(code)
+    }
+
+    /// Find verification code by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<VerificationCodeModel>> {
+        Ok(read(&self.codes)?.get(id).cloned())
+    }
+
+    /// Find valid verification code by user_id, medium, and purpose
+    async fn find_valid_code(
+        &self,
+        user_id: &DbId,
+        medium: VerificationMedium,
+        purpose: VerificationPurpose,
+    ) -> AuthResult<Option<VerificationCodeModel>> {
+        Ok(read(&self.codes)?
+            .values()
+            .find(|c| {
+                &c.user_id == user_id && c.medium == medium && c.purpose == purpose && c.is_valid()
+            })
+            .cloned())
+    }
+
+    /// Verify a code (mark as verified)
+    async fn verify(&self, id: &DbId) -> AuthResult<()> {
+        self.modify(id, |code| code.verified_at = Some(chrono::Utc::now().timestamp()))
+    }
+
+    /// Increment failed attempts
+    async fn increment_attempts(&self, id: &DbId) -> AuthResult<()> {
+        self.modify(id, |code| code.attempts += 1)
+    }
+
+    /// Delete/expire a code
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        write(&self.codes)?.remove(id);
+        Ok(())
+    }
+
+    /// Delete all codes for a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let mut codes = write(&self.codes)?;
+        let before = codes.len();
+        codes.retain(|_, c| &c.user_id != user_id);
+
+        Ok((before - codes.len()) as u64)
+    }
+
+    /// Cleanup expired codes
+    async fn cleanup_expired(&self) -> AuthResult<u64> {
+        let mut codes = write(&self.codes)?;
+        let before = codes.len();
+        codes.retain(|_, c| !c.is_expired());
+
+        Ok((before - codes.len()) as u64)
+    }
+}
@@ -0,0 +1,44 @@
+//! In-memory store implementations
+//!
+//! Thread-safe stand-ins for the MongoDB stores with the same uniqueness,
+//! expiry and revocation rules, for tests and demos. Nothing is persisted.
+//! Enabled in tests and with the `memory` feature.
+
+pub mod memory_api_key_store;
+pub mod memory_audit_store;
+pub mod memory_contact_change_store;
+pub mod memory_oauth_account_store;
+pub mod memory_organization_store;
+pub mod memory_passkey_store;
+pub mod memory_password_reset_store;
+pub mod memory_session_store;
+pub mod memory_user_invitation_store;
+pub mod memory_user_store;
+pub mod memory_verification_store;
+
+pub use memory_api_key_store::MemoryApiKeyStore;
+pub use memory_audit_store::MemoryAuditStore;
+pub use memory_contact_change_store::MemoryContactChangeStore;
+pub use memory_oauth_account_store::MemoryOAuthAccountStore;
+pub use memory_organization_store::MemoryOrganizationStore;
+pub use memory_passkey_store::MemoryPasskeyStore;
+pub use memory_password_reset_store::MemoryPasswordResetStore;
+pub use memory_session_store::MemorySessionStore;
+pub use memory_user_invitation_store::MemoryUserInvitationStore;
+pub use memory_user_store::MemoryUserStore;
+pub use memory_verification_store::MemoryVerificationStore;
+
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::utils::errors::{AuthError, AuthResult};
+
+/// Records keyed by id
+type Table<T> = std::collections::HashMap<database::utils::DbId, T>;
+
+fn read<T>(lock: &RwLock<T>) -> AuthResult<RwLockReadGuard<'_, T>> {
+    lock.read().map_err(|_| AuthError::internal_error("In-memory store lock poisoned"))
+}
+
+fn write<T>(lock: &RwLock<T>) -> AuthResult<RwLockWriteGuard<'_, T>> {
+    lock.write().map_err(|_| AuthError::internal_error("In-memory store lock poisoned"))
+}
@@ -1,13 +1,29 @@
+pub mod api_key_store;
+pub mod audit_store;
+pub mod contact_change_store;
 pub mod database;
+#[cfg(any(test, feature = "memory"))]
+pub mod memory;
 pub mod oauth_account_store;
+pub mod organization_store;
+pub mod passkey_store;
 pub mod password_reset_store;
 pub mod session_store;
+#[cfg(feature = "sql")]
+pub mod sql;
+pub mod user_invitation_store;
 pub mod user_store;
 pub mod verification_store;
 
 // Re-export traits
+pub use api_key_store::ApiKeyStore;
+pub use audit_store::AuditStore;
+pub use contact_change_store::ContactChangeStore;
 pub use oauth_account_store::OAuthAccountStore;
+pub use organization_store::OrganizationStore;
+pub use passkey_store::PasskeyStore;
 pub use password_reset_store::PasswordResetStore;
 pub use session_store::SessionStore;
+pub use user_invitation_store::UserInvitationStore;
 pub use user_store::UserStore;
 pub use verification_store::VerificationStore;
@@ -0,0 +1,72 @@
+//! Organization store module
+//!
+//! Provides a generic organization store that works with any database.
+//! Covers organizations, memberships and invitations.
+
+use async_trait::async_trait;
+use crate::models::organization::{
+    CreateMembership, CreateOrgInvitation, CreateOrganization, Membership, OrgInvitation,
+    OrgRole, Organization,
+};
+use crate::utils::errors::AuthResult;
+use database::utils::DbId;
+
+/// Organization store trait - implement this for each database
+#[async_trait]
+pub trait OrganizationStore: Send + Sync {
+    /// Create a new organization
+    async fn create(&self, input: CreateOrganization) -> AuthResult<Organization>;
+
+    /// Find organization by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<Organization>>;
+
+    /// Find organization by slug
+    async fn find_by_slug(&self, slug: &str) -> AuthResult<Option<Organization>>;
+
+    /// Find organizations by IDs
+    async fn find_by_ids(&self, ids: &[DbId]) -> AuthResult<Vec<Organization>>;
+
+    /// Delete an organization with its memberships and invitations
+    async fn delete(&self, id: &DbId) -> AuthResult<()>;
+
+    // Membership methods
+    /// Add a member
+    async fn add_member(&self, input: CreateMembership) -> AuthResult<Membership>;
+
+    /// Find a user's membership in an organization
+    async fn find_membership(&self, org_id: &DbId, user_id: &DbId) -> AuthResult<Option<Membership>>;
+
+    /// List members of an organization
+    async fn list_members(&self, org_id: &DbId) -> AuthResult<Vec<Membership>>;
+
+    /// List memberships of a user
+    async fn list_user_memberships(&self, user_id: &DbId) -> AuthResult<Vec<Membership>>;
+
+    /// Change a member's role
+    async fn update_member_role(&self, org_id: &DbId, user_id: &DbId, role: OrgRole) -> AuthResult<()>;
+
+    /// Remove a member - refuses to remove the organization's last owner
+    async fn remove_member(&self, org_id: &DbId, user_id: &DbId) -> AuthResult<()>;
+
+    // Invitation methods
+    /// Create an invitation
+    async fn create_invitation(&self, input: CreateOrgInvitation) -> AuthResult<OrgInvitation>;
+
+    /// Find invitation by ID
+    async fn find_invitation(&self, id: &DbId) -> AuthResult<Option<OrgInvitation>>;
+
+    /// Find invitation by token hash
+    async fn find_invitation_by_token(&self, token_hash: &str) -> AuthResult<Option<OrgInvitation>>;
+
+    /// List invitations of an organization
+    async fn list_invitations(&self, org_id: &DbId) -> AuthResult<Vec<OrgInvitation>>;
+
+    /// Mark invitation as accepted
+    async fn accept_invitation(&self, id: &DbId) -> AuthResult<()>;
+
+    /// Delete an invitation
+    async fn delete_invitation(&self, id: &DbId) -> AuthResult<()>;
+
+    /// Delete all invitations sent to an email address
+    async fn delete_invitations_for_email(&self, email: &str) -> AuthResult<u64>;
+}
@@ -0,0 +1,48 @@
+//! Passkey store module
+//!
+//! Provides a generic store for WebAuthn credentials and ceremony challenges.
+
+use async_trait::async_trait;
+use crate::models::passkey::{
+    CreatePasskeyCredential, CreateWebAuthnChallenge, PasskeyCredential, WebAuthnChallenge,
+};
+use crate::utils::errors::AuthResult;
+use database::utils::DbId;
+
+/// Passkey store trait - implement this for each database
+#[async_trait]
+pub trait PasskeyStore: Send + Sync {
+    /// Register a new passkey credential
+    async fn create(&self, input: CreatePasskeyCredential) -> AuthResult<PasskeyCredential>;
+
+    /// Find passkey by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<PasskeyCredential>>;
+
+    /// Find passkey by authenticator credential ID
+    async fn find_by_credential_id(&self, credential_id: &str) -> AuthResult<Option<PasskeyCredential>>;
+
+    /// List all passkeys for a user
+    async fn list_by_user(&self, user_id: &DbId) -> AuthResult<Vec<PasskeyCredential>>;
+
+    /// Store the new signature counter after a successful assertion
+    async fn update_sign_count(&self, id: &DbId, sign_count: u32) -> AuthResult<()>;
+
+    /// Delete a passkey
+    async fn delete(&self, id: &DbId) -> AuthResult<()>;
+
+    /// Delete all passkeys for a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64>;
+
+    // Challenge methods
+    /// Create a ceremony challenge
+    async fn create_challenge(&self, input: CreateWebAuthnChallenge) -> AuthResult<WebAuthnChallenge>;
+
+    /// Find challenge by its base64url value
+    async fn find_challenge(&self, challenge: &str) -> AuthResult<Option<WebAuthnChallenge>>;
+
+    /// Delete a challenge (challenges are single use)
+    async fn delete_challenge(&self, id: &DbId) -> AuthResult<()>;
+
+    /// Cleanup expired challenges
+    async fn cleanup_expired_challenges(&self) -> AuthResult<u64>;
+}
@@ -2,6 +2,7 @@
 //!
 //! Provides a generic session store that works with any database.
 
+use async_trait::async_trait;
 use crate::models::session::{
     CreateRefreshToken, CreateSession, RefreshTokenModel, SessionModel, UpdateSession,
 };
@@ -9,46 +10,56 @@ use crate::utils::errors::AuthResult;
 use database::utils::DbId;
 
 /// Session store trait - implement this for each database
+#[async_trait]
 pub trait SessionStore: Send + Sync {
     /// Create a new session
-    fn create(&self, input: CreateSession) -> AuthResult<SessionModel>;
+    async fn create(&self, input: CreateSession) -> AuthResult<SessionModel>;
 
     /// Find session by ID
-    fn find_by_id(&self, id: &DbId) -> AuthResult<Option<SessionModel>>;
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<SessionModel>>;
 
     /// Find session by access token hash
-    fn find_by_token(&self, token_hash: &str) -> AuthResult<Option<SessionModel>>;
+    async fn find_by_token(&self, token_hash: &str) -> AuthResult<Option<SessionModel>>;
 
     /// Find all sessions for a user
-    fn find_by_user_id(&self, user_id: &DbId) -> AuthResult<Vec<SessionModel>>;
+    async fn find_by_user_id(&self, user_id: &DbId) -> AuthResult<Vec<SessionModel>>;
+
+    /// Find all sessions issued for an organization (tenant)
+    async fn find_by_org(&self, org_id: &DbId) -> AuthResult<Vec<SessionModel>>;
 
     /// Update session (e.g., update last_used_at, extend expiry)
-    fn update(&self, id: &DbId, session: UpdateSession) -> AuthResult<UpdateSession>;
+    async fn update(&self, id: &DbId, session: UpdateSession) -> AuthResult<UpdateSession>;
+
+    /// Point a session at a newly issued access token (e.g. after re-authentication)
+    async fn rotate_access_token(&self, id: &DbId, access_token_hash: &str) -> AuthResult<()>;
 
     /// Revoke a session
-    fn revoke(&self, id: &DbId) -> AuthResult<()>;
+    async fn revoke(&self, id: &DbId) -> AuthResult<()>;
 
     /// Revoke all sessions for a user
-    fn revoke_all(&self, user_id: &DbId) -> AuthResult<u64>;
+    async fn revoke_all(&self, user_id: &DbId) -> AuthResult<u64>;
+
+    /// Delete all sessions and refresh tokens of a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64>;
 
     /// Delete expired sessions
-    fn cleanup_expired(&self) -> AuthResult<u64>;
+    async fn cleanup_expired(&self) -> AuthResult<u64>;
 
     // Refresh Token methods
     /// Create refresh token
-    fn create_refresh_token(&self, input: CreateRefreshToken) -> AuthResult<RefreshTokenModel>;
+    async fn create_refresh_token(&self, input: CreateRefreshToken) -> AuthResult<RefreshTokenModel>;
 
     /// Find refresh token by ID
-    fn find_refresh_token(&self, id: &DbId) -> AuthResult<Option<RefreshTokenModel>>;
+    async fn find_refresh_token(&self, id: &DbId) -> AuthResult<Option<RefreshTokenModel>>;
 
     /// Find refresh token by hash
-    fn find_refresh_token_by_hash(&self, token_hash: &str)
+    async fn find_refresh_token_by_hash(&self, token_hash: &str)
     -> AuthResult<Option<RefreshTokenModel>>;
 
     /// Revoke refresh token
-    fn revoke_refresh_token(&self, id: &DbId) -> AuthResult<()>;
+    async fn revoke_refresh_token(&self, id: &DbId) -> AuthResult<()>;
 
     /// Replace refresh token (for rotation)
-    fn replace_refresh_token(&self, old_id: &DbId, new_token: CreateRefreshToken)
+    async fn replace_refresh_token(&self, old_id: &DbId, new_token: CreateRefreshToken)
     -> AuthResult<()>;
 }
@@ -0,0 +1,266 @@
+//! Schema migrations for the auth tables
+//!
+//! Register these with `database::sql::SqlMigrationRunner`. Never edit a
+//! migration once it has shipped; add a new version instead.
+
+use database::sql::SqlMigration;
+
+/// All auth SQL migrations, oldest first
+pub fn migrations() -> Vec<SqlMigration> {
+    vec![SqlMigration::new(1, "create_auth_tables", CREATE_AUTH_TABLES).with_down(DROP_AUTH_TABLES)]
+}
+
+const CREATE_AUTH_TABLES: &str = r#"
+CREATE TABLE users (
+    id TEXT PRIMARY KEY,
+    email TEXT,
+    password_hash TEXT NOT NULL,
+    phone TEXT,
+    username TEXT,
+    username_key TEXT,
+    first_name TEXT,
+    last_name TEXT,
+    is_active BIGINT NOT NULL DEFAULT 1,
+    is_verified BIGINT NOT NULL DEFAULT 0,
+    login_attempts BIGINT NOT NULL DEFAULT 0,
+    locked_until BIGINT,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT,
+    last_login BIGINT,
+    deletion_scheduled_at BIGINT,
+    deleted_at BIGINT,
+    is_admin BIGINT NOT NULL DEFAULT 0,
+    profile TEXT NOT NULL DEFAULT '{}'
+);
+CREATE UNIQUE INDEX users_email_unique ON users (email);
+CREATE UNIQUE INDEX users_phone_unique ON users (phone);
+CREATE UNIQUE INDEX users_username_key_unique ON users (username_key);
+
+CREATE TABLE user_orgs (
+    user_id TEXT NOT NULL,
+    org_id TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    PRIMARY KEY (user_id, org_id)
+);
+CREATE INDEX user_orgs_org_id ON user_orgs (org_id);
+
+CREATE TABLE sessions (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    access_token_hash TEXT NOT NULL,
+    refresh_token_hash TEXT,
+    device TEXT,
+    ip_address TEXT,
+    user_agent TEXT,
+    org_id TEXT,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    last_used_at BIGINT NOT NULL,
+    is_revoked BIGINT NOT NULL DEFAULT 0
+);
+CREATE INDEX sessions_access_token_hash ON sessions (access_token_hash);
+CREATE INDEX sessions_user_id ON sessions (user_id);
+CREATE INDEX sessions_org_id ON sessions (org_id);
+CREATE INDEX sessions_expires_at ON sessions (expires_at);
+
+CREATE TABLE refresh_tokens (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    token_hash TEXT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL,
+    revoked BIGINT NOT NULL DEFAULT 0,
+    revoked_at BIGINT,
+    replaced_by TEXT
+);
+CREATE UNIQUE INDEX refresh_tokens_token_hash_unique ON refresh_tokens (token_hash);
+CREATE INDEX refresh_tokens_user_id ON refresh_tokens (user_id);
+
+CREATE TABLE verification_codes (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    code_hash TEXT NOT NULL,
+    medium TEXT NOT NULL,
+    purpose TEXT NOT NULL,
+    attempts BIGINT NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    verified_at BIGINT
+);
+CREATE INDEX verification_codes_user_medium_purpose ON verification_codes (user_id, medium, purpose);
+
+CREATE TABLE password_reset_tokens (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    token_hash TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    used_at BIGINT
+);
+CREATE UNIQUE INDEX password_reset_tokens_token_hash_unique ON password_reset_tokens (token_hash);
+CREATE INDEX password_reset_tokens_user_id ON password_reset_tokens (user_id);
+
+CREATE TABLE oauth_accounts (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    provider_user_id TEXT NOT NULL,
+    access_token TEXT,
+    refresh_token TEXT,
+    expires_at BIGINT,
+    scope TEXT,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT
+);
+CREATE UNIQUE INDEX oauth_accounts_provider_user_unique ON oauth_accounts (provider, provider_user_id);
+CREATE INDEX oauth_accounts_user_id_provider ON oauth_accounts (user_id, provider);
+
+CREATE TABLE passkeys (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    credential_id TEXT NOT NULL,
+    public_key TEXT NOT NULL,
+    sign_count BIGINT NOT NULL DEFAULT 0,
+    aaguid TEXT,
+    name TEXT,
+    transports TEXT NOT NULL DEFAULT '[]',
+    created_at BIGINT NOT NULL,
+    last_used_at BIGINT
+);
+CREATE UNIQUE INDEX passkeys_credential_id_unique ON passkeys (credential_id);
+CREATE INDEX passkeys_user_id ON passkeys (user_id);
+
+CREATE TABLE webauthn_challenges (
+    id TEXT PRIMARY KEY,
+    user_id TEXT,
+    challenge TEXT NOT NULL,
+    ceremony TEXT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+CREATE INDEX webauthn_challenges_challenge ON webauthn_challenges (challenge);
+
+CREATE TABLE api_keys (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    key_prefix TEXT NOT NULL,
+    key_hash TEXT NOT NULL,
+    scopes TEXT NOT NULL DEFAULT '[]',
+    rate_limit_per_minute BIGINT,
+    expires_at BIGINT,
+    last_used_at BIGINT,
+    last_used_ip TEXT,
+    is_revoked BIGINT NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL
+);
+CREATE UNIQUE INDEX api_keys_key_hash_unique ON api_keys (key_hash);
+CREATE INDEX api_keys_user_id ON api_keys (user_id);
+
+CREATE TABLE organizations (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    slug TEXT NOT NULL,
+    owner_id TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT
+);
+CREATE UNIQUE INDEX organizations_slug_unique ON organizations (slug);
+
+CREATE TABLE org_memberships (
+    id TEXT PRIMARY KEY,
+    org_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    role TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT
+);
+CREATE UNIQUE INDEX org_memberships_org_user_unique ON org_memberships (org_id, user_id);
+CREATE INDEX org_memberships_user_id ON org_memberships (user_id);
+
+CREATE TABLE org_invitations (
+    id TEXT PRIMARY KEY,
+    org_id TEXT NOT NULL,
+    email TEXT NOT NULL,
+    role TEXT NOT NULL,
+    token_hash TEXT NOT NULL,
+    invited_by TEXT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    accepted_at BIGINT,
+    created_at BIGINT NOT NULL
+);
+CREATE UNIQUE INDEX org_invitations_token_hash_unique ON org_invitations (token_hash);
+CREATE INDEX org_invitations_org_id ON org_invitations (org_id);
+CREATE INDEX org_invitations_email ON org_invitations (email);
+
+CREATE TABLE user_invitations (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    org_id TEXT NOT NULL,
+    email TEXT NOT NULL,
+    role TEXT NOT NULL,
+    nonce_hash TEXT NOT NULL,
+    invited_by TEXT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    accepted_at BIGINT,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT
+);
+CREATE INDEX user_invitations_org_id ON user_invitations (org_id);
+CREATE INDEX user_invitations_user_id ON user_invitations (user_id);
+
+CREATE TABLE contact_changes (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    medium TEXT NOT NULL,
+    old_value TEXT,
+    new_value TEXT NOT NULL,
+    code_hash TEXT NOT NULL,
+    revert_token_hash TEXT,
+    attempts BIGINT NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    revert_expires_at BIGINT NOT NULL,
+    confirmed_at BIGINT,
+    reverted_at BIGINT
+);
+CREATE INDEX contact_changes_user_id_medium ON contact_changes (user_id, medium);
+CREATE INDEX contact_changes_revert_token_hash ON contact_changes (revert_token_hash);
+
+CREATE TABLE audit_events (
+    id TEXT PRIMARY KEY,
+    action TEXT NOT NULL,
+    outcome TEXT NOT NULL,
+    actor_id TEXT,
+    target_id TEXT,
+    impersonator_id TEXT,
+    org_id TEXT,
+    ip_address TEXT,
+    user_agent TEXT,
+    reason TEXT,
+    created_at BIGINT NOT NULL
+);
+CREATE INDEX audit_events_created_at ON audit_events (created_at);
+CREATE INDEX audit_events_actor_id_created_at ON audit_events (actor_id, created_at);
+CREATE INDEX audit_events_target_id_created_at ON audit_events (target_id, created_at);
+CREATE INDEX audit_events_org_id_created_at ON audit_events (org_id, created_at);
+"#;
+
+const DROP_AUTH_TABLES: &str = r#"
+DROP TABLE IF EXISTS audit_events;
+DROP TABLE IF EXISTS contact_changes;
+DROP TABLE IF EXISTS user_invitations;
+DROP TABLE IF EXISTS org_invitations;
+DROP TABLE IF EXISTS org_memberships;
+DROP TABLE IF EXISTS organizations;
+DROP TABLE IF EXISTS api_keys;
+DROP TABLE IF EXISTS webauthn_challenges;
+DROP TABLE IF EXISTS passkeys;
+DROP TABLE IF EXISTS oauth_accounts;
+DROP TABLE IF EXISTS password_reset_tokens;
+DROP TABLE IF EXISTS verification_codes;
+DROP TABLE IF EXISTS refresh_tokens;
+DROP TABLE IF EXISTS sessions;
+DROP TABLE IF EXISTS user_orgs;
+DROP TABLE IF EXISTS users;
+"#;
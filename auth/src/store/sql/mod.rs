@@ -0,0 +1,219 @@
+//! SQL database store implementations
+//!
+//! Provides concrete implementations of the store traits over a sqlx `Any`
+//! pool, following the same rules as the MongoDB stores. Create the schema
+//! with `migrations::migrations()` before using them. Enabled with the `sql`
+//! feature; `postgres` and `sqlite` add the drivers.
+//!
+//! Ids are stored as `DbId` strings, times as epoch seconds, flags as 0/1 and
+//! lists or profiles as JSON text, so the same queries run on every backend.
+
+pub mod migrations;
+pub mod sql_api_key_store;
+pub mod sql_audit_store;
+pub mod sql_contact_change_store;
+pub mod sql_oauth_account_store;
+pub mod sql_organization_store;
+pub mod sql_passkey_store;
+pub mod sql_password_reset_store;
+pub mod sql_session_store;
+pub mod sql_user_invitation_store;
+pub mod sql_user_store;
+pub mod sql_verification_store;
+
+pub use sql_api_key_store::SqlApiKeyStore;
+pub use sql_audit_store::SqlAuditStore;
+pub use sql_contact_change_store::SqlContactChangeStore;
+pub use sql_oauth_account_store::SqlOAuthAccountStore;
+pub use sql_organization_store::SqlOrganizationStore;
+pub use sql_passkey_store::SqlPasskeyStore;
+pub use sql_password_reset_store::SqlPasswordResetStore;
+pub use sql_session_store::SqlSessionStore;
+pub use sql_user_invitation_store::SqlUserInvitationStore;
+pub use sql_user_store::SqlUserStore;
+pub use sql_verification_store::SqlVerificationStore;
+
+use database::pagination::Cursor;
+use database::utils::{DbId, parse_id};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sqlx::any::{AnyArguments, AnyRow};
+use sqlx::query::Query;
+use sqlx::{Any, Row};
+
+use crate::utils::errors::AuthError;
+
+/// Error mapper for a failed query, e.g. `failed("create session")`
+fn failed(action: &'static str) -> impl Fn(sqlx::Error) -> AuthError {
+    move |e| AuthError::internal_error(&format!("Failed to {}: {}", action, e))
+}
+
+fn decode_error(column: &str, error: impl std::error::Error + Send + Sync + 'static) -> sqlx::Error {
+    sqlx::Error::ColumnDecode {
+        index: column.to_string(),
+        source: Box::new(error),
+    }
+}
+
+/// Read an id column
+fn get_id(row: &AnyRow, column: &str) -> Result<DbId, sqlx::Error> {
+    let value: String = row.try_get(column)?;
+    parse_id(&value).map_err(|e| decode_error(column, e))
+}
+
+/// Read a nullable id column
+fn get_opt_id(row: &AnyRow, column: &str) -> Result<Option<DbId>, sqlx::Error> {
+    let value: Option<String> = row.try_get(column)?;
+    value
+        .map(|v| parse_id(&v).map_err(|e| decode_error(column, e)))
+        .transpose()
+}
+
+/// Read a 0/1 flag column
+fn get_flag(row: &AnyRow, column: &str) -> Result<bool, sqlx::Error> {
+    Ok(row.try_get::<i64, _>(column)? != 0)
+}
+
+/// Read a JSON text column (lists, profiles)
+fn get_json<T: DeserializeOwned>(row: &AnyRow, column: &str) -> Result<T, sqlx::Error> {
+    let value: String = row.try_get(column)?;
+    serde_json::from_str(&value).map_err(|e| decode_error(column, e))
+}
+
+/// Read an enum stored under its serde name
+fn get_enum<T: DeserializeOwned>(row: &AnyRow, column: &str) -> Result<T, sqlx::Error> {
+    let value: String = row.try_get(column)?;
+    serde_json::from_value(serde_json::Value::String(value)).map_err(|e| decode_error(column, e))
+}
+
+/// Serde name of a unit enum variant, as stored in enum columns
+fn enum_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "null".to_string())
+}
+
+fn flag(value: bool) -> i64 {
+    i64::from(value)
+}
+
+/// `count` numbered placeholders starting at `$first`
+fn placeholders(first: usize, count: usize) -> String {
+    (first..first + count)
+        .map(|n| format!("${}", n))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Argument of a query built at runtime
+enum Arg {
+    Int(i64),
+    Text(String),
+}
+
+/// Where clause collecting its arguments, numbered from `$1`
+#[derive(Default)]
+struct Conditions {
+    clauses: Vec<String>,
+    args: Vec<Arg>,
+}
+
+impl Conditions {
+    /// Placeholder for the next argument
+    fn next(&self) -> String {
+        format!("${}", self.args.len() + 1)
+    }
+
+    /// Add a clause without arguments
+    fn push(&mut self, clause: &str) {
+        self.clauses.push(clause.to_string());
+    }
+
+    /// Add an argument and return its placeholder, for clauses with several
+    fn arg(&mut self, arg: Arg) -> String {
+        let placeholder = self.next();
+        self.args.push(arg);
+        placeholder
+    }
+
+    /// Add `column <op> $n`
+    fn compare(&mut self, column: &str, op: &str, arg: Arg) {
+        let placeholder = self.arg(arg);
+        self.clauses.push(format!("{} {} {}", column, op, placeholder));
+    }
+
+    /// Keep rows past a cursor in `(created_at, id)` order, `>` ascending or `<` descending
+    fn after(&mut self, cursor: &Cursor, op: &str) {
+        let time = self.arg(Arg::Int(cursor.created_at));
+        let same_time = self.arg(Arg::Int(cursor.created_at));
+        let id = self.arg(Arg::Text(cursor.id.clone()));
+        self.clauses.push(format!(
+            "(created_at {op} {} OR (created_at = {} AND id {op} {}))",
+            time, same_time, id
+        ));
+    }
+
+    fn sql(&self) -> String {
+        if self.clauses.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", self.clauses.join(" AND "))
+        }
+    }
+
+    fn bind<'q>(&'q self, mut query: Query<'q, Any, AnyArguments<'q>>) -> Query<'q, Any, AnyArguments<'q>> {
+        for arg in &self.args {
+            query = match arg {
+                Arg::Int(value) => query.bind(*value),
+                Arg::Text(value) => query.bind(value.as_str()),
+            };
+        }
+        query
+    }
+}
+
+/// Fresh in-memory SQLite pool with the auth schema applied
+#[cfg(all(test, feature = "sqlite"))]
+async fn test_pool() -> sqlx::AnyPool {
+    use database::init::DatabaseConfig;
+    use database::sql::{SqlConnection, SqlMigrationRunner};
+
+    let db = SqlConnection::new(DatabaseConfig::sqlite_memory()).await.unwrap();
+    SqlMigrationRunner::new(db.pool().clone())
+        .with_migrations(migrations::migrations())
+        .migrate()
+        .await
+        .unwrap();
+    db.pool().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::organization::OrgRole;
+    use crate::models::verification::VerificationPurpose;
+
+    #[test]
+    fn test_enum_names_match_serde() {
+        assert_eq!(enum_name(&VerificationPurpose::SignUp), "sign_up");
+        assert_eq!(enum_name(&OrgRole::Admin), "admin");
+        assert_eq!(placeholders(2, 3), "$2, $3, $4");
+    }
+
+    #[test]
+    fn test_conditions_number_arguments() {
+        let mut conditions = Conditions::default();
+        conditions.push("deleted_at IS NULL");
+        conditions.compare("is_active", "=", Arg::Int(1));
+        conditions.compare("created_at", ">=", Arg::Int(10));
+
+        assert_eq!(conditions.sql(), " WHERE deleted_at IS NULL AND is_active = $1 AND created_at >= $2");
+        assert_eq!(conditions.next(), "$3");
+        assert_eq!(Conditions::default().sql(), "");
+    }
+}
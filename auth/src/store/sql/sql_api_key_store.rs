@@ -0,0 +1,170 @@
+//! SQL API Key Store Implementation
+
+use async_trait::async_trait;
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Row};
+
+use super::{failed, flag, get_flag, get_id, get_json, to_json};
+use crate::models::api_key::{ApiKey, CreateApiKey};
+use crate::store::api_key_store::ApiKeyStore;
+use crate::utils::errors::AuthResult;
+use database::utils::DbId;
+
+const COLUMNS: &str = "id, user_id, name, key_prefix, key_hash, scopes, rate_limit_per_minute, \
+    expires_at, last_used_at, last_used_ip, is_revoked, created_at";
+
+/// SQL implementation of ApiKeyStore
+pub struct SqlApiKeyStore {
+    pool: AnyPool,
+}
+
+impl SqlApiKeyStore {
+    /// Create a new SqlApiKeyStore
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+
+    async fn find_one(&self, column: &str, value: String) -> AuthResult<Option<ApiKey>> {
+        let row = sqlx::query(&format!("SELECT {} FROM api_keys WHERE {} = $1", COLUMNS, column))
+            .bind(value)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(failed("find API key"))?;
+
+        row.as_ref().map(api_key_from_row).transpose().map_err(failed("read API key"))
+    }
+}
+
+fn api_key_from_row(row: &AnyRow) -> Result<ApiKey, sqlx::Error> {
+    Ok(ApiKey {
+        id: get_id(row, "id")?,
+        user_id: get_id(row, "user_id")?,
+        name: row.try_get("name")?,
+        key_prefix: row.try_get("key_prefix")?,
+        key_hash: row.try_get("key_hash")?,
+        scopes: get_json(row, "scopes")?,
+        rate_limit_per_minute: row
+            .try_get::<Option<i64>, _>("rate_limit_per_minute")?
+            .map(|limit| limit as u32),
+        expires_at: row.try_get("expires_at")?,
+        last_used_at: row.try_get("last_used_at")?,
+        last_used_ip: row.try_get("last_used_ip")?,
+        is_revoked: get_flag(row, "is_revoked")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+#[async_trait]
+impl ApiKeyStore for SqlApiKeyStore {
+    /// Create a new API key
+    async fn create(&self, input: CreateApiKey) -> AuthResult<ApiKey> {
+        let now = chrono::Utc::now().timestamp();
+
+        let api_key = ApiKey {
+            id: DbId::new_uuid(),
+            user_id: input.user_id,
+            name: input.name,
+            key_prefix: input.key_prefix,
+            key_hash: input.key_hash,
+            scopes: input.scopes,
+            rate_limit_per_minute: input.rate_limit_per_minute,
+            expires_at: input.expires_in.map(|secs| now + secs),
+            last_used_at: None,
+            last_used_ip: None,
+            is_revoked: false,
+            created_at: now,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO api_keys ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+            COLUMNS
+        ))
+        .bind(api_key.id.to_string())
+        .bind(api_key.user_id.to_string())
+        .bind(&api_key.name)
+        .bind(&api_key.key_prefix)
+        .bind(&api_key.key_hash)
+        .bind(to_json(&api_key.scopes))
+        .bind(api_key.rate_limit_per_minute.map(i64::from))
+        .bind(api_key.expires_at)
+        .bind(api_key.last_used_at)
+        .bind(&api_key.last_used_ip)
+        .bind(flag(api_key.is_revoked))
+        .bind(api_key.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("create API key"))?;
+
+        Ok(api_key)
+    }
+
+    /// Find API key by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<ApiKey>> {
+        self.find_one("id", id.to_string()).await
+    }
+
+    /// Find API key by key hash
+    async fn find_by_hash(&self, key_hash: &str) -> AuthResult<Option<ApiKey>> {
+        self.find_one("key_hash", key_hash.to_string()).await
+    }
+
+    /// List all API keys for a user
+    async fn list_by_user(&self, user_id: &DbId) -> AuthResult<Vec<ApiKey>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM api_keys WHERE user_id = $1 ORDER BY created_at",
+            COLUMNS
+        ))
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(failed("list API keys"))?;
+
+        rows.iter().map(api_key_from_row).collect::<Result<_, _>>().map_err(failed("read API keys"))
+    }
+
+    /// Record that a key was used
+    async fn touch(&self, id: &DbId, ip_address: Option<&str>) -> AuthResult<()> {
+        sqlx::query("UPDATE api_keys SET last_used_at = $1, last_used_ip = $2 WHERE id = $3")
+            .bind(chrono::Utc::now().timestamp())
+            .bind(ip_address)
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("update API key"))?;
+
+        Ok(())
+    }
+
+    /// Revoke an API key
+    async fn revoke(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("UPDATE api_keys SET is_revoked = 1 WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("revoke API key"))?;
+
+        Ok(())
+    }
+
+    /// Revoke all API keys for a user
+    async fn revoke_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let result = sqlx::query("UPDATE api_keys SET is_revoked = 1 WHERE user_id = $1 AND is_revoked = 0")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("revoke API keys"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete all API keys of a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let result = sqlx::query("DELETE FROM api_keys WHERE user_id = $1")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete API keys"))?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,202 @@
+//! SQL Audit Store Implementation
+
+use async_trait::async_trait;
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Row};
+
+use super::{enum_name, failed, get_enum, get_id, get_opt_id, Arg, Conditions};
+use crate::models::audit::{AuditEvent, AuditFilter, CreateAuditEvent};
+use crate::store::audit_store::AuditStore;
+use crate::utils::errors::AuthResult;
+use database::pagination::{Page, PageRequest};
+use database::utils::DbId;
+
+const COLUMNS: &str =
+    "id, action, outcome, actor_id, target_id, impersonator_id, org_id, ip_address, user_agent, reason, created_at";
+
+/// SQL implementation of AuditStore
+pub struct SqlAuditStore {
+    pool: AnyPool,
+}
+
+impl SqlAuditStore {
+    /// Create a new SqlAuditStore
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn conditions(filter: &AuditFilter) -> Conditions {
+    let mut conditions = Conditions::default();
+
+    if let Some(actor_id) = &filter.actor_id {
+        conditions.compare("actor_id", "=", Arg::Text(actor_id.to_string()));
+    }
+    if let Some(target_id) = &filter.target_id {
+        conditions.compare("target_id", "=", Arg::Text(target_id.to_string()));
+    }
+    if let Some(org_id) = &filter.org_id {
+        conditions.compare("org_id", "=", Arg::Text(org_id.to_string()));
+    }
+    if let Some(action) = &filter.action {
+        conditions.compare("action", "=", Arg::Text(enum_name(action)));
+    }
+    if let Some(outcome) = &filter.outcome {
+        conditions.compare("outcome", "=", Arg::Text(enum_name(outcome)));
+    }
+    if let Some(reason) = &filter.reason {
+        conditions.compare("reason", "=", Arg::Text(reason.clone()));
+    }
+    if let Some(from) = filter.from {
+        conditions.compare("created_at", ">=", Arg::Int(from));
+    }
+    if let Some(to) = filter.to {
+        conditions.compare("created_at", "<=", Arg::Int(to));
+    }
+
+    conditions
+}
+
+fn event_from_row(row: &AnyRow) -> Result<AuditEvent, sqlx::Error> {
+    Ok(AuditEvent {
+        id: get_id(row, "id")?,
+        action: get_enum(row, "action")?,
+        outcome: get_enum(row, "outcome")?,
+        actor_id: get_opt_id(row, "actor_id")?,
+        target_id: get_opt_id(row, "target_id")?,
+        impersonator_id: get_opt_id(row, "impersonator_id")?,
+        org_id: get_opt_id(row, "org_id")?,
+        ip_address: row.try_get("ip_address")?,
+        user_agent: row.try_get("user_agent")?,
+        reason: row.try_get("reason")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+#[async_trait]
+impl AuditStore for SqlAuditStore {
+    /// Append an event
+    async fn record(&self, input: CreateAuditEvent) -> AuthResult<AuditEvent> {
+        let event = AuditEvent {
+            id: DbId::new_uuid(),
+            action: input.action,
+            outcome: input.outcome,
+            actor_id: input.actor_id,
+            target_id: input.target_id,
+            impersonator_id: input.impersonator_id,
+            org_id: input.org_id,
+            ip_address: input.ip_address,
+            user_agent: input.user_agent,
+            reason: input.reason,
+            created_at: chrono::Utc::now().timestamp(),
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO audit_events ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+            COLUMNS
+        ))
+        .bind(event.id.to_string())
+        .bind(enum_name(&event.action))
+        .bind(enum_name(&event.outcome))
+        .bind(event.actor_id.as_ref().map(DbId::to_string))
+        .bind(event.target_id.as_ref().map(DbId::to_string))
+        .bind(event.impersonator_id.as_ref().map(DbId::to_string))
+        .bind(event.org_id.as_ref().map(DbId::to_string))
+        .bind(&event.ip_address)
+        .bind(&event.user_agent)
+        .bind(&event.reason)
+        .bind(event.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("record audit event"))?;
+
+        Ok(event)
+    }
+
+    /// List events matching a filter, newest first
+    async fn list(&self, filter: &AuditFilter, page: u32, limit: u32) -> AuthResult<Vec<AuditEvent>> {
+        let conditions = conditions(filter);
+        let sql = format!(
+            "SELECT {} FROM audit_events{} ORDER BY created_at DESC, id LIMIT {} OFFSET ${}",
+            COLUMNS,
+            conditions.sql(),
+            conditions.next(),
+            conditions.args.len() + 2
+        );
+
+        let rows = conditions
+            .bind(sqlx::query(&sql))
+            .bind(i64::from(limit))
+            .bind(i64::from(page) * i64::from(limit))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(failed("list audit events"))?;
+
+        rows.iter().map(event_from_row).collect::<Result<_, _>>().map_err(failed("read audit events"))
+    }
+
+    /// List events after a cursor, newest first
+    async fn list_after(&self, filter: &AuditFilter, page: &PageRequest) -> AuthResult<Page<AuditEvent>> {
+        let mut conditions = conditions(filter);
+        if let Some(after) = &page.after {
+            conditions.after(after, "<");
+        }
+        let sql = format!(
+            "SELECT {} FROM audit_events{} ORDER BY created_at DESC, id DESC LIMIT {}",
+            COLUMNS,
+            conditions.sql(),
+            conditions.next()
+        );
+
+        let rows = conditions
+            .bind(sqlx::query(&sql))
+            .bind(page.fetch_limit())
+            .fetch_all(&self.pool)
+            .await
+            .map_err(failed("list audit events"))?;
+
+        let events = rows.iter().map(event_from_row).collect::<Result<_, _>>().map_err(failed("read audit events"))?;
+        Ok(Page::from_rows(events, page, AuditEvent::cursor))
+    }
+
+    /// Count events matching a filter
+    async fn count(&self, filter: &AuditFilter) -> AuthResult<u64> {
+        let conditions = conditions(filter);
+        let sql = format!("SELECT COUNT(*) AS count FROM audit_events{}", conditions.sql());
+
+        let row = conditions
+            .bind(sqlx::query(&sql))
+            .fetch_one(&self.pool)
+            .await
+            .map_err(failed("count audit events"))?;
+
+        let count: i64 = row.try_get("count").map_err(failed("count audit events"))?;
+        Ok(count as u64)
+    }
+
+    /// Delete events created before a timestamp
+    async fn delete_older_than(&self, before: i64) -> AuthResult<u64> {
+        let result = sqlx::query("DELETE FROM audit_events WHERE created_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .map_err(failed("purge audit events"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Clear the IP address and user agent of a user's events
+    async fn anonymize_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let result = sqlx::query(
+            "UPDATE audit_events SET ip_address = NULL, user_agent = NULL \
+             WHERE (actor_id = $1 OR target_id = $1) \
+             AND (ip_address IS NOT NULL OR user_agent IS NOT NULL)",
+        )
+        .bind(user_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(failed("anonymize audit events"))?;
+
+        Ok(result.rows_affected())
+    }
+}
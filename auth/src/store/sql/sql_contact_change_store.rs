@@ -0,0 +1,188 @@
+//! SQL Contact Change Store Implementation
+
+use async_trait::async_trait;
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Row};
+
+use super::{enum_name, failed, get_enum, get_id};
+use crate::models::contact_change::{ContactChangeModel, CreateContactChange};
+use crate::models::verification::VerificationMedium;
+use crate::store::contact_change_store::ContactChangeStore;
+use crate::utils::errors::AuthResult;
+use database::utils::DbId;
+
+const COLUMNS: &str = "id, user_id, medium, old_value, new_value, code_hash, revert_token_hash, \
+    attempts, created_at, expires_at, revert_expires_at, confirmed_at, reverted_at";
+
+/// SQL implementation of ContactChangeStore
+pub struct SqlContactChangeStore {
+    pool: AnyPool,
+}
+
+impl SqlContactChangeStore {
+    /// Create a new SqlContactChangeStore
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+
+    /// Run an update on a single request
+    async fn update(&self, sql: &str, id: &DbId, action: &'static str) -> AuthResult<()> {
+        sqlx::query(sql)
+            .bind(chrono::Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed(action))?;
+
+        Ok(())
+    }
+}
+
+fn change_from_row(row: &AnyRow) -> Result<ContactChangeModel, sqlx::Error> {
+    Ok(ContactChangeModel {
+        id: get_id(row, "id")?,
+        user_id: get_id(row, "user_id")?,
+        medium: get_enum(row, "medium")?,
+        old_value: row.try_get("old_value")?,
+        new_value: row.try_get("new_value")?,
+        code_hash: row.try_get("code_hash")?,
+        revert_token_hash: row.try_get("revert_token_hash")?,
+        attempts: row.try_get::<i64, _>("attempts")? as i32,
+        created_at: row.try_get("created_at")?,
+        expires_at: row.try_get("expires_at")?,
+        revert_expires_at: row.try_get("revert_expires_at")?,
+        confirmed_at: row.try_get("confirmed_at")?,
+        reverted_at: row.try_get("reverted_at")?,
+    })
+}
+
+#[async_trait]
+impl ContactChangeStore for SqlContactChangeStore {
+    /// Create a new contact change request
+    async fn create(&self, input: CreateContactChange) -> AuthResult<ContactChangeModel> {
+        let now = chrono::Utc::now().timestamp();
+
+        let change = ContactChangeModel {
+            id: DbId::new_uuid(),
+            user_id: input.user_id,
+            medium: input.medium,
+            old_value: input.old_value,
+            new_value: input.new_value,
+            code_hash: input.code_hash,
+            revert_token_hash: input.revert_token_hash,
+            attempts: 0,
+            created_at: now,
+            expires_at: now + input.expires_in,
+            revert_expires_at: now + input.revert_expires_in,
+            confirmed_at: None,
+            reverted_at: None,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO contact_changes ({}) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
+            COLUMNS
+        ))
+        .bind(change.id.to_string())
+        .bind(change.user_id.to_string())
+        .bind(enum_name(&change.medium))
+        .bind(&change.old_value)
+        .bind(&change.new_value)
+        .bind(&change.code_hash)
+        .bind(&change.revert_token_hash)
+        .bind(i64::from(change.attempts))
+        .bind(change.created_at)
+        .bind(change.expires_at)
+        .bind(change.revert_expires_at)
+        .bind(change.confirmed_at)
+        .bind(change.reverted_at)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("create contact change"))?;
+
+        Ok(change)
+    }
+
+    /// Find the pending request of a user for a medium
+    async fn find_pending(
+        &self,
+        user_id: &DbId,
+        medium: VerificationMedium,
+    ) -> AuthResult<Option<ContactChangeModel>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM contact_changes WHERE user_id = $1 AND medium = $2 AND expires_at > $3 \
+             AND confirmed_at IS NULL AND reverted_at IS NULL ORDER BY created_at DESC",
+            COLUMNS
+        ))
+        .bind(user_id.to_string())
+        .bind(enum_name(&medium))
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(failed("find contact change"))?;
+
+        row.as_ref().map(change_from_row).transpose().map_err(failed("read contact change"))
+    }
+
+    /// Find request by revert token hash
+    async fn find_by_revert_token(&self, token_hash: &str) -> AuthResult<Option<ContactChangeModel>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM contact_changes WHERE revert_token_hash = $1",
+            COLUMNS
+        ))
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(failed("find contact change"))?;
+
+        row.as_ref().map(change_from_row).transpose().map_err(failed("read contact change"))
+    }
+
+    /// Increment failed confirmation attempts
+    async fn increment_attempts(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("UPDATE contact_changes SET attempts = attempts + 1 WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("increment attempts"))?;
+
+        Ok(())
+    }
+
+    /// Mark request as confirmed
+    async fn mark_confirmed(&self, id: &DbId) -> AuthResult<()> {
+        self.update("UPDATE contact_changes SET confirmed_at = $1 WHERE id = $2", id, "confirm contact change")
+            .await
+    }
+
+    /// Mark request as reverted
+    async fn mark_reverted(&self, id: &DbId) -> AuthResult<()> {
+        self.update("UPDATE contact_changes SET reverted_at = $1 WHERE id = $2", id, "revert contact change")
+            .await
+    }
+
+    /// Cancel pending requests of a user for a medium
+    async fn cancel_pending(&self, user_id: &DbId, medium: VerificationMedium) -> AuthResult<u64> {
+        let result = sqlx::query(
+            "DELETE FROM contact_changes WHERE user_id = $1 AND medium = $2 AND confirmed_at IS NULL",
+        )
+        .bind(user_id.to_string())
+        .bind(enum_name(&medium))
+        .execute(&self.pool)
+        .await
+        .map_err(failed("cancel contact changes"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete all requests of a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let result = sqlx::query("DELETE FROM contact_changes WHERE user_id = $1")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete contact changes"))?;
+
+        Ok(result.rows_affected())
+    }
+}
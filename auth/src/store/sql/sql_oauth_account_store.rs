@@ -0,0 +1,196 @@
+//! SQL OAuth Account Store Implementation
+
+use async_trait::async_trait;
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Row};
+
+use super::{enum_name, failed, get_enum, get_id};
+use crate::models::oauth::{CreateOAuthAccount, OAuthAccount, OAuthProvider};
+use crate::store::oauth_account_store::OAuthAccountStore;
+use crate::utils::errors::AuthError;
+use database::utils::DbId;
+
+const COLUMNS: &str = "id, user_id, provider, provider_user_id, access_token, refresh_token, \
+    expires_at, scope, created_at, updated_at";
+
+/// SQL implementation of OAuthAccountStore
+pub struct SqlOAuthAccountStore {
+    pool: AnyPool,
+}
+
+impl SqlOAuthAccountStore {
+    /// Create a new SqlOAuthAccountStore
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn account_from_row(row: &AnyRow) -> Result<OAuthAccount, sqlx::Error> {
+    Ok(OAuthAccount {
+        id: get_id(row, "id")?,
+        user_id: get_id(row, "user_id")?,
+        provider: get_enum(row, "provider")?,
+        provider_user_id: row.try_get("provider_user_id")?,
+        access_token: row.try_get("access_token")?,
+        refresh_token: row.try_get("refresh_token")?,
+        expires_at: row.try_get("expires_at")?,
+        scope: row.try_get("scope")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+#[async_trait]
+impl OAuthAccountStore for SqlOAuthAccountStore {
+    async fn create(&self, account: CreateOAuthAccount) -> Result<OAuthAccount, AuthError> {
+        let now = chrono::Utc::now().timestamp();
+
+        let oauth_account = OAuthAccount {
+            id: DbId::new_uuid(),
+            user_id: account.user_id,
+            provider: account.provider,
+            provider_user_id: account.provider_user_id,
+            access_token: account.access_token,
+            refresh_token: account.refresh_token,
+            expires_at: account.expires_in.map(|expires_in| now + expires_in),
+            scope: account.scope,
+            created_at: now,
+            updated_at: None,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO oauth_accounts ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            COLUMNS
+        ))
+        .bind(oauth_account.id.to_string())
+        .bind(oauth_account.user_id.to_string())
+        .bind(enum_name(&oauth_account.provider))
+        .bind(&oauth_account.provider_user_id)
+        .bind(&oauth_account.access_token)
+        .bind(&oauth_account.refresh_token)
+        .bind(oauth_account.expires_at)
+        .bind(&oauth_account.scope)
+        .bind(oauth_account.created_at)
+        .bind(oauth_account.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("create OAuth account"))?;
+
+        Ok(oauth_account)
+    }
+
+    async fn find_by_id(&self, id: &DbId) -> Result<Option<OAuthAccount>, AuthError> {
+        let row = sqlx::query(&format!("SELECT {} FROM oauth_accounts WHERE id = $1", COLUMNS))
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(failed("find OAuth account"))?;
+
+        row.as_ref().map(account_from_row).transpose().map_err(failed("read OAuth account"))
+    }
+
+    async fn find_by_user_and_provider(
+        &self,
+        user_id: &DbId,
+        provider: &OAuthProvider,
+    ) -> Result<Option<OAuthAccount>, AuthError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM oauth_accounts WHERE user_id = $1 AND provider = $2",
+            COLUMNS
+        ))
+        .bind(user_id.to_string())
+        .bind(enum_name(provider))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(failed("find OAuth account"))?;
+
+        row.as_ref().map(account_from_row).transpose().map_err(failed("read OAuth account"))
+    }
+
+    async fn find_by_provider_user_id(
+        &self,
+        provider: &OAuthProvider,
+        provider_user_id: &str,
+    ) -> Result<Option<OAuthAccount>, AuthError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM oauth_accounts WHERE provider = $1 AND provider_user_id = $2",
+            COLUMNS
+        ))
+        .bind(enum_name(provider))
+        .bind(provider_user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(failed("find OAuth account"))?;
+
+        row.as_ref().map(account_from_row).transpose().map_err(failed("read OAuth account"))
+    }
+
+    async fn list_by_user(&self, user_id: &DbId) -> Result<Vec<OAuthAccount>, AuthError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM oauth_accounts WHERE user_id = $1 ORDER BY created_at",
+            COLUMNS
+        ))
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(failed("list OAuth accounts"))?;
+
+        rows.iter().map(account_from_row).collect::<Result<_, _>>().map_err(failed("read OAuth accounts"))
+    }
+
+    async fn update(&self, id: &DbId, account: &OAuthAccount) -> Result<OAuthAccount, AuthError> {
+        let mut updated_account = account.clone();
+        updated_account.updated_at = Some(chrono::Utc::now().timestamp());
+
+        sqlx::query(
+            "UPDATE oauth_accounts SET access_token = $1, refresh_token = $2, expires_at = $3, \
+             scope = $4, updated_at = $5 WHERE id = $6",
+        )
+        .bind(&updated_account.access_token)
+        .bind(&updated_account.refresh_token)
+        .bind(updated_account.expires_at)
+        .bind(&updated_account.scope)
+        .bind(updated_account.updated_at)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(failed("update OAuth account"))?;
+
+        Ok(updated_account)
+    }
+
+    async fn delete(&self, id: &DbId) -> Result<(), AuthError> {
+        sqlx::query("DELETE FROM oauth_accounts WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete OAuth account"))?;
+
+        Ok(())
+    }
+
+    async fn delete_by_user(&self, user_id: &DbId) -> Result<(), AuthError> {
+        sqlx::query("DELETE FROM oauth_accounts WHERE user_id = $1")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete OAuth accounts"))?;
+
+        Ok(())
+    }
+
+    async fn delete_by_user_and_provider(
+        &self,
+        user_id: &DbId,
+        provider: &OAuthProvider,
+    ) -> Result<(), AuthError> {
+        sqlx::query("DELETE FROM oauth_accounts WHERE user_id = $1 AND provider = $2")
+            .bind(user_id.to_string())
+            .bind(enum_name(provider))
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete OAuth account"))?;
+
+        Ok(())
+    }
+}
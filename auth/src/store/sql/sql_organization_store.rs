@@ -0,0 +1,400 @@
+//! SQL Organization Store Implementation
+
+use async_trait::async_trait;
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Row};
+
+use super::{enum_name, failed, get_enum, get_id, placeholders};
+use crate::models::organization::{
+    CreateMembership, CreateOrgInvitation, CreateOrganization, Membership, OrgInvitation,
+    OrgRole, Organization,
+};
+use crate::store::organization_store::OrganizationStore;
+use crate::utils::errors::{AuthError, AuthResult};
+use database::utils::DbId;
+
+const ORG_COLUMNS: &str = "id, name, slug, owner_id, created_at, updated_at";
+
+const MEMBERSHIP_COLUMNS: &str = "id, org_id, user_id, role, created_at, updated_at";
+
+const INVITATION_COLUMNS: &str =
+    "id, org_id, email, role, token_hash, invited_by, expires_at, accepted_at, created_at";
+
+/// SQL implementation of OrganizationStore
+pub struct SqlOrganizationStore {
+    pool: AnyPool,
+}
+
+impl SqlOrganizationStore {
+    /// Create a new SqlOrganizationStore
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+
+    async fn find_org(&self, column: &str, value: String) -> AuthResult<Option<Organization>> {
+        let row = sqlx::query(&format!("SELECT {} FROM organizations WHERE {} = $1", ORG_COLUMNS, column))
+            .bind(value)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(failed("find organization"))?;
+
+        row.as_ref().map(org_from_row).transpose().map_err(failed("read organization"))
+    }
+
+    async fn list_memberships(&self, column: &str, value: String) -> AuthResult<Vec<Membership>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM org_memberships WHERE {} = $1 ORDER BY created_at",
+            MEMBERSHIP_COLUMNS, column
+        ))
+        .bind(value)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(failed("list memberships"))?;
+
+        rows.iter().map(membership_from_row).collect::<Result<_, _>>().map_err(failed("read memberships"))
+    }
+
+    async fn find_invitation_by(&self, column: &str, value: String) -> AuthResult<Option<OrgInvitation>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM org_invitations WHERE {} = $1",
+            INVITATION_COLUMNS, column
+        ))
+        .bind(value)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(failed("find invitation"))?;
+
+        row.as_ref().map(invitation_from_row).transpose().map_err(failed("read invitation"))
+    }
+}
+
+fn org_from_row(row: &AnyRow) -> Result<Organization, sqlx::Error> {
+    Ok(Organization {
+        id: get_id(row, "id")?,
+        name: row.try_get("name")?,
+        slug: row.try_get("slug")?,
+        owner_id: get_id(row, "owner_id")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+fn membership_from_row(row: &AnyRow) -> Result<Membership, sqlx::Error> {
+    Ok(Membership {
+        id: get_id(row, "id")?,
+        org_id: get_id(row, "org_id")?,
+        user_id: get_id(row, "user_id")?,
+        role: get_enum(row, "role")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+fn invitation_from_row(row: &AnyRow) -> Result<OrgInvitation, sqlx::Error> {
+    Ok(OrgInvitation {
+        id: get_id(row, "id")?,
+        org_id: get_id(row, "org_id")?,
+        email: row.try_get("email")?,
+        role: get_enum(row, "role")?,
+        token_hash: row.try_get("token_hash")?,
+        invited_by: get_id(row, "invited_by")?,
+        expires_at: row.try_get("expires_at")?,
+        accepted_at: row.try_get("accepted_at")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+#[async_trait]
+impl OrganizationStore for SqlOrganizationStore {
+    /// Create a new organization
+    async fn create(&self, input: CreateOrganization) -> AuthResult<Organization> {
+        let org = Organization {
+            id: DbId::new_uuid(),
+            name: input.name,
+            slug: input.slug,
+            owner_id: input.owner_id,
+            created_at: chrono::Utc::now().timestamp(),
+            updated_at: None,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO organizations ({}) VALUES ($1, $2, $3, $4, $5, $6)",
+            ORG_COLUMNS
+        ))
+        .bind(org.id.to_string())
+        .bind(&org.name)
+        .bind(&org.slug)
+        .bind(org.owner_id.to_string())
+        .bind(org.created_at)
+        .bind(org.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("create organization"))?;
+
+        Ok(org)
+    }
+
+    /// Find organization by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<Organization>> {
+        self.find_org("id", id.to_string()).await
+    }
+
+    /// Find organization by slug
+    async fn find_by_slug(&self, slug: &str) -> AuthResult<Option<Organization>> {
+        self.find_org("slug", slug.to_string()).await
+    }
+
+    /// Find organizations by IDs
+    async fn find_by_ids(&self, ids: &[DbId]) -> AuthResult<Vec<Organization>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT {} FROM organizations WHERE id IN ({}) ORDER BY created_at",
+            ORG_COLUMNS,
+            placeholders(1, ids.len())
+        );
+        let mut query = sqlx::query(&sql);
+        for id in ids {
+            query = query.bind(id.to_string());
+        }
+
+        let rows = query.fetch_all(&self.pool).await.map_err(failed("list organizations"))?;
+
+        rows.iter().map(org_from_row).collect::<Result<_, _>>().map_err(failed("read organizations"))
+    }
+
+    /// Delete an organization with its memberships and invitations
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        let mut tx = self.pool.begin().await.map_err(failed("delete organization"))?;
+
+        for statement in [
+            "DELETE FROM org_memberships WHERE org_id = $1",
+            "DELETE FROM org_invitations WHERE org_id = $1",
+            "DELETE FROM organizations WHERE id = $1",
+        ] {
+            sqlx::query(statement)
+                .bind(id.to_string())
+                .execute(&mut *tx)
+                .await
+                .map_err(failed("delete organization"))?;
+        }
+
+        tx.commit().await.map_err(failed("delete organization"))
+    }
+
+    /// Add a member
+    async fn add_member(&self, input: CreateMembership) -> AuthResult<Membership> {
+        let membership = Membership {
+            id: DbId::new_uuid(),
+            org_id: input.org_id,
+            user_id: input.user_id,
+            role: input.role,
+            created_at: chrono::Utc::now().timestamp(),
+            updated_at: None,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO org_memberships ({}) VALUES ($1, $2, $3, $4, $5, $6)",
+            MEMBERSHIP_COLUMNS
+        ))
+        .bind(membership.id.to_string())
+        .bind(membership.org_id.to_string())
+        .bind(membership.user_id.to_string())
+        .bind(enum_name(&membership.role))
+        .bind(membership.created_at)
+        .bind(membership.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("add member"))?;
+
+        Ok(membership)
+    }
+
+    /// Find a user's membership in an organization
+    async fn find_membership(&self, org_id: &DbId, user_id: &DbId) -> AuthResult<Option<Membership>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM org_memberships WHERE org_id = $1 AND user_id = $2",
+            MEMBERSHIP_COLUMNS
+        ))
+        .bind(org_id.to_string())
+        .bind(user_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(failed("find membership"))?;
+
+        row.as_ref().map(membership_from_row).transpose().map_err(failed("read membership"))
+    }
+
+    /// List members of an organization
+    async fn list_members(&self, org_id: &DbId) -> AuthResult<Vec<Membership>> {
+        self.list_memberships("org_id", org_id.to_string()).await
+    }
+
+    /// List memberships of a user
+    async fn list_user_memberships(&self, user_id: &DbId) -> AuthResult<Vec<Membership>> {
+        self.list_memberships("user_id", user_id.to_string()).await
+    }
+
+    /// Change a member's role
+    async fn update_member_role(&self, org_id: &DbId, user_id: &DbId, role: OrgRole) -> AuthResult<()> {
+        let result = sqlx::query(
+            "UPDATE org_memberships SET role = $1, updated_at = $2 WHERE org_id = $3 AND user_id = $4",
+        )
+        .bind(enum_name(&role))
+        .bind(chrono::Utc::now().timestamp())
+        .bind(org_id.to_string())
+        .bind(user_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(failed("update member"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthError::not_found("Member not found"));
+        }
+
+        Ok(())
+    }
+
+    /// Remove a member, unless it is the last owner
+    async fn remove_member(&self, org_id: &DbId, user_id: &DbId) -> AuthResult<()> {
+        // One statement, so a concurrent removal can't take the other owner away
+        let result = sqlx::query(
+            "DELETE FROM org_memberships WHERE org_id = $1 AND user_id = $2 \
+             AND (role <> $3 OR EXISTS (SELECT 1 FROM org_memberships other \
+             WHERE other.org_id = $1 AND other.user_id <> $2 AND other.role = $3))",
+        )
+        .bind(org_id.to_string())
+        .bind(user_id.to_string())
+        .bind(enum_name(&OrgRole::Owner))
+        .execute(&self.pool)
+        .await
+        .map_err(failed("remove member"))?;
+
+        if result.rows_affected() == 0 && self.find_membership(org_id, user_id).await?.is_some() {
+            return Err(AuthError::last_owner());
+        }
+
+        Ok(())
+    }
+
+    /// Create an invitation
+    async fn create_invitation(&self, input: CreateOrgInvitation) -> AuthResult<OrgInvitation> {
+        let now = chrono::Utc::now().timestamp();
+
+        let invitation = OrgInvitation {
+            id: DbId::new_uuid(),
+            org_id: input.org_id,
+            email: input.email,
+            role: input.role,
+            token_hash: input.token_hash,
+            invited_by: input.invited_by,
+            expires_at: now + input.expires_in,
+            accepted_at: None,
+            created_at: now,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO org_invitations ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            INVITATION_COLUMNS
+        ))
+        .bind(invitation.id.to_string())
+        .bind(invitation.org_id.to_string())
+        .bind(&invitation.email)
+        .bind(enum_name(&invitation.role))
+        .bind(&invitation.token_hash)
+        .bind(invitation.invited_by.to_string())
+        .bind(invitation.expires_at)
+        .bind(invitation.accepted_at)
+        .bind(invitation.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("create invitation"))?;
+
+        Ok(invitation)
+    }
+
+    /// Find invitation by ID
+    async fn find_invitation(&self, id: &DbId) -> AuthResult<Option<OrgInvitation>> {
+        self.find_invitation_by("id", id.to_string()).await
+    }
+
+    /// Find invitation by token hash
+    async fn find_invitation_by_token(&self, token_hash: &str) -> AuthResult<Option<OrgInvitation>> {
+        self.find_invitation_by("token_hash", token_hash.to_string()).await
+    }
+
+    /// List invitations of an organization
+    async fn list_invitations(&self, org_id: &DbId) -> AuthResult<Vec<OrgInvitation>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM org_invitations WHERE org_id = $1 ORDER BY created_at",
+            INVITATION_COLUMNS
+        ))
+        .bind(org_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(failed("list invitations"))?;
+
+        rows.iter().map(invitation_from_row).collect::<Result<_, _>>().map_err(failed("read invitations"))
+    }
+
+    /// Mark invitation as accepted
+    async fn accept_invitation(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("UPDATE org_invitations SET accepted_at = $1 WHERE id = $2")
+            .bind(chrono::Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("accept invitation"))?;
+
+        Ok(())
+    }
+
+    /// Delete an invitation
+    async fn delete_invitation(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("DELETE FROM org_invitations WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete invitation"))?;
+
+        Ok(())
+    }
+
+    /// Delete all invitations sent to an email address
+    async fn delete_invitations_for_email(&self, email: &str) -> AuthResult<u64> {
+        let result = sqlx::query("DELETE FROM org_invitations WHERE email = $1")
+            .bind(email)
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete invitations"))?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::store::sql::test_pool;
+
+    #[actix_web::test]
+    async fn test_last_owner_cannot_be_removed() {
+        let store = SqlOrganizationStore::new(test_pool().await);
+        let org_id = DbId::new_uuid();
+        let (owner_id, other_id) = (DbId::new_uuid(), DbId::new_uuid());
+        let member = |user_id: &DbId, role| CreateMembership { org_id: org_id.clone(), user_id: user_id.clone(), role };
+
+        store.add_member(member(&owner_id, OrgRole::Owner)).await.unwrap();
+        store.add_member(member(&other_id, OrgRole::Member)).await.unwrap();
+        assert!(store.remove_member(&org_id, &owner_id).await.is_err());
+
+        // Removing someone who isn't a member is still a no-op
+        store.remove_member(&org_id, &DbId::new_uuid()).await.unwrap();
+
+        store.update_member_role(&org_id, &other_id, OrgRole::Owner).await.unwrap();
+        store.remove_member(&org_id, &owner_id).await.unwrap();
+        assert_eq!(store.list_members(&org_id).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,230 @@
+//! SQL Passkey Store Implementation
+
+use async_trait::async_trait;
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Row};
+
+use super::{enum_name, failed, get_enum, get_id, get_json, get_opt_id, to_json};
+use crate::models::passkey::{
+    CreatePasskeyCredential, CreateWebAuthnChallenge, PasskeyCredential, WebAuthnChallenge,
+};
+use crate::store::passkey_store::PasskeyStore;
+use crate::utils::errors::AuthResult;
+use database::utils::DbId;
+
+const CREDENTIAL_COLUMNS: &str = "id, user_id, credential_id, public_key, sign_count, aaguid, \
+    name, transports, created_at, last_used_at";
+
+const CHALLENGE_COLUMNS: &str = "id, user_id, challenge, ceremony, expires_at, created_at";
+
+/// SQL implementation of PasskeyStore
+pub struct SqlPasskeyStore {
+    pool: AnyPool,
+}
+
+impl SqlPasskeyStore {
+    /// Create a new SqlPasskeyStore
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+
+    async fn find_credential(&self, column: &str, value: String) -> AuthResult<Option<PasskeyCredential>> {
+        let row = sqlx::query(&format!("SELECT {} FROM passkeys WHERE {} = $1", CREDENTIAL_COLUMNS, column))
+            .bind(value)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(failed("find passkey"))?;
+
+        row.as_ref().map(credential_from_row).transpose().map_err(failed("read passkey"))
+    }
+}
+
+fn credential_from_row(row: &AnyRow) -> Result<PasskeyCredential, sqlx::Error> {
+    Ok(PasskeyCredential {
+        id: get_id(row, "id")?,
+        user_id: get_id(row, "user_id")?,
+        credential_id: row.try_get("credential_id")?,
+        public_key: row.try_get("public_key")?,
+        sign_count: row.try_get::<i64, _>("sign_count")? as u32,
+        aaguid: row.try_get("aaguid")?,
+        name: row.try_get("name")?,
+        transports: get_json(row, "transports")?,
+        created_at: row.try_get("created_at")?,
+        last_used_at: row.try_get("last_used_at")?,
+    })
+}
+
+fn challenge_from_row(row: &AnyRow) -> Result<WebAuthnChallenge, sqlx::Error> {
+    Ok(WebAuthnChallenge {
+        id: get_id(row, "id")?,
+        user_id: get_opt_id(row, "user_id")?,
+        challenge: row.try_get("challenge")?,
+        ceremony: get_enum(row, "ceremony")?,
+        expires_at: row.try_get("expires_at")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+#[async_trait]
+impl PasskeyStore for SqlPasskeyStore {
+    /// Register a new passkey credential
+    async fn create(&self, input: CreatePasskeyCredential) -> AuthResult<PasskeyCredential> {
+        let credential = PasskeyCredential {
+            id: DbId::new_uuid(),
+            user_id: input.user_id,
+            credential_id: input.credential_id,
+            public_key: input.public_key,
+            sign_count: input.sign_count,
+            aaguid: input.aaguid,
+            name: input.name,
+            transports: input.transports,
+            created_at: chrono::Utc::now().timestamp(),
+            last_used_at: None,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO passkeys ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            CREDENTIAL_COLUMNS
+        ))
+        .bind(credential.id.to_string())
+        .bind(credential.user_id.to_string())
+        .bind(&credential.credential_id)
+        .bind(&credential.public_key)
+        .bind(i64::from(credential.sign_count))
+        .bind(&credential.aaguid)
+        .bind(&credential.name)
+        .bind(to_json(&credential.transports))
+        .bind(credential.created_at)
+        .bind(credential.last_used_at)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("create passkey"))?;
+
+        Ok(credential)
+    }
+
+    /// Find passkey by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<PasskeyCredential>> {
+        self.find_credential("id", id.to_string()).await
+    }
+
+    /// Find passkey by authenticator credential ID
+    async fn find_by_credential_id(&self, credential_id: &str) -> AuthResult<Option<PasskeyCredential>> {
+        self.find_credential("credential_id", credential_id.to_string()).await
+    }
+
+    /// List all passkeys for a user
+    async fn list_by_user(&self, user_id: &DbId) -> AuthResult<Vec<PasskeyCredential>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM passkeys WHERE user_id = $1 ORDER BY created_at",
+            CREDENTIAL_COLUMNS
+        ))
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(failed("list passkeys"))?;
+
+        rows.iter().map(credential_from_row).collect::<Result<_, _>>().map_err(failed("read passkeys"))
+    }
+
+    /// Store the new signature counter after a successful assertion
+    async fn update_sign_count(&self, id: &DbId, sign_count: u32) -> AuthResult<()> {
+        sqlx::query("UPDATE passkeys SET sign_count = $1, last_used_at = $2 WHERE id = $3")
+            .bind(i64::from(sign_count))
+            .bind(chrono::Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("update passkey counter"))?;
+
+        Ok(())
+    }
+
+    /// Delete a passkey
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("DELETE FROM passkeys WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete passkey"))?;
+
+        Ok(())
+    }
+
+    /// Delete all passkeys for a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let result = sqlx::query("DELETE FROM passkeys WHERE user_id = $1")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete passkeys"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Create a ceremony challenge
+    async fn create_challenge(&self, input: CreateWebAuthnChallenge) -> AuthResult<WebAuthnChallenge> {
+        let now = chrono::Utc::now().timestamp();
+
+        let challenge = WebAuthnChallenge {
+            id: DbId::new_uuid(),
+            user_id: input.user_id,
+            challenge: input.challenge,
+            ceremony: input.ceremony,
+            expires_at: now + input.expires_in,
+            created_at: now,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO webauthn_challenges ({}) VALUES ($1, $2, $3, $4, $5, $6)",
+            CHALLENGE_COLUMNS
+        ))
+        .bind(challenge.id.to_string())
+        .bind(challenge.user_id.as_ref().map(DbId::to_string))
+        .bind(&challenge.challenge)
+        .bind(enum_name(&challenge.ceremony))
+        .bind(challenge.expires_at)
+        .bind(challenge.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("create challenge"))?;
+
+        Ok(challenge)
+    }
+
+    /// Find challenge by its base64url value
+    async fn find_challenge(&self, challenge: &str) -> AuthResult<Option<WebAuthnChallenge>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM webauthn_challenges WHERE challenge = $1",
+            CHALLENGE_COLUMNS
+        ))
+        .bind(challenge)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(failed("find challenge"))?;
+
+        row.as_ref().map(challenge_from_row).transpose().map_err(failed("read challenge"))
+    }
+
+    /// Delete a challenge
+    async fn delete_challenge(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("DELETE FROM webauthn_challenges WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete challenge"))?;
+
+        Ok(())
+    }
+
+    /// Cleanup expired challenges
+    async fn cleanup_expired_challenges(&self) -> AuthResult<u64> {
+        let result = sqlx::query("DELETE FROM webauthn_challenges WHERE expires_at < $1")
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("cleanup challenges"))?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,153 @@
+//! SQL Password Reset Store Implementation
+
+use async_trait::async_trait;
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Row};
+
+use super::{failed, get_id};
+use crate::models::reset_password::{CreatePasswordResetToken, PasswordResetTokenModel};
+use crate::store::password_reset_store::PasswordResetStore;
+use crate::utils::errors::AuthResult;
+use database::utils::DbId;
+
+const COLUMNS: &str = "id, user_id, token_hash, created_at, expires_at, used_at";
+
+/// SQL implementation of PasswordResetStore
+pub struct SqlPasswordResetStore {
+    pool: AnyPool,
+}
+
+impl SqlPasswordResetStore {
+    /// Create a new SqlPasswordResetStore
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+
+    async fn find_one(&self, column: &str, value: String) -> AuthResult<Option<PasswordResetTokenModel>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM password_reset_tokens WHERE {} = $1",
+            COLUMNS, column
+        ))
+        .bind(value)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(failed("find reset token"))?;
+
+        row.as_ref().map(token_from_row).transpose().map_err(failed("read reset token"))
+    }
+}
+
+fn token_from_row(row: &AnyRow) -> Result<PasswordResetTokenModel, sqlx::Error> {
+    Ok(PasswordResetTokenModel {
+        id: get_id(row, "id")?,
+        user_id: get_id(row, "user_id")?,
+        token_hash: row.try_get("token_hash")?,
+        created_at: row.try_get("created_at")?,
+        expires_at: row.try_get("expires_at")?,
+        used_at: row.try_get("used_at")?,
+    })
+}
+
+#[async_trait]
+impl PasswordResetStore for SqlPasswordResetStore {
+    /// Create a new password reset token
+    async fn create(&self, input: CreatePasswordResetToken) -> AuthResult<PasswordResetTokenModel> {
+        let now = chrono::Utc::now().timestamp();
+
+        let token = PasswordResetTokenModel {
+            id: DbId::new_uuid(),
+            user_id: input.user_id,
+            token_hash: input.token_hash,
+            created_at: now,
+            expires_at: now + input.expires_in,
+            used_at: None,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO password_reset_tokens ({}) VALUES ($1, $2, $3, $4, $5, $6)",
+            COLUMNS
+        ))
+        .bind(token.id.to_string())
+        .bind(token.user_id.to_string())
+        .bind(&token.token_hash)
+        .bind(token.created_at)
+        .bind(token.expires_at)
+        .bind(token.used_at)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("create reset token"))?;
+
+        Ok(token)
+    }
+
+    /// Find password reset token by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<PasswordResetTokenModel>> {
+        self.find_one("id", id.to_string()).await
+    }
+
+    /// Find valid password reset token by user_id
+    async fn find_valid_token(&self, user_id: &DbId) -> AuthResult<Option<PasswordResetTokenModel>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM password_reset_tokens WHERE user_id = $1 AND expires_at > $2 \
+             AND used_at IS NULL ORDER BY created_at DESC",
+            COLUMNS
+        ))
+        .bind(user_id.to_string())
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(failed("find valid reset token"))?;
+
+        row.as_ref().map(token_from_row).transpose().map_err(failed("read reset token"))
+    }
+
+    /// Find token by hash
+    async fn find_by_hash(&self, token_hash: &str) -> AuthResult<Option<PasswordResetTokenModel>> {
+        self.find_one("token_hash", token_hash.to_string()).await
+    }
+
+    /// Mark token as used
+    async fn mark_used(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("UPDATE password_reset_tokens SET used_at = $1 WHERE id = $2")
+            .bind(chrono::Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("mark token as used"))?;
+
+        Ok(())
+    }
+
+    /// Delete/expire a token
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("DELETE FROM password_reset_tokens WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete reset token"))?;
+
+        Ok(())
+    }
+
+    /// Delete all tokens for a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let result = sqlx::query("DELETE FROM password_reset_tokens WHERE user_id = $1")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete reset tokens"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Cleanup expired tokens
+    async fn cleanup_expired(&self) -> AuthResult<u64> {
+        let result = sqlx::query("DELETE FROM password_reset_tokens WHERE expires_at < $1")
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("cleanup expired tokens"))?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,376 @@
+//! SQL Session Store Implementation
+
+use async_trait::async_trait;
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Row};
+
+use super::{failed, flag, get_flag, get_id, get_opt_id};
+use crate::models::session::{
+    CreateRefreshToken, CreateSession, RefreshTokenModel, SessionModel, UpdateSession,
+};
+use crate::store::session_store::SessionStore;
+use crate::utils::errors::AuthResult;
+use database::utils::DbId;
+
+const SESSION_COLUMNS: &str = "id, user_id, access_token_hash, refresh_token_hash, device, \
+    ip_address, user_agent, org_id, created_at, expires_at, last_used_at, is_revoked";
+
+const REFRESH_TOKEN_COLUMNS: &str =
+    "id, user_id, token_hash, expires_at, created_at, revoked, revoked_at, replaced_by";
+
+/// SQL implementation of SessionStore
+pub struct SqlSessionStore {
+    pool: AnyPool,
+}
+
+impl SqlSessionStore {
+    /// Create a new SqlSessionStore
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+
+    async fn find_session(&self, column: &str, value: String) -> AuthResult<Option<SessionModel>> {
+        let row = sqlx::query(&format!("SELECT {} FROM sessions WHERE {} = $1", SESSION_COLUMNS, column))
+            .bind(value)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(failed("find session"))?;
+
+        row.as_ref().map(session_from_row).transpose().map_err(failed("read session"))
+    }
+
+    async fn list_sessions(&self, column: &str, value: String) -> AuthResult<Vec<SessionModel>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM sessions WHERE {} = $1 ORDER BY created_at",
+            SESSION_COLUMNS, column
+        ))
+        .bind(value)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(failed("find sessions"))?;
+
+        rows.iter().map(session_from_row).collect::<Result<_, _>>().map_err(failed("read sessions"))
+    }
+
+    async fn find_token(&self, column: &str, value: String) -> AuthResult<Option<RefreshTokenModel>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM refresh_tokens WHERE {} = $1",
+            REFRESH_TOKEN_COLUMNS, column
+        ))
+        .bind(value)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(failed("find refresh token"))?;
+
+        row.as_ref().map(refresh_token_from_row).transpose().map_err(failed("read refresh token"))
+    }
+}
+
+fn session_from_row(row: &AnyRow) -> Result<SessionModel, sqlx::Error> {
+    Ok(SessionModel {
+        id: get_id(row, "id")?,
+        user_id: get_id(row, "user_id")?,
+        access_token_hash: row.try_get("access_token_hash")?,
+        refresh_token_hash: row.try_get("refresh_token_hash")?,
+        device: row.try_get("device")?,
+        ip_address: row.try_get("ip_address")?,
+        user_agent: row.try_get("user_agent")?,
+        org_id: get_opt_id(row, "org_id")?,
+        created_at: row.try_get("created_at")?,
+        expires_at: row.try_get("expires_at")?,
+        last_used_at: row.try_get("last_used_at")?,
+        is_revoked: get_flag(row, "is_revoked")?,
+    })
+}
+
+fn refresh_token_from_row(row: &AnyRow) -> Result<RefreshTokenModel, sqlx::Error> {
+    Ok(RefreshTokenModel {
+        id: get_id(row, "id")?,
+        user_id: get_id(row, "user_id")?,
+        token_hash: row.try_get("token_hash")?,
+        expires_at: row.try_get("expires_at")?,
+        created_at: row.try_get("created_at")?,
+        revoked: get_flag(row, "revoked")?,
+        revoked_at: row.try_get("revoked_at")?,
+        replaced_by: row.try_get("replaced_by")?,
+    })
+}
+
+#[async_trait]
+impl SessionStore for SqlSessionStore {
+    /// Create a new session
+    async fn create(&self, input: CreateSession) -> AuthResult<SessionModel> {
+        let now = chrono::Utc::now().timestamp();
+
+        let session = SessionModel {
+            id: DbId::new_uuid(),
+            user_id: input.user_id,
+            access_token_hash: input.access_token_hash,
+            refresh_token_hash: input.refresh_token_hash,
+            device: input.device,
+            ip_address: input.ip_address,
+            user_agent: input.user_agent,
+            org_id: input.org_id,
+            created_at: now,
+            expires_at: now + input.expires_in,
+            last_used_at: now,
+            is_revoked: false,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO sessions ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+            SESSION_COLUMNS
+        ))
+        .bind(session.id.to_string())
+        .bind(session.user_id.to_string())
+        .bind(&session.access_token_hash)
+        .bind(&session.refresh_token_hash)
+        .bind(&session.device)
+        .bind(&session.ip_address)
+        .bind(&session.user_agent)
+        .bind(session.org_id.as_ref().map(DbId::to_string))
+        .bind(session.created_at)
+        .bind(session.expires_at)
+        .bind(session.last_used_at)
+        .bind(flag(session.is_revoked))
+        .execute(&self.pool)
+        .await
+        .map_err(failed("create session"))?;
+
+        Ok(session)
+    }
+
+    /// Find session by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<SessionModel>> {
+        self.find_session("id", id.to_string()).await
+    }
+
+    /// Find session by access token hash
+    async fn find_by_token(&self, token_hash: &str) -> AuthResult<Option<SessionModel>> {
+        self.find_session("access_token_hash", token_hash.to_string()).await
+    }
+
+    /// Find all sessions for a user
+    async fn find_by_user_id(&self, user_id: &DbId) -> AuthResult<Vec<SessionModel>> {
+        self.list_sessions("user_id", user_id.to_string()).await
+    }
+
+    /// Find all sessions issued for an organization
+    async fn find_by_org(&self, org_id: &DbId) -> AuthResult<Vec<SessionModel>> {
+        self.list_sessions("org_id", org_id.to_string()).await
+    }
+
+    /// Update session
+    async fn update(&self, id: &DbId, session: UpdateSession) -> AuthResult<UpdateSession> {
+        sqlx::query(
+            "UPDATE sessions SET access_token_hash = $1, refresh_token_hash = $2, expires_at = $3, \
+             is_revoked = $4 WHERE id = $5",
+        )
+        .bind(&session.access_token_hash)
+        .bind(&session.refresh_token_hash)
+        .bind(session.expires_at)
+        .bind(flag(session.is_revoked))
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(failed("update session"))?;
+
+        Ok(session)
+    }
+
+    /// Point a session at a newly issued access token
+    async fn rotate_access_token(&self, id: &DbId, access_token_hash: &str) -> AuthResult<()> {
+        sqlx::query("UPDATE sessions SET access_token_hash = $1, last_used_at = $2 WHERE id = $3")
+            .bind(access_token_hash)
+            .bind(chrono::Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("rotate access token"))?;
+
+        Ok(())
+    }
+
+    /// Revoke a session
+    async fn revoke(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("UPDATE sessions SET is_revoked = 1 WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("revoke session"))?;
+
+        Ok(())
+    }
+
+    /// Revoke all sessions for a user
+    async fn revoke_all(&self, user_id: &DbId) -> AuthResult<u64> {
+        let result = sqlx::query("UPDATE sessions SET is_revoked = 1 WHERE user_id = $1 AND is_revoked = 0")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("revoke sessions"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete all sessions and refresh tokens of a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let sessions = sqlx::query("DELETE FROM sessions WHERE user_id = $1")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete sessions"))?;
+
+        sqlx::query("DELETE FROM refresh_tokens WHERE user_id = $1")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete refresh tokens"))?;
+
+        Ok(sessions.rows_affected())
+    }
+
+    /// Delete expired sessions
+    async fn cleanup_expired(&self) -> AuthResult<u64> {
+        let result = sqlx::query("DELETE FROM sessions WHERE expires_at < $1")
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("cleanup sessions"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Create refresh token
+    async fn create_refresh_token(&self, input: CreateRefreshToken) -> AuthResult<RefreshTokenModel> {
+        let now = chrono::Utc::now().timestamp();
+
+        let token = RefreshTokenModel {
+            id: DbId::new_uuid(),
+            user_id: input.user_id,
+            token_hash: input.token_hash,
+            expires_at: now + input.expires_in,
+            created_at: now,
+            revoked: false,
+            revoked_at: None,
+            replaced_by: None,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO refresh_tokens ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            REFRESH_TOKEN_COLUMNS
+        ))
+        .bind(token.id.to_string())
+        .bind(token.user_id.to_string())
+        .bind(&token.token_hash)
+        .bind(token.expires_at)
+        .bind(token.created_at)
+        .bind(flag(token.revoked))
+        .bind(token.revoked_at)
+        .bind(&token.replaced_by)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("create refresh token"))?;
+
+        Ok(token)
+    }
+
+    /// Find refresh token by ID
+    async fn find_refresh_token(&self, id: &DbId) -> AuthResult<Option<RefreshTokenModel>> {
+        self.find_token("id", id.to_string()).await
+    }
+
+    /// Find refresh token by hash
+    async fn find_refresh_token_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> AuthResult<Option<RefreshTokenModel>> {
+        self.find_token("token_hash", token_hash.to_string()).await
+    }
+
+    /// Revoke refresh token
+    async fn revoke_refresh_token(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("UPDATE refresh_tokens SET revoked = 1, revoked_at = $1 WHERE id = $2")
+            .bind(chrono::Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("revoke refresh token"))?;
+
+        Ok(())
+    }
+
+    /// Replace refresh token (for rotation)
+    async fn replace_refresh_token(
+        &self,
+        old_id: &DbId,
+        new_token: CreateRefreshToken,
+    ) -> AuthResult<()> {
+        // Revoke old token
+        self.revoke_refresh_token(old_id).await?;
+
+        // Create new token and link the old one to it
+        let token = self.create_refresh_token(new_token).await?;
+
+        sqlx::query("UPDATE refresh_tokens SET replaced_by = $1 WHERE id = $2")
+            .bind(token.id.to_string())
+            .bind(old_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("replace refresh token"))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::store::sql::test_pool;
+
+    fn session(user_id: DbId, expires_in: i64) -> CreateSession {
+        CreateSession {
+            user_id,
+            access_token_hash: DbId::new_uuid().to_string(),
+            refresh_token_hash: None,
+            device: None,
+            ip_address: None,
+            user_agent: None,
+            org_id: None,
+            expires_in,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_revoke_and_cleanup() {
+        let store = SqlSessionStore::new(test_pool().await);
+        let user_id = DbId::new_uuid();
+        let active = store.create(session(user_id.clone(), 3600)).await.unwrap();
+        store.create(session(user_id.clone(), -10)).await.unwrap();
+
+        assert_eq!(store.cleanup_expired().await.unwrap(), 1);
+        assert_eq!(store.revoke_all(&user_id).await.unwrap(), 1);
+        assert!(!store.find_by_id(&active.id).await.unwrap().unwrap().is_valid());
+    }
+
+    #[actix_web::test]
+    async fn test_replace_refresh_token_links_rotation() {
+        let store = SqlSessionStore::new(test_pool().await);
+        let user_id = DbId::new_uuid();
+        let old = store
+            .create_refresh_token(CreateRefreshToken { user_id: user_id.clone(), token_hash: "old".into(), expires_in: 60 })
+            .await
+            .unwrap();
+
+        store
+            .replace_refresh_token(&old.id, CreateRefreshToken { user_id, token_hash: "new".into(), expires_in: 60 })
+            .await
+            .unwrap();
+
+        let old = store.find_refresh_token(&old.id).await.unwrap().unwrap();
+        let new = store.find_refresh_token_by_hash("new").await.unwrap().unwrap();
+        assert!(!old.is_valid());
+        assert_eq!(old.replaced_by, Some(new.id.to_string()));
+        assert!(new.is_valid());
+    }
+}
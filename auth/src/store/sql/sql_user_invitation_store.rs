@@ -0,0 +1,170 @@
+//! SQL User Invitation Store Implementation
+
+use async_trait::async_trait;
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Row};
+
+use super::{enum_name, failed, get_enum, get_id};
+use crate::models::user_invitation::{CreateUserInvitation, UserInvitation};
+use crate::store::user_invitation_store::UserInvitationStore;
+use crate::utils::errors::{AuthError, AuthResult};
+use database::utils::DbId;
+
+const COLUMNS: &str = "id, user_id, org_id, email, role, nonce_hash, invited_by, expires_at, \
+    accepted_at, created_at, updated_at";
+
+/// SQL implementation of UserInvitationStore
+pub struct SqlUserInvitationStore {
+    pool: AnyPool,
+}
+
+impl SqlUserInvitationStore {
+    /// Create a new SqlUserInvitationStore
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn invitation_from_row(row: &AnyRow) -> Result<UserInvitation, sqlx::Error> {
+    Ok(UserInvitation {
+        id: get_id(row, "id")?,
+        user_id: get_id(row, "user_id")?,
+        org_id: get_id(row, "org_id")?,
+        email: row.try_get("email")?,
+        role: get_enum(row, "role")?,
+        nonce_hash: row.try_get("nonce_hash")?,
+        invited_by: get_id(row, "invited_by")?,
+        expires_at: row.try_get("expires_at")?,
+        accepted_at: row.try_get("accepted_at")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+#[async_trait]
+impl UserInvitationStore for SqlUserInvitationStore {
+    /// Create a new invitation
+    async fn create(&self, input: CreateUserInvitation) -> AuthResult<UserInvitation> {
+        let now = chrono::Utc::now().timestamp();
+
+        let invitation = UserInvitation {
+            id: DbId::new_uuid(),
+            user_id: input.user_id,
+            org_id: input.org_id,
+            email: input.email,
+            role: input.role,
+            nonce_hash: input.nonce_hash,
+            invited_by: input.invited_by,
+            expires_at: now + input.expires_in,
+            accepted_at: None,
+            created_at: now,
+            updated_at: None,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO user_invitations ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+            COLUMNS
+        ))
+        .bind(invitation.id.to_string())
+        .bind(invitation.user_id.to_string())
+        .bind(invitation.org_id.to_string())
+        .bind(&invitation.email)
+        .bind(enum_name(&invitation.role))
+        .bind(&invitation.nonce_hash)
+        .bind(invitation.invited_by.to_string())
+        .bind(invitation.expires_at)
+        .bind(invitation.accepted_at)
+        .bind(invitation.created_at)
+        .bind(invitation.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("create invitation"))?;
+
+        Ok(invitation)
+    }
+
+    /// Find invitation by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<UserInvitation>> {
+        let row = sqlx::query(&format!("SELECT {} FROM user_invitations WHERE id = $1", COLUMNS))
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(failed("find invitation"))?;
+
+        row.as_ref().map(invitation_from_row).transpose().map_err(failed("read invitation"))
+    }
+
+    /// List pending invitations of an organization
+    async fn list_pending(&self, org_id: &DbId) -> AuthResult<Vec<UserInvitation>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM user_invitations WHERE org_id = $1 AND accepted_at IS NULL \
+             AND expires_at > $2 ORDER BY created_at",
+            COLUMNS
+        ))
+        .bind(org_id.to_string())
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(failed("list invitations"))?;
+
+        rows.iter().map(invitation_from_row).collect::<Result<_, _>>().map_err(failed("read invitations"))
+    }
+
+    /// Replace the link nonce and extend the expiry
+    async fn renew(&self, id: &DbId, nonce_hash: &str, expires_in: i64) -> AuthResult<()> {
+        let now = chrono::Utc::now().timestamp();
+
+        let result = sqlx::query(
+            "UPDATE user_invitations SET nonce_hash = $1, expires_at = $2, updated_at = $3 WHERE id = $4",
+        )
+        .bind(nonce_hash)
+        .bind(now + expires_in)
+        .bind(now)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(failed("renew invitation"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthError::not_found("Invitation not found"));
+        }
+
+        Ok(())
+    }
+
+    /// Mark invitation as accepted
+    async fn mark_accepted(&self, id: &DbId) -> AuthResult<()> {
+        let now = chrono::Utc::now().timestamp();
+
+        sqlx::query("UPDATE user_invitations SET accepted_at = $1, updated_at = $1 WHERE id = $2")
+            .bind(now)
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("accept invitation"))?;
+
+        Ok(())
+    }
+
+    /// Delete an invitation
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("DELETE FROM user_invitations WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete invitation"))?;
+
+        Ok(())
+    }
+
+    /// Delete invitations addressed to a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let result = sqlx::query("DELETE FROM user_invitations WHERE user_id = $1")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete invitations"))?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,684 @@
+//! SQL User Store Implementation
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use database::sql::Dialect;
+use sqlx::any::{AnyArguments, AnyRow};
+use sqlx::query::Query;
+use sqlx::{Any, AnyPool, Row};
+
+use super::{failed, flag, get_flag, get_id, get_json, placeholders, to_json, Arg, Conditions};
+use crate::models::profile::Profile;
+use crate::models::user::{CreateUserInput, UpdateUserInput, User, UserFilter};
+use crate::models::verification::VerificationMedium;
+use crate::store::user_store::{IdentifierType, UserStore, identify_user};
+use crate::utils::errors::{AuthError, AuthResult};
+use crate::utils::usernames;
+use database::pagination::{Page, PageRequest};
+use database::utils::DbId;
+
+const COLUMNS: &str = "id, email, password_hash, phone, username, username_key, first_name, \
+    last_name, is_active, is_verified, login_attempts, locked_until, created_at, updated_at, \
+    last_login, deletion_scheduled_at, deleted_at, is_admin, profile";
+
+/// SQL implementation of UserStore
+///
+/// Organization ids live in the `user_orgs` join table and are loaded with
+/// the users.
+pub struct SqlUserStore {
+    pool: AnyPool,
+    dialect: Dialect,
+}
+
+/// Unique column a write collided with
+///
+/// Postgres names the index (`users_email_unique`), SQLite the column
+/// (`users.email`); both contain the column name.
+fn duplicate_column(error: &sqlx::Error) -> Option<&'static str> {
+    let error = error.as_database_error().filter(|e| e.is_unique_violation())?;
+    let text = format!("{} {}", error.constraint().unwrap_or_default(), error.message());
+
+    ["username_key", "email", "phone"]
+        .into_iter()
+        .find(|column| text.contains(column))
+}
+
+/// Error naming the identifier that is already taken, if any
+fn identifier_taken(
+    error: &sqlx::Error,
+    email: Option<&str>,
+    phone: Option<&str>,
+    username: Option<&str>,
+) -> Option<AuthError> {
+    match duplicate_column(error)? {
+        "email" => email.map(AuthError::email_already_exists),
+        "phone" => phone.map(AuthError::phone_already_exists),
+        _ => username.map(AuthError::username_already_exists),
+    }
+}
+
+fn get_time(row: &AnyRow, column: &str) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let secs: Option<i64> = row.try_get(column)?;
+    Ok(secs.and_then(|secs| DateTime::from_timestamp(secs, 0)))
+}
+
+/// User without its organizations, see `SqlUserStore::with_org_ids`
+fn user_from_row(row: &AnyRow) -> Result<User, sqlx::Error> {
+    Ok(User {
+        id:             get_id(row, "id")?,
+        email:          row.try_get("email")?,
+        password_hash:  row.try_get("password_hash")?,
+        phone:          row.try_get("phone")?,
+        username:       row.try_get("username")?,
+        username_key:   row.try_get("username_key")?,
+        first_name:     row.try_get("first_name")?,
+        last_name:      row.try_get("last_name")?,
+        is_active:      get_flag(row, "is_active")?,
+        is_verified:    get_flag(row, "is_verified")?,
+        login_attempts: row.try_get::<i64, _>("login_attempts")? as i32,
+        locked_until:   get_time(row, "locked_until")?,
+        created_at:     get_time(row, "created_at")?.unwrap_or_default(),
+        updated_at:     get_time(row, "updated_at")?,
+        last_login:     get_time(row, "last_login")?,
+        org_ids:        Vec::new(),
+        deletion_scheduled_at: get_time(row, "deletion_scheduled_at")?,
+        deleted_at:     get_time(row, "deleted_at")?,
+        is_admin:       get_flag(row, "is_admin")?,
+        profile:        get_json(row, "profile")?,
+    })
+}
+
+impl SqlUserStore {
+    /// Create a new SqlUserStore
+    pub fn new(pool: AnyPool, dialect: Dialect) -> Self {
+        Self { pool, dialect }
+    }
+
+    /// Where clause for a user list filter
+    fn conditions(&self, filter: &UserFilter) -> Conditions {
+        let mut conditions = Conditions::default();
+        conditions.push("deleted_at IS NULL");
+
+        if let Some(is_active) = filter.is_active {
+            conditions.compare("is_active", "=", Arg::Int(flag(is_active)));
+        }
+        if let Some(is_verified) = filter.is_verified {
+            conditions.compare("is_verified", "=", Arg::Int(flag(is_verified)));
+        }
+        for (name, value) in &filter.profile {
+            let name = conditions.arg(Arg::Text(name.clone()));
+            let value = conditions.arg(Arg::Text(to_json(value)));
+            let clause = match self.dialect {
+                Dialect::Postgres => format!("(profile::jsonb -> {}) = {}::jsonb", name, value),
+                // A text label on the right of `->` is read as the path `$.label`
+                Dialect::Sqlite => format!("(profile -> {}) = json({})", name, value),
+            };
+            conditions.push(&clause);
+        }
+
+        conditions
+    }
+
+    async fn find_one(&self, column: &str, value: String) -> AuthResult<Option<User>> {
+        let row = sqlx::query(&format!("SELECT {} FROM users WHERE {} = $1", COLUMNS, column))
+            .bind(value)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(failed("find user"))?;
+
+        let user = row.as_ref().map(user_from_row).transpose().map_err(failed("read user"))?;
+        Ok(self.with_org_ids(user.into_iter().collect()).await?.pop())
+    }
+
+    async fn fetch_users<'q>(&self, query: Query<'q, Any, AnyArguments<'q>>) -> AuthResult<Vec<User>> {
+        let rows = query.fetch_all(&self.pool).await.map_err(failed("list users"))?;
+        let users = rows.iter().map(user_from_row).collect::<Result<_, _>>().map_err(failed("read users"))?;
+
+        self.with_org_ids(users).await
+    }
+
+    /// Fill in the organizations of loaded users
+    async fn with_org_ids(&self, mut users: Vec<User>) -> AuthResult<Vec<User>> {
+        if users.is_empty() {
+            return Ok(users);
+        }
+
+        let sql = format!(
+            "SELECT user_id, org_id FROM user_orgs WHERE user_id IN ({}) ORDER BY created_at",
+            placeholders(1, users.len())
+        );
+        let mut query = sqlx::query(&sql);
+        for user in &users {
+            query = query.bind(user.id.to_string());
+        }
+        let rows = query.fetch_all(&self.pool).await.map_err(failed("load organizations"))?;
+
+        let mut org_ids: HashMap<DbId, Vec<DbId>> = HashMap::new();
+        for row in &rows {
+            let user_id = get_id(row, "user_id").map_err(failed("load organizations"))?;
+            let org_id = get_id(row, "org_id").map_err(failed("load organizations"))?;
+            org_ids.entry(user_id).or_default().push(org_id);
+        }
+        for user in &mut users {
+            user.org_ids = org_ids.remove(&user.id).unwrap_or_default();
+        }
+
+        Ok(users)
+    }
+
+    /// Set a 0/1 status column on a user
+    async fn set_flag(&self, id: &DbId, column: &str, value: bool) -> AuthResult<()> {
+        let result = sqlx::query(&format!("UPDATE users SET {} = $1, updated_at = $2 WHERE id = $3", column))
+            .bind(flag(value))
+            .bind(Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("update user"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthError::not_found("User not found"));
+        }
+
+        Ok(())
+    }
+
+    /// Set `login_attempts` to a SQL expression, so increments are atomic
+    async fn set_login_attempts(&self, id: &DbId, value: &str) -> AuthResult<()> {
+        let result = sqlx::query(&format!("UPDATE users SET login_attempts = {}, updated_at = $1 WHERE id = $2", value))
+            .bind(Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("update login attempts"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthError::not_found("User not found"));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UserStore for SqlUserStore {
+    /// Create a new user
+    async fn create(&self, input: CreateUserInput) -> AuthResult<User> {
+        // Stored with second precision, so the returned user matches a reload
+        let now = DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap_or_default();
+
+        let user = User {
+            id:             DbId::new_uuid(),
+            email:          input.email,
+            password_hash:  input.password,
+            phone:          input.phone,
+            username_key:   input.username.as_deref().map(usernames::skeleton),
+            username:       input.username,
+            first_name:     None,
+            last_name:      None,
+            is_active:      true,
+            is_verified:    false,
+            login_attempts: 0,
+            locked_until:   None,
+            created_at:     now,
+            updated_at:     None,
+            last_login:     None,
+            org_ids:        Vec::new(),
+            deletion_scheduled_at: None,
+            deleted_at:     None,
+            is_admin:       false,
+            profile:        Default::default(),
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO users ({}) VALUES ({})",
+            COLUMNS,
+            placeholders(1, 19)
+        ))
+        .bind(user.id.to_string())
+        .bind(&user.email)
+        .bind(&user.password_hash)
+        .bind(&user.phone)
+        .bind(&user.username)
+        .bind(&user.username_key)
+        .bind(&user.first_name)
+        .bind(&user.last_name)
+        .bind(flag(user.is_active))
+        .bind(flag(user.is_verified))
+        .bind(i64::from(user.login_attempts))
+        .bind(user.locked_until.map(|t| t.timestamp()))
+        .bind(user.created_at.timestamp())
+        .bind(user.updated_at.map(|t| t.timestamp()))
+        .bind(user.last_login.map(|t| t.timestamp()))
+        .bind(user.deletion_scheduled_at.map(|t| t.timestamp()))
+        .bind(user.deleted_at.map(|t| t.timestamp()))
+        .bind(flag(user.is_admin))
+        .bind(to_json(&user.profile))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            identifier_taken(&e, user.email.as_deref(), user.phone.as_deref(), user.username.as_deref())
+                .unwrap_or_else(|| AuthError::internal_error(&format!("Failed to create user: {}", e)))
+        })?;
+
+        Ok(user)
+    }
+
+    /// Find user by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<User>> {
+        self.find_one("id", id.to_string()).await
+    }
+
+    /// Find user by email
+    async fn find_by_email(&self, email: &str) -> AuthResult<Option<User>> {
+        self.find_one("email", email.to_string()).await
+    }
+
+    /// Find user by phone
+    async fn find_by_phone(&self, phone: &str) -> AuthResult<Option<User>> {
+        self.find_one("phone", phone.to_string()).await
+    }
+
+    /// Find user by username - matches case-insensitively and across lookalikes
+    async fn find_by_username(&self, username: &str) -> AuthResult<Option<User>> {
+        let normalized = usernames::normalize(username);
+        self.find_one("username_key", usernames::skeleton(&normalized)).await
+    }
+
+    /// Find user by any identifier (email, phone, or username)
+    async fn find_by_identifier(&self, identifier: &str) -> AuthResult<Option<User>> {
+        match identify_user(identifier) {
+            IdentifierType::Email => self.find_by_email(identifier).await,
+            IdentifierType::Phone => self.find_by_phone(identifier).await,
+            IdentifierType::Username => self.find_by_username(identifier).await,
+        }
+    }
+
+    /// Update user
+    async fn update(&self, id: &DbId, input: UpdateUserInput) -> AuthResult<User> {
+        let mut tx = self.pool.begin().await.map_err(failed("update user"))?;
+
+        let row = sqlx::query("SELECT profile FROM users WHERE id = $1")
+            .bind(id.to_string())
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(failed("update user"))?
+            .ok_or_else(|| AuthError::not_found("User not found"))?;
+
+        // Profile changes touch single keys so other fields are kept
+        let mut profile: Profile = get_json(&row, "profile").map_err(failed("read user"))?;
+        for (name, value) in input.profile.iter().flatten() {
+            if value.is_null() {
+                profile.remove(name);
+            } else {
+                profile.insert(name.clone(), value.clone());
+            }
+        }
+
+        sqlx::query(
+            "UPDATE users SET username = COALESCE($1, username), username_key = COALESCE($2, username_key), \
+             first_name = COALESCE($3, first_name), last_name = COALESCE($4, last_name), \
+             profile = $5, updated_at = $6 WHERE id = $7",
+        )
+        .bind(&input.username)
+        .bind(input.username.as_deref().map(usernames::skeleton))
+        .bind(&input.first_name)
+        .bind(&input.last_name)
+        .bind(to_json(&profile))
+        .bind(Utc::now().timestamp())
+        .bind(id.to_string())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            identifier_taken(&e, None, None, input.username.as_deref())
+                .unwrap_or_else(|| AuthError::internal_error(&format!("Failed to update user: {}", e)))
+        })?;
+
+        tx.commit().await.map_err(failed("update user"))?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| AuthError::not_found("User not found"))
+    }
+
+    /// Update user password
+    async fn update_password(&self, id: &DbId, password_hash: &str) -> AuthResult<()> {
+        let result = sqlx::query("UPDATE users SET password_hash = $1, updated_at = $2 WHERE id = $3")
+            .bind(password_hash)
+            .bind(Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("update password"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthError::not_found("User not found"));
+        }
+
+        Ok(())
+    }
+
+    /// Replace a confirmed email or phone
+    async fn set_contact(&self, id: &DbId, medium: &VerificationMedium, value: &str) -> AuthResult<()> {
+        let column = match medium {
+            VerificationMedium::Email => "email",
+            VerificationMedium::Phone => "phone",
+        };
+
+        let result = sqlx::query(&format!("UPDATE users SET {} = $1, updated_at = $2 WHERE id = $3", column))
+            .bind(value)
+            .bind(Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                identifier_taken(&e, Some(value), Some(value), None)
+                    .unwrap_or_else(|| AuthError::internal_error(&format!("Failed to update contact: {}", e)))
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthError::not_found("User not found"));
+        }
+
+        Ok(())
+    }
+
+    /// Activate or deactivate user
+    async fn set_active(&self, id: &DbId, is_active: bool) -> AuthResult<()> {
+        self.set_flag(id, "is_active", is_active).await
+    }
+
+    /// Mark user as verified or unverified
+    async fn set_verified(&self, id: &DbId, is_verified: bool) -> AuthResult<()> {
+        self.set_flag(id, "is_verified", is_verified).await
+    }
+
+    /// Count a failed password attempt
+    async fn record_failed_login(&self, id: &DbId) -> AuthResult<()> {
+        self.set_login_attempts(id, "login_attempts + 1").await
+    }
+
+    /// Clear the failed attempt count
+    async fn reset_login_attempts(&self, id: &DbId) -> AuthResult<()> {
+        self.set_login_attempts(id, "0").await
+    }
+
+    /// Delete user
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        let mut tx = self.pool.begin().await.map_err(failed("delete user"))?;
+
+        for statement in ["DELETE FROM user_orgs WHERE user_id = $1", "DELETE FROM users WHERE id = $1"] {
+            sqlx::query(statement)
+                .bind(id.to_string())
+                .execute(&mut *tx)
+                .await
+                .map_err(failed("delete user"))?;
+        }
+
+        tx.commit().await.map_err(failed("delete user"))
+    }
+
+    /// Schedule the account for deletion, or cancel with `None`
+    async fn schedule_deletion(&self, id: &DbId, at: Option<DateTime<Utc>>) -> AuthResult<()> {
+        let result = sqlx::query("UPDATE users SET deletion_scheduled_at = $1, updated_at = $2 WHERE id = $3")
+            .bind(at.map(|t| t.timestamp()))
+            .bind(Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("schedule deletion"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthError::not_found("User not found"));
+        }
+
+        Ok(())
+    }
+
+    /// List users whose scheduled deletion is due
+    async fn list_due_for_deletion(&self, now: DateTime<Utc>) -> AuthResult<Vec<User>> {
+        let sql = format!(
+            "SELECT {} FROM users WHERE deletion_scheduled_at IS NOT NULL \
+             AND deletion_scheduled_at <= $1 AND deleted_at IS NULL ORDER BY deletion_scheduled_at",
+            COLUMNS
+        );
+
+        self.fetch_users(sqlx::query(&sql).bind(now.timestamp())).await
+    }
+
+    /// Strip personal data and mark the user deleted
+    async fn anonymize(&self, id: &DbId) -> AuthResult<()> {
+        let now = Utc::now().timestamp();
+        let mut tx = self.pool.begin().await.map_err(failed("anonymize user"))?;
+
+        let result = sqlx::query(
+            "UPDATE users SET email = NULL, phone = NULL, username = NULL, username_key = NULL, \
+             first_name = NULL, last_name = NULL, profile = '{}', password_hash = '', \
+             is_active = 0, is_verified = 0, login_attempts = 0, locked_until = NULL, \
+             last_login = NULL, deletion_scheduled_at = NULL, deleted_at = $1, updated_at = $1 \
+             WHERE id = $2",
+        )
+        .bind(now)
+        .bind(id.to_string())
+        .execute(&mut *tx)
+        .await
+        .map_err(failed("anonymize user"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthError::not_found("User not found"));
+        }
+
+        sqlx::query("DELETE FROM user_orgs WHERE user_id = $1")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(failed("anonymize user"))?;
+
+        tx.commit().await.map_err(failed("anonymize user"))
+    }
+
+    /// List all users (with pagination)
+    async fn list(&self, filter: &UserFilter, page: u32, limit: u32) -> AuthResult<Vec<User>> {
+        let conditions = self.conditions(filter);
+        let sql = format!(
+            "SELECT {} FROM users{} ORDER BY created_at, id LIMIT {} OFFSET ${}",
+            COLUMNS,
+            conditions.sql(),
+            conditions.next(),
+            conditions.args.len() + 2
+        );
+
+        let query = conditions
+            .bind(sqlx::query(&sql))
+            .bind(i64::from(limit))
+            .bind(i64::from(page) * i64::from(limit));
+
+        self.fetch_users(query).await
+    }
+
+    /// List users after a cursor
+    async fn list_after(&self, filter: &UserFilter, page: &PageRequest) -> AuthResult<Page<User>> {
+        let mut conditions = self.conditions(filter);
+        if let Some(after) = &page.after {
+            conditions.after(after, ">");
+        }
+        let sql = format!(
+            "SELECT {} FROM users{} ORDER BY created_at, id LIMIT {}",
+            COLUMNS,
+            conditions.sql(),
+            conditions.next()
+        );
+
+        let query = conditions.bind(sqlx::query(&sql)).bind(page.fetch_limit());
+        let users = self.fetch_users(query).await?;
+        Ok(Page::from_rows(users, page, User::cursor))
+    }
+
+    /// Count users matching the filter
+    async fn count(&self, filter: &UserFilter) -> AuthResult<u64> {
+        let conditions = self.conditions(filter);
+        let sql = format!("SELECT COUNT(*) AS count FROM users{}", conditions.sql());
+
+        let row = conditions
+            .bind(sqlx::query(&sql))
+            .fetch_one(&self.pool)
+            .await
+            .map_err(failed("count users"))?;
+
+        let count: i64 = row.try_get("count").map_err(failed("count users"))?;
+        Ok(count as u64)
+    }
+
+    /// List users belonging to an organization (with pagination)
+    async fn list_by_org(&self, org_id: &DbId, page: u32, limit: u32) -> AuthResult<Vec<User>> {
+        let sql = format!(
+            "SELECT {} FROM users WHERE id IN (SELECT user_id FROM user_orgs WHERE org_id = $1) \
+             ORDER BY created_at, id LIMIT $2 OFFSET $3",
+            COLUMNS
+        );
+        let query = sqlx::query(&sql)
+            .bind(org_id.to_string())
+            .bind(i64::from(limit))
+            .bind(i64::from(page) * i64::from(limit));
+
+        self.fetch_users(query).await
+    }
+
+    /// Count users belonging to an organization
+    async fn count_by_org(&self, org_id: &DbId) -> AuthResult<u64> {
+        let row = sqlx::query("SELECT COUNT(*) AS count FROM user_orgs WHERE org_id = $1")
+            .bind(org_id.to_string())
+            .fetch_one(&self.pool)
+            .await
+            .map_err(failed("count users"))?;
+
+        let count: i64 = row.try_get("count").map_err(failed("count users"))?;
+        Ok(count as u64)
+    }
+
+    /// Add user to an organization
+    async fn add_to_org(&self, id: &DbId, org_id: &DbId) -> AuthResult<()> {
+        sqlx::query(
+            "INSERT INTO user_orgs (user_id, org_id, created_at) VALUES ($1, $2, $3) \
+             ON CONFLICT (user_id, org_id) DO NOTHING",
+        )
+        .bind(id.to_string())
+        .bind(org_id.to_string())
+        .bind(Utc::now().timestamp())
+        .execute(&self.pool)
+        .await
+        .map_err(failed("add user to organization"))?;
+
+        Ok(())
+    }
+
+    /// Remove user from an organization
+    async fn remove_from_org(&self, id: &DbId, org_id: &DbId) -> AuthResult<()> {
+        sqlx::query("DELETE FROM user_orgs WHERE user_id = $1 AND org_id = $2")
+            .bind(id.to_string())
+            .bind(org_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("remove user from organization"))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::store::sql::test_pool;
+    use crate::utils::errors::AuthErrorCode;
+
+    fn input(email: Option<&str>, username: Option<&str>) -> CreateUserInput {
+        CreateUserInput {
+            email:    email.map(String::from),
+            phone:    None,
+            username: username.map(String::from),
+            password: "hash".to_string(),
+        }
+    }
+
+    async fn store() -> SqlUserStore {
+        SqlUserStore::new(test_pool().await, Dialect::Sqlite)
+    }
+
+    #[actix_web::test]
+    async fn test_rejects_duplicate_identifiers() {
+        let store = store().await;
+        store.create(input(Some("amani@example.com"), Some("amani"))).await.unwrap();
+
+        let err = store.create(input(Some("amani@example.com"), None)).await.unwrap_err();
+        assert!(matches!(err.code, AuthErrorCode::EmailAlreadyExists));
+
+        // Cyrillic "а" shares the skeleton of the Latin name
+        let err = store.create(input(None, Some("аmani"))).await.unwrap_err();
+        assert!(matches!(err.code, AuthErrorCode::UsernameAlreadyExists));
+    }
+
+    #[actix_web::test]
+    async fn test_list_filters_on_profile_and_skips_deleted_users() {
+        let store = store().await;
+        let kept = store.create(input(Some("a@example.com"), None)).await.unwrap();
+        let gone = store.create(input(Some("b@example.com"), None)).await.unwrap();
+        store.anonymize(&gone.id).await.unwrap();
+
+        let mut profile = Profile::new();
+        profile.insert("city".to_string(), serde_json::json!("Nairobi"));
+        let update = UpdateUserInput { username: None, first_name: None, last_name: None, profile: Some(profile.clone()) };
+        store.update(&kept.id, update).await.unwrap();
+
+        let filter = UserFilter { profile, ..Default::default() };
+        let users = store.list(&filter, 0, 10).await.unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].id, kept.id);
+        assert_eq!(store.count(&UserFilter::default()).await.unwrap(), 1);
+
+        // Anonymized users free their email
+        assert!(store.create(input(Some("b@example.com"), None)).await.is_ok());
+    }
+
+    #[actix_web::test]
+    async fn test_org_membership_is_loaded_with_the_user() {
+        let store = store().await;
+        let user = store.create(input(Some("a@example.com"), None)).await.unwrap();
+        let org_id = DbId::new_uuid();
+
+        store.add_to_org(&user.id, &org_id).await.unwrap();
+        store.add_to_org(&user.id, &org_id).await.unwrap();
+        assert_eq!(store.find_by_id(&user.id).await.unwrap().unwrap().org_ids, vec![org_id.clone()]);
+        assert_eq!(store.count_by_org(&org_id).await.unwrap(), 1);
+
+        store.remove_from_org(&user.id, &org_id).await.unwrap();
+        assert!(store.list_by_org(&org_id, 0, 10).await.unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_list_after_walks_every_user_once() {
+        let store = store().await;
+        let mut created = Vec::new();
+        for n in 0..5 {
+            let user = store.create(input(Some(&format!("{}@example.com", n)), None)).await.unwrap();
+            created.push(user.id);
+        }
+
+        let mut seen = Vec::new();
+        let mut request = PageRequest::first(2);
+        loop {
+            let page = store.list_after(&UserFilter::default(), &request).await.unwrap();
+            assert!(page.items.len() <= 2);
+            seen.extend(page.items.iter().map(|u| u.id.clone()));
+            match page.next {
+                Some(cursor) => request = PageRequest::after(cursor, 2),
+                None => break,
+            }
+        }
+
+        seen.sort();
+        created.sort();
+        assert_eq!(seen, created);
+    }
+}
@@ -0,0 +1,171 @@
+//! SQL Verification Store Implementation
+
+use async_trait::async_trait;
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Row};
+
+use super::{enum_name, failed, get_enum, get_id};
+use crate::models::verification::{
+    CreateVerificationCode, VerificationCodeModel, VerificationMedium, VerificationPurpose,
+};
+use crate::store::verification_store::VerificationStore;
+use crate::utils::errors::AuthResult;
+use database::utils::DbId;
+
+const COLUMNS: &str =
+    "id, user_id, code_hash, medium, purpose, attempts, created_at, expires_at, verified_at";
+
+/// SQL implementation of VerificationStore
+pub struct SqlVerificationStore {
+    pool: AnyPool,
+}
+
+impl SqlVerificationStore {
+    /// Create a new SqlVerificationStore
+    pub fn new(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn code_from_row(row: &AnyRow) -> Result<VerificationCodeModel, sqlx::Error> {
+    Ok(VerificationCodeModel {
+        id: get_id(row, "id")?,
+        user_id: get_id(row, "user_id")?,
+        code_hash: row.try_get("code_hash")?,
+        medium: get_enum(row, "medium")?,
+        purpose: get_enum(row, "purpose")?,
+        attempts: row.try_get::<i64, _>("attempts")? as i32,
+        created_at: row.try_get("created_at")?,
+        expires_at: row.try_get("expires_at")?,
+        verified_at: row.try_get("verified_at")?,
+    })
+}
+
+#[async_trait]
+impl VerificationStore for SqlVerificationStore {
+    /// Create a new verification code
+    async fn create(&self, input: CreateVerificationCode) -> AuthResult<VerificationCodeModel> {
+        let now = chrono::Utc::now().timestamp();
+
+        let code = VerificationCodeModel {
+            id: DbId::new_uuid(),
+            user_id: input.user_id,
+            code_hash: input.code_hash,
+            medium: input.medium,
+            purpose: input.purpose,
+            attempts: 0,
+            created_at: now,
+            expires_at: now + input.expires_in,
+            verified_at: None,
+        };
+
+        sqlx::query(&format!(
+            "INSERT INTO verification_codes ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            COLUMNS
+        ))
+        .bind(code.id.to_string())
+        .bind(code.user_id.to_string())
+        .bind(&code.code_hash)
+        .bind(enum_name(&code.medium))
+        .bind(enum_name(&code.purpose))
+        .bind(i64::from(code.attempts))
+        .bind(code.created_at)
+        .bind(code.expires_at)
+        .bind(code.verified_at)
+        .execute(&self.pool)
+        .await
+        .map_err(failed("create verification code"))?;
+
+        Ok(code)
+    }
+
+    /// Find verification code by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<VerificationCodeModel>> {
+        let row = sqlx::query(&format!("SELECT {} FROM verification_codes WHERE id = $1", COLUMNS))
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(failed("find verification code"))?;
+
+        row.as_ref().map(code_from_row).transpose().map_err(failed("read verification code"))
+    }
+
+    /// Find valid verification code by user_id, medium, and purpose
+    async fn find_valid_code(
+        &self,
+        user_id: &DbId,
+        medium: VerificationMedium,
+        purpose: VerificationPurpose,
+    ) -> AuthResult<Option<VerificationCodeModel>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM verification_codes WHERE user_id = $1 AND medium = $2 AND purpose = $3 \
+             AND expires_at > $4 AND verified_at IS NULL ORDER BY created_at DESC",
+            COLUMNS
+        ))
+        .bind(user_id.to_string())
+        .bind(enum_name(&medium))
+        .bind(enum_name(&purpose))
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(failed("find valid verification code"))?;
+
+        row.as_ref().map(code_from_row).transpose().map_err(failed("read verification code"))
+    }
+
+    /// Verify a code (mark as verified)
+    async fn verify(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("UPDATE verification_codes SET verified_at = $1 WHERE id = $2")
+            .bind(chrono::Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("verify code"))?;
+
+        Ok(())
+    }
+
+    /// Increment failed attempts
+    async fn increment_attempts(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("UPDATE verification_codes SET attempts = attempts + 1 WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("increment attempts"))?;
+
+        Ok(())
+    }
+
+    /// Delete/expire a code
+    async fn delete(&self, id: &DbId) -> AuthResult<()> {
+        sqlx::query("DELETE FROM verification_codes WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete verification code"))?;
+
+        Ok(())
+    }
+
+    /// Delete all codes for a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64> {
+        let result = sqlx::query("DELETE FROM verification_codes WHERE user_id = $1")
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("delete verification codes"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Cleanup expired codes
+    async fn cleanup_expired(&self) -> AuthResult<u64> {
+        let result = sqlx::query("DELETE FROM verification_codes WHERE expires_at < $1")
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.pool)
+            .await
+            .map_err(failed("cleanup expired codes"))?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,33 @@
+//! User invitation store module
+//!
+//! Provides a generic store for invitations that onboard new users.
+
+use async_trait::async_trait;
+use crate::models::user_invitation::{CreateUserInvitation, UserInvitation};
+use crate::utils::errors::AuthResult;
+use database::utils::DbId;
+
+/// User invitation store trait - implement this for each database
+#[async_trait]
+pub trait UserInvitationStore: Send + Sync {
+    /// Create a new invitation
+    async fn create(&self, input: CreateUserInvitation) -> AuthResult<UserInvitation>;
+
+    /// Find invitation by ID
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<UserInvitation>>;
+
+    /// List pending (not accepted, not expired) invitations of an organization
+    async fn list_pending(&self, org_id: &DbId) -> AuthResult<Vec<UserInvitation>>;
+
+    /// Replace the link nonce and extend the expiry (invalidates earlier links)
+    async fn renew(&self, id: &DbId, nonce_hash: &str, expires_in: i64) -> AuthResult<()>;
+
+    /// Mark invitation as accepted
+    async fn mark_accepted(&self, id: &DbId) -> AuthResult<()>;
+
+    /// Delete an invitation
+    async fn delete(&self, id: &DbId) -> AuthResult<()>;
+
+    /// Delete invitations addressed to a user
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64>;
+}
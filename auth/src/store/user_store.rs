@@ -3,60 +3,121 @@
 //! Provides a generic user store that works with any database.
 //! Uses DbId from database crate for flexible database support.
 
-use crate::models::user::{CreateUserInput, UpdateUserInput, User};
+use async_trait::async_trait;
+use crate::models::user::{CreateUserInput, UpdateUserInput, User, UserFilter};
+use crate::models::verification::VerificationMedium;
 use crate::utils::errors::AuthResult;
+use chrono::{DateTime, Utc};
+use database::pagination::{Page, PageRequest};
 use database::utils::DbId;
+use utils::phone;
 
 /// User store trait - implement this for each database
+#[async_trait]
 pub trait UserStore: Send + Sync {
     /// Create a new user
-    fn create(&self, input: CreateUserInput) -> AuthResult<User>;
+    async fn create(&self, input: CreateUserInput) -> AuthResult<User>;
 
     /// Find user by ID
-    fn find_by_id(&self, id: &DbId) -> AuthResult<Option<User>>;
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<User>>;
 
     /// Find user by email
-    fn find_by_email(&self, email: &str) -> AuthResult<Option<User>>;
+    async fn find_by_email(&self, email: &str) -> AuthResult<Option<User>>;
 
     /// Find user by phone
-    fn find_by_phone(&self, phone: &str) -> AuthResult<Option<User>>;
+    async fn find_by_phone(&self, phone: &str) -> AuthResult<Option<User>>;
 
     /// Find user by username
-    fn find_by_username(&self, username: &str) -> AuthResult<Option<User>>;
+    async fn find_by_username(&self, username: &str) -> AuthResult<Option<User>>;
 
     /// Find user by any identifier (email, phone, or username)
-    fn find_by_identifier(&self, identifier: &str) -> AuthResult<Option<User>>;
+    async fn find_by_identifier(&self, identifier: &str) -> AuthResult<Option<User>>;
 
     /// Update user
-    fn update(&self, id: &DbId, input: UpdateUserInput) -> AuthResult<User>;
+    async fn update(&self, id: &DbId, input: UpdateUserInput) -> AuthResult<User>;
 
     /// Update user password
-    fn update_password(&self, id: &DbId, password_hash: &str) -> AuthResult<()>;
+    async fn update_password(&self, id: &DbId, password_hash: &str) -> AuthResult<()>;
+
+    /// Replace email or phone - only after the change has been confirmed
+    async fn set_contact(&self, id: &DbId, medium: &VerificationMedium, value: &str) -> AuthResult<()>;
+
+    /// Activate or deactivate user
+    async fn set_active(&self, id: &DbId, is_active: bool) -> AuthResult<()>;
+
+    /// Mark user as verified or unverified
+    async fn set_verified(&self, id: &DbId, is_verified: bool) -> AuthResult<()>;
+
+    /// Count a failed password attempt
+    async fn record_failed_login(&self, id: &DbId) -> AuthResult<()>;
+
+    /// Clear the failed attempt count after a successful sign-in
+    async fn reset_login_attempts(&self, id: &DbId) -> AuthResult<()>;
 
     /// Delete user
-    fn delete(&self, id: &DbId) -> AuthResult<()>;
+    async fn delete(&self, id: &DbId) -> AuthResult<()>;
+
+    /// Schedule the account for deletion, or cancel with `None`
+    async fn schedule_deletion(&self, id: &DbId, at: Option<DateTime<Utc>>) -> AuthResult<()>;
+
+    /// List users whose scheduled deletion is due
+    async fn list_due_for_deletion(&self, now: DateTime<Utc>) -> AuthResult<Vec<User>>;
+
+    /// Strip personal data and mark the user deleted, keeping the record for references
+    async fn anonymize(&self, id: &DbId) -> AuthResult<()>;
+
+    /// List users matching the filter (with pagination)
+    async fn list(&self, filter: &UserFilter, page: u32, limit: u32) -> AuthResult<Vec<User>>;
 
-    /// List all users (with pagination)
-    fn list(&self, page: u32, limit: u32) -> AuthResult<Vec<User>>;
+    /// List users matching the filter in creation order, continuing after a cursor
+    async fn list_after(&self, filter: &UserFilter, page: &PageRequest) -> AuthResult<Page<User>>;
 
-    /// Count total users
-    fn count(&self) -> AuthResult<u64>;
+    /// Count users matching the filter
+    async fn count(&self, filter: &UserFilter) -> AuthResult<u64>;
+
+    /// List users belonging to an organization (with pagination)
+    async fn list_by_org(&self, org_id: &DbId, page: u32, limit: u32) -> AuthResult<Vec<User>>;
+
+    /// Count users belonging to an organization
+    async fn count_by_org(&self, org_id: &DbId) -> AuthResult<u64>;
+
+    /// Add user to an organization
+    async fn add_to_org(&self, id: &DbId, org_id: &DbId) -> AuthResult<()>;
+
+    /// Remove user from an organization
+    async fn remove_from_org(&self, id: &DbId, org_id: &DbId) -> AuthResult<()>;
 }
 
 /// Helper to check if identifier is email, phone, or username
+///
+/// Phone numbers may be written with `+`, spaces or dashes. Usernames start
+/// with a letter, so they never look like one.
 pub fn identify_user(identifier: &str) -> IdentifierType {
     if identifier.contains('@') {
         IdentifierType::Email
-    } else if identifier.chars().all(|c| c.is_ascii_digit()) && identifier.len() >= 10 {
+    } else if phone::looks_like_phone(identifier) {
         IdentifierType::Phone
     } else {
         IdentifierType::Username
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IdentifierType {
     Email,
     Phone,
     Username,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identify_user() {
+        assert_eq!(identify_user("amani@example.com"), IdentifierType::Email);
+        assert_eq!(identify_user("+254 712 345 678"), IdentifierType::Phone);
+        assert_eq!(identify_user("0712345678"), IdentifierType::Phone);
+        assert_eq!(identify_user("amani_k"), IdentifierType::Username);
+    }
+}
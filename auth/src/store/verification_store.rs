@@ -2,6 +2,7 @@
 //!
 //! Provides a generic verification store for email/phone verification codes.
 
+use async_trait::async_trait;
 use crate::models::verification::{
     CreateVerificationCode, VerificationCodeModel, VerificationMedium, VerificationPurpose,
 };
@@ -9,15 +10,16 @@ use crate::utils::errors::AuthResult;
 use database::utils::DbId;
 
 /// Verification store trait - implement this for each database
+#[async_trait]
 pub trait VerificationStore: Send + Sync {
     /// Create a new verification code
-    fn create(&self, input: CreateVerificationCode) -> AuthResult<VerificationCodeModel>;
+    async fn create(&self, input: CreateVerificationCode) -> AuthResult<VerificationCodeModel>;
 
     /// Find verification code by ID
-    fn find_by_id(&self, id: &DbId) -> AuthResult<Option<VerificationCodeModel>>;
+    async fn find_by_id(&self, id: &DbId) -> AuthResult<Option<VerificationCodeModel>>;
 
     /// Find valid verification code by user_id, medium, and purpose
-    fn find_valid_code(
+    async fn find_valid_code(
         &self,
         user_id: &DbId,
         medium: VerificationMedium,
@@ -25,17 +27,17 @@ pub trait VerificationStore: Send + Sync {
     ) -> AuthResult<Option<VerificationCodeModel>>;
 
     /// Verify a code (mark as verified)
-    fn verify(&self, id: &DbId) -> AuthResult<()>;
+    async fn verify(&self, id: &DbId) -> AuthResult<()>;
 
     /// Increment failed attempts
-    fn increment_attempts(&self, id: &DbId) -> AuthResult<()>;
+    async fn increment_attempts(&self, id: &DbId) -> AuthResult<()>;
 
     /// Delete/expire a code
-    fn delete(&self, id: &DbId) -> AuthResult<()>;
+    async fn delete(&self, id: &DbId) -> AuthResult<()>;
 
     /// Delete all codes for a user
-    fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64>;
+    async fn delete_all_for_user(&self, user_id: &DbId) -> AuthResult<u64>;
 
     /// Cleanup expired codes
-    fn cleanup_expired(&self) -> AuthResult<u64>;
+    async fn cleanup_expired(&self) -> AuthResult<u64>;
 }
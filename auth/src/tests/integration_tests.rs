@@ -1,554 +1,978 @@
 //! Integration tests for authentication functionality
 //!
-//! These tests verify the complete authentication flow including:
-//! - User registration (signup)
-//! - Email/phone validation
-//! - Password hashing
-//! - Database operations
-//! - Error handling
+//! These tests drive the real routes over HTTP against the in-memory stores,
+//! so they need no database or other external service:
+//! - User registration (signup) and duplicate identifiers
+//! - Login, token refresh and logout
+//! - Email verification
+//! - Password reset
+//! - Profile updates
 
 use actix_web::{
-    test,
-    web::{self},
-    App, Result,
+    App,
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test, web,
 };
-use chrono::Utc;
-use mongodb::{
-    bson::{doc, oid::ObjectId},
-    sync::{Client, Database},
-};
-use rand::Rng;
-use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
 
 use crate::{
-    handler::signup_user,
-    models::user::{CreateUserInput, User},
-    routes::AppState,
-    store::database::MongoUserStore,
-    store::user_store::UserStore,
+    config::webauthn::WebAuthnConfig,
+    handler::{access_claims, encode_access_token, generate_refresh_token},
+    models::api_key::{AUDIT_READ_SCOPE, CreateApiKey},
+    models::audit::{AuditAction, AuditFilter},
+    models::contact_change::CreateContactChange,
+    models::organization::{CreateMembership, CreateOrgInvitation, CreateOrganization, OrgRole},
+    models::reset_password::CreatePasswordResetToken,
+    models::session::{CreateRefreshToken, CreateSession},
+    models::verification::{VerificationMedium, VerificationPurpose},
+    routes::{AppState, AuthSettings, OptionalStores, configure},
+    service::account,
+    service::api_key::ApiKeyService,
+    service::risk::{LoginAttempt, RiskAssessment, RiskDecision, RiskEvaluator},
+    service::webauthn::tests::{ORIGIN, RP_ID, SoftAuthenticator},
+    store::memory::{
+        MemoryApiKeyStore, MemoryAuditStore, MemoryContactChangeStore, MemoryOAuthAccountStore,
+        MemoryOrganizationStore, MemoryPasskeyStore, MemoryPasswordResetStore, MemorySessionStore,
+        MemoryUserInvitationStore, MemoryUserStore, MemoryVerificationStore,
+    },
 };
-use database::utils::DbId;
-use utils::response::ApiResponse;
-use utils::email::EmailService;
-use crate::utils::types::{SignInResponse, SignUpResponseData};
-
-/// Test user data for integration tests
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TestUser {
-    pub email: String,
-    pub phone: String,
-    pub username: String,
-    pub password: String,
-    pub first_name: String,
-    pub last_name: String,
-}
-
-impl TestUser {
-    pub fn new() -> Self {
-        let timestamp = Utc::now().timestamp();
-        let mut rng = rand::thread_rng();
-        Self {
-            email: format!("test_user_{}@example.com", timestamp),
-            phone: format!("+2547{}{}{}{}{}{}{}", 
-                rng.gen_range(0..10),
-                rng.gen_range(0..10),
-                rng.gen_range(0..10),
-                rng.gen_range(0..10),
-                rng.gen_range(0..10),
-                rng.gen_range(0..10),
-                rng.gen_range(0..10),
-            ),
-            username: format!("testuser_{}", timestamp),
-            password: "SecurePassword123!".to_string(),
-            first_name: "Test".to_string(),
-            last_name: "User".to_string(),
-        }
-    }
+use database::utils::{DbId, generate_id, parse_id};
+use middleware::jwt::{AMR_PASSKEY, AMR_PASSWORD, ActorClaim, Claims};
+use utils::email::{EmailService, SmtpConfig};
+use utils::hash::hash_sha256;
+
+const JWT_SECRET: &str = "test_jwt_secret_key_for_integration_tests";
+const PASSWORD: &str = "SecurePassword123!";
+
+/// App state backed by fresh in-memory stores
+fn test_state() -> AppState {
+    let stores = OptionalStores {
+        password_resets:  Some(Arc::new(MemoryPasswordResetStore::new())),
+        verifications:    Some(Arc::new(MemoryVerificationStore::new())),
+        oauth_accounts:   Some(Arc::new(MemoryOAuthAccountStore::new())),
+        passkeys:         Some(Arc::new(MemoryPasskeyStore::new())),
+        api_keys:         Some(Arc::new(MemoryApiKeyStore::new())),
+        organizations:    Some(Arc::new(MemoryOrganizationStore::new())),
+        user_invitations: Some(Arc::new(MemoryUserInvitationStore::new())),
+        contact_changes:  Some(Arc::new(MemoryContactChangeStore::new())),
+        audit:            Some(Arc::new(MemoryAuditStore::new())),
+    };
+    let settings = AuthSettings {
+        jwt_secret:                JWT_SECRET.to_string(),
+        jwt_expiry_minutes:        60,
+        refresh_token_expiry_days: 30,
+        email:                     Arc::new(EmailService::smtp(SmtpConfig::new(
+            "smtp.example.com",
+            587,
+            "test@example.com",
+            "test_password",
+        ))),
+        sms:                       None,
+        email_from:                "noreply@example.com".to_string(),
+        app_name:                  "Test App".to_string(),
+        frontend_url:              "http://localhost:3000".to_string(),
+    };
+
+    AppState::with_jwt(
+        Arc::new(MemoryUserStore::new()),
+        Arc::new(MemorySessionStore::new()),
+        stores,
+        settings,
+    )
 }
 
-/// Test setup for integration tests
-pub struct TestSetup {
-    pub db: Database,
-    pub user_store: Arc<MongoUserStore>,
-    pub app_state: AppState,
-}
-
-impl TestSetup {
-    /// Create a new test setup with a test database
-    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        // Connect to test database
-        let client = Client::with_uri_str("mongodb://localhost:27017")?;
-        let db = client.database("nyumberni_test");
-        
-        // Create user collection
-        let user_collection = db.collection::<User>("users_test");
-        let user_store = Arc::new(MongoUserStore::new(user_collection));
-
-        // Create app state
-        let app_state = AppState {
-            users: user_store.clone(),
-            sessions: Arc::new(crate::store::database::MongoSessionStore::new(
-                db.collection("sessions_test"),
-                db.collection("refresh_tokens_test"),
-            )),
-            password_resets: Some(Arc::new(crate::store::database::MongoPasswordResetStore::new(
-                db.collection("password_reset_tokens_test"),
-            ))),
-            verifications: Some(Arc::new(crate::store::database::MongoVerificationStore::new(
-                db.collection("verification_codes_test"),
-            ))),
-            oauth_accounts: Some(Arc::new(crate::store::database::MongoOAuthAccountStore::new(
-                db.collection("oauth_accounts_test"),
-            ))),
-            jwt_secret: "test_jwt_secret_key_for_integration_tests".to_string(),
-            jwt_expiry_minutes: 60,
-            refresh_token_expiry_days: 30,
-            email: Arc::new(EmailService::smtp(
-                utils::email::SmtpConfig::new(
-                    "smtp.example.com".to_string(),
-                    587,
-                    "test@example.com".to_string(),
-                    "test_password".to_string(),
-                )
-            )),
-            email_from: "noreply@example.com".to_string(),
-            app_name: "Test App".to_string(),
-            frontend_url: "http://localhost:3000".to_string(),
-        };
-
-        Ok(Self {
-            db,
-            user_store,
-            app_state,
-        })
-    }
+/// Initialize the auth routes over `state`
+macro_rules! test_app {
+    ($state:expr) => {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new($state.clone()))
+                .app_data($state.api_key_validator().unwrap())
+                .configure(|cfg| configure(cfg, JWT_SECRET.to_string())),
+        )
+        .await
+    };
+}
 
-    /// Clean up test data
-    pub fn cleanup(&self) -> Result<(), Box<dyn std::error::Error>> {
-        // Drop test collections
-        self.db.collection::<User>("users_test").drop(None)?;
-        self.db.collection::<User>("sessions_test").drop(None)?;
-        self.db.collection::<User>("refresh_tokens_test").drop(None)?;
-        self.db.collection::<User>("password_reset_tokens_test").drop(None)?;
-        self.db.collection::<User>("verification_codes_test").drop(None)?;
-        self.db.collection::<User>("oauth_accounts_test").drop(None)?;
-        Ok(())
-    }
+/// Send a request and return the status and JSON body
+async fn send<S, B>(app: &S, req: test::TestRequest) -> (StatusCode, Value)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    let resp = test::call_service(app, req.to_request()).await;
+    let status = resp.status();
+    let body = test::read_body(resp).await;
+
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
 }
 
-/// Integration test for successful user registration
-#[actix_web::test]
-async fn test_successful_user_registration() -> Result<(), Box<dyn std::error::Error>> {
-    let setup = TestSetup::new()?;
-    let _cleanup_guard = CleanupGuard(&setup);
-
-    let test_user = TestUser::new();
-    let signup_request = crate::utils::types::SignUpRequest {
-        email: Some(test_user.email.clone()),
-        phone: Some(test_user.phone.clone()),
-        username: Some(test_user.username.clone()),
-        password: test_user.password.clone(),
-        first_name: Some(test_user.first_name.clone()),
-        last_name: Some(test_user.last_name.clone()),
-    };
+fn post(uri: &str, body: Value) -> test::TestRequest {
+    test::TestRequest::post().uri(uri).set_json(body)
+}
+
+fn bearer(req: test::TestRequest, access_token: &str) -> test::TestRequest {
+    req.insert_header(("Authorization", format!("Bearer {}", access_token)))
+}
+
+/// Store a session for `claims` and return its access token
+async fn session_for(state: &AppState, claims: &Claims) -> String {
+    let access_token = encode_access_token(claims, JWT_SECRET).unwrap();
+    state.sessions.create(CreateSession {
+        user_id:            parse_id(&claims.sub).unwrap(),
+        access_token_hash:  hash_sha256(&access_token),
+        refresh_token_hash: None,
+        device:             None,
+        ip_address:         None,
+        user_agent:         None,
+        org_id:             None,
+        expires_in:         900,
+    }).await.unwrap();
+
+    access_token
+}
+
+/// Register a user and return its id
+async fn signup<S, B>(app: &S, body: Value) -> String
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    let (status, body) = send(app, post("/auth/register", body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
 
-    // Create test app
-    let app = test::init_service(
-        App::new()
-            .app_data(web::Data::new(setup.app_state.clone()))
-            .route("/signup", web::post().to(signup_user)),
+    body["data"]["user_id"].as_str().unwrap().to_string()
+}
+
+/// Sign in and return the access and refresh tokens
+async fn login<S, B>(app: &S, identifier: &str, password: &str) -> (String, String)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    let body = json!({ "identifier": identifier, "password": password });
+    let (status, body) = send(app, post("/auth/login", body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    (
+        body["data"]["access_token"].as_str().unwrap().to_string(),
+        body["data"]["refresh_token"].as_str().unwrap().to_string(),
+    )
+}
+
+#[actix_web::test]
+async fn test_signup_normalizes_and_stores_user() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(
+        &app,
+        json!({
+            "email": " Amani@Example.com ",
+            "phone": "0712 345 678",
+            "username": "Amani_K",
+            "password": PASSWORD,
+        }),
     )
     .await;
 
-    // Send signup request
-    let req = test::TestRequest::post()
-        .uri("/signup")
-        .set_json(&signup_request)
-        .to_request();
-
-    let resp = test::call_service(&app, req).await;
-
-    // Verify response
-    assert_eq!(resp.status(), 200);
-    let response: ApiResponse<SignUpResponseData> = test::read_body_json(resp).await;
-    
-    assert!(response.success);
-    assert_eq!(response.message, "User registered successfully");
-    assert!(response.data.is_some());
-    
-    let user_data = response.data.unwrap();
-    assert!(!user_data.user_id.is_empty());
-    assert_eq!(user_data.email, Some(test_user.email.clone()));
-
-    // Verify user was created in database
-    let user = setup.user_store.find_by_email(&test_user.email)?;
-    assert!(user.is_some());
-    
-    let user = user.unwrap();
-    assert_eq!(user.email, Some(test_user.email));
-    assert_eq!(user.phone, Some(test_user.phone));
-    assert_eq!(user.username, Some(test_user.username));
-    assert_eq!(user.first_name, Some(test_user.first_name));
-    assert_eq!(user.last_name, Some(test_user.last_name));
-    assert_eq!(user.is_active, true);
-    assert_eq!(user.is_verified, false);
-    assert_eq!(user.failed_login_attempts, 0);
-    assert!(user.created_at > 0);
-
-    // Verify password was hashed
-    assert_ne!(user.password_hash, test_user.password);
-    assert!(user.password_hash.starts_with("$argon2"));
+    let user = state.users.find_by_id(&parse_id(&user_id).unwrap()).await.unwrap().unwrap();
+    assert_eq!(user.email.as_deref(), Some("amani@example.com"));
+    assert_eq!(user.phone.as_deref(), Some("+254712345678"));
+    assert_eq!(user.username.as_deref(), Some("amani_k"));
+    assert!(user.is_active);
+    assert!(!user.is_verified);
 
-    Ok(())
+    // Password is hashed with Argon2
+    assert_ne!(user.password_hash, PASSWORD);
+    assert!(user.password_hash.starts_with("$argon2"));
 }
 
-/// Integration test for email already exists error
 #[actix_web::test]
-async fn test_email_already_exists() -> Result<(), Box<dyn std::error::Error>> {
-    let setup = TestSetup::new()?;
-    let _cleanup_guard = CleanupGuard(&setup);
-
-    let test_user = TestUser::new();
-    
-    // Create first user
-    let create_input = CreateUserInput {
-        email: Some(test_user.email.clone()),
-        phone: Some(test_user.phone.clone()),
-        username: Some(test_user.username.clone()),
-        password: test_user.password.clone(),
-        first_name: Some(test_user.first_name.clone()),
-        last_name: Some(test_user.last_name.clone()),
-    };
-    
-    setup.user_store.create(create_input)?;
-
-    // Try to register with same email
-    let signup_request = crate::utils::types::SignUpRequest {
-        email: Some(test_user.email.clone()),
-        phone: Some("different_phone@example.com".to_string()),
-        username: Some("different_username".to_string()),
-        password: "DifferentPassword123!".to_string(),
-        first_name: Some("Different".to_string()),
-        last_name: Some("User".to_string()),
-    };
+async fn test_minimal_signup() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": null, "phone": null, "password": PASSWORD })).await;
 
-    let app = test::init_service(
-        App::new()
-            .app_data(web::Data::new(setup.app_state.clone()))
-            .route("/signup", web::post().to(signup_user)),
+    let user = state.users.find_by_id(&parse_id(&user_id).unwrap()).await.unwrap().unwrap();
+    assert_eq!(user.email, None);
+    assert_eq!(user.phone, None);
+    assert_eq!(user.username, None);
+}
+
+#[actix_web::test]
+async fn test_signup_rejects_taken_identifiers() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    signup(
+        &app,
+        json!({
+            "email": "amani@example.com",
+            "phone": "+254712345678",
+            "username": "amani",
+            "password": PASSWORD,
+        }),
     )
     .await;
 
-    let req = test::TestRequest::post()
-        .uri("/signup")
-        .set_json(&signup_request)
-        .to_request();
-
-    let resp = test::call_service(&app, req).await;
+    // Same identifiers written differently
+    let taken = [
+        json!({ "email": "AMANI@example.com", "phone": null, "password": PASSWORD }),
+        json!({ "email": null, "phone": "0712345678", "password": PASSWORD }),
+        json!({ "email": null, "phone": null, "username": "Amani", "password": PASSWORD }),
+    ];
+    for body in taken {
+        let (status, response) = send(&app, post("/auth/register", body.clone())).await;
+        assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+        assert_eq!(response["success"], false);
+    }
 
-    // Verify conflict response
-    assert_eq!(resp.status(), 409);
-    let response: ApiResponse<SignUpResponseData> = test::read_body_json(resp).await;
-    
-    assert!(!response.success);
-    assert!(response.message.contains("already registered"));
+    // Cyrillic "а" in place of the Latin one is refused outright
+    let lookalike = json!({ "email": null, "phone": null, "username": "аmani", "password": PASSWORD });
+    let (status, _) = send(&app, post("/auth/register", lookalike)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
 
-    Ok(())
+    let availability = test::TestRequest::get().uri("/auth/username/availability?username=AMANI");
+    let (status, body) = send(&app, availability).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["available"], false);
 }
 
-/// Integration test for phone already exists error
 #[actix_web::test]
-async fn test_phone_already_exists() -> Result<(), Box<dyn std::error::Error>> {
-    let setup = TestSetup::new()?;
-    let _cleanup_guard = CleanupGuard(&setup);
-
-    let test_user = TestUser::new();
-    
-    // Create first user
-    let create_input = CreateUserInput {
-        email: Some("different_email@example.com".to_string()),
-        phone: Some(test_user.phone.clone()),
-        username: Some(test_user.username.clone()),
-        password: test_user.password.clone(),
-        first_name: Some(test_user.first_name.clone()),
-        last_name: Some(test_user.last_name.clone()),
-    };
-    
-    setup.user_store.create(create_input)?;
-
-    // Try to register with same phone
-    let signup_request = crate::utils::types::SignUpRequest {
-        email: Some("new_email@example.com".to_string()),
-        phone: Some(test_user.phone.clone()),
-        username: Some("different_username".to_string()),
-        password: "DifferentPassword123!".to_string(),
-        first_name: Some("Different".to_string()),
-        last_name: Some("User".to_string()),
-    };
-
-    let app = test::init_service(
-        App::new()
-            .app_data(web::Data::new(setup.app_state.clone()))
-            .route("/signup", web::post().to(signup_user)),
+async fn test_login_with_any_identifier() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    signup(
+        &app,
+        json!({
+            "email": "amani@example.com",
+            "phone": "+254712345678",
+            "username": "amani",
+            "password": PASSWORD,
+        }),
     )
     .await;
 
-    let req = test::TestRequest::post()
-        .uri("/signup")
-        .set_json(&signup_request)
-        .to_request();
+    for identifier in ["Amani@Example.com", "0712 345 678", "AMANI"] {
+        let (access_token, refresh_token) = login(&app, identifier, PASSWORD).await;
+        assert!(!access_token.is_empty());
+        assert!(!refresh_token.is_empty());
+    }
 
-    let resp = test::call_service(&app, req).await;
+    // Wrong password and unknown user get the same answer
+    let wrong_password = json!({ "identifier": "amani", "password": "WrongPassword123!" });
+    let unknown_user = json!({ "identifier": "nobody", "password": PASSWORD });
+    let (status, wrong) = send(&app, post("/auth/login", wrong_password)).await;
+    let (unknown_status, unknown) = send(&app, post("/auth/login", unknown_user)).await;
 
-    // Verify conflict response
-    assert_eq!(resp.status(), 409);
-    let response: ApiResponse<SignInResponse> = test::read_body_json(resp).await;
-    
-    assert!(!response.success);
-    assert!(response.message.contains("already registered"));
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(unknown_status, status);
+    assert_eq!(wrong["message"], unknown["message"]);
+}
 
-    Ok(())
+/// Risk evaluator returning scripted decisions and recording the failure counts it saw
+#[derive(Default)]
+struct ScriptedRisk {
+    decisions: Mutex<Vec<RiskDecision>>,
+    failures:  Mutex<Vec<u64>>,
+}
+
+impl RiskEvaluator for ScriptedRisk {
+    fn evaluate(&self, attempt: &LoginAttempt) -> RiskAssessment {
+        self.failures.lock().unwrap().push(attempt.recent_failures);
+
+        let mut assessment = RiskAssessment::allow();
+        assessment.decision = self.decisions.lock().unwrap().pop().unwrap_or(RiskDecision::Allow);
+        assessment
+    }
 }
 
-/// Integration test for minimal user registration (password only)
 #[actix_web::test]
-async fn test_minimal_user_registration() -> Result<(), Box<dyn std::error::Error>> {
-    let setup = TestSetup::new()?;
-    let _cleanup_guard = CleanupGuard(&setup);
-
-    let signup_request = crate::utils::types::SignUpRequest {
-        email: None,
-        phone: None,
-        username: None,
-        password: "MinimalPassword123!".to_string(),
-        first_name: None,
-        last_name: None,
+async fn test_risk_checks_count_only_wrong_passwords() {
+    let risk = Arc::new(ScriptedRisk::default());
+    risk.decisions.lock().unwrap().push(RiskDecision::Deny);
+    let state = test_state().with_risk(risk.clone());
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let id = parse_id(&user_id).unwrap();
+
+    let body = json!({ "identifier": "amani@example.com", "password": "WrongPassword123!" });
+    let (status, _) = send(&app, post("/auth/login", body)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(state.users.find_by_id(&id).await.unwrap().unwrap().login_attempts, 1);
+
+    let body = json!({ "identifier": "amani@example.com", "password": PASSWORD });
+    let (status, _) = send(&app, post("/auth/login", body)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    login(&app, "amani@example.com", PASSWORD).await;
+
+    // The denial is in the audit log but is not a failure the next check sees
+    assert_eq!(*risk.failures.lock().unwrap(), vec![1, 1]);
+    assert_eq!(state.users.find_by_id(&id).await.unwrap().unwrap().login_attempts, 0);
+}
+
+#[actix_web::test]
+async fn test_refresh_rotates_tokens_and_logout_revokes() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let (access_token, refresh_token) = login(&app, "amani@example.com", PASSWORD).await;
+
+    let refresh = |access_token: &str, refresh_token: &str| {
+        bearer(
+            post("/auth/pt/refresh-token", json!({ "refresh_token": refresh_token })),
+            access_token,
+        )
     };
 
-    let app = test::init_service(
-        App::new()
-            .app_data(web::Data::new(setup.app_state.clone()))
-            .route("/signup", web::post().to(signup_user)),
+    let (status, body) = send(&app, refresh(&access_token, &refresh_token)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let access_token = body["data"]["access_token"].as_str().unwrap().to_string();
+    let new_refresh_token = body["data"]["refresh_token"].as_str().unwrap().to_string();
+    assert_ne!(new_refresh_token, refresh_token);
+
+    // The old refresh token was revoked by the rotation
+    let (status, _) = send(&app, refresh(&access_token, &refresh_token)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // The new access token is backed by a live session
+    let me = format!("/auth/pt/users/{}", user_id);
+    let (status, body) = send(&app, bearer(test::TestRequest::get().uri(&me), &access_token)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, _) = send(&app, bearer(post("/auth/pt/logout", json!({})), &access_token)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Logging out revokes the session behind the token
+    let (status, _) = send(&app, bearer(test::TestRequest::get().uri(&me), &access_token)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send(&app, refresh(&access_token, &new_refresh_token)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn test_refresh_keeps_active_organization() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let user_id = parse_id(&user_id).unwrap();
+    let org_id = generate_id();
+
+    // A session issued for an organization, as switching to it does
+    let mut claims = access_claims(&user_id.to_string(), Some("amani@example.com"), 60);
+    claims.org_id = Some(org_id.to_string());
+    let access_token = encode_access_token(&claims, JWT_SECRET).unwrap();
+    let refresh_token = generate_refresh_token();
+    state.sessions.create(CreateSession {
+        user_id:            user_id.clone(),
+        access_token_hash:  hash_sha256(&access_token),
+        refresh_token_hash: Some(hash_sha256(&refresh_token)),
+        device:             None,
+        ip_address:         None,
+        user_agent:         None,
+        org_id:             Some(org_id.clone()),
+        expires_in:         3600,
+    }).await.unwrap();
+    state.sessions.create_refresh_token(CreateRefreshToken {
+        user_id,
+        token_hash: hash_sha256(&refresh_token),
+        expires_in: 3600,
+    }).await.unwrap();
+
+    let req = bearer(post("/auth/pt/refresh-token", json!({ "refresh_token": refresh_token })), &access_token);
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let refreshed = body["data"]["access_token"].as_str().unwrap();
+    let claims = jsonwebtoken::decode::<Claims>(
+        refreshed,
+        &jsonwebtoken::DecodingKey::from_secret(JWT_SECRET.as_bytes()),
+        &jsonwebtoken::Validation::default(),
     )
-    .await;
+    .unwrap()
+    .claims;
+    assert_eq!(claims.org_id, Some(org_id.to_string()));
+}
 
-    let req = test::TestRequest::post()
-        .uri("/signup")
-        .set_json(&signup_request)
-        .to_request();
-
-    let resp = test::call_service(&app, req).await;
-
-    // Verify response
-    assert_eq!(resp.status(), 200);
-    let response: ApiResponse<SignUpResponseData> = test::read_body_json(resp).await;
-    
-    assert!(response.success);
-    assert_eq!(response.message, "User registered successfully");
-    assert!(response.data.is_some());
-    
-    let user_data = response.data.unwrap();
-    assert!(!user_data.user_id.is_empty());
-    assert_eq!(user_data.email, None);
-
-    // Verify user was created in database
-    let user = setup.user_store.find_by_id(&DbId::from_bytes(
-        ObjectId::parse_str(&user_data.user_id)?.bytes()
-    ))?;
-    assert!(user.is_some());
-    
-    let user = user.unwrap();
-    assert_eq!(user.email, None);
-    assert_eq!(user.phone, None);
-    assert_eq!(user.username, None);
-    assert_eq!(user.first_name, None);
-    assert_eq!(user.last_name, None);
-    assert_eq!(user.is_active, true);
-    assert_eq!(user.is_verified, false);
+#[actix_web::test]
+async fn test_impersonated_session_cannot_switch_organization() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let user_id = parse_id(&user_id).unwrap();
+
+    // A session issued to an admin impersonating the user
+    let mut claims = access_claims(&user_id.to_string(), Some("amani@example.com"), 15);
+    claims.act = Some(ActorClaim { sub: generate_id().to_string() });
+    let access_token = encode_access_token(&claims, JWT_SECRET).unwrap();
+    state.sessions.create(CreateSession {
+        user_id,
+        access_token_hash:  hash_sha256(&access_token),
+        refresh_token_hash: None,
+        device:             None,
+        ip_address:         None,
+        user_agent:         None,
+        org_id:             None,
+        expires_in:         900,
+    }).await.unwrap();
+
+    let uri = format!("/auth/pt/orgs/{}/switch", generate_id());
+    let (status, body) = send(&app, bearer(post(&uri, json!({})), &access_token)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{}", body);
+}
 
-    // Verify password was hashed
-    assert_ne!(user.password_hash, signup_request.password);
-    assert!(user.password_hash.starts_with("$argon2"));
+#[actix_web::test]
+async fn test_organization_session_is_scoped_to_its_organization() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let id = parse_id(&user_id).unwrap();
+
+    let organizations = state.organizations.clone().unwrap();
+    let mut org_ids = Vec::new();
+    for slug in ["acme", "globex"] {
+        let org = organizations.create(CreateOrganization {
+            name:     slug.to_string(),
+            slug:     slug.to_string(),
+            owner_id: id.clone(),
+        }).await.unwrap();
+        organizations.add_member(CreateMembership {
+            org_id:  org.id.clone(),
+            user_id: id.clone(),
+            role:    OrgRole::Owner,
+        }).await.unwrap();
+        org_ids.push(org.id);
+    }
+
+    // A session switched into the first organization can't reach the second
+    let mut claims = access_claims(&user_id, Some("amani@example.com"), 15);
+    claims.org_id = Some(org_ids[0].to_string());
+    let access_token = session_for(&state, &claims).await;
+
+    let req = test::TestRequest::get().uri(&format!("/auth/pt/orgs/{}", org_ids[0]));
+    let (status, body) = send(&app, bearer(req, &access_token)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    for path in ["", "/members", "/invitations"] {
+        let req = test::TestRequest::get().uri(&format!("/auth/pt/orgs/{}{}", org_ids[1], path));
+        let (status, body) = send(&app, bearer(req, &access_token)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{}: {}", path, body);
+    }
 
-    Ok(())
+    // Without an organization in the session, membership alone decides
+    let access_token = session_for(&state, &access_claims(&user_id, Some("amani@example.com"), 30)).await;
+    let req = test::TestRequest::get().uri(&format!("/auth/pt/orgs/{}", org_ids[1]));
+    let (status, body) = send(&app, bearer(req, &access_token)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
 }
 
-/// Integration test for user registration with only email
 #[actix_web::test]
-async fn test_user_registration_with_email_only() -> Result<(), Box<dyn std::error::Error>> {
-    let setup = TestSetup::new()?;
-    let _cleanup_guard = CleanupGuard(&setup);
-
-    let test_user = TestUser::new();
-    let signup_request = crate::utils::types::SignUpRequest {
-        email: Some(test_user.email.clone()),
-        phone: None,
-        username: None,
-        password: test_user.password.clone(),
-        first_name: None,
-        last_name: None,
+async fn test_organization_invitation_requires_a_verified_email() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let id = parse_id(&user_id).unwrap();
+    let (access_token, _) = login(&app, "amani@example.com", PASSWORD).await;
+
+    let organizations = state.organizations.clone().unwrap();
+    let org_id = generate_id();
+    organizations.create_invitation(CreateOrgInvitation {
+        org_id:     org_id.clone(),
+        email:      "amani@example.com".to_string(),
+        role:       OrgRole::Member,
+        token_hash: hash_sha256("invitation-token"),
+        invited_by: generate_id(),
+        expires_in: 3600,
+    }).await.unwrap();
+
+    // Signing up with the invited address isn't enough
+    let accept = || bearer(post("/auth/pt/orgs/invitations/accept", json!({ "token": "invitation-token" })), &access_token);
+    let (status, body) = send(&app, accept()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", body);
+    assert!(organizations.find_membership(&org_id, &id).await.unwrap().is_none());
+
+    state.users.set_verified(&id, true).await.unwrap();
+    let (status, body) = send(&app, accept()).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(organizations.find_membership(&org_id, &id).await.unwrap().is_some());
+}
+
+#[actix_web::test]
+async fn test_passkey_second_factor_refreshes_the_session() {
+    let state = test_state().with_webauthn(Some(WebAuthnConfig::new(RP_ID, "Example", ORIGIN)));
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+
+    // A password session signed in an hour ago
+    let mut claims = access_claims(&user_id, Some("amani@example.com"), 15);
+    claims.auth_time = Some(claims.iat - 3600);
+    claims.amr = vec![AMR_PASSWORD.to_string()];
+    let stale_token = session_for(&state, &claims).await;
+
+    let mut authenticator = SoftAuthenticator::new();
+    let req = bearer(post("/auth/pt/passkeys/register/options", json!({ "name": null })), &stale_token);
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let credential = authenticator.register(body["data"]["challenge"].as_str().unwrap(), "none");
+    let req = post("/auth/pt/passkeys/register/verify", json!({ "name": "laptop", "credential": credential }));
+    let (status, body) = send(&app, bearer(req, &stale_token)).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+
+    let delete_account = |token: &str| {
+        bearer(test::TestRequest::delete().uri(&format!("/auth/pt/users/{}", user_id)), token)
     };
+    let (status, _) = send(&app, delete_account(&stale_token)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = send(&app, bearer(post("/auth/pt/2fa/passkey/options", json!({})), &stale_token)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let credential = authenticator.assert(body["data"]["challenge"].as_str().unwrap(), ORIGIN);
+    let req = post("/auth/pt/2fa/passkey/verify", json!({ "credential": credential }));
+    let (status, body) = send(&app, bearer(req, &stale_token)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["amr"], json!([AMR_PASSWORD, AMR_PASSKEY]));
+
+    // The session moved to the new token, which passes the step-up check
+    let fresh_token = body["data"]["access_token"].as_str().unwrap();
+    let (status, _) = send(&app, delete_account(&stale_token)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, body) = send(&app, delete_account(fresh_token)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+}
 
-    let app = test::init_service(
-        App::new()
-            .app_data(web::Data::new(setup.app_state.clone()))
-            .route("/signup", web::post().to(signup_user)),
-    )
-    .await;
+#[actix_web::test]
+async fn test_email_verification() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let id = parse_id(&user_id).unwrap();
+
+    // Signup already created a code
+    let verifications = state.verifications.clone().unwrap();
+    let first = verifications
+        .find_valid_code(&id, VerificationMedium::Email, VerificationPurpose::SignUp)
+        .await
+        .unwrap()
+        .expect("signup creates a code");
+
+    let send_code = json!({ "identifier": "amani@example.com", "medium": "email" });
+    let (status, _) = send(&app, post("/auth/verification/send", send_code)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let sent = verifications
+        .find_valid_code(&id, VerificationMedium::Email, VerificationPurpose::SignUp)
+        .await
+        .unwrap()
+        .expect("a code was sent");
+    assert_ne!(sent.id, first.id);
+
+    // Codes are only stored hashed - start over to learn the plain code
+    let user = state.users.find_by_id(&id).await.unwrap().unwrap();
+    let (_, code) = state
+        .user_service()
+        .start_verification(&user, VerificationMedium::Email)
+        .await
+        .unwrap();
+    assert!(verifications.find_by_id(&sent.id).await.unwrap().is_none(), "earlier code is replaced");
+
+    let verify = format!("/auth/verify/{}", user_id);
+    let (status, _) = send(&app, post(&verify, json!({ "code": "000000x", "medium": "email" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(&app, post(&verify, json!({ "code": code, "medium": "email" }))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(state.users.find_by_id(&id).await.unwrap().unwrap().is_verified);
+
+    // The code can't be used twice
+    let (status, _) = send(&app, post(&verify, json!({ "code": code, "medium": "email" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
 
-    let req = test::TestRequest::post()
-        .uri("/signup")
-        .set_json(&signup_request)
-        .to_request();
-
-    let resp = test::call_service(&app, req).await;
-
-    // Verify response
-    assert_eq!(resp.status(), 200);
-    let response: ApiResponse<SignUpResponseData> = test::read_body_json(resp).await;
-    
-    assert!(response.success);
-    assert_eq!(response.message, "User registered successfully");
-    assert!(response.data.is_some());
-    
-    let user_data = response.data.unwrap();
-    assert!(!user_data.user_id.is_empty());
-    assert_eq!(user_data.email, Some(test_user.email.clone()));
-
-    // Verify user was created in database
-    let user = setup.user_store.find_by_email(&test_user.email)?;
-    assert!(user.is_some());
-    
-    let user = user.unwrap();
-    assert_eq!(user.email, Some(test_user.email));
-    assert_eq!(user.phone, None);
-    assert_eq!(user.username, None);
-    assert_eq!(user.first_name, None);
-    assert_eq!(user.last_name, None);
-    assert_eq!(user.is_active, true);
-    assert_eq!(user.is_verified, false);
+#[actix_web::test]
+async fn test_password_reset() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let id = parse_id(&user_id).unwrap();
+
+    login(&app, "amani@example.com", PASSWORD).await;
+
+    let (status, _) = send(&app, post("/auth/forgot-password", json!({ "identifier": "amani@example.com" }))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Tokens are only stored hashed - replace it with one we know
+    let password_resets = state.password_resets.clone().unwrap();
+    assert!(password_resets.find_valid_token(&id).await.unwrap().is_some());
+    password_resets.delete_all_for_user(&id).await.unwrap();
+    password_resets
+        .create(CreatePasswordResetToken {
+            user_id:    id.clone(),
+            token_hash: hash_sha256("known-reset-token"),
+            expires_in: 300,
+        })
+        .await
+        .unwrap();
+
+    let new_password = "AnotherPassword456!";
+    let reset = json!({
+        "token": "known-reset-token",
+        "new_password": new_password,
+        "confirm_password": new_password,
+    });
+    let (status, body) = send(&app, post("/auth/reset-password", reset.clone())).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    // Sessions from before the reset are revoked with it
+    let sessions = state.sessions.find_by_user_id(&id).await.unwrap();
+    assert!(!sessions.is_empty() && sessions.iter().all(|s| !s.is_valid()));
+
+    login(&app, "amani@example.com", new_password).await;
+    let old = json!({ "identifier": "amani@example.com", "password": PASSWORD });
+    let (status, _) = send(&app, post("/auth/login", old)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Reset tokens work once
+    let (status, _) = send(&app, post("/auth/reset-password", reset)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn test_password_reset_needs_no_session() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    // Never signed in, so there is no session or access token to send
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    state.password_resets.clone().unwrap()
+        .create(CreatePasswordResetToken {
+            user_id:    parse_id(&user_id).unwrap(),
+            token_hash: hash_sha256("known-reset-token"),
+            expires_in: 300,
+        })
+        .await
+        .unwrap();
+
+    let new_password = "AnotherPassword456!";
+    let reset = json!({
+        "token": "known-reset-token",
+        "new_password": new_password,
+        "confirm_password": new_password,
+    });
+    let (status, body) = send(&app, post("/auth/reset-password", reset)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    login(&app, "amani@example.com", new_password).await;
+}
 
-    Ok(())
+#[actix_web::test]
+async fn test_profile_update() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    signup(&app, json!({ "email": "taken@example.com", "phone": null, "username": "taken", "password": PASSWORD })).await;
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let (access_token, _) = login(&app, "amani@example.com", PASSWORD).await;
+
+    let uri = format!("/auth/pt/users/{}", user_id);
+    let update = |body: Value| bearer(test::TestRequest::put().uri(&uri).set_json(body), &access_token);
+
+    let (status, body) = send(&app, update(json!({ "first_name": "Amani", "username": "Amani_K" }))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["username"], "amani_k");
+    assert_eq!(body["data"]["first_name"], "Amani");
+
+    let (status, _) = send(&app, update(json!({ "username": "TAKEN" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // Users can only edit their own account
+    let other = signup(&app, json!({ "email": "other@example.com", "phone": null, "password": PASSWORD })).await;
+    let req = test::TestRequest::put()
+        .uri(&format!("/auth/pt/users/{}", other))
+        .set_json(json!({ "first_name": "Mallory" }));
+    let (status, _) = send(&app, bearer(req, &access_token)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn test_api_keys_only_reach_scoped_routes() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/auth/pt/users/{}", user_id))
+        .insert_header(("X-API-Key", "ak_1a2b3c4d_key"));
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["message"], "API keys are not accepted on this route");
+
+    // The audit log checks key scopes, so keys get as far as validation
+    let req = test::TestRequest::get()
+        .uri("/auth/pt/audit-events")
+        .insert_header(("X-API-Key", "ak_1a2b3c4d_key"));
+    let (status, _) = send(&app, req).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
 }
 
-/// Integration test for user registration with only phone
 #[actix_web::test]
-async fn test_user_registration_with_phone_only() -> Result<(), Box<dyn std::error::Error>> {
-    let setup = TestSetup::new()?;
-    let _cleanup_guard = CleanupGuard(&setup);
-
-    let test_user = TestUser::new();
-    let signup_request = crate::utils::types::SignUpRequest {
-        email: None,
-        phone: Some(test_user.phone.clone()),
-        username: None,
-        password: test_user.password.clone(),
-        first_name: None,
-        last_name: None,
+async fn test_api_keys_stop_working_when_the_owner_is_deactivated() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let user_id = parse_id(&user_id).unwrap();
+    let generated = ApiKeyService::generate_key();
+    state.api_keys.clone().unwrap().create(CreateApiKey {
+        user_id:               user_id.clone(),
+        name:                  "reports".to_string(),
+        key_prefix:            generated.prefix.clone(),
+        key_hash:              generated.hash.clone(),
+        scopes:                vec![AUDIT_READ_SCOPE.to_string()],
+        rate_limit_per_minute: None,
+        expires_in:            None,
+    }).await.unwrap();
+
+    let audit_events = || {
+        test::TestRequest::get()
+            .uri("/auth/pt/audit-events")
+            .insert_header(("X-API-Key", generated.raw.as_str()))
     };
 
-    let app = test::init_service(
-        App::new()
-            .app_data(web::Data::new(setup.app_state.clone()))
-            .route("/signup", web::post().to(signup_user)),
-    )
-    .await;
+    // The key is accepted; the owner just isn't an admin
+    let (status, body) = send(&app, audit_events()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{}", body);
 
-    let req = test::TestRequest::post()
-        .uri("/signup")
-        .set_json(&signup_request)
-        .to_request();
-
-    let resp = test::call_service(&app, req).await;
-
-    // Verify response
-    assert_eq!(resp.status(), 200);
-    let response: ApiResponse<SignUpResponseData> = test::read_body_json(resp).await;
-    
-    assert!(response.success);
-    assert_eq!(response.message, "User registered successfully");
-    assert!(response.data.is_some());
-    
-    let user_data = response.data.unwrap();
-    assert!(!user_data.user_id.is_empty());
-    assert_eq!(user_data.email, None);
-
-    // Verify user was created in database
-    let user = setup.user_store.find_by_phone(&test_user.phone)?;
-    assert!(user.is_some());
-    
-    let user = user.unwrap();
-    assert_eq!(user.email, None);
-    assert_eq!(user.phone, Some(test_user.phone));
-    assert_eq!(user.username, None);
-    assert_eq!(user.first_name, None);
-    assert_eq!(user.last_name, None);
-    assert_eq!(user.is_active, true);
-    assert_eq!(user.is_verified, false);
+    state.users.set_active(&user_id, false).await.unwrap();
+    let (status, body) = send(&app, audit_events()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", body);
+}
+
+#[actix_web::test]
+async fn test_purging_deleted_users_requires_admin() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let (access_token, _) = login(&app, "amani@example.com", PASSWORD).await;
 
-    Ok(())
+    let req = bearer(post("/auth/pt/users/deletions/purge", json!({})), &access_token);
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{}", body);
 }
 
-/// Cleanup guard to ensure test data is cleaned up
-struct CleanupGuard<'a>(&'a TestSetup);
+/// Give a user an API key and return the raw key
+async fn create_api_key(state: &AppState, user_id: &DbId) -> String {
+    let generated = ApiKeyService::generate_key();
+    state.api_keys.clone().unwrap().create(CreateApiKey {
+        user_id:               user_id.clone(),
+        name:                  "reports".to_string(),
+        key_prefix:            generated.prefix.clone(),
+        key_hash:              generated.hash.clone(),
+        scopes:                vec![AUDIT_READ_SCOPE.to_string()],
+        rate_limit_per_minute: None,
+        expires_in:            None,
+    }).await.unwrap();
+
+    generated.raw
+}
 
-impl<'a> Drop for CleanupGuard<'a> {
-    fn drop(&mut self) {
-        let _ = self.0.cleanup();
-    }
+#[actix_web::test]
+async fn test_account_deletion_can_be_cancelled() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let id = parse_id(&user_id).unwrap();
+    create_api_key(&state, &id).await;
+    let (access_token, _) = login(&app, "amani@example.com", PASSWORD).await;
+
+    let req = bearer(test::TestRequest::delete().uri(&format!("/auth/pt/users/{}", user_id)), &access_token);
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    // Sessions and API keys stop working straight away
+    let user = state.users.find_by_id(&id).await.unwrap().unwrap();
+    assert!(user.is_deletion_scheduled());
+    assert!(state.sessions.find_by_user_id(&id).await.unwrap().iter().all(|s| s.is_revoked));
+    let keys = state.api_keys.clone().unwrap().list_by_user(&id).await.unwrap();
+    assert!(keys.iter().all(|k| k.is_revoked));
+
+    // A sign-in within the same second would reuse the revoked token
+    let access_token = session_for(&state, &access_claims(&user_id, Some("amani@example.com"), 30)).await;
+    let uri = format!("/auth/pt/users/{}/deletion/cancel", user_id);
+    let (status, body) = send(&app, bearer(post(&uri, json!({})), &access_token)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let user = state.users.find_by_id(&id).await.unwrap().unwrap();
+    assert!(!user.is_deletion_scheduled());
+    assert!(account::purge_due(&state).await.unwrap().purged.is_empty());
 }
 
-/// Helper function to create a test user in the database
-pub async fn create_test_user(
-    setup: &TestSetup,
-    test_user: &TestUser,
-) -> Result<User, Box<dyn std::error::Error>> {
-    let create_input = CreateUserInput {
-        email: Some(test_user.email.clone()),
-        phone: Some(test_user.phone.clone()),
-        username: Some(test_user.username.clone()),
-        password: test_user.password.clone(),
-        first_name: Some(test_user.first_name.clone()),
-        last_name: Some(test_user.last_name.clone()),
-    };
-    
-    let user = setup.user_store.create(create_input)?;
-    Ok(user)
-}
-
-/// Helper function to verify user exists in database
-pub async fn verify_user_exists(
-    setup: &TestSetup,
-    email: &str,
-) -> Result<User, Box<dyn std::error::Error>> {
-    let user = setup.user_store.find_by_email(email)?;
-    assert!(user.is_some(), "User should exist in database");
-    Ok(user.unwrap())
-}
-
-/// Helper function to verify password is properly hashed
-pub fn verify_password_hashed(user: &User, original_password: &str) {
-    assert_ne!(user.password_hash, original_password, "Password should be hashed");
-    assert!(user.password_hash.starts_with("$argon2"), "Password should use Argon2 hashing");
-}
-
-/// Helper function to verify user fields
-pub fn verify_user_fields(
-    user: &User,
-    expected_email: Option<String>,
-    expected_phone: Option<String>,
-    expected_username: Option<String>,
-    expected_first_name: Option<String>,
-    expected_last_name: Option<String>,
-) {
-    assert_eq!(user.email, expected_email);
-    assert_eq!(user.phone, expected_phone);
-    assert_eq!(user.username, expected_username);
-    assert_eq!(user.first_name, expected_first_name);
-    assert_eq!(user.last_name, expected_last_name);
-    assert_eq!(user.is_active, true);
-    assert_eq!(user.is_verified, false);
-    assert_eq!(user.failed_login_attempts, 0);
-    assert!(user.created_at > 0);
-}
\ No newline at end of file
+#[actix_web::test]
+async fn test_account_purge_removes_personal_data() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let id = parse_id(&user_id).unwrap();
+    create_api_key(&state, &id).await;
+    let (access_token, _) = login(&app, "amani@example.com", PASSWORD).await;
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/auth/pt/users/{}", user_id))
+        .insert_header(("User-Agent", "integration-test"));
+    let (status, body) = send(&app, bearer(req, &access_token)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let filter = AuditFilter { actor_id: Some(id.clone()), ..Default::default() };
+    let events = state.audit.clone().unwrap().list(&filter, 0, 100).await.unwrap();
+    assert!(events.iter().any(|e| e.user_agent.as_deref() == Some("integration-test")));
+
+    // Not due until the grace period has passed
+    assert!(account::purge_due(&state).await.unwrap().purged.is_empty());
+    state.users.schedule_deletion(&id, Some(chrono::Utc::now() - chrono::Duration::minutes(1))).await.unwrap();
+
+    let report = account::purge_due(&state).await.unwrap();
+    assert_eq!(report.purged, vec![id.clone()]);
+    assert!(report.failed.is_empty());
+
+    let user = state.users.find_by_id(&id).await.unwrap().unwrap();
+    assert!(user.deleted_at.is_some());
+    assert!(user.email.is_none());
+    assert!(state.users.find_by_email("amani@example.com").await.unwrap().is_none());
+    assert!(state.sessions.find_by_user_id(&id).await.unwrap().is_empty());
+    assert!(state.api_keys.clone().unwrap().list_by_user(&id).await.unwrap().is_empty());
+
+    // The audit trail stays, without the network details
+    let events = state.audit.clone().unwrap().list(&filter, 0, 100).await.unwrap();
+    assert!(events.iter().any(|e| e.action == AuditAction::AccountDeletionScheduled));
+    assert!(events.iter().all(|e| e.ip_address.is_none() && e.user_agent.is_none()));
+}
+
+#[actix_web::test]
+async fn test_account_purge_keeps_an_owner_for_every_organization() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let other_id = signup(&app, json!({ "email": "baraka@example.com", "phone": null, "password": PASSWORD })).await;
+    let (id, other_id) = (parse_id(&user_id).unwrap(), parse_id(&other_id).unwrap());
+
+    let organizations = state.organizations.clone().unwrap();
+    let org_id = generate_id();
+    let member = |user_id: &DbId, role| CreateMembership { org_id: org_id.clone(), user_id: user_id.clone(), role };
+    organizations.add_member(member(&id, OrgRole::Owner)).await.unwrap();
+    organizations.add_member(member(&other_id, OrgRole::Member)).await.unwrap();
+
+    // The sole owner can't even schedule deletion
+    let user = state.users.find_by_id(&id).await.unwrap().unwrap();
+    assert!(account::schedule_deletion(&state, &user).await.is_err());
+
+    // Ownership can still be lost during the grace period, so the purge checks again
+    state.users.schedule_deletion(&id, Some(chrono::Utc::now() - chrono::Duration::minutes(1))).await.unwrap();
+    let report = account::purge_due(&state).await.unwrap();
+    assert!(report.purged.is_empty());
+    assert_eq!(report.failed.len(), 1);
+    assert!(organizations.find_membership(&org_id, &id).await.unwrap().is_some());
+    assert!(state.users.find_by_id(&id).await.unwrap().unwrap().deleted_at.is_none());
+
+    organizations.update_member_role(&org_id, &other_id, OrgRole::Owner).await.unwrap();
+    let report = account::purge_due(&state).await.unwrap();
+    assert_eq!(report.purged, vec![id.clone()]);
+    assert!(organizations.find_membership(&org_id, &id).await.unwrap().is_none());
+}
+
+#[actix_web::test]
+async fn test_contact_change_revert_keeps_addresses_unique() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let id = parse_id(&user_id).unwrap();
+
+    // A confirmed change away from the original address
+    let contact_changes = state.contact_changes.clone().unwrap();
+    let change = contact_changes.create(CreateContactChange {
+        user_id:           id.clone(),
+        medium:            VerificationMedium::Email,
+        old_value:         Some("amani@example.com".to_string()),
+        new_value:         "amani@example.org".to_string(),
+        code_hash:         hash_sha256("123456"),
+        revert_token_hash: Some(hash_sha256("revert-token")),
+        expires_in:        900,
+        revert_expires_in: 3600,
+    }).await.unwrap();
+    contact_changes.mark_confirmed(&change.id).await.unwrap();
+    state.users.set_contact(&id, &VerificationMedium::Email, "amani@example.org").await.unwrap();
+
+    // The released address is taken before the revert link is used
+    signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+
+    let revert = || post("/auth/contact/revert", json!({ "token": "revert-token" }));
+    let (status, body) = send(&app, revert()).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+
+    let user = state.users.find_by_id(&id).await.unwrap().unwrap();
+    assert_eq!(user.email.as_deref(), Some("amani@example.org"));
+    let change = contact_changes.find_by_revert_token(&hash_sha256("revert-token")).await.unwrap().unwrap();
+    assert!(change.can_revert());
+}
+
+#[actix_web::test]
+async fn test_account_export_contents() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    let user_id = signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let raw_key = create_api_key(&state, &parse_id(&user_id).unwrap()).await;
+    let (access_token, _) = login(&app, "amani@example.com", PASSWORD).await;
+
+    let req = test::TestRequest::get().uri(&format!("/auth/pt/users/{}/export", user_id));
+    let (status, body) = send(&app, bearer(req, &access_token)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    assert_eq!(body["profile"]["id"], user_id);
+    assert_eq!(body["profile"]["email"], "amani@example.com");
+    assert_eq!(body["sessions"].as_array().unwrap().len(), 1);
+    assert_eq!(body["api_keys"].as_array().unwrap().len(), 1);
+    assert_eq!(body["api_keys"][0]["name"], "reports");
+
+    // Secrets stay out of the export
+    let exported = body.to_string();
+    assert!(!exported.contains(&raw_key));
+    assert!(!exported.contains("password"));
+}
+
+#[actix_web::test]
+async fn test_only_admins_invite_new_users() {
+    let state = test_state();
+    let app = test_app!(state);
+
+    signup(&app, json!({ "email": "amani@example.com", "phone": null, "password": PASSWORD })).await;
+    let (access_token, _) = login(&app, "amani@example.com", PASSWORD).await;
+
+    // Inviting creates the account, which would claim the address
+    let uri = format!("/auth/pt/orgs/{}/users/invite", generate_id());
+    let req = bearer(post(&uri, json!({ "email": "squatted@example.com" })), &access_token);
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{}", body);
+
+    let squatted = state.users.find_by_email("squatted@example.com").await.unwrap();
+    assert!(squatted.is_none());
+}
@@ -0,0 +1,119 @@
+//! Audit logging helpers
+//!
+//! Handlers describe what happened with an `AuditEntry` and record it against
+//! the request. The IP address and user agent come from the request, and the
+//! actor and organization default to the caller's claims on protected routes
+//! (for impersonation tokens the actor is the admin, not the user).
+//! Recording never fails the request - an unavailable audit store is logged.
+
+use actix_web::HttpRequest;
+
+use crate::models::audit::{AuditAction, AuditOutcome, CreateAuditEvent};
+use crate::routes::AppState;
+use database::utils::{DbId, parse_id};
+use middleware::jwt::JwtClaims;
+
+/// Audit event under construction
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    event: CreateAuditEvent,
+}
+
+impl AuditEntry {
+    fn new(action: AuditAction, outcome: AuditOutcome) -> Self {
+        Self {
+            event: CreateAuditEvent {
+                action,
+                outcome,
+                actor_id: None,
+                target_id: None,
+                impersonator_id: None,
+                org_id: None,
+                ip_address: None,
+                user_agent: None,
+                reason: None,
+            },
+        }
+    }
+
+    /// Action that went through
+    pub fn success(action: AuditAction) -> Self {
+        Self::new(action, AuditOutcome::Success)
+    }
+
+    /// Action that was refused, with a short machine-readable reason
+    pub fn failure(action: AuditAction, reason: &str) -> Self {
+        Self::new(action, AuditOutcome::Failure).reason(reason)
+    }
+
+    pub fn actor(mut self, actor_id: &DbId) -> Self {
+        self.event.actor_id = Some(actor_id.clone());
+        self
+    }
+
+    pub fn target(mut self, target_id: &DbId) -> Self {
+        self.event.target_id = Some(target_id.clone());
+        self
+    }
+
+    /// Actor and target are the same user (logins, own account changes)
+    pub fn user(self, user_id: &DbId) -> Self {
+        self.actor(user_id).target(user_id)
+    }
+
+    pub fn org(mut self, org_id: &DbId) -> Self {
+        self.event.org_id = Some(org_id.clone());
+        self
+    }
+
+    pub fn reason(mut self, reason: &str) -> Self {
+        self.event.reason = Some(reason.to_string());
+        self
+    }
+
+    /// Fill in request details and write the event
+    pub async fn record(mut self, state: &AppState, req: &HttpRequest) {
+        let Some(store) = state.audit.as_ref() else {
+            return;
+        };
+
+        if let Some(claims) = req.claims() {
+            if self.event.actor_id.is_none() {
+                self.event.actor_id = parse_id(&claims.sub).ok();
+            }
+            // The admin behind an impersonation token is kept whoever the actor is
+            self.event.impersonator_id = claims.act.as_ref().and_then(|act| parse_id(&act.sub).ok());
+            if self.event.org_id.is_none() {
+                self.event.org_id = claims.org_id.as_deref().and_then(|id| parse_id(id).ok());
+            }
+        }
+
+        self.event.ip_address = req.connection_info().realip_remote_addr().map(String::from);
+        self.event.user_agent = req
+            .headers()
+            .get("user-agent")
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+
+        if let Err(e) = store.record(self.event).await {
+            crate::warn!("Failed to record audit event: {}", e.message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use database::utils::generate_id;
+
+    #[test]
+    fn test_entry_builder() {
+        let user_id = generate_id();
+        let entry = AuditEntry::failure(AuditAction::Login, "invalid_credentials").user(&user_id);
+
+        assert_eq!(entry.event.outcome, AuditOutcome::Failure);
+        assert_eq!(entry.event.actor_id, Some(user_id.clone()));
+        assert_eq!(entry.event.target_id, Some(user_id));
+        assert_eq!(entry.event.reason.as_deref(), Some("invalid_credentials"));
+    }
+}
@@ -52,6 +52,18 @@ impl std::fmt::Display for AuthError {
 
 impl std::error::Error for AuthError {}
 
+/// Database failures surface as internal errors
+impl From<database::utils::DbError> for AuthError {
+    fn from(error: database::utils::DbError) -> Self {
+        // The driver message names indexes and values, so it stays in the logs
+        if error.is_duplicate_key() {
+            crate::warn!("Duplicate key: {}", error.message);
+            return AuthError::conflict("Resource already exists");
+        }
+        AuthError::internal_error(&error.to_string())
+    }
+}
+
 impl actix_web::ResponseError for AuthError {
     fn error_response(&self) -> actix_web::HttpResponse {
         actix_web::HttpResponse::build(self.status_code()).json(self.to_response::<()>())
@@ -68,6 +80,10 @@ impl actix_web::ResponseError for AuthError {
             AuthErrorCode::EmailAlreadyExists
             | AuthErrorCode::PhoneAlreadyExists
             | AuthErrorCode::UsernameAlreadyExists => actix_web::http::StatusCode::CONFLICT,
+            AuthErrorCode::InvalidEmail
+            | AuthErrorCode::InvalidPhoneNumber
+            | AuthErrorCode::InvalidUsername
+            | AuthErrorCode::WeakPassword => actix_web::http::StatusCode::BAD_REQUEST,
             AuthErrorCode::InvalidCredentials
             | AuthErrorCode::AccountLocked
             | AuthErrorCode::AccountNotVerified
@@ -75,12 +91,13 @@ impl actix_web::ResponseError for AuthError {
             AuthErrorCode::InvalidResetToken | AuthErrorCode::ResetTokenExpired => {
                 actix_web::http::StatusCode::BAD_REQUEST
             }
-            AuthErrorCode::InvalidVerificationCode | AuthErrorCode::VerificationCodeExpired => {
-                actix_web::http::StatusCode::BAD_REQUEST
-            }
+            AuthErrorCode::InvalidVerificationCode
+            | AuthErrorCode::VerificationCodeExpired
+            | AuthErrorCode::AlreadyVerified => actix_web::http::StatusCode::BAD_REQUEST,
             AuthErrorCode::SessionExpired
             | AuthErrorCode::InvalidSession
-            | AuthErrorCode::SessionRevoked => actix_web::http::StatusCode::UNAUTHORIZED,
+            | AuthErrorCode::SessionRevoked
+            | AuthErrorCode::InvalidPasskey => actix_web::http::StatusCode::UNAUTHORIZED,
             _ => actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -128,6 +145,9 @@ pub enum AuthErrorCode {
     SessionExpired,
     InvalidSession,
     SessionRevoked,
+
+    // Passkey errors
+    InvalidPasskey,
 }
 
 impl std::fmt::Display for AuthErrorCode {
@@ -160,6 +180,7 @@ impl std::fmt::Display for AuthErrorCode {
             AuthErrorCode::SessionExpired => "SESSION_EXPIRED",
             AuthErrorCode::InvalidSession => "INVALID_SESSION",
             AuthErrorCode::SessionRevoked => "SESSION_REVOKED",
+            AuthErrorCode::InvalidPasskey => "INVALID_PASSKEY",
         };
         write!(f, "{}", s)
     }
@@ -221,6 +242,13 @@ impl AuthError {
         )
     }
 
+    pub fn invalid_phone_number(phone: &str) -> Self {
+        Self::new(
+            AuthErrorCode::InvalidPhoneNumber,
+            format!("Invalid phone number: {}", phone),
+        )
+    }
+
     pub fn invalid_username(username: &str) -> Self {
         Self::new(
             AuthErrorCode::InvalidUsername,
@@ -323,7 +351,34 @@ impl AuthError {
     pub fn invalid_session() -> Self {
         Self::new(AuthErrorCode::InvalidSession, "Invalid session")
     }
+
+    // Organization errors
+    pub fn last_owner() -> Self {
+        Self::invalid_request("An organization must keep at least one owner")
+    }
+
+    // Passkey errors
+    pub fn invalid_passkey(reason: &str) -> Self {
+        Self::new(
+            AuthErrorCode::InvalidPasskey,
+            format!("Passkey verification failed: {}", reason),
+        )
+    }
 }
 
 /// Result type for auth operations
 pub type AuthResult<T> = Result<T, AuthError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use database::utils::DbError;
+
+    #[test]
+    fn test_duplicate_key_hides_driver_message() {
+        let error = AuthError::from(DbError::duplicate_key("E11000 dup key: { email: \"amani@example.com\" }"));
+
+        assert!(matches!(error.code, AuthErrorCode::Conflict));
+        assert_eq!(error.message, "Resource already exists");
+    }
+}
@@ -0,0 +1,59 @@
+//! Email and phone identifiers
+//!
+//! Identifiers are normalized before they are stored or looked up, so the
+//! same address or number can't hold two accounts. Phone numbers use
+//! `utils::phone` (E.164); emails are trimmed and lowercased, and Gmail
+//! addresses can optionally be reduced to their canonical mailbox.
+
+const GMAIL_DOMAINS: &[&str] = &["gmail.com", "googlemail.com"];
+
+/// Normalize an email address
+///
+/// With `canonical_gmail`, dots and `+tags` are dropped from Gmail addresses
+/// (Gmail delivers `j.doe+news@gmail.com` to `jdoe@gmail.com`).
+pub fn normalize_email(email: &str, canonical_gmail: bool) -> Result<String, &'static str> {
+    let email = email.trim().to_lowercase();
+    let (local, domain) = email.split_once('@').ok_or("missing '@'")?;
+
+    if local.is_empty() || domain.contains('@') {
+        return Err("expected one address like name@example.com");
+    }
+    if !domain.contains('.') || domain.starts_with('.') || domain.ends_with('.') || domain.contains("..") {
+        return Err("invalid domain");
+    }
+    if email.chars().any(char::is_whitespace) {
+        return Err("must not contain spaces");
+    }
+
+    if canonical_gmail && GMAIL_DOMAINS.contains(&domain) {
+        let mailbox = local.split('+').next().unwrap_or_default().replace('.', "");
+        if mailbox.is_empty() {
+            return Err("invalid Gmail address");
+        }
+        return Ok(format!("{}@gmail.com", mailbox));
+    }
+
+    Ok(email)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_email() {
+        assert_eq!(normalize_email("  Amani@Example.COM ", false).unwrap(), "amani@example.com");
+        assert_eq!(normalize_email("J.Doe+news@gmail.com", false).unwrap(), "j.doe+news@gmail.com");
+        assert!(normalize_email("amani", false).is_err());
+        assert!(normalize_email("amani@localhost", false).is_err());
+        assert!(normalize_email("a@b@example.com", false).is_err());
+    }
+
+    #[test]
+    fn test_canonical_gmail() {
+        assert_eq!(normalize_email("J.Doe+news@gmail.com", true).unwrap(), "jdoe@gmail.com");
+        assert_eq!(normalize_email("jdoe@googlemail.com", true).unwrap(), "jdoe@gmail.com");
+        // Other providers treat dots as significant
+        assert_eq!(normalize_email("j.doe@example.com", true).unwrap(), "j.doe@example.com");
+    }
+}
@@ -1,4 +1,7 @@
+pub mod audit;
 pub mod errors;
+pub mod identifiers;
 pub mod passwords;
 pub mod session_validation;
 pub mod types;
+pub mod usernames;
@@ -1,6 +1,7 @@
 use crate::routes::AppState;
 use crate::utils::errors::AuthError;
 use actix_web::{HttpRequest, web};
+use middleware::jwt::JwtClaims;
 use middleware::token_validation::{ExtractedTokenInfo, validate_token_extraction};
 
 pub async fn validate_access_token(
@@ -11,8 +12,13 @@ pub async fn validate_access_token(
     let token_info =
         validate_token_extraction(req).map_err(|e| AuthError::unauthorized(&e.message))?;
 
+    // API keys are validated by the middleware and have no session
+    if req.api_key().is_some() {
+        return Ok(token_info);
+    }
+
     // Validate token exists in active session
-    match state.sessions.find_by_token(&token_info.token_hash) {
+    match state.sessions.find_by_token(&token_info.token_hash).await {
         Ok(Some(session)) => {
             if session.is_revoked {
                 return Err(AuthError::unauthorized("Access token has been revoked"));
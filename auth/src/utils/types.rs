@@ -8,9 +8,20 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use database::pagination::{CursorCodec, PageRequest};
 use utils::response::{ResponseMeta};
 
+use crate::models::api_key::ApiKey;
+use crate::models::audit::{AuditAction, AuditEvent, AuditOutcome};
+use crate::models::oauth::{OAuthAccount, OAuthProvider};
+use crate::models::organization::{Membership, OrgInvitation, OrgRole};
+use crate::models::passkey::{PasskeyCredential, PasskeyLoginVerifyRequest};
+use crate::models::profile::Profile;
+use crate::models::session::SessionModel;
+use crate::models::user::User;
+use crate::models::user_invitation::UserInvitation;
 use crate::models::verification::VerificationMedium;
+use crate::utils::errors::{AuthError, AuthResult};
 
 /// User without sensitive data (for public API responses)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,9 +31,34 @@ pub struct UserPublic {
     pub first_name:  Option<String>,
     pub last_name:   Option<String>,
     pub is_verified: bool,
+    #[serde(default, skip_serializing_if = "Profile::is_empty")]
+    pub profile:     Profile,
     pub created_at:  DateTime<Utc>,
 }
 
+impl From<&User> for UserPublic {
+    fn from(user: &User) -> Self {
+        Self {
+            id:          user.id.to_string(),
+            username:    user.username.clone().unwrap_or_default(),
+            first_name:  user.first_name.clone(),
+            last_name:   user.last_name.clone(),
+            is_verified: user.is_verified,
+            profile:     user.profile.clone(),
+            created_at:  user.created_at,
+        }
+    }
+}
+
+/// Update user request - profile changes are merged, `null` removes a field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateUserRequest {
+    pub username:   Option<String>,
+    pub first_name: Option<String>,
+    pub last_name:  Option<String>,
+    pub profile:    Option<Profile>,
+}
+
 // ============================================
 // Sign Up Types
 // ============================================
@@ -32,14 +68,33 @@ pub struct UserPublic {
 pub struct SignUpRequest {
     pub email:      Option<String>,
     pub phone:      Option<String>,
+    #[serde(default)]
+    pub username:   Option<String>,
     pub password:   String,
 }
 
 /// Sign up response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignUpResponseData {
-    pub user_id: String,
-    pub email:   Option<String>,
+    pub user_id:  String,
+    pub email:    Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+/// Username availability query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsernameAvailabilityQuery {
+    pub username: String,
+}
+
+/// Username availability - `username` is the normalized form that would be stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsernameAvailabilityResponse {
+    pub username:  String,
+    pub available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason:    Option<String>,
 }
 
 // ============================================
@@ -53,6 +108,21 @@ pub struct SignInRequest {
     pub password:   String,
 }
 
+/// Sign-in paused for a second factor after a risky password login
+#[derive(Debug, Clone, Serialize)]
+pub struct LoginChallengeResponse {
+    pub challenge_id: String,
+    pub method:       String,
+    pub expires_at:   i64,
+}
+
+/// Finish a challenged sign-in with the emailed code
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoginChallengeVerifyRequest {
+    pub challenge_id: String,
+    pub code:         String,
+}
+
 /// Sign in response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignInResponse {
@@ -64,6 +134,15 @@ pub struct SignInResponse {
     pub expires_in:    DateTime<Utc>,
 }
 
+/// Short-lived token issued to an admin acting as a user
+#[derive(Debug, Serialize)]
+pub struct ImpersonationResponse {
+    pub user:         UserPublic,
+    pub access_token: String,
+    pub actor_id:     String,
+    pub expires_in:   DateTime<Utc>,
+}
+
 
 // ============================================
 // Password Reset Types
@@ -83,6 +162,23 @@ pub struct PasswordResetConfirm {
     pub confirm_password: String,
 }
 
+/// Re-authenticate before a sensitive action - either the password or a
+/// passkey assertion for a challenge from `/2fa/passkey/options`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReauthenticateRequest {
+    pub password: Option<String>,
+    pub passkey:  Option<PasskeyLoginVerifyRequest>,
+}
+
+/// Fresh access token for the current session
+#[derive(Debug, Serialize)]
+pub struct ReauthenticateResponse {
+    pub access_token: String,
+    pub auth_time:    i64,
+    pub amr:          Vec<String>,
+    pub expires_in:   DateTime<Utc>,
+}
+
 /// Change password (when logged in)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChangePasswordRequest {
@@ -98,7 +194,8 @@ pub struct ChangePasswordRequest {
 /// Send verification code request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendVerificationRequest {
-    pub medium: VerificationMedium, // email or phone
+    pub identifier: String,             // email, phone, or username
+    pub medium:     VerificationMedium, // email or phone
 }
 
 /// Verify code request
@@ -108,6 +205,26 @@ pub struct VerifyCodeRequest {
     pub medium: VerificationMedium,
 }
 
+/// Request to change email or phone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactChangeRequest {
+    pub medium:    VerificationMedium,
+    pub new_value: String,
+}
+
+/// Confirm a contact change with the code sent to the new address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactChangeConfirm {
+    pub medium: VerificationMedium,
+    pub code:   String,
+}
+
+/// Undo a contact change from the old address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactChangeRevert {
+    pub token: String,
+}
+
 // ============================================
 // Token Types
 // ============================================
@@ -148,10 +265,321 @@ pub struct SessionsResponse {
     pub current_session_id: String,
 }
 
+// ============================================
+// Passkey Types (API layer)
+// ============================================
+
+/// Registered passkey without key material
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasskeyPublic {
+    pub id:           String,
+    pub name:         Option<String>,
+    pub transports:   Vec<String>,
+    pub created_at:   i64,
+    pub last_used_at: Option<i64>,
+}
+
+impl From<PasskeyCredential> for PasskeyPublic {
+    fn from(credential: PasskeyCredential) -> Self {
+        Self {
+            id:           credential.id.to_string(),
+            name:         credential.name,
+            transports:   credential.transports,
+            created_at:   credential.created_at,
+            last_used_at: credential.last_used_at,
+        }
+    }
+}
+
+// ============================================
+// API Key Types (API layer)
+// ============================================
+
+/// Create API key request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name:                  String,
+    pub scopes:                Vec<String>,
+    pub expires_in_days:       Option<i64>,
+    pub rate_limit_per_minute: Option<u32>,
+}
+
+/// API key without its hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyPublic {
+    pub id:                    String,
+    pub name:                  String,
+    pub key_prefix:            String,
+    pub scopes:                Vec<String>,
+    pub rate_limit_per_minute: Option<u32>,
+    pub expires_at:            Option<i64>,
+    pub last_used_at:          Option<i64>,
+    pub last_used_ip:          Option<String>,
+    pub is_revoked:            bool,
+    pub created_at:            i64,
+}
+
+impl From<ApiKey> for ApiKeyPublic {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            id:                    key.id.to_string(),
+            name:                  key.name,
+            key_prefix:            key.key_prefix,
+            scopes:                key.scopes,
+            rate_limit_per_minute: key.rate_limit_per_minute,
+            expires_at:            key.expires_at,
+            last_used_at:          key.last_used_at,
+            last_used_ip:          key.last_used_ip,
+            is_revoked:            key.is_revoked,
+            created_at:            key.created_at,
+        }
+    }
+}
+
+/// Create API key response - the only time the full key is returned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyCreatedResponse {
+    pub key:     String,
+    pub api_key: ApiKeyPublic,
+}
+
+// ============================================
+// Organization Types (API layer)
+// ============================================
+
+/// Create organization request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateOrganizationRequest {
+    pub name: String,
+    pub slug: Option<String>, // Derived from the name when omitted
+}
+
+/// Organization with the caller's role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationResponse {
+    pub id:         String,
+    pub name:       String,
+    pub slug:       String,
+    pub role:       OrgRole,
+    pub created_at: i64,
+}
+
+/// Organization member
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrgMemberResponse {
+    pub user:      UserPublic,
+    pub role:      OrgRole,
+    pub joined_at: i64,
+}
+
+/// Invite member request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteMemberRequest {
+    pub email: String,
+    pub role:  OrgRole,
+}
+
+/// Update member role request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateMemberRoleRequest {
+    pub role: OrgRole,
+}
+
+/// Accept invitation request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcceptInvitationRequest {
+    pub token: String,
+}
+
+/// Invitation without its token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrgInvitationPublic {
+    pub id:          String,
+    pub email:       String,
+    pub role:        OrgRole,
+    pub expires_at:  i64,
+    pub accepted_at: Option<i64>,
+    pub created_at:  i64,
+}
+
+impl From<OrgInvitation> for OrgInvitationPublic {
+    fn from(invitation: OrgInvitation) -> Self {
+        Self {
+            id:          invitation.id.to_string(),
+            email:       invitation.email,
+            role:        invitation.role,
+            expires_at:  invitation.expires_at,
+            accepted_at: invitation.accepted_at,
+            created_at:  invitation.created_at,
+        }
+    }
+}
+
+// ============================================
+// User Invitation Types
+// ============================================
+
+/// Invite a new user into an organization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteUserRequest {
+    pub email:      String,
+    pub first_name: Option<String>,
+    pub last_name:  Option<String>,
+    #[serde(default = "default_invite_role")]
+    pub role:       OrgRole,
+}
+
+fn default_invite_role() -> OrgRole {
+    OrgRole::Member
+}
+
+/// Accept an invitation by setting a password
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcceptUserInvitationRequest {
+    /// Query string of the invite link, including signature and timestamp
+    pub query:            String,
+    pub password:         String,
+    pub confirm_password: String,
+}
+
+/// User invitation (for public API responses)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserInvitationPublic {
+    pub id:          String,
+    pub user_id:     String,
+    pub email:       String,
+    pub role:        OrgRole,
+    pub expires_at:  i64,
+    pub accepted_at: Option<i64>,
+    pub created_at:  i64,
+}
+
+impl From<UserInvitation> for UserInvitationPublic {
+    fn from(invitation: UserInvitation) -> Self {
+        Self {
+            id:          invitation.id.to_string(),
+            user_id:     invitation.user_id.to_string(),
+            email:       invitation.email,
+            role:        invitation.role,
+            expires_at:  invitation.expires_at,
+            accepted_at: invitation.accepted_at,
+            created_at:  invitation.created_at,
+        }
+    }
+}
+
+// ============================================
+// Audit Types (API layer)
+// ============================================
+
+/// Audit log query - filters plus pagination, all optional
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEventsQuery {
+    pub page:      Option<u32>,
+    pub per_page:  Option<u32>,
+    pub cursor:    Option<String>,
+    pub actor_id:  Option<String>,
+    pub target_id: Option<String>,
+    pub org_id:    Option<String>,
+    pub action:    Option<AuditAction>,
+    pub outcome:   Option<AuditOutcome>,
+    pub reason:    Option<String>,
+    pub from:      Option<i64>, // Unix timestamp, inclusive
+    pub to:        Option<i64>, // Unix timestamp, inclusive
+}
+
+impl AuditEventsQuery {
+    pub fn pagination(&self) -> PaginationQuery {
+        PaginationQuery {
+            page:     self.page,
+            per_page: self.per_page,
+            cursor:   self.cursor.clone(),
+        }
+    }
+}
+
+/// Audit event for API responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEventPublic {
+    pub id:         String,
+    pub action:     AuditAction,
+    pub outcome:    AuditOutcome,
+    pub actor_id:   Option<String>,
+    pub target_id:  Option<String>,
+    pub impersonator_id: Option<String>,
+    pub org_id:     Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub reason:     Option<String>,
+    pub created_at: i64,
+}
+
+impl From<AuditEvent> for AuditEventPublic {
+    fn from(event: AuditEvent) -> Self {
+        Self {
+            id:         event.id.to_string(),
+            action:     event.action,
+            outcome:    event.outcome,
+            actor_id:   event.actor_id.map(|id| id.to_string()),
+            target_id:  event.target_id.map(|id| id.to_string()),
+            impersonator_id: event.impersonator_id.map(|id| id.to_string()),
+            org_id:     event.org_id.map(|id| id.to_string()),
+            ip_address: event.ip_address,
+            user_agent: event.user_agent,
+            reason:     event.reason,
+            created_at: event.created_at,
+        }
+    }
+}
+
 // ============================================
 // Request/Response Helpers
 // ============================================
 
+/// Pagination query parameters (`page` is 1-based)
+///
+/// A `cursor` (empty for the first page) switches lists that support it to
+/// cursor paging, and `page` is ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginationQuery {
+    pub page:     Option<u32>,
+    pub per_page: Option<u32>,
+    pub cursor:   Option<String>,
+}
+
+impl PaginationQuery {
+    pub const MAX_PER_PAGE: u32 = 100;
+
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> u32 {
+        self.per_page.unwrap_or(20).clamp(1, Self::MAX_PER_PAGE)
+    }
+
+    /// Zero-based page index for store queries
+    pub fn offset_page(&self) -> u32 {
+        self.page() - 1
+    }
+
+    /// Keyset page to fetch, `None` when paging by number
+    pub fn page_request(&self, codec: &CursorCodec) -> AuthResult<Option<PageRequest>> {
+        let request = match self.cursor.as_deref() {
+            None => return Ok(None),
+            Some("") => PageRequest::first(self.per_page()),
+            Some(token) => {
+                let cursor = codec
+                    .decode(token)
+                    .map_err(|_| AuthError::invalid_request("Invalid cursor"))?;
+                PageRequest::after(cursor, self.per_page())
+            }
+        };
+
+        Ok(Some(request))
+    }
+}
+
 /// Paginated users response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsersListResponse {
@@ -160,6 +588,133 @@ pub struct UsersListResponse {
 }
 
 
+// ============================================
+// Account Types (API layer)
+// ============================================
+
+/// Account deletion status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountDeletionResponse {
+    pub deletion_scheduled_at: DateTime<Utc>,
+}
+
+/// Profile section of a data export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedProfile {
+    pub id:          String,
+    pub email:       Option<String>,
+    pub phone:       Option<String>,
+    pub username:    Option<String>,
+    pub first_name:  Option<String>,
+    pub last_name:   Option<String>,
+    pub is_active:   bool,
+    pub is_verified: bool,
+    pub profile:     Profile,
+    pub created_at:  DateTime<Utc>,
+    pub updated_at:  Option<DateTime<Utc>>,
+    pub last_login:  Option<DateTime<Utc>>,
+    pub deletion_scheduled_at: Option<DateTime<Utc>>,
+}
+
+impl From<&User> for ExportedProfile {
+    fn from(user: &User) -> Self {
+        Self {
+            id:          user.id.to_string(),
+            email:       user.email.clone(),
+            phone:       user.phone.clone(),
+            username:    user.username.clone(),
+            first_name:  user.first_name.clone(),
+            last_name:   user.last_name.clone(),
+            is_active:   user.is_active,
+            is_verified: user.is_verified,
+            profile:     user.profile.clone(),
+            created_at:  user.created_at,
+            updated_at:  user.updated_at,
+            last_login:  user.last_login,
+            deletion_scheduled_at: user.deletion_scheduled_at,
+        }
+    }
+}
+
+/// Session section of a data export (no token hashes)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedSession {
+    pub id:           String,
+    pub device:       Option<String>,
+    pub ip_address:   Option<String>,
+    pub user_agent:   Option<String>,
+    pub created_at:   i64,
+    pub expires_at:   i64,
+    pub last_used_at: i64,
+    pub is_revoked:   bool,
+}
+
+impl From<SessionModel> for ExportedSession {
+    fn from(session: SessionModel) -> Self {
+        Self {
+            id:           session.id.to_string(),
+            device:       session.device,
+            ip_address:   session.ip_address,
+            user_agent:   session.user_agent,
+            created_at:   session.created_at,
+            expires_at:   session.expires_at,
+            last_used_at: session.last_used_at,
+            is_revoked:   session.is_revoked,
+        }
+    }
+}
+
+/// Linked OAuth account section of a data export (no provider tokens)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedOAuthAccount {
+    pub provider:         OAuthProvider,
+    pub provider_user_id: String,
+    pub scope:            Option<String>,
+    pub created_at:       i64,
+}
+
+impl From<OAuthAccount> for ExportedOAuthAccount {
+    fn from(account: OAuthAccount) -> Self {
+        Self {
+            provider:         account.provider,
+            provider_user_id: account.provider_user_id,
+            scope:            account.scope,
+            created_at:       account.created_at,
+        }
+    }
+}
+
+/// Organization membership section of a data export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedMembership {
+    pub org_id:    String,
+    pub role:      OrgRole,
+    pub joined_at: i64,
+}
+
+impl From<Membership> for ExportedMembership {
+    fn from(membership: Membership) -> Self {
+        Self {
+            org_id:    membership.org_id.to_string(),
+            role:      membership.role,
+            joined_at: membership.created_at,
+        }
+    }
+}
+
+/// Everything held about a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountExport {
+    pub exported_at:    DateTime<Utc>,
+    pub profile:        ExportedProfile,
+    pub sessions:       Vec<ExportedSession>,
+    pub oauth_accounts: Vec<ExportedOAuthAccount>,
+    pub passkeys:       Vec<PasskeyPublic>,
+    pub api_keys:       Vec<ApiKeyPublic>,
+    pub memberships:    Vec<ExportedMembership>,
+}
+
+
 /// Status response (for simple boolean responses)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatusResponse {
@@ -0,0 +1,136 @@
+//! Username rules
+//!
+//! Usernames are NFKC-normalized and case folded before they are stored, so
+//! `Amani` and `ａｍａｎｉ` are the same name. Letters from one script only:
+//! a Cyrillic `а` inside a Latin name is refused. Uniqueness and the reserved
+//! list are checked on the confusable skeleton, so an all-Cyrillic `ѕуѕтем` is
+//! as reserved as `system`.
+
+use unicode_normalization::UnicodeNormalization;
+
+pub const MIN_LENGTH: usize = 3;
+pub const MAX_LENGTH: usize = 30;
+
+/// Names that belong to the platform, not to users
+const RESERVED: &[&str] = &[
+    "about", "account", "admin", "administrator", "api", "app", "auth", "billing", "dashboard",
+    "help", "info", "login", "logout", "me", "mod", "moderator", "null", "oauth", "official",
+    "owner", "register", "root", "security", "settings", "signin", "signup", "staff", "status",
+    "support", "superuser", "system", "undefined", "user", "users", "webmaster", "www",
+];
+
+/// Cyrillic and Greek letters that look like Latin ones (lowercase)
+const CONFUSABLES: &[(char, char)] = &[
+    ('а', 'a'), ('в', 'b'), ('с', 'c'), ('ԁ', 'd'), ('е', 'e'), ('һ', 'h'), ('і', 'i'),
+    ('ј', 'j'), ('к', 'k'), ('ӏ', 'l'), ('м', 'm'), ('о', 'o'), ('р', 'p'), ('ԛ', 'q'),
+    ('ѕ', 's'), ('т', 't'), ('ѵ', 'v'), ('ԝ', 'w'), ('х', 'x'), ('у', 'y'),
+    ('α', 'a'), ('β', 'b'), ('ε', 'e'), ('ι', 'i'), ('κ', 'k'), ('ν', 'v'), ('ο', 'o'),
+    ('ρ', 'p'), ('τ', 't'), ('υ', 'u'), ('χ', 'x'), ('γ', 'y'),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Other,
+}
+
+fn script(c: char) -> Script {
+    match c {
+        'a'..='z' | '\u{00C0}'..='\u{024F}' => Script::Latin,
+        '\u{0370}'..='\u{03FF}' => Script::Greek,
+        '\u{0400}'..='\u{052F}' => Script::Cyrillic,
+        _ => Script::Other,
+    }
+}
+
+/// Stored form of a username - NFKC, case folded, trimmed
+pub fn normalize(username: &str) -> String {
+    username.trim().nfkc().flat_map(char::to_lowercase).collect()
+}
+
+/// Confusable skeleton of a normalized username, used for uniqueness
+pub fn skeleton(normalized: &str) -> String {
+    normalized
+        .chars()
+        .map(|c| {
+            CONFUSABLES
+                .iter()
+                .find(|(from, _)| *from == c)
+                .map_or(c, |(_, to)| *to)
+        })
+        .collect()
+}
+
+/// Validate a username and return its normalized form
+///
+/// Errors are short reasons, shown after "Invalid username: ".
+pub fn validate(username: &str) -> Result<String, &'static str> {
+    let normalized = normalize(username);
+    let length = normalized.chars().count();
+
+    if !(MIN_LENGTH..=MAX_LENGTH).contains(&length) {
+        return Err("must be 3 to 30 characters long");
+    }
+    if !normalized.chars().next().is_some_and(char::is_alphabetic) {
+        return Err("must start with a letter");
+    }
+    if !normalized.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+        return Err("may only contain letters, digits, '_' and '.'");
+    }
+    if normalized.ends_with('.') || normalized.contains("..") {
+        return Err("can't end with '.' or contain '..'");
+    }
+
+    // Digits are shared by all scripts; letters must come from one
+    let mut scripts = normalized
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .map(script);
+    let first = scripts.next();
+    if first == Some(Script::Other) || scripts.any(|s| Some(s) != first) {
+        return Err("must use letters from a single alphabet");
+    }
+
+    let key = skeleton(&normalized);
+    if RESERVED.contains(&key.as_str()) {
+        return Err("reserved name");
+    }
+
+    Ok(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalizes_case_and_width() {
+        assert_eq!(validate("  Amani_K ").unwrap(), "amani_k");
+        assert_eq!(validate("ＡＭＡＮＩ").unwrap(), "amani");
+        assert_eq!(validate("Ölmez").unwrap(), "ölmez");
+    }
+
+    #[test]
+    fn test_rejects_bad_shapes() {
+        for bad in ["ab", "1amani", "amani!", "amani.", "a..b", &"a".repeat(31)] {
+            assert!(validate(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_rejects_mixed_scripts() {
+        // Latin "p", "y", "p", "l" with Cyrillic "а"
+        assert!(validate("pаypal").is_err());
+        assert!(validate("иван").is_ok());
+    }
+
+    #[test]
+    fn test_reserved_through_confusables() {
+        assert!(validate("Admin").is_err());
+        // All Cyrillic, so a single script, but reads as "system"
+        assert_eq!(skeleton("ѕуѕтем"), "system");
+        assert!(validate("ѕуѕтем").is_err());
+    }
+}
@@ -0,0 +1,628 @@
+//! Change feeds over MongoDB change streams
+//!
+//! A `ChangeFeed<T>` watches one collection and yields typed `Change<T>`
+//! events, so code can react to writes (a user verified, a session revoked)
+//! without polling:
+//!
+//! ```ignore
+//! let feed = ChangeFeed::new(db.collection::<User>("users"))
+//!     .only(&[ChangeKind::Update])
+//!     .matching(Filter::new().eq("updateDescription.updatedFields.isVerified", true))
+//!     .resume_with(Arc::new(MongoResumeTokens::new(db.collection("change_stream_tokens"))));
+//!
+//! let mut changes = feed.subscribe().await?;
+//! while let Some(change) = changes.next().await? {
+//!     // handle the change
+//! }
+//! ```
+//!
+//! With a `ResumeTokenStore`, the position of the last handled event is saved
+//! when the next one is requested (or on `checkpoint`), and a new subscription
+//! picks up from there. Delivery is at least once: an event handled right
+//! before a crash may be seen again. Events whose documents don't decode as
+//! `T` are logged and skipped, so one bad document can't stall the feed.
+//!
+//! When the stream is invalidated (the collection dropped or renamed),
+//! `next` returns `None` and saves the invalidate event's token; the next
+//! subscription starts after it.
+//!
+//! Change streams need a replica set or sharded cluster. A resume token older
+//! than the oplog window fails the subscription; drop the saved token to
+//! start from the current position.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mongodb::bson::{self, Bson, Document, doc};
+use mongodb::change_stream::event::{ChangeStreamEvent, OperationType, ResumeToken};
+use mongodb::change_stream::ChangeStream;
+use mongodb::options::{ChangeStreamOptions, FullDocumentBeforeChangeType, FullDocumentType};
+use mongodb::Collection;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use utils::websocket::{LiveUpdate, UpdateAction, WsService};
+
+use crate::repository::{Filter, Repository};
+use crate::utils::{DbError, DbResult};
+
+/// Kind of write a change event reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Insert,
+    Update,
+    Replace,
+    Delete,
+}
+
+impl ChangeKind {
+    pub const ALL: [ChangeKind; 4] = [Self::Insert, Self::Update, Self::Replace, Self::Delete];
+
+    /// Name of the operation in change events
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Insert => "insert",
+            Self::Update => "update",
+            Self::Replace => "replace",
+            Self::Delete => "delete",
+        }
+    }
+
+    fn from_operation(operation: &OperationType) -> Option<Self> {
+        match operation {
+            OperationType::Insert => Some(Self::Insert),
+            OperationType::Update => Some(Self::Update),
+            OperationType::Replace => Some(Self::Replace),
+            OperationType::Delete => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// What a raw event means for the subscriber
+enum Step<T> {
+    Change(Change<T>),
+    /// Not a write, or a write that didn't decode
+    Skip,
+    /// The stream was invalidated
+    End,
+}
+
+impl<T: DeserializeOwned> Step<T> {
+    fn from_event(collection: &str, event: ChangeStreamEvent<Document>) -> Self {
+        if event.operation_type == OperationType::Invalidate {
+            return Self::End;
+        }
+
+        match Change::from_event(collection, event) {
+            Ok(Some(change)) => Self::Change(change),
+            Ok(None) => Self::Skip,
+            Err(e) => {
+                tracing::warn!("Skipping change to {}: {}", collection, e.message);
+                Self::Skip
+            }
+        }
+    }
+}
+
+/// One write to a watched collection
+#[derive(Debug, Clone)]
+pub struct Change<T> {
+    pub collection: String,
+    pub kind: ChangeKind,
+    /// The document's `id`, or MongoDB's `_id` when no image carries it
+    pub id: Option<String>,
+    /// Document after the write; `None` for deletes
+    pub document: Option<T>,
+    /// Document before the write, when the feed asked for pre-images
+    pub previous: Option<T>,
+    /// Fields an update set
+    pub updated_fields: Vec<String>,
+    /// Fields an update removed
+    pub removed_fields: Vec<String>,
+    /// Server time of the write, in seconds
+    pub timestamp: i64,
+}
+
+impl<T: DeserializeOwned> Change<T> {
+    /// Typed change for a raw event; `None` for events other than writes
+    fn from_event(collection: &str, event: ChangeStreamEvent<Document>) -> DbResult<Option<Self>> {
+        let Some(kind) = ChangeKind::from_operation(&event.operation_type) else {
+            return Ok(None);
+        };
+
+        let id = [event.full_document.as_ref(), event.full_document_before_change.as_ref()]
+            .into_iter()
+            .flatten()
+            .find_map(|doc| doc.get(crate::repository::ID_FIELD))
+            .or_else(|| event.document_key.as_ref().and_then(|key| key.get("_id")))
+            .map(key_string);
+
+        let (updated_fields, removed_fields) = match event.update_description {
+            Some(description) => (
+                description.updated_fields.keys().cloned().collect(),
+                description.removed_fields,
+            ),
+            None => (Vec::new(), Vec::new()),
+        };
+
+        Ok(Some(Self {
+            collection: collection.to_string(),
+            kind,
+            id,
+            document: event.full_document.map(decode).transpose()?,
+            previous: event.full_document_before_change.map(decode).transpose()?,
+            updated_fields,
+            removed_fields,
+            timestamp: event
+                .wall_time
+                .map(|time| time.timestamp_millis() / 1000)
+                .unwrap_or_else(|| chrono::Utc::now().timestamp()),
+        }))
+    }
+}
+
+impl<T> Change<T> {
+    /// Whether an update set or removed the field
+    pub fn touched(&self, field: &str) -> bool {
+        self.updated_fields.iter().chain(&self.removed_fields).any(|f| f == field)
+    }
+
+    /// Live update naming the changed fields
+    ///
+    /// Documents are left out since they can hold secrets; set `data` on the
+    /// result to send more.
+    pub fn to_live_update(&self, entity_type: &str) -> LiveUpdate {
+        let data = match self.kind {
+            ChangeKind::Update | ChangeKind::Replace => serde_json::json!({
+                "updated": self.updated_fields,
+                "removed": self.removed_fields,
+            }),
+            ChangeKind::Insert | ChangeKind::Delete => serde_json::json!({}),
+        };
+        LiveUpdate::new(entity_type, self.id.clone().unwrap_or_default(), self.action(), data)
+    }
+
+    /// Action a live update for this change carries
+    pub fn action(&self) -> UpdateAction {
+        match self.kind {
+            ChangeKind::Insert => UpdateAction::Create,
+            ChangeKind::Update | ChangeKind::Replace => UpdateAction::Update,
+            ChangeKind::Delete => UpdateAction::Delete,
+        }
+    }
+}
+
+fn decode<T: DeserializeOwned>(document: Document) -> DbResult<T> {
+    bson::from_document(document)
+        .map_err(|e| DbError::internal_error(&format!("Failed to decode change: {}", e)))
+}
+
+/// Ids as they appear in `DbId`'s string form
+fn key_string(key: &Bson) -> String {
+    match key {
+        Bson::ObjectId(oid) => oid.to_hex(),
+        Bson::String(s) => s.clone(),
+        Bson::Int32(n) => n.to_string(),
+        Bson::Int64(n) => n.to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Where feeds keep their resume tokens
+#[async_trait]
+pub trait ResumeTokenStore: Send + Sync {
+    /// Token saved for the feed, if any
+    async fn load(&self, feed: &str) -> DbResult<Option<ResumeToken>>;
+
+    /// Save the position of the last handled event, or of an invalidate event
+    async fn save(&self, feed: &str, token: &ResumeToken) -> DbResult<()>;
+
+    /// Forget the feed's position, so it starts from now
+    async fn clear(&self, feed: &str) -> DbResult<()>;
+}
+
+/// Resume token as stored in MongoDB, one document per feed
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedToken {
+    #[serde(rename = "_id")]
+    feed: String,
+    token: ResumeToken,
+    updated_at: i64,
+}
+
+/// Resume tokens in a MongoDB collection
+pub struct MongoResumeTokens {
+    tokens: Repository<SavedToken>,
+}
+
+impl MongoResumeTokens {
+    pub fn new(collection: Collection<Document>) -> Self {
+        Self {
+            tokens: Repository::new(collection.clone_with_type()),
+        }
+    }
+}
+
+#[async_trait]
+impl ResumeTokenStore for MongoResumeTokens {
+    async fn load(&self, feed: &str) -> DbResult<Option<ResumeToken>> {
+        let saved = self.tokens.find_one(Filter::new().eq("_id", feed)).await?;
+        Ok(saved.map(|saved| saved.token))
+    }
+
+    async fn save(&self, feed: &str, token: &ResumeToken) -> DbResult<()> {
+        let saved = SavedToken {
+            feed: feed.to_string(),
+            token: token.clone(),
+            updated_at: chrono::Utc::now().timestamp(),
+        };
+        self.tokens.upsert(Filter::new().eq("_id", feed), &saved).await
+    }
+
+    async fn clear(&self, feed: &str) -> DbResult<()> {
+        self.tokens.delete_one(Filter::new().eq("_id", feed)).await?;
+        Ok(())
+    }
+}
+
+/// Resume tokens in memory, for tests and single-process setups
+#[derive(Default)]
+pub struct MemoryResumeTokens {
+    tokens: Mutex<HashMap<String, ResumeToken>>,
+}
+
+impl MemoryResumeTokens {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ResumeTokenStore for MemoryResumeTokens {
+    async fn load(&self, feed: &str) -> DbResult<Option<ResumeToken>> {
+        Ok(self.tokens.lock().unwrap().get(feed).cloned())
+    }
+
+    async fn save(&self, feed: &str, token: &ResumeToken) -> DbResult<()> {
+        self.tokens.lock().unwrap().insert(feed.to_string(), token.clone());
+        Ok(())
+    }
+
+    async fn clear(&self, feed: &str) -> DbResult<()> {
+        self.tokens.lock().unwrap().remove(feed);
+        Ok(())
+    }
+}
+
+/// Subscription settings for one collection
+pub struct ChangeFeed<T: Send + Sync> {
+    collection: Collection<T>,
+    name: String,
+    kinds: Vec<ChangeKind>,
+    filter: Filter,
+    pre_images: bool,
+    tokens: Option<Arc<dyn ResumeTokenStore>>,
+}
+
+impl<T> ChangeFeed<T>
+where
+    T: DeserializeOwned + Unpin + Send + Sync,
+{
+    /// Feed of every write to the collection, named after it
+    pub fn new(collection: Collection<T>) -> Self {
+        Self {
+            name: collection.name().to_string(),
+            collection,
+            kinds: ChangeKind::ALL.to_vec(),
+            filter: Filter::new(),
+            pre_images: false,
+            tokens: None,
+        }
+    }
+
+    /// Name resume tokens are saved under; set it when several feeds watch one collection
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Only report these kinds of writes
+    pub fn only(mut self, kinds: &[ChangeKind]) -> Self {
+        self.kinds = kinds.to_vec();
+        self
+    }
+
+    /// Only report events matching the filter, on change event fields
+    /// (`fullDocument.<field>`, `updateDescription.updatedFields.<field>`)
+    pub fn matching(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Include the document before each write
+    ///
+    /// Needs MongoDB 6.0 with `changeStreamPreAndPostImages` enabled on the
+    /// collection. Without it, deletes only carry MongoDB's `_id`.
+    pub fn with_pre_images(mut self) -> Self {
+        self.pre_images = true;
+        self
+    }
+
+    /// Save positions in `tokens` and resume from the saved one
+    pub fn resume_with(mut self, tokens: Arc<dyn ResumeTokenStore>) -> Self {
+        self.tokens = Some(tokens);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Aggregation pipeline the change stream runs
+    pub fn pipeline(&self) -> Vec<Document> {
+        let kinds = self.kinds.iter().map(|kind| kind.as_str());
+        let filter = self.filter.clone().is_in("operationType", kinds);
+        vec![doc! { "$match": filter.into_document() }]
+    }
+
+    /// Open the change stream, after the saved position if there is one
+    pub async fn subscribe(&self) -> DbResult<Subscription<T>> {
+        let start_after = match &self.tokens {
+            Some(tokens) => tokens.load(&self.name).await?,
+            None => None,
+        };
+
+        let options = ChangeStreamOptions::builder()
+            .full_document(Some(FullDocumentType::UpdateLookup))
+            .full_document_before_change(
+                self.pre_images.then_some(FullDocumentBeforeChangeType::WhenAvailable),
+            )
+            // Unlike `resume_after`, this also accepts invalidate tokens
+            .start_after(start_after)
+            .build();
+
+        let stream = self
+            .collection
+            .clone_with_type::<Document>()
+            .watch(self.pipeline(), options)
+            .await
+            .map_err(|e| {
+                let mut error = DbError::from(e);
+                error.message = format!("Failed to watch {}: {}", self.collection.name(), error.message);
+                error
+            })?;
+
+        Ok(Subscription {
+            stream,
+            feed: self.name.clone(),
+            collection: self.collection.name().to_string(),
+            tokens: self.tokens.clone(),
+            pending: None,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// Open change stream yielding typed changes
+pub struct Subscription<T> {
+    stream: ChangeStream<ChangeStreamEvent<Document>>,
+    feed: String,
+    collection: String,
+    tokens: Option<Arc<dyn ResumeTokenStore>>,
+    /// Token of the last event handled and not yet saved
+    pending: Option<ResumeToken>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> Subscription<T> {
+    /// Next change, saving the position of the previous one first
+    ///
+    /// `None` once the stream ends, e.g. when the collection is dropped.
+    pub async fn next(&mut self) -> DbResult<Option<Change<T>>> {
+        self.checkpoint().await?;
+
+        while let Some(event) = self.stream.next().await {
+            let event = event?;
+            let token = event.id.clone();
+            let step = Step::from_event(&self.collection, event);
+            self.pending = Some(token);
+
+            match step {
+                Step::Change(change) => return Ok(Some(change)),
+                Step::Skip => {}
+                Step::End => {
+                    self.checkpoint().await?;
+                    return Ok(None);
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Save the position of the last change handed out
+    pub async fn checkpoint(&mut self) -> DbResult<()> {
+        if let (Some(tokens), Some(token)) = (&self.tokens, &self.pending) {
+            tokens.save(&self.feed, token).await?;
+        }
+        self.pending = None;
+        Ok(())
+    }
+}
+
+/// Forwards selected changes to WebSocket channels as `LiveUpdate` messages
+#[derive(Clone)]
+pub struct LiveUpdateBridge {
+    ws: Arc<WsService>,
+}
+
+impl LiveUpdateBridge {
+    pub fn new(ws: Arc<WsService>) -> Self {
+        Self { ws }
+    }
+
+    /// Forward a feed's changes until its stream ends
+    ///
+    /// `route` picks the channel and update for a change, or `None` to skip
+    /// it. On error, calling again resumes from the feed's saved position.
+    pub async fn forward<T, F>(&self, feed: &ChangeFeed<T>, route: F) -> DbResult<()>
+    where
+        T: DeserializeOwned + Unpin + Send + Sync,
+        F: Fn(&Change<T>) -> Option<(String, LiveUpdate)>,
+    {
+        let mut changes = feed.subscribe().await?;
+        while let Some(change) = changes.next().await? {
+            self.deliver(&change, &route);
+        }
+        changes.checkpoint().await
+    }
+
+    /// Broadcast the update `route` picks; whether one was sent
+    fn deliver<T, F>(&self, change: &Change<T>, route: &F) -> bool
+    where
+        F: Fn(&Change<T>) -> Option<(String, LiveUpdate)>,
+    {
+        match route(change) {
+            Some((channel, update)) => {
+                self.ws.broadcast_update(&channel, update);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::oid::ObjectId;
+    use utils::websocket::WsServerConfig;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        id: ObjectId,
+        #[serde(rename = "isVerified")]
+        is_verified: bool,
+    }
+
+    fn event(fields: Document) -> ChangeStreamEvent<Document> {
+        let mut event = doc! {
+            "_id": { "_data": "8263A1" },
+            "ns": { "db": "app", "coll": "users" },
+            "documentKey": { "_id": ObjectId::new() },
+        };
+        event.extend(fields);
+        bson::from_document(event).unwrap()
+    }
+
+    #[test]
+    fn test_update_event_becomes_typed_change() {
+        let id = ObjectId::new();
+        let update = event(doc! {
+            "operationType": "update",
+            "updateDescription": { "updatedFields": { "isVerified": true }, "removedFields": ["otp"] },
+            "fullDocument": { "id": id, "isVerified": true },
+        });
+
+        let change = Change::<User>::from_event("users", update).unwrap().unwrap();
+        assert_eq!(change.kind, ChangeKind::Update);
+        assert_eq!(change.id, Some(id.to_hex()));
+        assert_eq!(change.document, Some(User { id, is_verified: true }));
+        assert!(change.touched("isVerified") && change.touched("otp"));
+        assert!(!change.touched("email"));
+
+        let dropped = event(doc! { "operationType": "drop" });
+        assert!(Change::<User>::from_event("users", dropped).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_undecodable_and_invalidate_events() {
+        let malformed = event(doc! {
+            "operationType": "insert",
+            "fullDocument": { "id": "not-an-object-id", "isVerified": "yes" },
+        });
+        assert!(matches!(Step::<User>::from_event("users", malformed), Step::Skip));
+
+        let invalidate = event(doc! { "operationType": "invalidate" });
+        assert!(matches!(Step::<User>::from_event("users", invalidate), Step::End));
+    }
+
+    #[test]
+    fn test_delete_falls_back_to_document_key() {
+        let change = Change::<User>::from_event("users", event(doc! { "operationType": "delete" }))
+            .unwrap()
+            .unwrap();
+        assert!(change.document.is_none());
+        assert!(change.id.is_some());
+
+        let update = change.to_live_update("user");
+        assert_eq!(update.action, UpdateAction::Delete);
+        assert_eq!(Some(update.entity_id), change.id);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_matches_kinds_and_filter() {
+        // Building a client doesn't connect
+        let collection = mongodb::Client::with_uri_str("mongodb://localhost:27017")
+            .await
+            .unwrap()
+            .database("app")
+            .collection::<User>("users");
+
+        let feed = ChangeFeed::new(collection)
+            .only(&[ChangeKind::Update])
+            .matching(Filter::new().eq("updateDescription.updatedFields.isVerified", true));
+
+        assert_eq!(feed.name(), "users");
+        assert_eq!(
+            feed.pipeline(),
+            vec![doc! { "$match": {
+                "updateDescription.updatedFields.isVerified": true,
+                "operationType": { "$in": ["update"] },
+            }}]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bridge_broadcasts_routed_changes() {
+        let ws = Arc::new(WsService::new(WsServerConfig::default()));
+        let mut receiver = ws.hub().get_or_create_channel("users").subscribe();
+        let bridge = LiveUpdateBridge::new(ws);
+
+        let change = Change::<User>::from_event(
+            "users",
+            event(doc! {
+                "operationType": "update",
+                "updateDescription": { "updatedFields": { "isVerified": true }, "removedFields": [] },
+            }),
+        )
+        .unwrap()
+        .unwrap();
+
+        let route = |change: &Change<User>| {
+            change
+                .touched("isVerified")
+                .then(|| ("users".to_string(), change.to_live_update("user")))
+        };
+        assert!(bridge.deliver(&change, &route));
+        assert!(!bridge.deliver(&change, &|_: &Change<User>| None));
+
+        let message = receiver.try_recv().unwrap();
+        let update: LiveUpdate = serde_json::from_str(&message.payload).unwrap();
+        assert_eq!(update.action, UpdateAction::Update);
+        assert_eq!(update.data["updated"], serde_json::json!(["isVerified"]));
+    }
+
+    #[tokio::test]
+    async fn test_memory_tokens_round_trip() {
+        let tokens = MemoryResumeTokens::new();
+        let token: ResumeToken = bson::from_bson(Bson::Document(doc! { "_data": "8263A1" })).unwrap();
+        tokens.save("users", &token).await.unwrap();
+        assert_eq!(tokens.load("users").await.unwrap(), Some(token));
+        tokens.clear("users").await.unwrap();
+        assert!(tokens.load("users").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,180 @@
+//! Database indexes module
+//!
+//! Stores declare their indexes as `IndexSpec`s and call `ensure_indexes` at
+//! startup. Creating an index that already exists with the same options is a
+//! no-op, so this is safe to run on every boot.
+
+use mongodb::bson::{self, doc, DateTime, Document};
+use mongodb::options::IndexOptions;
+use mongodb::Collection;
+use mongodb::IndexModel;
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::utils::{DbError, DbResult};
+
+/// BSON date copy of `expires_at`, used by TTL indexes
+///
+/// TTL indexes only act on BSON dates, while models keep `expires_at` as
+/// epoch seconds. Documents written with `with_ttl` carry both.
+pub const TTL_FIELD: &str = "expires_at_date";
+
+/// Declaration of a single index
+#[derive(Debug, Clone)]
+pub struct IndexSpec {
+    pub name: String,
+    pub keys: Document,
+    pub unique: bool,
+    pub partial_filter: Option<Document>,
+    pub expire_after_secs: Option<u64>,
+}
+
+impl IndexSpec {
+    pub fn new(name: impl Into<String>, keys: Document) -> Self {
+        Self {
+            name: name.into(),
+            keys,
+            unique: false,
+            partial_filter: None,
+            expire_after_secs: None,
+        }
+    }
+
+    /// Reject documents that repeat the indexed keys
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    /// Unique among documents where `field` is set, so many can leave it empty
+    pub fn unique_when_set(mut self, field: &str) -> Self {
+        self.unique = true;
+        self.partial_filter = Some(doc! { field: { "$type": "string" } });
+        self
+    }
+
+    /// Only index documents matching `filter`
+    pub fn partial(mut self, filter: Document) -> Self {
+        self.partial_filter = Some(filter);
+        self
+    }
+
+    /// Delete documents `secs` after the indexed date
+    pub fn expire_after(mut self, secs: u64) -> Self {
+        self.expire_after_secs = Some(secs);
+        self
+    }
+
+    /// TTL index on `TTL_FIELD`, deleting documents once they expire
+    pub fn ttl() -> Self {
+        Self::new("expires_at_ttl", doc! { TTL_FIELD: 1 }).expire_after(0)
+    }
+
+    /// Human readable form
+    pub fn describe(&self) -> String {
+        let mut description = format!("{} {}", self.name, self.keys);
+        if self.unique {
+            description.push_str(" unique");
+        }
+        if let Some(ref filter) = self.partial_filter {
+            description.push_str(&format!(" where {}", filter));
+        }
+        if let Some(secs) = self.expire_after_secs {
+            description.push_str(&format!(" expire after {}s", secs));
+        }
+        description
+    }
+
+    pub fn model(&self) -> IndexModel {
+        let options = IndexOptions::builder()
+            .name(self.name.clone())
+            .unique(self.unique.then_some(true))
+            .partial_filter_expression(self.partial_filter.clone())
+            .expire_after(self.expire_after_secs.map(Duration::from_secs))
+            .build();
+
+        IndexModel::builder().keys(self.keys.clone()).options(options).build()
+    }
+}
+
+/// Index that could not be created
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexFailure {
+    pub name: String,
+    pub error: String,
+}
+
+/// Outcome of `ensure_indexes` for one collection
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IndexReport {
+    pub collection: String,
+    pub created: Vec<String>,
+    pub existing: Vec<String>,
+    pub failed: Vec<IndexFailure>,
+}
+
+impl IndexReport {
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Create any missing indexes of a collection
+///
+/// Failures are reported rather than returned, so one bad index (e.g. a
+/// unique index over existing duplicates) doesn't hide the others.
+pub async fn ensure_indexes<T>(collection: &Collection<T>, specs: &[IndexSpec]) -> IndexReport {
+    let mut report = IndexReport {
+        collection: collection.name().to_string(),
+        ..Default::default()
+    };
+
+    // Fails when the collection doesn't exist yet
+    let existing = collection.list_index_names().await.unwrap_or_default();
+
+    for spec in specs {
+        match collection.create_index(spec.model(), None).await {
+            Ok(_) if existing.contains(&spec.name) => report.existing.push(spec.name.clone()),
+            Ok(_) => report.created.push(spec.name.clone()),
+            Err(e) => report.failed.push(IndexFailure {
+                name: spec.name.clone(),
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    report
+}
+
+/// BSON date for an epoch-seconds expiry
+pub fn ttl_date(expires_at: i64) -> DateTime {
+    DateTime::from_millis(expires_at.saturating_mul(1000))
+}
+
+/// Serialize `value` and add its `TTL_FIELD`
+pub fn with_ttl<T: Serialize>(value: &T, expires_at: i64) -> DbResult<Document> {
+    let mut document = bson::to_document(value)
+        .map_err(|e| DbError::internal_error(&format!("Failed to encode document: {}", e)))?;
+    document.insert(TTL_FIELD, ttl_date(expires_at));
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl_field_is_a_date() {
+        #[derive(Serialize)]
+        struct Token {
+            expires_at: i64,
+        }
+
+        let document = with_ttl(&Token { expires_at: 1_700_000_000 }, 1_700_000_000).unwrap();
+        assert_eq!(document.get_i64("expires_at").unwrap(), 1_700_000_000);
+        assert_eq!(
+            document.get_datetime(TTL_FIELD).unwrap().timestamp_millis(),
+            1_700_000_000_000
+        );
+    }
+}
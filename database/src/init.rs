@@ -2,8 +2,16 @@
 //!
 //! Provides initialization functions for MongoDB database.
 
+use std::time::Duration;
+
+use async_trait::async_trait;
+
 use crate::mongo;
-use crate::utils::DbResult;
+use crate::pool::PoolStats;
+use crate::utils::{DbError, DbResult};
+
+/// Longest wait between two connection attempts
+pub(crate) const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
 
 /// Database configuration for MongoDB
 #[derive(Debug, Clone)]
@@ -13,6 +21,10 @@ pub struct DatabaseConfig {
     pub max_connections: u32,
     pub min_connections: u32,
     pub connection_timeout_secs: u64,
+    /// Extra connection attempts at startup before giving up
+    pub connect_retries: u32,
+    /// Delay before the first retry, doubled after each failed attempt
+    pub retry_backoff_ms: u64,
 }
 
 impl DatabaseConfig {
@@ -23,9 +35,23 @@ impl DatabaseConfig {
             max_connections: 10,
             min_connections: 1,
             connection_timeout_secs: 30,
+            connect_retries: 5,
+            retry_backoff_ms: 500,
         }
     }
 
+    /// SQLite database in a file, created if missing (needs the `sqlite` feature)
+    pub fn sqlite(path: impl AsRef<std::path::Path>) -> Self {
+        let path = path.as_ref();
+        let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("main");
+        Self::new(format!("sqlite://{}?mode=rwc", path.display()), name)
+    }
+
+    /// Private in-memory SQLite database, dropped when the pool closes
+    pub fn sqlite_memory() -> Self {
+        Self::new("sqlite::memory:", "memory")
+    }
+
     pub fn with_pool(mut self, max: u32, min: u32) -> Self {
         self.max_connections = max;
         self.min_connections = min;
@@ -36,26 +62,75 @@ impl DatabaseConfig {
         self.connection_timeout_secs = secs;
         self
     }
+
+    pub fn with_retry(mut self, retries: u32, backoff_ms: u64) -> Self {
+        self.connect_retries = retries;
+        self.retry_backoff_ms = backoff_ms;
+        self
+    }
+
+    /// Reject pool settings the driver would refuse or misbehave with
+    pub fn validate(&self) -> DbResult<()> {
+        if self.max_connections == 0 {
+            return Err(DbError::invalid_config("max_connections must be at least 1"));
+        }
+        if self.min_connections > self.max_connections {
+            return Err(DbError::invalid_config(&format!(
+                "min_connections ({}) exceeds max_connections ({})",
+                self.min_connections, self.max_connections
+            )));
+        }
+        if self.connection_timeout_secs == 0 {
+            return Err(DbError::invalid_config("connection_timeout_secs must be at least 1"));
+        }
+        Ok(())
+    }
 }
 
 /// Database connection trait
+#[async_trait]
 pub trait Database: Send + Sync {
     /// Get database name
     fn db_name(&self) -> &str;
 
     /// Test the connection
-    fn ping(&self) -> DbResult<()>;
+    async fn ping(&self) -> DbResult<()>;
 
     /// Close the connection
-    fn close(&self) -> DbResult<()>;
+    async fn close(&self) -> DbResult<()>;
+
+    /// Connection pool statistics
+    fn pool_stats(&self) -> PoolStats;
 }
 
-/// Initialize MongoDB database
-pub fn init_database(config: DatabaseConfig) -> DbResult<mongo::MongoConnection> {
-    mongo::MongoConnection::new(config)
+/// Initialize MongoDB database, retrying with backoff until it answers
+pub async fn init_database(config: DatabaseConfig) -> DbResult<mongo::MongoConnection> {
+    mongo::MongoConnection::new(config).await
 }
 
 /// Get default MongoDB connection string
 pub fn default_connection_string() -> String {
     "mongodb://localhost:27017".to_string()
 }
+
+/// Delay before retry number `attempt` (zero based)
+pub(crate) fn retry_delay(backoff: Duration, attempt: u32) -> Duration {
+    backoff
+        .checked_mul(2u32.saturating_pow(attempt))
+        .map_or(MAX_RETRY_DELAY, |delay| delay.min(MAX_RETRY_DELAY))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_cap() {
+        let backoff = Duration::from_millis(500);
+
+        assert_eq!(retry_delay(backoff, 0), Duration::from_millis(500));
+        assert_eq!(retry_delay(backoff, 3), Duration::from_secs(4));
+        assert_eq!(retry_delay(backoff, 10), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(backoff, 40), MAX_RETRY_DELAY);
+    }
+}
@@ -1,7 +1,17 @@
 //! Database crate
 //!
-//! Provides MongoDB database utilities for the application.
+//! Provides MongoDB database utilities for the application, and SQL
+//! backends behind the `sql`, `postgres` and `sqlite` features.
 
+pub mod change_feed;
+pub mod indexes;
 pub mod init;
+pub mod migrations;
 pub mod mongo;
+pub mod pagination;
+pub mod pool;
+pub mod repository;
+#[cfg(feature = "sql")]
+pub mod sql;
+pub mod transaction;
 pub mod utils;
@@ -0,0 +1,113 @@
+//! Database migrations module
+//!
+//! Versioned schema changes for MongoDB. Each migration has an `up` step and,
+//! when it can be undone, a `down` step. Applied migrations are recorded in the
+//! `_migrations` collection together with a checksum, so an edited migration
+//! is caught before anything else runs.
+
+pub mod runner;
+pub mod steps;
+
+pub use runner::MigrationRunner;
+pub use steps::{Step, StepMigration};
+
+use async_trait::async_trait;
+use mongodb::Database;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{DbError, DbResult};
+
+/// Collection holding one record per applied migration
+pub const MIGRATIONS_COLLECTION: &str = "_migrations";
+
+/// Collection holding the lock taken while migrations run
+pub const MIGRATIONS_LOCK_COLLECTION: &str = "_migrations_lock";
+
+/// A versioned schema change
+#[async_trait]
+pub trait Migration: Send + Sync {
+    /// Version number; migrations run in ascending order
+    fn version(&self) -> u32;
+
+    /// Short name, e.g. `backfill_user_defaults`
+    fn name(&self) -> &str;
+
+    /// Fingerprint of what the migration does
+    ///
+    /// Defaults to the version and name. Override it when the migration body
+    /// can change without a new version.
+    fn checksum(&self) -> String {
+        checksum(&[&self.version().to_string(), self.name()])
+    }
+
+    /// Apply the migration
+    async fn up(&self, db: &Database) -> DbResult<()>;
+
+    /// Revert the migration
+    async fn down(&self, _db: &Database) -> DbResult<()> {
+        Err(DbError::not_supported(&format!(
+            "Migration {} ({}) cannot be reverted",
+            self.version(),
+            self.name()
+        )))
+    }
+}
+
+/// Version, name and checksum the runners plan with
+pub(crate) trait Versioned {
+    fn version(&self) -> u32;
+    fn name(&self) -> &str;
+    fn checksum(&self) -> String;
+}
+
+impl Versioned for Box<dyn Migration> {
+    fn version(&self) -> u32 {
+        Migration::version(self.as_ref())
+    }
+
+    fn name(&self) -> &str {
+        Migration::name(self.as_ref())
+    }
+
+    fn checksum(&self) -> String {
+        Migration::checksum(self.as_ref())
+    }
+}
+
+/// Applies and reverts migrations, whatever the backend
+#[async_trait]
+pub trait Migrator: Send + Sync {
+    /// Known and applied migrations, in version order
+    async fn status(&self) -> DbResult<Vec<MigrationStatus>>;
+
+    /// Apply all pending migrations and return the ones that ran
+    async fn migrate(&self) -> DbResult<Vec<MigrationRecord>>;
+
+    /// Revert applied migrations newer than `target` and return their versions
+    async fn rollback(&self, target: u32) -> DbResult<Vec<u32>>;
+}
+
+/// Record stored in the migrations collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    pub version: u32,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: i64,
+    pub duration_ms: i64,
+}
+
+/// State of a known migration
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    pub version: u32,
+    pub name: String,
+    pub applied_at: Option<i64>,
+    /// The applied checksum differs from the migration in code
+    pub checksum_mismatch: bool,
+}
+
+/// Hash a list of parts into a migration checksum
+pub fn checksum(parts: &[&str]) -> String {
+    ::utils::hash::hash_sha256(&parts.join("\n"))
+}
@@ -0,0 +1,394 @@
+//! Migration runner
+//!
+//! Applies pending migrations in version order and reverts them in reverse.
+//! A lock document keeps two instances from migrating at the same time; a
+//! lock left behind by a crashed instance expires after `lock_ttl_secs`.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::error::{Error as MongoError, ErrorKind, WriteFailure};
+use mongodb::options::{FindOptions, UpdateOptions};
+use mongodb::{Collection, Database};
+
+use super::{
+    Migration, MigrationRecord, MigrationStatus, Migrator, Versioned, MIGRATIONS_COLLECTION,
+    MIGRATIONS_LOCK_COLLECTION,
+};
+use crate::utils::{DbError, DbResult};
+
+const LOCK_ID: &str = "migrations";
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Runs migrations against a MongoDB database
+pub struct MigrationRunner {
+    db: Database,
+    migrations: Vec<Box<dyn Migration>>,
+    owner: String,
+    lock_ttl_secs: i64,
+    lock_wait: Duration,
+}
+
+impl MigrationRunner {
+    pub fn new(db: Database) -> Self {
+        Self {
+            db,
+            migrations: Vec::new(),
+            owner: uuid::Uuid::new_v4().to_string(),
+            lock_ttl_secs: 600,
+            lock_wait: Duration::from_secs(60),
+        }
+    }
+
+    /// Register a migration
+    pub fn with_migration(mut self, migration: impl Migration + 'static) -> Self {
+        self.migrations.push(Box::new(migration));
+        self
+    }
+
+    /// Register several migrations
+    pub fn with_migrations(mut self, migrations: Vec<Box<dyn Migration>>) -> Self {
+        self.migrations.extend(migrations);
+        self
+    }
+
+    /// How long a lock is honoured before another instance may take it over
+    pub fn with_lock_ttl(mut self, secs: i64) -> Self {
+        self.lock_ttl_secs = secs;
+        self
+    }
+
+    /// How long to wait for another instance to release the lock
+    pub fn with_lock_wait(mut self, secs: u64) -> Self {
+        self.lock_wait = Duration::from_secs(secs);
+        self
+    }
+
+    /// Known and applied migrations, in version order
+    pub async fn status(&self) -> DbResult<Vec<MigrationStatus>> {
+        status(&self.migrations, &self.applied().await?)
+    }
+
+    /// Apply all pending migrations and return the ones that ran
+    pub async fn migrate(&self) -> DbResult<Vec<MigrationRecord>> {
+        self.acquire_lock().await?;
+        let result = self.apply_pending().await;
+        release(self.release_lock().await, result)
+    }
+
+    /// Revert applied migrations newer than `target` and return their versions
+    pub async fn rollback(&self, target: u32) -> DbResult<Vec<u32>> {
+        self.acquire_lock().await?;
+        let result = self.revert_to(target).await;
+        release(self.release_lock().await, result)
+    }
+
+    async fn apply_pending(&self) -> DbResult<Vec<MigrationRecord>> {
+        let applied = self.applied().await?;
+        let mut records = Vec::new();
+
+        for migration in plan_up(&self.migrations, &applied)? {
+            let started = Instant::now();
+            migration.up(&self.db).await.map_err(|e| failed(migration, "apply", e))?;
+
+            let record = MigrationRecord {
+                version: migration.version(),
+                name: migration.name().to_string(),
+                checksum: migration.checksum(),
+                applied_at: chrono::Utc::now().timestamp(),
+                duration_ms: started.elapsed().as_millis() as i64,
+            };
+            self.records()
+                .insert_one(&record, None)
+                .await
+                .map_err(|e| DbError::query_failed(&e.to_string()))?;
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+
+    async fn revert_to(&self, target: u32) -> DbResult<Vec<u32>> {
+        let applied = self.applied().await?;
+        let mut reverted = Vec::new();
+
+        for migration in plan_down(&self.migrations, &applied, target)? {
+            migration.down(&self.db).await.map_err(|e| failed(migration, "revert", e))?;
+            self.records()
+                .delete_one(doc! { "version": migration.version() as i64 }, None)
+                .await
+                .map_err(|e| DbError::query_failed(&e.to_string()))?;
+            reverted.push(migration.version());
+        }
+
+        Ok(reverted)
+    }
+
+    fn records(&self) -> Collection<MigrationRecord> {
+        self.db.collection(MIGRATIONS_COLLECTION)
+    }
+
+    async fn applied(&self) -> DbResult<Vec<MigrationRecord>> {
+        let options = FindOptions::builder().sort(doc! { "version": 1 }).build();
+        self.records()
+            .find(None, options)
+            .await
+            .map_err(|e| DbError::query_failed(&e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| DbError::query_failed(&e.to_string()))
+    }
+
+    async fn acquire_lock(&self) -> DbResult<()> {
+        let locks = self.db.collection::<Document>(MIGRATIONS_LOCK_COLLECTION);
+        let deadline = Instant::now() + self.lock_wait;
+
+        loop {
+            // Matches only a missing or expired lock; otherwise the upsert
+            // collides with the held lock on `_id`
+            let now = chrono::Utc::now().timestamp();
+            let result = locks
+                .update_one(
+                    doc! { "_id": LOCK_ID, "expires_at": { "$lt": now } },
+                    doc! {
+                        "$set": {
+                            "owner": &self.owner,
+                            "locked_at": now,
+                            "expires_at": now + self.lock_ttl_secs
+                        }
+                    },
+                    UpdateOptions::builder().upsert(true).build(),
+                )
+                .await;
+
+            match result {
+                Ok(_) => return Ok(()),
+                Err(e) if is_duplicate_key(&e) && Instant::now() < deadline => {
+                    tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+                }
+                Err(e) if is_duplicate_key(&e) => {
+                    return Err(DbError::migration_failed(
+                        "Migrations are locked by another instance",
+                    ));
+                }
+                Err(e) => return Err(DbError::query_failed(&e.to_string())),
+            }
+        }
+    }
+
+    async fn release_lock(&self) -> DbResult<()> {
+        self.db
+            .collection::<Document>(MIGRATIONS_LOCK_COLLECTION)
+            .delete_one(doc! { "_id": LOCK_ID, "owner": &self.owner }, None)
+            .await
+            .map_err(|e| DbError::query_failed(&e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Migrator for MigrationRunner {
+    async fn status(&self) -> DbResult<Vec<MigrationStatus>> {
+        MigrationRunner::status(self).await
+    }
+
+    async fn migrate(&self) -> DbResult<Vec<MigrationRecord>> {
+        MigrationRunner::migrate(self).await
+    }
+
+    async fn rollback(&self, target: u32) -> DbResult<Vec<u32>> {
+        MigrationRunner::rollback(self, target).await
+    }
+}
+
+/// Known and applied migrations, in version order
+pub(crate) fn status<M: Versioned>(
+    migrations: &[M],
+    applied: &[MigrationRecord],
+) -> DbResult<Vec<MigrationStatus>> {
+    let mut status: Vec<MigrationStatus> = sorted(migrations)?
+        .into_iter()
+        .map(|m| {
+            let record = applied.iter().find(|r| r.version == m.version());
+            MigrationStatus {
+                version: m.version(),
+                name: m.name().to_string(),
+                applied_at: record.map(|r| r.applied_at),
+                checksum_mismatch: record.is_some_and(|r| r.checksum != m.checksum()),
+            }
+        })
+        .collect();
+
+    // Applied by a newer build but unknown to this one
+    let known: HashSet<u32> = status.iter().map(|s| s.version).collect();
+    for record in applied.iter().filter(|r| !known.contains(&r.version)) {
+        status.push(MigrationStatus {
+            version: record.version,
+            name: record.name.clone(),
+            applied_at: Some(record.applied_at),
+            checksum_mismatch: false,
+        });
+    }
+    status.sort_by_key(|s| s.version);
+
+    Ok(status)
+}
+
+/// Result of a locked run, failing if the lock could not be released
+pub(crate) fn release<T>(released: DbResult<()>, result: DbResult<T>) -> DbResult<T> {
+    let value = result?;
+    released?;
+    Ok(value)
+}
+
+/// Duplicate key in a unique index (server code 11000)
+fn is_duplicate_key(error: &MongoError) -> bool {
+    match error.kind.as_ref() {
+        ErrorKind::Write(WriteFailure::WriteError(e)) => e.code == 11000,
+        ErrorKind::Command(e) => e.code == 11000,
+        _ => false,
+    }
+}
+
+pub(crate) fn failed<M: Versioned + ?Sized>(migration: &M, action: &str, error: DbError) -> DbError {
+    DbError::migration_failed(&format!(
+        "Failed to {} migration {} ({}): {}",
+        action,
+        migration.version(),
+        migration.name(),
+        error.message
+    ))
+    .with_details(serde_json::json!({
+        "version": migration.version(),
+        "name": migration.name(),
+    }))
+}
+
+/// Migrations in version order, rejecting duplicate versions
+fn sorted<M: Versioned>(migrations: &[M]) -> DbResult<Vec<&M>> {
+    let mut seen = HashSet::new();
+    let mut sorted: Vec<&M> = Vec::with_capacity(migrations.len());
+
+    for migration in migrations {
+        if !seen.insert(migration.version()) {
+            return Err(DbError::invalid_config(&format!(
+                "Duplicate migration version {}",
+                migration.version()
+            )));
+        }
+        sorted.push(migration);
+    }
+    sorted.sort_by_key(|m| m.version());
+
+    Ok(sorted)
+}
+
+/// Migrations to apply, after checking applied ones haven't changed
+pub(crate) fn plan_up<'a, M: Versioned>(
+    migrations: &'a [M],
+    applied: &[MigrationRecord],
+) -> DbResult<Vec<&'a M>> {
+    let mut pending = Vec::new();
+
+    for migration in sorted(migrations)? {
+        match applied.iter().find(|r| r.version == migration.version()) {
+            Some(record) if record.checksum != migration.checksum() => {
+                return Err(DbError::migration_failed(&format!(
+                    "Migration {} ({}) changed after it was applied",
+                    migration.version(),
+                    migration.name()
+                )));
+            }
+            Some(_) => {}
+            None => pending.push(migration),
+        }
+    }
+
+    Ok(pending)
+}
+
+/// Applied migrations newer than `target`, newest first
+pub(crate) fn plan_down<'a, M: Versioned>(
+    migrations: &'a [M],
+    applied: &[MigrationRecord],
+    target: u32,
+) -> DbResult<Vec<&'a M>> {
+    let known = sorted(migrations)?;
+    let mut revert = Vec::new();
+
+    for record in applied.iter().filter(|r| r.version > target) {
+        let migration = known
+            .iter()
+            .find(|m| m.version() == record.version)
+            .ok_or_else(|| {
+                DbError::migration_not_found(&format!(
+                    "Migration {} ({}) is not known to this build",
+                    record.version, record.name
+                ))
+            })?;
+        revert.push(*migration);
+    }
+    revert.sort_by_key(|m| std::cmp::Reverse(m.version()));
+
+    Ok(revert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::{Step, StepMigration};
+    use crate::utils::DbErrorCode;
+
+    fn migrations() -> Vec<Box<dyn Migration>> {
+        vec![
+            Box::new(StepMigration::new(2, "backfill_org_ids").with_step(Step::backfill("users", "org_ids", Vec::<String>::new()))),
+            Box::new(StepMigration::new(1, "rename_is_active").with_step(Step::rename_field("users", "isActive", "is_active"))),
+            Box::new(StepMigration::new(3, "drop_legacy_index").with_step(Step::drop_index("users", "legacy"))),
+        ]
+    }
+
+    fn record(migration: &dyn Migration) -> MigrationRecord {
+        MigrationRecord {
+            version: migration.version(),
+            name: migration.name().to_string(),
+            checksum: migration.checksum(),
+            applied_at: 0,
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_plans_pending_in_order() {
+        let migrations = migrations();
+        let applied = vec![record(migrations[1].as_ref())];
+
+        let up: Vec<u32> = plan_up(&migrations, &applied).unwrap().iter().map(|m| m.version()).collect();
+        assert_eq!(up, vec![2, 3]);
+
+        let applied: Vec<MigrationRecord> = migrations.iter().map(|m| record(m.as_ref())).collect();
+        let down: Vec<u32> = plan_down(&migrations, &applied, 1).unwrap().iter().map(|m| m.version()).collect();
+        assert_eq!(down, vec![3, 2]);
+    }
+
+    #[test]
+    fn test_rejects_changed_and_unknown_migrations() {
+        let migrations = migrations();
+        let mut changed = record(migrations[0].as_ref());
+        changed.checksum = "edited".to_string();
+        let err = plan_up(&migrations, &[changed]).err().unwrap();
+        assert!(matches!(err.code, DbErrorCode::MigrationFailed));
+
+        let unknown = MigrationRecord { version: 9, name: "future".to_string(), ..record(migrations[0].as_ref()) };
+        let err = plan_down(&migrations, &[unknown], 0).err().unwrap();
+        assert!(matches!(err.code, DbErrorCode::MigrationNotFound));
+
+        let duplicate: Vec<Box<dyn Migration>> = vec![
+            Box::new(StepMigration::new(1, "a")),
+            Box::new(StepMigration::new(1, "b")),
+        ];
+        assert!(plan_up(&duplicate, &[]).is_err());
+    }
+}
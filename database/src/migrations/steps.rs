@@ -0,0 +1,230 @@
+//! Declarative migration steps
+//!
+//! Covers the common cases (renaming fields, backfilling defaults and managing
+//! indexes) so most migrations don't need a hand-written `Migration` impl.
+
+use async_trait::async_trait;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::Database;
+
+use super::{checksum, Migration};
+use crate::indexes::IndexSpec;
+use crate::utils::{DbError, DbResult};
+
+/// A single schema change
+#[derive(Debug, Clone)]
+pub enum Step {
+    /// Rename a field on every document that has it
+    RenameField {
+        collection: String,
+        from: String,
+        to: String,
+    },
+    /// Set a field on every document where it is missing
+    Backfill {
+        collection: String,
+        field: String,
+        value: Bson,
+    },
+    /// Create an index
+    CreateIndex { collection: String, index: IndexSpec },
+    /// Drop an index by name
+    DropIndex { collection: String, name: String },
+}
+
+impl Step {
+    pub fn rename_field(collection: &str, from: &str, to: &str) -> Self {
+        Step::RenameField {
+            collection: collection.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    pub fn backfill(collection: &str, field: &str, value: impl Into<Bson>) -> Self {
+        Step::Backfill {
+            collection: collection.to_string(),
+            field: field.to_string(),
+            value: value.into(),
+        }
+    }
+
+    pub fn create_index(collection: &str, index: IndexSpec) -> Self {
+        Step::CreateIndex {
+            collection: collection.to_string(),
+            index,
+        }
+    }
+
+    pub fn drop_index(collection: &str, name: &str) -> Self {
+        Step::DropIndex {
+            collection: collection.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    /// Human readable form, also used for checksums
+    pub fn describe(&self) -> String {
+        match self {
+            Step::RenameField { collection, from, to } => {
+                format!("rename {}.{} to {}", collection, from, to)
+            }
+            Step::Backfill { collection, field, value } => {
+                format!("backfill {}.{} with {}", collection, field, value)
+            }
+            Step::CreateIndex { collection, index } => {
+                format!("create index {} on {}", index.describe(), collection)
+            }
+            Step::DropIndex { collection, name } => {
+                format!("drop index {} on {}", name, collection)
+            }
+        }
+    }
+
+    /// Run the step against the database
+    pub async fn apply(&self, db: &Database) -> DbResult<()> {
+        let failed = |e: mongodb::error::Error| {
+            DbError::migration_failed(&format!("Failed to {}: {}", self.describe(), e))
+        };
+
+        match self {
+            Step::RenameField { collection, from, to } => {
+                db.collection::<Document>(collection)
+                    .update_many(
+                        doc! { from.as_str(): { "$exists": true } },
+                        doc! { "$rename": { from.as_str(): to.as_str() } },
+                        None,
+                    )
+                    .await
+                    .map_err(failed)?;
+            }
+            Step::Backfill { collection, field, value } => {
+                db.collection::<Document>(collection)
+                    .update_many(
+                        doc! { field.as_str(): { "$exists": false } },
+                        doc! { "$set": { field.as_str(): value.clone() } },
+                        None,
+                    )
+                    .await
+                    .map_err(failed)?;
+            }
+            Step::CreateIndex { collection, index } => {
+                db.collection::<Document>(collection)
+                    .create_index(index.model(), None)
+                    .await
+                    .map_err(failed)?;
+            }
+            Step::DropIndex { collection, name } => {
+                db.collection::<Document>(collection)
+                    .drop_index(name.as_str(), None)
+                    .await
+                    .map_err(failed)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Migration built from a list of steps
+///
+/// Its checksum covers every step, so editing an applied migration is detected.
+///
+/// ```ignore
+/// StepMigration::new(1, "rename_is_active")
+///     .with_step(Step::rename_field("users", "isActive", "is_active"))
+///     .with_down_step(Step::rename_field("users", "is_active", "isActive"))
+/// ```
+#[derive(Debug, Clone)]
+pub struct StepMigration {
+    version: u32,
+    name: String,
+    up: Vec<Step>,
+    down: Vec<Step>,
+}
+
+impl StepMigration {
+    pub fn new(version: u32, name: impl Into<String>) -> Self {
+        Self {
+            version,
+            name: name.into(),
+            up: Vec::new(),
+            down: Vec::new(),
+        }
+    }
+
+    /// Add a step to run when migrating up
+    pub fn with_step(mut self, step: Step) -> Self {
+        self.up.push(step);
+        self
+    }
+
+    /// Add a step to run when reverting
+    pub fn with_down_step(mut self, step: Step) -> Self {
+        self.down.push(step);
+        self
+    }
+}
+
+#[async_trait]
+impl Migration for StepMigration {
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn checksum(&self) -> String {
+        let version = self.version.to_string();
+        let up: Vec<String> = self.up.iter().map(|s| format!("up: {}", s.describe())).collect();
+        let down: Vec<String> = self.down.iter().map(|s| format!("down: {}", s.describe())).collect();
+
+        let mut parts = vec![version.as_str(), self.name.as_str()];
+        parts.extend(up.iter().chain(down.iter()).map(String::as_str));
+        checksum(&parts)
+    }
+
+    async fn up(&self, db: &Database) -> DbResult<()> {
+        for step in &self.up {
+            step.apply(db).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, db: &Database) -> DbResult<()> {
+        if self.down.is_empty() {
+            return Err(DbError::not_supported(&format!(
+                "Migration {} ({}) cannot be reverted",
+                self.version, self.name
+            )));
+        }
+        for step in &self.down {
+            step.apply(db).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_covers_steps() {
+        let base = StepMigration::new(1, "rename_is_active")
+            .with_step(Step::rename_field("users", "isActive", "is_active"));
+        let edited = StepMigration::new(1, "rename_is_active")
+            .with_step(Step::rename_field("users", "isActive", "active"));
+
+        assert_eq!(base.checksum(), base.clone().checksum());
+        assert_ne!(base.checksum(), edited.checksum());
+        assert_ne!(
+            base.checksum(),
+            base.clone()
+                .with_down_step(Step::rename_field("users", "is_active", "isActive"))
+                .checksum()
+        );
+    }
+}
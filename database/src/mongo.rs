@@ -1,25 +1,61 @@
 //! MongoDB database module
 
-use mongodb::sync::{Client, Database};
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::init::{Database as DbTrait, DatabaseConfig};
-use crate::utils::DbResult;
+use async_trait::async_trait;
+use mongodb::bson::doc;
+use mongodb::error::{Error as MongoError, ErrorKind};
+use mongodb::options::ClientOptions;
+use mongodb::{Client, Database};
+
+use crate::init::{retry_delay, Database as DbTrait, DatabaseConfig};
+use crate::pool::{PoolMonitor, PoolStats};
+use crate::utils::{DbError, DbResult};
 
 /// MongoDB connection
 pub struct MongoConnection {
     client: Client,
     db_name: String,
+    monitor: Arc<PoolMonitor>,
 }
 
 impl MongoConnection {
-    pub fn new(config: DatabaseConfig) -> DbResult<MongoConnection> {
-        let client = Client::with_uri_str(config.connection_string)
-            .map_err(|e| crate::utils::DbError::connection_failed(&e.to_string()))?;
+    /// Connect, retrying with exponential backoff until the server answers a ping
+    pub async fn new(config: DatabaseConfig) -> DbResult<MongoConnection> {
+        config.validate()?;
+
+        let monitor = Arc::new(PoolMonitor::new(config.max_connections, config.min_connections));
+        let mut options = ClientOptions::parse(&config.connection_string)
+            .await
+            .map_err(|e| DbError::invalid_config(&format!("Invalid connection string: {}", e)))?;
+        apply_config(&mut options, &config);
+        options.cmap_event_handler = Some(monitor.clone());
 
-        Ok(MongoConnection {
+        let client = Client::with_options(options).map_err(|e| connection_error(&e))?;
+        let connection = MongoConnection {
             client,
             db_name: config.db_name,
-        })
+            monitor,
+        };
+
+        let backoff = Duration::from_millis(config.retry_backoff_ms);
+        let mut attempt = 0;
+        loop {
+            match connection.ping().await {
+                Ok(()) => return Ok(connection),
+                Err(_) if attempt < config.connect_retries => {
+                    tokio::time::sleep(retry_delay(backoff, attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return Err(DbError::new(
+                        e.code,
+                        format!("{} (after {} attempts)", e.message, attempt + 1),
+                    ))
+                }
+            }
+        }
     }
 
     /// Get the MongoDB client
@@ -33,18 +69,65 @@ impl MongoConnection {
     }
 }
 
+#[async_trait]
 impl DbTrait for MongoConnection {
     fn db_name(&self) -> &str {
         &self.db_name
     }
 
-    fn ping(&self) -> DbResult<()> {
-        // TODO: Implement ping
+    async fn ping(&self) -> DbResult<()> {
+        self.database()
+            .run_command(doc! { "ping": 1 }, None)
+            .await
+            .map_err(|e| connection_error(&e))?;
         Ok(())
     }
 
-    fn close(&self) -> DbResult<()> {
-        // TODO: Implement close
+    /// Close every pooled connection; the client can't be used afterwards
+    async fn close(&self) -> DbResult<()> {
+        self.client.clone().shutdown().await;
         Ok(())
     }
+
+    fn pool_stats(&self) -> PoolStats {
+        self.monitor.stats()
+    }
+}
+
+/// Map pool and timeout settings onto the driver options
+fn apply_config(options: &mut ClientOptions, config: &DatabaseConfig) {
+    let timeout = Duration::from_secs(config.connection_timeout_secs);
+
+    options.max_pool_size = Some(config.max_connections);
+    options.min_pool_size = Some(config.min_connections);
+    options.connect_timeout = Some(timeout);
+    options.server_selection_timeout = Some(timeout);
+}
+
+fn connection_error(error: &MongoError) -> DbError {
+    match error.kind.as_ref() {
+        ErrorKind::ServerSelection { .. } => DbError::connection_timeout(&error.to_string()),
+        _ => DbError::connection_failed(&error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_config_is_applied_to_client_options() {
+        let config = DatabaseConfig::new("mongodb://localhost:27017", "app")
+            .with_pool(20, 2)
+            .with_timeout(5);
+        let mut options = ClientOptions::parse(&config.connection_string).await.unwrap();
+        apply_config(&mut options, &config);
+
+        assert_eq!(options.max_pool_size, Some(20));
+        assert_eq!(options.min_pool_size, Some(2));
+        assert_eq!(options.connect_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(options.server_selection_timeout, Some(Duration::from_secs(5)));
+
+        assert!(DatabaseConfig::new("mongodb://localhost", "app").with_pool(1, 2).validate().is_err());
+    }
 }
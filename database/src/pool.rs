@@ -0,0 +1,128 @@
+//! Connection pool statistics
+//!
+//! The driver reports pool activity through CMAP events. `PoolMonitor` counts
+//! them so health checks and metrics can show how busy the pool is.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use mongodb::event::cmap::{
+    CmapEventHandler, ConnectionCheckedInEvent, ConnectionCheckedOutEvent,
+    ConnectionCheckoutFailedEvent, ConnectionClosedEvent, ConnectionCreatedEvent,
+    PoolClearedEvent,
+};
+use serde::Serialize;
+
+/// Snapshot of the connection pool
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PoolStats {
+    /// Configured maximum connections per server
+    pub max_size: u32,
+    /// Configured minimum connections per server
+    pub min_size: u32,
+    /// Open connections, across all servers
+    pub open: u64,
+    /// Connections currently checked out by operations
+    pub in_use: u64,
+    /// Open connections waiting in the pool
+    pub idle: u64,
+    /// Checkouts that failed, e.g. on timeout or a closed pool
+    pub checkout_failures: u64,
+    /// Times a pool was cleared after a network error
+    pub cleared: u64,
+}
+
+/// Counts pool events reported by the driver
+#[derive(Debug, Default)]
+pub struct PoolMonitor {
+    max_size: u32,
+    min_size: u32,
+    created: AtomicU64,
+    closed: AtomicU64,
+    checked_out: AtomicU64,
+    checked_in: AtomicU64,
+    checkout_failures: AtomicU64,
+    cleared: AtomicU64,
+}
+
+impl PoolMonitor {
+    pub fn new(max_size: u32, min_size: u32) -> Self {
+        Self {
+            max_size,
+            min_size,
+            ..Default::default()
+        }
+    }
+
+    /// Current pool statistics
+    pub fn stats(&self) -> PoolStats {
+        let open = count(&self.created).saturating_sub(count(&self.closed));
+        let in_use = count(&self.checked_out).saturating_sub(count(&self.checked_in));
+
+        PoolStats {
+            max_size: self.max_size,
+            min_size: self.min_size,
+            open,
+            in_use,
+            idle: open.saturating_sub(in_use),
+            checkout_failures: count(&self.checkout_failures),
+            cleared: count(&self.cleared),
+        }
+    }
+}
+
+fn count(counter: &AtomicU64) -> u64 {
+    counter.load(Ordering::Relaxed)
+}
+
+fn increment(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+impl CmapEventHandler for PoolMonitor {
+    fn handle_pool_cleared_event(&self, _event: PoolClearedEvent) {
+        increment(&self.cleared);
+    }
+
+    fn handle_connection_created_event(&self, _event: ConnectionCreatedEvent) {
+        increment(&self.created);
+    }
+
+    fn handle_connection_closed_event(&self, _event: ConnectionClosedEvent) {
+        increment(&self.closed);
+    }
+
+    fn handle_connection_checkout_failed_event(&self, _event: ConnectionCheckoutFailedEvent) {
+        increment(&self.checkout_failures);
+    }
+
+    fn handle_connection_checked_out_event(&self, _event: ConnectionCheckedOutEvent) {
+        increment(&self.checked_out);
+    }
+
+    fn handle_connection_checked_in_event(&self, _event: ConnectionCheckedInEvent) {
+        increment(&self.checked_in);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_track_open_and_in_use_connections() {
+        let monitor = PoolMonitor::new(10, 1);
+        for _ in 0..3 {
+            increment(&monitor.created);
+        }
+        increment(&monitor.closed);
+        increment(&monitor.checked_out);
+        increment(&monitor.checked_out);
+        increment(&monitor.checked_in);
+
+        let stats = monitor.stats();
+        assert_eq!(stats.max_size, 10);
+        assert_eq!(stats.open, 2);
+        assert_eq!(stats.in_use, 1);
+        assert_eq!(stats.idle, 1);
+    }
+}
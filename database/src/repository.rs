@@ -0,0 +1,466 @@
+//! Typed repositories over MongoDB collections
+//!
+//! `Repository<T>` wraps a collection with the operations stores keep
+//! rewriting: lookups by `DbId`, filter and update builders, projection,
+//! upsert and soft delete. Every call joins the unit of work running on the
+//! current task (see `transaction`), and driver errors come back as `DbError`
+//! with a matching code, e.g. `DuplicateKey` naming the unique index hit.
+//!
+//! ```ignore
+//! let users: Repository<User> = Repository::new(db.collection("users"));
+//!
+//! let user = users.find_one(Filter::new().eq("email", email).not_deleted()).await?;
+//! users.update_by_id(&user_id, Update::new().set("isVerified", true)).await?;
+//! ```
+//!
+//! Documents are keyed by an `id` field holding the `DbId`, next to MongoDB's
+//! own `_id`.
+
+use chrono::Utc;
+use mongodb::bson::{self, Bson, Document, doc};
+use mongodb::options::{FindOneAndUpdateOptions, FindOneOptions, FindOptions, ReplaceOptions};
+use mongodb::error::Error as MongoError;
+use mongodb::Collection;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::indexes::with_ttl;
+use crate::transaction::TransactionExt;
+use crate::utils::{DbError, DbId, DbResult};
+
+/// Field holding a document's `DbId`
+pub const ID_FIELD: &str = "id";
+
+/// Field set by `Repository::soft_delete`
+pub const DELETED_AT_FIELD: &str = "deleted_at";
+
+/// Encode a serializable value (an enum, a timestamp) for a filter or update
+pub fn to_bson<V: Serialize + ?Sized>(value: &V) -> DbResult<Bson> {
+    bson::to_bson(value).map_err(|e| DbError::internal_error(&format!("Failed to encode value: {}", e)))
+}
+
+/// Query filter builder
+///
+/// Comparisons on the same field merge, so `gte` then `lt` gives a range.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    doc: Document,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match the document with this id
+    pub fn by_id(id: &DbId) -> Self {
+        Self::new().eq(ID_FIELD, id.to_bson())
+    }
+
+    pub fn eq(mut self, field: &str, value: impl Into<Bson>) -> Self {
+        self.doc.insert(field, value.into());
+        self
+    }
+
+    pub fn ne(self, field: &str, value: impl Into<Bson>) -> Self {
+        self.op(field, "$ne", value.into())
+    }
+
+    pub fn gt(self, field: &str, value: impl Into<Bson>) -> Self {
+        self.op(field, "$gt", value.into())
+    }
+
+    pub fn gte(self, field: &str, value: impl Into<Bson>) -> Self {
+        self.op(field, "$gte", value.into())
+    }
+
+    pub fn lt(self, field: &str, value: impl Into<Bson>) -> Self {
+        self.op(field, "$lt", value.into())
+    }
+
+    pub fn lte(self, field: &str, value: impl Into<Bson>) -> Self {
+        self.op(field, "$lte", value.into())
+    }
+
+    /// Match any of the values
+    pub fn is_in<V: Into<Bson>>(self, field: &str, values: impl IntoIterator<Item = V>) -> Self {
+        let values: Vec<Bson> = values.into_iter().map(Into::into).collect();
+        self.op(field, "$in", Bson::Array(values))
+    }
+
+    pub fn exists(self, field: &str, exists: bool) -> Self {
+        self.op(field, "$exists", Bson::Boolean(exists))
+    }
+
+    /// Skip soft-deleted documents
+    pub fn not_deleted(self) -> Self {
+        self.eq(DELETED_AT_FIELD, Bson::Null)
+    }
+
+    /// Also match at least one of the alternatives
+    pub fn any_of(mut self, filters: impl IntoIterator<Item = Filter>) -> Self {
+        let filters: Vec<Document> = filters.into_iter().map(Filter::into_document).collect();
+        self.doc.insert("$or", filters);
+        self
+    }
+
+    pub fn into_document(self) -> Document {
+        self.doc
+    }
+
+    /// Add `{ field: { op: value } }`, next to other operators on the field
+    fn op(mut self, field: &str, op: &str, value: Bson) -> Self {
+        match self.doc.get_mut(field) {
+            Some(Bson::Document(ops)) => {
+                ops.insert(op, value);
+            }
+            _ => {
+                self.doc.insert(field, doc! { op: value });
+            }
+        }
+        self
+    }
+}
+
+impl From<Document> for Filter {
+    fn from(doc: Document) -> Self {
+        Self { doc }
+    }
+}
+
+impl From<Filter> for Document {
+    fn from(filter: Filter) -> Self {
+        filter.doc
+    }
+}
+
+/// Update builder, one section per update operator
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Update {
+    set: Document,
+    unset: Document,
+    inc: Document,
+    add_to_set: Document,
+    pull: Document,
+}
+
+impl Update {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(mut self, field: &str, value: impl Into<Bson>) -> Self {
+        self.set.insert(field, value.into());
+        self
+    }
+
+    pub fn unset(mut self, field: &str) -> Self {
+        self.unset.insert(field, "");
+        self
+    }
+
+    pub fn inc(mut self, field: &str, by: i64) -> Self {
+        self.inc.insert(field, by);
+        self
+    }
+
+    /// Append to an array unless already present
+    pub fn add_to_set(mut self, field: &str, value: impl Into<Bson>) -> Self {
+        self.add_to_set.insert(field, value.into());
+        self
+    }
+
+    /// Remove matching values from an array
+    pub fn pull(mut self, field: &str, value: impl Into<Bson>) -> Self {
+        self.pull.insert(field, value.into());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+            && self.unset.is_empty()
+            && self.inc.is_empty()
+            && self.add_to_set.is_empty()
+            && self.pull.is_empty()
+    }
+
+    pub fn into_document(self) -> Document {
+        let mut update = Document::new();
+        for (op, fields) in [
+            ("$set", self.set),
+            ("$unset", self.unset),
+            ("$inc", self.inc),
+            ("$addToSet", self.add_to_set),
+            ("$pull", self.pull),
+        ] {
+            if !fields.is_empty() {
+                update.insert(op, fields);
+            }
+        }
+        update
+    }
+}
+
+impl From<Update> for Document {
+    fn from(update: Update) -> Self {
+        update.into_document()
+    }
+}
+
+/// Typed operations on one collection
+pub struct Repository<T: Send + Sync> {
+    collection: Collection<T>,
+}
+
+impl<T: Send + Sync> Clone for Repository<T> {
+    fn clone(&self) -> Self {
+        Self {
+            collection: self.collection.clone(),
+        }
+    }
+}
+
+impl<T> Repository<T>
+where
+    T: Serialize + DeserializeOwned + Unpin + Send + Sync,
+{
+    pub fn new(collection: Collection<T>) -> Self {
+        Self { collection }
+    }
+
+    /// The wrapped collection, for queries the repository doesn't cover
+    pub fn collection(&self) -> &Collection<T> {
+        &self.collection
+    }
+
+    pub async fn insert(&self, item: &T) -> DbResult<()> {
+        self.collection
+            .insert_one_tx(item, None)
+            .await
+            .map_err(|e| self.failed("insert into", e))?;
+        Ok(())
+    }
+
+    /// Insert with the TTL field set, so the TTL index drops it at `expires_at`
+    pub async fn insert_expiring(&self, item: &T, expires_at: i64) -> DbResult<()> {
+        self.collection
+            .clone_with_type::<Document>()
+            .insert_one_tx(with_ttl(item, expires_at)?, None)
+            .await
+            .map_err(|e| self.failed("insert into", e))?;
+        Ok(())
+    }
+
+    pub async fn find_by_id(&self, id: &DbId) -> DbResult<Option<T>> {
+        self.find_one(Filter::by_id(id)).await
+    }
+
+    pub async fn find_one(&self, filter: Filter) -> DbResult<Option<T>> {
+        self.find_one_with(filter, None).await
+    }
+
+    /// First match in the given sort order
+    pub async fn find_first(&self, filter: Filter, sort: Document) -> DbResult<Option<T>> {
+        self.find_one_with(filter, FindOneOptions::builder().sort(sort).build()).await
+    }
+
+    /// All matches, with optional sort, skip and limit
+    pub async fn find(&self, filter: Filter, options: impl Into<Option<FindOptions>> + Send) -> DbResult<Vec<T>> {
+        self.collection
+            .find_tx(filter.into_document(), options)
+            .await
+            .map_err(|e| self.failed("read", e))
+    }
+
+    /// Matches with only the projected fields, read as `P`
+    pub async fn find_projected<P: DeserializeOwned>(
+        &self,
+        filter: Filter,
+        projection: Document,
+    ) -> DbResult<Vec<P>> {
+        let options = FindOptions::builder().projection(projection).build();
+        let documents = self
+            .collection
+            .clone_with_type::<Document>()
+            .find_tx(filter.into_document(), options)
+            .await
+            .map_err(|e| self.failed("read", e))?;
+
+        documents
+            .into_iter()
+            .map(|document| {
+                bson::from_document(document)
+                    .map_err(|e| DbError::internal_error(&format!("Failed to decode projection: {}", e)))
+            })
+            .collect()
+    }
+
+    pub async fn count(&self, filter: Filter) -> DbResult<u64> {
+        self.collection
+            .count_documents_tx(filter.into_document(), None)
+            .await
+            .map_err(|e| self.failed("count", e))
+    }
+
+    pub async fn exists(&self, filter: Filter) -> DbResult<bool> {
+        Ok(self.find_one(filter).await?.is_some())
+    }
+
+    /// Update the first match, returning whether one matched
+    pub async fn update_one(&self, filter: Filter, update: Update) -> DbResult<bool> {
+        let result = self
+            .collection
+            .update_one_tx(filter.into_document(), update.into_document(), None)
+            .await
+            .map_err(|e| self.failed("update", e))?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    pub async fn update_by_id(&self, id: &DbId, update: Update) -> DbResult<bool> {
+        self.update_one(Filter::by_id(id), update).await
+    }
+
+    /// Update every match, returning how many changed
+    pub async fn update_many(&self, filter: Filter, update: Update) -> DbResult<u64> {
+        let result = self
+            .collection
+            .update_many_tx(filter.into_document(), update.into_document(), None)
+            .await
+            .map_err(|e| self.failed("update", e))?;
+
+        Ok(result.modified_count)
+    }
+
+    /// Update the first match and return it as it is after the update
+    pub async fn find_one_and_update(&self, filter: Filter, update: Update) -> DbResult<Option<T>> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(mongodb::options::ReturnDocument::After)
+            .build();
+
+        self.collection
+            .find_one_and_update_tx(filter.into_document(), update.into_document(), options)
+            .await
+            .map_err(|e| self.failed("update", e))
+    }
+
+    /// Replace the first match, inserting the document when nothing matches
+    pub async fn upsert(&self, filter: Filter, item: &T) -> DbResult<()> {
+        let options = ReplaceOptions::builder().upsert(true).build();
+
+        self.collection
+            .replace_one_tx(filter.into_document(), item, options)
+            .await
+            .map_err(|e| self.failed("upsert into", e))?;
+        Ok(())
+    }
+
+    /// Delete the first match, returning whether one matched
+    pub async fn delete_one(&self, filter: Filter) -> DbResult<bool> {
+        let result = self
+            .collection
+            .delete_one_tx(filter.into_document(), None)
+            .await
+            .map_err(|e| self.failed("delete from", e))?;
+
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn delete_by_id(&self, id: &DbId) -> DbResult<bool> {
+        self.delete_one(Filter::by_id(id)).await
+    }
+
+    /// Delete every match, returning how many went
+    pub async fn delete_many(&self, filter: Filter) -> DbResult<u64> {
+        let result = self
+            .collection
+            .delete_many_tx(filter.into_document(), None)
+            .await
+            .map_err(|e| self.failed("delete from", e))?;
+
+        Ok(result.deleted_count)
+    }
+
+    /// Stamp `deleted_at` on matches not deleted yet, returning how many
+    ///
+    /// The time is encoded like chrono fields in models, so
+    /// `Option<DateTime<Utc>>` reads it back.
+    pub async fn soft_delete(&self, filter: Filter) -> DbResult<u64> {
+        let update = Update::new().set(DELETED_AT_FIELD, to_bson(&Utc::now())?);
+        self.update_many(filter.not_deleted(), update).await
+    }
+
+    async fn find_one_with(
+        &self,
+        filter: Filter,
+        options: impl Into<Option<FindOneOptions>> + Send,
+    ) -> DbResult<Option<T>> {
+        self.collection
+            .find_one_tx(filter.into_document(), options)
+            .await
+            .map_err(|e| self.failed("read", e))
+    }
+
+    /// Driver error with the operation and collection in the message
+    fn failed(&self, action: &str, error: MongoError) -> DbError {
+        let mut error = DbError::from(error);
+        error.message = format!("Failed to {} {}: {}", action, self.collection.name(), error.message);
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_merges_operators_on_a_field() {
+        let filter = Filter::new()
+            .eq("org_id", "acme")
+            .gte("created_at", 10)
+            .lt("created_at", 20)
+            .not_deleted();
+
+        assert_eq!(
+            filter.into_document(),
+            doc! {
+                "org_id": "acme",
+                "created_at": { "$gte": 10, "$lt": 20 },
+                "deleted_at": null,
+            }
+        );
+    }
+
+    #[test]
+    fn test_filter_by_id_and_alternatives() {
+        let id = DbId::new_uuid();
+        let filter = Filter::by_id(&id).any_of([Filter::new().eq("a", 1), Filter::new().exists("b", true)]);
+
+        assert_eq!(
+            filter.into_document(),
+            doc! {
+                "id": id.to_bson(),
+                "$or": [{ "a": 1 }, { "b": { "$exists": true } }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_update_groups_fields_by_operator() {
+        let update = Update::new()
+            .set("name", "Amani")
+            .set("active", true)
+            .unset("locked_until")
+            .inc("login_attempts", 1);
+
+        assert_eq!(
+            update.into_document(),
+            doc! {
+                "$set": { "name": "Amani", "active": true },
+                "$unset": { "locked_until": "" },
+                "$inc": { "login_attempts": 1_i64 },
+            }
+        );
+        assert!(Update::new().is_empty());
+    }
+}
@@ -0,0 +1,372 @@
+//! SQL migrations
+//!
+//! Versioned schema scripts with the same planning, checksums and locking as
+//! the MongoDB runner. Each script runs in a transaction together with its
+//! record in `_migrations`, so a failed script leaves nothing half applied.
+
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Executor, Row};
+
+use super::query_error;
+use crate::migrations::runner::{failed, plan_down, plan_up, release, status};
+use crate::migrations::{
+    checksum, MigrationRecord, MigrationStatus, Migrator, Versioned, MIGRATIONS_COLLECTION,
+    MIGRATIONS_LOCK_COLLECTION,
+};
+use crate::utils::{DbError, DbResult};
+
+const LOCK_ID: &str = "migrations";
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A versioned schema script
+#[derive(Debug, Clone)]
+pub struct SqlMigration {
+    version: u32,
+    name: String,
+    up: String,
+    down: Option<String>,
+}
+
+impl SqlMigration {
+    /// Migration applying `up`, which may hold several statements
+    pub fn new(version: u32, name: impl Into<String>, up: impl Into<String>) -> Self {
+        Self {
+            version,
+            name: name.into(),
+            up: up.into(),
+            down: None,
+        }
+    }
+
+    /// Script reverting the migration
+    pub fn with_down(mut self, down: impl Into<String>) -> Self {
+        self.down = Some(down.into());
+        self
+    }
+}
+
+impl Versioned for SqlMigration {
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Covers the `up` script, so editing a shipped script is caught
+    fn checksum(&self) -> String {
+        checksum(&[&self.version.to_string(), &self.name, &self.up])
+    }
+}
+
+/// Runs migrations against a SQL database
+pub struct SqlMigrationRunner {
+    pool: AnyPool,
+    migrations: Vec<SqlMigration>,
+    owner: String,
+    lock_ttl_secs: i64,
+    lock_wait: Duration,
+}
+
+impl SqlMigrationRunner {
+    pub fn new(pool: AnyPool) -> Self {
+        Self {
+            pool,
+            migrations: Vec::new(),
+            owner: uuid::Uuid::new_v4().to_string(),
+            lock_ttl_secs: 600,
+            lock_wait: Duration::from_secs(60),
+        }
+    }
+
+    /// Register a migration
+    pub fn with_migration(mut self, migration: SqlMigration) -> Self {
+        self.migrations.push(migration);
+        self
+    }
+
+    /// Register several migrations
+    pub fn with_migrations(mut self, migrations: Vec<SqlMigration>) -> Self {
+        self.migrations.extend(migrations);
+        self
+    }
+
+    /// How long a lock is honoured before another instance may take it over
+    pub fn with_lock_ttl(mut self, secs: i64) -> Self {
+        self.lock_ttl_secs = secs;
+        self
+    }
+
+    /// How long to wait for another instance to release the lock
+    pub fn with_lock_wait(mut self, secs: u64) -> Self {
+        self.lock_wait = Duration::from_secs(secs);
+        self
+    }
+
+    /// Known and applied migrations, in version order
+    pub async fn status(&self) -> DbResult<Vec<MigrationStatus>> {
+        self.ensure_tables().await?;
+        status(&self.migrations, &self.applied().await?)
+    }
+
+    /// Apply all pending migrations and return the ones that ran
+    pub async fn migrate(&self) -> DbResult<Vec<MigrationRecord>> {
+        self.ensure_tables().await?;
+        self.acquire_lock().await?;
+        let result = self.apply_pending().await;
+        release(self.release_lock().await, result)
+    }
+
+    /// Revert applied migrations newer than `target` and return their versions
+    pub async fn rollback(&self, target: u32) -> DbResult<Vec<u32>> {
+        self.ensure_tables().await?;
+        self.acquire_lock().await?;
+        let result = self.revert_to(target).await;
+        release(self.release_lock().await, result)
+    }
+
+    async fn apply_pending(&self) -> DbResult<Vec<MigrationRecord>> {
+        let applied = self.applied().await?;
+        let mut records = Vec::new();
+
+        for migration in plan_up(&self.migrations, &applied)? {
+            let started = Instant::now();
+            let mut tx = self.pool.begin().await.map_err(|e| query_error(&e))?;
+            // Through `Executor` rather than `RawSql::execute`, whose future
+            // isn't `Send` for every lifetime
+            tx.execute(sqlx::raw_sql(&migration.up))
+                .await
+                .map_err(|e| failed(migration, "apply", query_error(&e)))?;
+
+            let record = MigrationRecord {
+                version: migration.version,
+                name: migration.name.clone(),
+                checksum: migration.checksum(),
+                applied_at: chrono::Utc::now().timestamp(),
+                duration_ms: started.elapsed().as_millis() as i64,
+            };
+            sqlx::query(&format!(
+                "INSERT INTO {} (version, name, checksum, applied_at, duration_ms) VALUES ($1, $2, $3, $4, $5)",
+                MIGRATIONS_COLLECTION
+            ))
+            .bind(i64::from(record.version))
+            .bind(&record.name)
+            .bind(&record.checksum)
+            .bind(record.applied_at)
+            .bind(record.duration_ms)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| query_error(&e))?;
+
+            tx.commit().await.map_err(|e| query_error(&e))?;
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+
+    async fn revert_to(&self, target: u32) -> DbResult<Vec<u32>> {
+        let applied = self.applied().await?;
+        let mut reverted = Vec::new();
+
+        for migration in plan_down(&self.migrations, &applied, target)? {
+            let down = migration.down.as_ref().ok_or_else(|| {
+                DbError::not_supported(&format!(
+                    "Migration {} ({}) cannot be reverted",
+                    migration.version, migration.name
+                ))
+            })?;
+
+            let mut tx = self.pool.begin().await.map_err(|e| query_error(&e))?;
+            tx.execute(sqlx::raw_sql(down))
+                .await
+                .map_err(|e| failed(migration, "revert", query_error(&e)))?;
+            sqlx::query(&format!("DELETE FROM {} WHERE version = $1", MIGRATIONS_COLLECTION))
+                .bind(i64::from(migration.version))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| query_error(&e))?;
+
+            tx.commit().await.map_err(|e| query_error(&e))?;
+            reverted.push(migration.version);
+        }
+
+        Ok(reverted)
+    }
+
+    /// Create the bookkeeping tables on first use
+    async fn ensure_tables(&self) -> DbResult<()> {
+        let statements = [
+            format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    version BIGINT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    checksum TEXT NOT NULL,
+                    applied_at BIGINT NOT NULL,
+                    duration_ms BIGINT NOT NULL
+                )",
+                MIGRATIONS_COLLECTION
+            ),
+            format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    id TEXT PRIMARY KEY,
+                    owner TEXT NOT NULL,
+                    locked_at BIGINT NOT NULL,
+                    expires_at BIGINT NOT NULL
+                )",
+                MIGRATIONS_LOCK_COLLECTION
+            ),
+        ];
+
+        for statement in &statements {
+            sqlx::query(statement)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| query_error(&e))?;
+        }
+        Ok(())
+    }
+
+    async fn applied(&self) -> DbResult<Vec<MigrationRecord>> {
+        let rows = sqlx::query(&format!(
+            "SELECT version, name, checksum, applied_at, duration_ms FROM {} ORDER BY version",
+            MIGRATIONS_COLLECTION
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| query_error(&e))?;
+
+        rows.iter()
+            .map(record_from_row)
+            .collect::<Result<_, _>>()
+            .map_err(|e| query_error(&e))
+    }
+
+    async fn acquire_lock(&self) -> DbResult<()> {
+        let deadline = Instant::now() + self.lock_wait;
+        // Takes a missing or expired lock; a held one is left untouched
+        let statement = format!(
+            "INSERT INTO {table} (id, owner, locked_at, expires_at) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (id) DO UPDATE SET
+                owner = excluded.owner,
+                locked_at = excluded.locked_at,
+                expires_at = excluded.expires_at
+             WHERE {table}.expires_at < $3",
+            table = MIGRATIONS_LOCK_COLLECTION
+        );
+
+        loop {
+            let now = chrono::Utc::now().timestamp();
+            let result = sqlx::query(&statement)
+                .bind(LOCK_ID)
+                .bind(&self.owner)
+                .bind(now)
+                .bind(now + self.lock_ttl_secs)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| query_error(&e))?;
+
+            if result.rows_affected() > 0 {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(DbError::migration_failed(
+                    "Migrations are locked by another instance",
+                ));
+            }
+            tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+        }
+    }
+
+    async fn release_lock(&self) -> DbResult<()> {
+        sqlx::query(&format!(
+            "DELETE FROM {} WHERE id = $1 AND owner = $2",
+            MIGRATIONS_LOCK_COLLECTION
+        ))
+        .bind(LOCK_ID)
+        .bind(&self.owner)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| query_error(&e))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Migrator for SqlMigrationRunner {
+    async fn status(&self) -> DbResult<Vec<MigrationStatus>> {
+        SqlMigrationRunner::status(self).await
+    }
+
+    async fn migrate(&self) -> DbResult<Vec<MigrationRecord>> {
+        SqlMigrationRunner::migrate(self).await
+    }
+
+    async fn rollback(&self, target: u32) -> DbResult<Vec<u32>> {
+        SqlMigrationRunner::rollback(self, target).await
+    }
+}
+
+fn record_from_row(row: &AnyRow) -> Result<MigrationRecord, sqlx::Error> {
+    Ok(MigrationRecord {
+        version: row.try_get::<i64, _>("version")? as u32,
+        name: row.try_get("name")?,
+        checksum: row.try_get("checksum")?,
+        applied_at: row.try_get("applied_at")?,
+        duration_ms: row.try_get("duration_ms")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_covers_the_script() {
+        let migration = SqlMigration::new(1, "create_users", "CREATE TABLE users (id TEXT)");
+        let edited = SqlMigration::new(1, "create_users", "CREATE TABLE users (id BIGINT)");
+        assert_ne!(migration.checksum(), edited.checksum());
+
+        let record = MigrationRecord {
+            version: 1,
+            name: "create_users".to_string(),
+            checksum: migration.checksum(),
+            applied_at: 0,
+            duration_ms: 0,
+        };
+        assert!(plan_up(&[migration], std::slice::from_ref(&record)).unwrap().is_empty());
+        assert!(plan_up(&[edited], &[record]).is_err());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_migrate_and_rollback_on_sqlite() {
+        use crate::init::DatabaseConfig;
+        use crate::sql::SqlConnection;
+
+        let db = SqlConnection::new(DatabaseConfig::sqlite_memory()).await.unwrap();
+        let runner = SqlMigrationRunner::new(db.pool().clone()).with_migrations(vec![
+            SqlMigration::new(1, "create_items", "CREATE TABLE items (id TEXT PRIMARY KEY)")
+                .with_down("DROP TABLE items"),
+            SqlMigration::new(2, "add_item_name", "ALTER TABLE items ADD COLUMN name TEXT"),
+        ]);
+
+        assert_eq!(runner.migrate().await.unwrap().len(), 2);
+        assert!(runner.migrate().await.unwrap().is_empty());
+        sqlx::query("INSERT INTO items (id, name) VALUES ($1, $2)")
+            .bind("a")
+            .bind("first")
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        // Version 2 has no down script, so nothing is reverted
+        assert!(runner.rollback(0).await.is_err());
+        let status = runner.status().await.unwrap();
+        assert!(status.iter().all(|s| s.applied_at.is_some()));
+    }
+}
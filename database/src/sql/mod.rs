@@ -0,0 +1,252 @@
+//! SQL database module
+//!
+//! Relational backends through sqlx's `Any` driver, so stores written once
+//! run on every SQL database compiled in. Enabled with the `sql` feature; the
+//! `postgres` and `sqlite` features add the drivers.
+//!
+//! Stores keep to portable SQL: ids are `TEXT` holding a `DbId`, times and
+//! flags are `BIGINT`, lists and documents are JSON in `TEXT`.
+
+pub mod migrations;
+
+pub use migrations::{SqlMigration, SqlMigrationRunner};
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use sqlx::any::AnyPoolOptions;
+use sqlx::AnyPool;
+
+use crate::init::{retry_delay, Database, DatabaseConfig};
+use crate::pool::PoolStats;
+use crate::utils::{DbError, DbErrorCode, DbResult};
+
+/// SQL flavour behind a connection, for the few queries that differ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Postgres,
+    Sqlite,
+}
+
+impl Dialect {
+    /// Dialect of a connection string, from its scheme
+    pub fn from_url(url: &str) -> Option<Dialect> {
+        match url.split(':').next()? {
+            "postgres" | "postgresql" => Some(Dialect::Postgres),
+            "sqlite" => Some(Dialect::Sqlite),
+            _ => None,
+        }
+    }
+
+    /// Whether the driver for this dialect was compiled in
+    pub fn is_enabled(self) -> bool {
+        match self {
+            Dialect::Postgres => cfg!(feature = "postgres"),
+            Dialect::Sqlite => cfg!(feature = "sqlite"),
+        }
+    }
+
+    /// Feature that compiles the driver in
+    fn feature(self) -> &'static str {
+        match self {
+            Dialect::Postgres => "postgres",
+            Dialect::Sqlite => "sqlite",
+        }
+    }
+}
+
+/// Whether a connection string points at a SQL database rather than MongoDB
+pub fn is_sql_url(url: &str) -> bool {
+    Dialect::from_url(url).is_some()
+}
+
+/// Whether a connection string names an in-memory SQLite database
+pub fn is_memory_url(url: &str) -> bool {
+    Dialect::from_url(url) == Some(Dialect::Sqlite)
+        && (url.contains(":memory:") || url.contains("mode=memory"))
+}
+
+/// SQL connection pool
+pub struct SqlConnection {
+    pool: AnyPool,
+    db_name: String,
+    dialect: Dialect,
+    max_size: u32,
+    min_size: u32,
+}
+
+impl SqlConnection {
+    /// Connect, retrying with exponential backoff until the server answers
+    pub async fn new(config: DatabaseConfig) -> DbResult<SqlConnection> {
+        config.validate()?;
+        let dialect = Dialect::from_url(&config.connection_string).ok_or_else(|| {
+            DbError::invalid_config("SQL connection string must start with postgres:// or sqlite:")
+        })?;
+        if !dialect.is_enabled() {
+            return Err(DbError::not_supported(&format!(
+                "{:?} support requires the `{}` feature",
+                dialect,
+                dialect.feature()
+            )));
+        }
+
+        sqlx::any::install_default_drivers();
+        let options = pool_options(&config);
+        let backoff = Duration::from_millis(config.retry_backoff_ms);
+        let mut attempt = 0;
+        loop {
+            match options.clone().connect(&config.connection_string).await {
+                Ok(pool) => {
+                    return Ok(SqlConnection {
+                        pool,
+                        db_name: config.db_name,
+                        dialect,
+                        max_size: config.max_connections,
+                        min_size: config.min_connections,
+                    })
+                }
+                Err(sqlx::Error::Configuration(e)) => {
+                    return Err(DbError::invalid_config(&format!(
+                        "Invalid connection string: {}",
+                        e
+                    )))
+                }
+                Err(_) if attempt < config.connect_retries => {
+                    tokio::time::sleep(retry_delay(backoff, attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    let error = connection_error(&e);
+                    return Err(DbError::new(
+                        error.code,
+                        format!("{} (after {} attempts)", error.message, attempt + 1),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Get the connection pool
+    pub fn pool(&self) -> &AnyPool {
+        &self.pool
+    }
+
+    /// Get the SQL dialect
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+}
+
+#[async_trait]
+impl Database for SqlConnection {
+    fn db_name(&self) -> &str {
+        &self.db_name
+    }
+
+    async fn ping(&self) -> DbResult<()> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| connection_error(&e))?;
+        Ok(())
+    }
+
+    /// Close every pooled connection; the pool can't be used afterwards
+    async fn close(&self) -> DbResult<()> {
+        self.pool.close().await;
+        Ok(())
+    }
+
+    /// sqlx doesn't report checkout failures or clears, so those stay at zero
+    fn pool_stats(&self) -> PoolStats {
+        let open = u64::from(self.pool.size());
+        let idle = self.pool.num_idle() as u64;
+
+        PoolStats {
+            max_size: self.max_size,
+            min_size: self.min_size,
+            open,
+            in_use: open.saturating_sub(idle),
+            idle,
+            ..Default::default()
+        }
+    }
+}
+
+/// Map pool and timeout settings onto the pool options
+///
+/// An in-memory SQLite database lives as long as its connection and isn't
+/// shared with other connections, so the pool keeps exactly one, forever.
+fn pool_options(config: &DatabaseConfig) -> AnyPoolOptions {
+    let options = AnyPoolOptions::new()
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .acquire_timeout(Duration::from_secs(config.connection_timeout_secs));
+
+    if is_memory_url(&config.connection_string) {
+        options
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+    } else {
+        options
+    }
+}
+
+fn connection_error(error: &sqlx::Error) -> DbError {
+    match error {
+        sqlx::Error::PoolTimedOut => DbError::connection_timeout(&error.to_string()),
+        _ => DbError::connection_failed(&error.to_string()),
+    }
+}
+
+/// Map a driver error onto a database error code
+pub fn query_error(error: &sqlx::Error) -> DbError {
+    let code = match error {
+        sqlx::Error::RowNotFound => DbErrorCode::NotFound,
+        sqlx::Error::PoolTimedOut => DbErrorCode::PoolExhausted,
+        sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolClosed => {
+            DbErrorCode::ConnectionFailed
+        }
+        sqlx::Error::Database(e) if e.is_unique_violation() => DbErrorCode::DuplicateKey,
+        sqlx::Error::Database(e) if e.is_foreign_key_violation() || e.is_check_violation() => {
+            DbErrorCode::ConstraintViolation
+        }
+        _ => DbErrorCode::QueryFailed,
+    };
+    DbError::new(code, error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dialect_from_url() {
+        assert_eq!(Dialect::from_url("postgres://localhost/app"), Some(Dialect::Postgres));
+        assert_eq!(Dialect::from_url("postgresql://u:p@db:5432/app"), Some(Dialect::Postgres));
+        assert_eq!(Dialect::from_url("sqlite://data/app.db?mode=rwc"), Some(Dialect::Sqlite));
+        assert_eq!(Dialect::from_url("mongodb://localhost:27017"), None);
+        assert!(!is_sql_url("mongodb+srv://cluster.example.com"));
+
+        assert!(is_memory_url("sqlite::memory:"));
+        assert!(!is_memory_url("sqlite://data/app.db"));
+    }
+
+    #[test]
+    fn test_pool_options_follow_config() {
+        let config = DatabaseConfig::new("postgres://localhost/app", "app")
+            .with_pool(20, 2)
+            .with_timeout(5);
+        let options = pool_options(&config);
+
+        assert_eq!(options.get_max_connections(), 20);
+        assert_eq!(options.get_min_connections(), 2);
+        assert_eq!(options.get_acquire_timeout(), Duration::from_secs(5));
+
+        let memory = pool_options(&DatabaseConfig::sqlite_memory().with_pool(20, 2));
+        assert_eq!(memory.get_max_connections(), 1);
+        assert_eq!(memory.get_idle_timeout(), None);
+    }
+}
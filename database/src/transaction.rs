@@ -0,0 +1,447 @@
+//! Transactions
+//!
+//! A unit of work groups writes so they commit or roll back together:
+//!
+//! ```ignore
+//! transactions.run(|| async {
+//!     users.update_password(&user_id, &hash).await?;
+//!     resets.mark_used(&token_id).await?;
+//!     Ok::<_, AuthError>(())
+//! }).await?;
+//! ```
+//!
+//! Nothing is passed to the stores: the unit of work is bound to the running
+//! task, and store methods join it by calling the collection through
+//! `TransactionExt` (`update_one_tx` instead of `update_one`, and so on).
+//!
+//! MongoDB only runs transactions on replica sets and sharded clusters. On a
+//! standalone server the work runs as separate writes. So does everything
+//! under `Transactions::disabled()`, which the SQL and in-memory backends use:
+//! their stores write straight to the pool (or memory) and can't join a unit
+//! of work, so a failure part way through leaves the earlier writes in place.
+
+use std::borrow::Borrow;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mongodb::bson::{doc, Document};
+use mongodb::error::{
+    Error as MongoError, Result as MongoResult, TRANSIENT_TRANSACTION_ERROR,
+    UNKNOWN_TRANSACTION_COMMIT_RESULT,
+};
+use futures_util::TryStreamExt;
+use mongodb::options::{
+    CountOptions, DeleteOptions, FindOneAndUpdateOptions, FindOneOptions, FindOptions,
+    InsertOneOptions, ReplaceOptions, UpdateModifications, UpdateOptions,
+};
+use mongodb::results::{DeleteResult, InsertOneResult, UpdateResult};
+use mongodb::{Client, ClientSession, Collection};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::utils::{DbError, DbResult};
+
+/// Attempts at a unit of work that keeps hitting transient errors
+const MAX_ATTEMPTS: u32 = 3;
+
+tokio::task_local! {
+    static CURRENT: Arc<MongoTransaction>;
+}
+
+/// Session of the unit of work running on this task
+struct MongoTransaction {
+    session: Mutex<ClientSession>,
+    /// An operation failed with an error the server says is worth retrying
+    transient: AtomicBool,
+}
+
+impl MongoTransaction {
+    async fn start(client: &Client) -> DbResult<MongoTransaction> {
+        let mut session = client.start_session(None).await.map_err(|e| failed("start", &e))?;
+        session.start_transaction(None).await.map_err(|e| failed("start", &e))?;
+
+        Ok(MongoTransaction {
+            session: Mutex::new(session),
+            transient: AtomicBool::new(false),
+        })
+    }
+
+    /// Commit, retrying while the outcome is unknown
+    async fn commit(&self) -> MongoResult<()> {
+        let mut session = self.session.lock().await;
+        let mut attempt = 1;
+        loop {
+            match session.commit_transaction().await {
+                Err(e) if e.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT) && attempt < MAX_ATTEMPTS => {
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Abort; the error that stopped the work matters more than this one
+    async fn abort(&self) {
+        let _ = self.session.lock().await.abort_transaction().await;
+    }
+
+    fn note<T>(&self, result: MongoResult<T>) -> MongoResult<T> {
+        if let Err(e) = &result {
+            if e.contains_label(TRANSIENT_TRANSACTION_ERROR) {
+                self.transient.store(true, Ordering::Relaxed);
+            }
+        }
+        result
+    }
+}
+
+/// Starts units of work on a database
+#[derive(Clone, Default)]
+pub struct Transactions {
+    client: Option<Client>,
+    /// Whether the deployment supports transactions, asked once
+    supported: Arc<OnceCell<bool>>,
+}
+
+impl Transactions {
+    /// Units of work on MongoDB sessions
+    pub fn mongo(client: Client) -> Self {
+        Self {
+            client: Some(client),
+            supported: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// No transactions: work runs as separate writes
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Whether `run` makes its work atomic on this deployment
+    pub async fn is_supported(&self) -> DbResult<bool> {
+        let Some(client) = &self.client else {
+            return Ok(false);
+        };
+
+        self.supported
+            .get_or_try_init(|| async {
+                let hello = client
+                    .database("admin")
+                    .run_command(doc! { "hello": 1 }, None)
+                    .await
+                    .map_err(|e| DbError::connection_failed(&e.to_string()))?;
+                Ok(is_replicated(&hello))
+            })
+            .await
+            .copied()
+    }
+
+    /// Run `work` as one unit of work
+    ///
+    /// Commits when the work returns `Ok` and aborts when it returns `Err`.
+    /// Work started inside another unit of work joins it. Work that failed
+    /// on a transient error, such as a write conflict, runs again, so it must
+    /// not have side effects outside the database.
+    pub async fn run<T, E, F, Fut>(&self, mut work: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<DbError>,
+    {
+        let client = match &self.client {
+            Some(client) if CURRENT.try_with(|_| ()).is_err() && self.is_supported().await? => client,
+            _ => return work().await,
+        };
+
+        let mut attempt = 1;
+        loop {
+            let transaction = Arc::new(MongoTransaction::start(client).await?);
+            match CURRENT.scope(transaction.clone(), work()).await {
+                Ok(value) => match transaction.commit().await {
+                    Ok(()) => return Ok(value),
+                    Err(e) if e.contains_label(TRANSIENT_TRANSACTION_ERROR) && attempt < MAX_ATTEMPTS => {}
+                    Err(e) => return Err(failed("commit", &e).into()),
+                },
+                Err(error) => {
+                    transaction.abort().await;
+                    if !transaction.transient.load(Ordering::Relaxed) || attempt >= MAX_ATTEMPTS {
+                        return Err(error);
+                    }
+                }
+            }
+            attempt += 1;
+        }
+    }
+}
+
+/// Replica set members answer `hello` with their set name, mongos with `isdbgrid`
+fn is_replicated(hello: &Document) -> bool {
+    hello.contains_key("setName") || hello.get_str("msg").map(|m| m == "isdbgrid").unwrap_or(false)
+}
+
+fn failed(action: &str, error: &MongoError) -> DbError {
+    DbError::transaction_failed(&format!("Failed to {} transaction: {}", action, error))
+}
+
+/// Collection operations that join the unit of work running on this task
+///
+/// Outside a unit of work they behave exactly like the plain methods.
+#[async_trait]
+pub trait TransactionExt<T> {
+    async fn insert_one_tx(
+        &self,
+        doc: impl Borrow<T> + Send,
+        options: impl Into<Option<InsertOneOptions>> + Send,
+    ) -> MongoResult<InsertOneResult>;
+
+    async fn find_one_tx(
+        &self,
+        filter: impl Into<Option<Document>> + Send,
+        options: impl Into<Option<FindOneOptions>> + Send,
+    ) -> MongoResult<Option<T>>;
+
+    /// All matching documents, read to the end of the cursor
+    async fn find_tx(
+        &self,
+        filter: impl Into<Option<Document>> + Send,
+        options: impl Into<Option<FindOptions>> + Send,
+    ) -> MongoResult<Vec<T>>;
+
+    async fn find_one_and_update_tx(
+        &self,
+        filter: Document,
+        update: impl Into<UpdateModifications> + Send,
+        options: impl Into<Option<FindOneAndUpdateOptions>> + Send,
+    ) -> MongoResult<Option<T>>;
+
+    async fn update_one_tx(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications> + Send,
+        options: impl Into<Option<UpdateOptions>> + Send,
+    ) -> MongoResult<UpdateResult>;
+
+    async fn replace_one_tx(
+        &self,
+        query: Document,
+        replacement: impl Borrow<T> + Send,
+        options: impl Into<Option<ReplaceOptions>> + Send,
+    ) -> MongoResult<UpdateResult>;
+
+    async fn update_many_tx(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications> + Send,
+        options: impl Into<Option<UpdateOptions>> + Send,
+    ) -> MongoResult<UpdateResult>;
+
+    async fn delete_one_tx(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>> + Send,
+    ) -> MongoResult<DeleteResult>;
+
+    async fn delete_many_tx(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>> + Send,
+    ) -> MongoResult<DeleteResult>;
+
+    async fn count_documents_tx(
+        &self,
+        filter: impl Into<Option<Document>> + Send,
+        options: impl Into<Option<CountOptions>> + Send,
+    ) -> MongoResult<u64>;
+}
+
+/// Transaction of this task, if any
+fn current() -> Option<Arc<MongoTransaction>> {
+    CURRENT.try_with(Arc::clone).ok()
+}
+
+#[async_trait]
+impl<T> TransactionExt<T> for Collection<T>
+where
+    T: Serialize + DeserializeOwned + Unpin + Send + Sync,
+{
+    async fn insert_one_tx(
+        &self,
+        doc: impl Borrow<T> + Send,
+        options: impl Into<Option<InsertOneOptions>> + Send,
+    ) -> MongoResult<InsertOneResult> {
+        match current() {
+            Some(tx) => {
+                let mut session = tx.session.lock().await;
+                tx.note(self.insert_one_with_session(doc, options, &mut session).await)
+            }
+            None => self.insert_one(doc, options).await,
+        }
+    }
+
+    async fn find_one_tx(
+        &self,
+        filter: impl Into<Option<Document>> + Send,
+        options: impl Into<Option<FindOneOptions>> + Send,
+    ) -> MongoResult<Option<T>> {
+        match current() {
+            Some(tx) => {
+                let mut session = tx.session.lock().await;
+                tx.note(self.find_one_with_session(filter, options, &mut session).await)
+            }
+            None => self.find_one(filter, options).await,
+        }
+    }
+
+    async fn find_tx(
+        &self,
+        filter: impl Into<Option<Document>> + Send,
+        options: impl Into<Option<FindOptions>> + Send,
+    ) -> MongoResult<Vec<T>> {
+        match current() {
+            Some(tx) => {
+                let mut session = tx.session.lock().await;
+                let result = async {
+                    let mut cursor = self.find_with_session(filter, options, &mut session).await?;
+                    cursor.stream(&mut session).try_collect().await
+                }
+                .await;
+                tx.note(result)
+            }
+            None => self.find(filter, options).await?.try_collect().await,
+        }
+    }
+
+    async fn find_one_and_update_tx(
+        &self,
+        filter: Document,
+        update: impl Into<UpdateModifications> + Send,
+        options: impl Into<Option<FindOneAndUpdateOptions>> + Send,
+    ) -> MongoResult<Option<T>> {
+        match current() {
+            Some(tx) => {
+                let mut session = tx.session.lock().await;
+                tx.note(self.find_one_and_update_with_session(filter, update, options, &mut session).await)
+            }
+            None => self.find_one_and_update(filter, update, options).await,
+        }
+    }
+
+    async fn update_one_tx(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications> + Send,
+        options: impl Into<Option<UpdateOptions>> + Send,
+    ) -> MongoResult<UpdateResult> {
+        match current() {
+            Some(tx) => {
+                let mut session = tx.session.lock().await;
+                tx.note(self.update_one_with_session(query, update, options, &mut session).await)
+            }
+            None => self.update_one(query, update, options).await,
+        }
+    }
+
+    async fn replace_one_tx(
+        &self,
+        query: Document,
+        replacement: impl Borrow<T> + Send,
+        options: impl Into<Option<ReplaceOptions>> + Send,
+    ) -> MongoResult<UpdateResult> {
+        match current() {
+            Some(tx) => {
+                let mut session = tx.session.lock().await;
+                tx.note(self.replace_one_with_session(query, replacement, options, &mut session).await)
+            }
+            None => self.replace_one(query, replacement, options).await,
+        }
+    }
+
+    async fn update_many_tx(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications> + Send,
+        options: impl Into<Option<UpdateOptions>> + Send,
+    ) -> MongoResult<UpdateResult> {
+        match current() {
+            Some(tx) => {
+                let mut session = tx.session.lock().await;
+                tx.note(self.update_many_with_session(query, update, options, &mut session).await)
+            }
+            None => self.update_many(query, update, options).await,
+        }
+    }
+
+    async fn delete_one_tx(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>> + Send,
+    ) -> MongoResult<DeleteResult> {
+        match current() {
+            Some(tx) => {
+                let mut session = tx.session.lock().await;
+                tx.note(self.delete_one_with_session(query, options, &mut session).await)
+            }
+            None => self.delete_one(query, options).await,
+        }
+    }
+
+    async fn delete_many_tx(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>> + Send,
+    ) -> MongoResult<DeleteResult> {
+        match current() {
+            Some(tx) => {
+                let mut session = tx.session.lock().await;
+                tx.note(self.delete_many_with_session(query, options, &mut session).await)
+            }
+            None => self.delete_many(query, options).await,
+        }
+    }
+
+    async fn count_documents_tx(
+        &self,
+        filter: impl Into<Option<Document>> + Send,
+        options: impl Into<Option<CountOptions>> + Send,
+    ) -> MongoResult<u64> {
+        match current() {
+            Some(tx) => {
+                let mut session = tx.session.lock().await;
+                tx.note(self.count_documents_with_session(filter, options, &mut session).await)
+            }
+            None => self.count_documents(filter, options).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_replicated_deployments() {
+        assert!(is_replicated(&doc! { "isWritablePrimary": true, "setName": "rs0" }));
+        assert!(is_replicated(&doc! { "isWritablePrimary": true, "msg": "isdbgrid" }));
+        assert!(!is_replicated(&doc! { "isWritablePrimary": true }));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_runs_work_directly() {
+        let transactions = Transactions::disabled();
+        assert!(!transactions.is_supported().await.unwrap());
+
+        let mut runs = 0;
+        let result: Result<u32, DbError> = transactions
+            .run(|| {
+                runs += 1;
+                async { Err(DbError::query_failed("boom")) }
+            })
+            .await;
+
+        assert_eq!(result.unwrap_err().message, "boom");
+        assert_eq!(runs, 1);
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Provides error types for database operations.
 
+use mongodb::error::{CommandError, Error as MongoError, ErrorKind, WriteError, WriteFailure};
 use serde::{Deserialize, Serialize};
 
 /// Database error enum
@@ -25,6 +26,18 @@ impl DbError {
         self.details = Some(details);
         self
     }
+
+    pub fn is_duplicate_key(&self) -> bool {
+        matches!(self.code, DbErrorCode::DuplicateKey)
+    }
+
+    /// Unique index a duplicate key error collided with, when the server named it
+    pub fn duplicate_index(&self) -> Option<&str> {
+        if !self.is_duplicate_key() {
+            return None;
+        }
+        self.details.as_ref()?.get("index")?.as_str()
+    }
 }
 
 impl std::fmt::Display for DbError {
@@ -110,6 +123,10 @@ impl DbError {
         Self::new(DbErrorCode::QueryFailed, msg)
     }
 
+    pub fn invalid_query(msg: &str) -> Self {
+        Self::new(DbErrorCode::InvalidQuery, msg)
+    }
+
     pub fn internal_error(msg: &str) -> Self {
         Self::new(DbErrorCode::InternalError, msg)
     }
@@ -121,7 +138,66 @@ impl DbError {
     pub fn not_supported(msg: &str) -> Self {
         Self::new(DbErrorCode::NotSupported, msg)
     }
+
+    pub fn transaction_failed(msg: &str) -> Self {
+        Self::new(DbErrorCode::TransactionFailed, msg)
+    }
+
+    pub fn migration_failed(msg: &str) -> Self {
+        Self::new(DbErrorCode::MigrationFailed, msg)
+    }
+
+    pub fn migration_not_found(msg: &str) -> Self {
+        Self::new(DbErrorCode::MigrationNotFound, msg)
+    }
+}
+
+/// Driver errors by kind; duplicate keys (server code 11000) carry the index name
+impl From<MongoError> for DbError {
+    fn from(error: MongoError) -> Self {
+        let message = error.to_string();
+
+        match error.kind.as_ref() {
+            ErrorKind::Write(WriteFailure::WriteError(WriteError { code: 11000, message: server, .. }))
+            | ErrorKind::Command(CommandError { code: 11000, message: server, .. }) => {
+                let error = DbError::duplicate_key(&message);
+                match index_name(server) {
+                    Some(index) => error.with_details(serde_json::json!({ "index": index })),
+                    None => error,
+                }
+            }
+            ErrorKind::ServerSelection { .. } => DbError::connection_timeout(&message),
+            ErrorKind::Io(_) | ErrorKind::ConnectionPoolCleared { .. } => {
+                DbError::connection_failed(&message)
+            }
+            ErrorKind::BsonSerialization(_) | ErrorKind::BsonDeserialization(_) => {
+                DbError::internal_error(&message)
+            }
+            _ => DbError::query_failed(&message),
+        }
+    }
+}
+
+/// Index named in a duplicate key message (`... index: email_unique dup key: ...`)
+fn index_name(message: &str) -> Option<&str> {
+    message.split("index: ").nth(1)?.split_whitespace().next()
 }
 
 /// Result type for database operations
 pub type DbResult<T> = Result<T, DbError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_key_names_the_index() {
+        let error = DbError::duplicate_key("E11000").with_details(serde_json::json!({ "index": "email_unique" }));
+        assert_eq!(error.duplicate_index(), Some("email_unique"));
+        assert_eq!(DbError::query_failed("boom").duplicate_index(), None);
+
+        let message = r#"E11000 duplicate key error collection: app.users index: email_unique dup key: { email: "a@example.com" }"#;
+        assert_eq!(index_name(message), Some("email_unique"));
+        assert_eq!(index_name("E11000 duplicate key error"), None);
+    }
+}
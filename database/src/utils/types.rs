@@ -1,12 +1,14 @@
 //! Database ID types module
 //!
-//! Provides MongoDB ObjectId types for the database.
+//! Provides the `DbId` record key: a MongoDB ObjectId, or a UUID or bigint
+//! key for relational backends.
 
-use mongodb::bson;
+use mongodb::bson::{self, Bson};
+use serde::de::{self, MapAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// MongoDB ObjectId wrapper
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ObjectId {
     bytes: [u8; 12],
 }
@@ -53,11 +55,6 @@ impl ObjectId {
         Ok(Self { bytes })
     }
 
-    /// Convert to hex string
-    pub fn to_string(&self) -> String {
-        self.bytes.iter().map(|b| format!("{:02x}", b)).collect()
-    }
-
     /// Get bytes
     pub fn as_bytes(&self) -> &[u8; 12] {
         &self.bytes
@@ -84,9 +81,10 @@ impl Default for ObjectId {
     }
 }
 
+/// Formats as the 24-character hex string
 impl std::fmt::Display for ObjectId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+        self.bytes.iter().try_for_each(|b| write!(f, "{:02x}", b))
     }
 }
 
@@ -108,18 +106,203 @@ impl std::fmt::Display for IdError {
 
 impl std::error::Error for IdError {}
 
-/// Type alias for database ID (MongoDB ObjectId)
-pub type DbId = ObjectId;
+/// Database record ID
+///
+/// Mongo stores key records by ObjectId; relational stores use UUID or
+/// bigint primary keys. IDs of one kind never match records of another.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DbId {
+    ObjectId(ObjectId),
+    Uuid(uuid::Uuid),
+    Int(i64),
+}
+
+impl DbId {
+    /// ObjectId from 12 bytes
+    pub fn from_bytes(bytes: [u8; 12]) -> Self {
+        DbId::ObjectId(ObjectId::from_bytes(bytes))
+    }
+
+    /// Random UUID (v4)
+    pub fn new_uuid() -> Self {
+        DbId::Uuid(uuid::Uuid::new_v4())
+    }
+
+    pub fn as_object_id(&self) -> Option<&ObjectId> {
+        match self {
+            DbId::ObjectId(oid) => Some(oid),
+            _ => None,
+        }
+    }
+
+    pub fn as_uuid(&self) -> Option<uuid::Uuid> {
+        match self {
+            DbId::Uuid(uuid) => Some(*uuid),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            DbId::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Raw bytes: 12 for an ObjectId, 16 for a UUID, 8 (big endian) for an int
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            DbId::ObjectId(oid) => oid.as_bytes().to_vec(),
+            DbId::Uuid(uuid) => uuid.as_bytes().to_vec(),
+            DbId::Int(value) => value.to_be_bytes().to_vec(),
+        }
+    }
+
+    /// BSON value for Mongo filters and documents
+    pub fn to_bson(&self) -> Bson {
+        match self {
+            DbId::ObjectId(oid) => Bson::ObjectId(bson::oid::ObjectId::from_bytes(*oid.as_bytes())),
+            DbId::Uuid(uuid) => Bson::String(uuid.to_string()),
+            DbId::Int(value) => Bson::Int64(*value),
+        }
+    }
+}
+
+impl Default for DbId {
+    fn default() -> Self {
+        generate_id()
+    }
+}
+
+impl From<ObjectId> for DbId {
+    fn from(oid: ObjectId) -> Self {
+        DbId::ObjectId(oid)
+    }
+}
+
+impl From<uuid::Uuid> for DbId {
+    fn from(uuid: uuid::Uuid) -> Self {
+        DbId::Uuid(uuid)
+    }
+}
+
+impl From<i64> for DbId {
+    fn from(value: i64) -> Self {
+        DbId::Int(value)
+    }
+}
+
+impl std::fmt::Display for DbId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DbId::ObjectId(oid) => write!(f, "{}", oid),
+            DbId::Uuid(uuid) => write!(f, "{}", uuid),
+            DbId::Int(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Serialize for DbId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            DbId::ObjectId(oid) => oid.serialize(serializer),
+            DbId::Uuid(uuid) => serializer.serialize_str(&uuid.to_string()),
+            DbId::Int(value) => serializer.serialize_i64(*value),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DbId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DbIdVisitor)
+    }
+}
+
+struct DbIdVisitor;
+
+impl<'de> Visitor<'de> for DbIdVisitor {
+    type Value = DbId;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("an ObjectId, UUID or integer id")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<DbId, E> {
+        parse_id(value).map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<DbId, E> {
+        Ok(DbId::Int(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<DbId, E> {
+        i64::try_from(value)
+            .map(DbId::Int)
+            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+    }
+
+    // BSON ObjectIds arrive as `{ "$oid": "<hex>" }`
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<DbId, A::Error> {
+        let oid = bson::oid::ObjectId::deserialize(de::value::MapAccessDeserializer::new(map))?;
+        Ok(DbId::from_bytes(oid.bytes()))
+    }
+}
 
 /// Result type for ID operations
 pub type IdResult<T> = Result<T, IdError>;
 
 /// Generate new MongoDB ObjectId
 pub fn generate_id() -> DbId {
-    ObjectId::new()
+    DbId::ObjectId(ObjectId::new())
 }
 
-/// Parse ID from string
+/// Parse an ObjectId (24 hex characters), UUID or integer id
 pub fn parse_id(value: &str) -> IdResult<DbId> {
-    ObjectId::from_str(value)
+    if value.len() == 24 {
+        return ObjectId::from_str(value).map(DbId::ObjectId);
+    }
+    if let Ok(uuid) = uuid::Uuid::parse_str(value) {
+        return Ok(DbId::Uuid(uuid));
+    }
+    value.parse().map(DbId::Int).map_err(|_| IdError::InvalidFormat)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_id_kinds() {
+        let oid = generate_id();
+        assert_eq!(parse_id(&oid.to_string()).unwrap(), oid);
+
+        let uuid = DbId::new_uuid();
+        assert_eq!(parse_id(&uuid.to_string()).unwrap(), uuid);
+
+        assert_eq!(parse_id("42").unwrap(), DbId::Int(42));
+        assert!(parse_id("not-an-id").is_err());
+    }
+
+    #[test]
+    fn test_bson_round_trip() {
+        #[derive(Serialize, Deserialize)]
+        struct Record {
+            id: DbId,
+        }
+
+        for id in [generate_id(), DbId::new_uuid(), DbId::Int(7)] {
+            let document = bson::to_document(&Record { id: id.clone() }).unwrap();
+            assert_eq!(document.get("id"), Some(&id.to_bson()));
+            let record: Record = bson::from_slice(&bson::to_vec(&document).unwrap()).unwrap();
+            assert_eq!(record.id, id);
+            let record: Record = bson::from_document(document).unwrap();
+            assert_eq!(record.id, id);
+        }
+    }
 }
@@ -0,0 +1,138 @@
+//! Impersonation guard
+//!
+//! Impersonation tokens let an admin see the app as a user, but must not be
+//! able to change the user's credentials or move money. Wrap sensitive routes
+//! (password change, 2FA, payments, ...) in `DenyImpersonation`, inside the
+//! `JwtMiddleware` so the claims are already on the request.
+
+use actix_web::{
+    Error, HttpMessage, HttpResponse,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+};
+use futures_util::future::{LocalBoxFuture, Ready, ready};
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use crate::jwt::Claims;
+
+/// Refuses requests made with an impersonation token
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenyImpersonation;
+
+impl<S, B> Transform<S, ServiceRequest> for DenyImpersonation
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<actix_web::body::EitherBody<B>>;
+    type Error = Error;
+    type Transform = DenyImpersonationImpl<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(DenyImpersonationImpl {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct DenyImpersonationImpl<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for DenyImpersonationImpl<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<actix_web::body::EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let svc = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let impersonated = req
+                .extensions()
+                .get::<Claims>()
+                .is_some_and(Claims::is_impersonated);
+
+            if impersonated {
+                return Ok(req.into_response(
+                    HttpResponse::Forbidden()
+                        .json(serde_json::json!({
+                            "success": false,
+                            "message": "Not allowed while impersonating a user"
+                        }))
+                        .map_into_right_body(),
+                ));
+            }
+
+            let res = svc.call(req).await;
+            Ok(res?.map_into_left_body())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jwt::ActorClaim;
+    use actix_web::test::{TestRequest, call_service, init_service};
+    use actix_web::{App, http::StatusCode, web};
+
+    fn claims(act: Option<ActorClaim>) -> Claims {
+        Claims {
+            sub:    "user".to_string(),
+            email:  None,
+            exp:    0,
+            iat:    0,
+            org_id: None,
+            act,
+            auth_time: None,
+            amr:    Vec::new(),
+        }
+    }
+
+    async fn status_for(claims: Claims) -> StatusCode {
+        let app = init_service(
+            App::new()
+                .wrap(DenyImpersonation)
+                .route("/", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::post().uri("/").to_request();
+        req.extensions_mut().insert(claims);
+
+        call_service(&app, req).await.status()
+    }
+
+    #[actix_web::test]
+    async fn test_allows_regular_token() {
+        assert_eq!(status_for(claims(None)).await, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_refuses_impersonation_token() {
+        let act = ActorClaim { sub: "admin".to_string() };
+        assert_eq!(status_for(claims(Some(act))).await, StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_act_claim_omitted_when_absent() {
+        let json = serde_json::to_value(claims(None)).unwrap();
+        assert!(json.get("act").is_none());
+
+        let parsed: Claims = serde_json::from_value(json).unwrap();
+        assert!(!parsed.is_impersonated());
+    }
+}
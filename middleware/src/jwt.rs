@@ -1,9 +1,10 @@
 //! JWT Authentication
 //!
 //! Provides JWT token generation, validation, and middleware for request authentication.
+//! The middleware also accepts API keys; see `crate::api_key`.
 
 use actix_web::{
-    Error, HttpMessage, HttpResponse,
+    Error, HttpMessage, HttpResponse, web,
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
 };
 use futures_util::future::{LocalBoxFuture, Ready, ready};
@@ -12,6 +13,9 @@ use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 use std::task::{Context, Poll};
 
+use crate::api_key::{ApiKeyInfo, ApiKeyValidator, extract_api_key};
+use crate::token_validation::TokenValidationError;
+
 /// JWT configuration
 #[derive(Debug, Clone)]
 pub struct JwtConfig {
@@ -37,6 +41,42 @@ pub struct Claims {
     pub email: Option<String>,
     pub exp: i64,
     pub iat: i64,
+    /// Active organization (tenant) for this token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org_id: Option<String>,
+    /// Admin acting as the subject, set on impersonation tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<ActorClaim>,
+    /// When the user last actively authenticated (kept across refreshes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<i64>,
+    /// Authentication methods used at `auth_time` (RFC 8176 values)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub amr: Vec<String>,
+}
+
+/// Authentication method references (RFC 8176)
+pub const AMR_PASSWORD: &str = "pwd";
+pub const AMR_PASSKEY: &str = "hwk";
+pub const AMR_FEDERATED: &str = "fed";
+pub const AMR_OTP: &str = "otp";
+
+/// Actor claim (RFC 8693) - who is really behind the token
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ActorClaim {
+    pub sub: String,
+}
+
+impl Claims {
+    /// Whether the token was issued to an admin acting as the subject
+    pub fn is_impersonated(&self) -> bool {
+        self.act.is_some()
+    }
+
+    /// Whether the user authenticated no more than `max_age` seconds before `now`
+    pub fn authenticated_within(&self, max_age: i64, now: i64) -> bool {
+        self.auth_time.is_some_and(|t| t <= now && now - t <= max_age)
+    }
 }
 
 /// Token information including raw token for session validation
@@ -55,16 +95,26 @@ pub enum TokenType {
 /// JWT Middleware for request authentication
 pub struct JwtMiddleware {
     secret: String,
+    api_keys: bool,
 }
 
 impl JwtMiddleware {
     pub fn new(secret: String) -> Self {
-        Self { secret }
+        Self {
+            secret,
+            api_keys: true,
+        }
     }
 
     pub fn from_secret(secret: impl Into<String>) -> Self {
         Self::new(secret.into())
     }
+
+    /// Refuse API keys, for routes that don't check key scopes
+    pub fn without_api_keys(mut self) -> Self {
+        self.api_keys = false;
+        self
+    }
 }
 
 impl<S, B> Transform<S, ServiceRequest> for JwtMiddleware
@@ -83,6 +133,7 @@ where
         ready(Ok(JwtMiddlewareImpl {
             service: Rc::new(service),
             secret: self.secret.clone(),
+            api_keys: self.api_keys,
         }))
     }
 }
@@ -90,6 +141,7 @@ where
 pub struct JwtMiddlewareImpl<S> {
     service: Rc<S>,
     secret: String,
+    api_keys: bool,
 }
 
 impl<S, B> Service<ServiceRequest> for JwtMiddlewareImpl<S>
@@ -109,8 +161,58 @@ where
     fn call(&self, req: ServiceRequest) -> Self::Future {
         let svc = Rc::clone(&self.service);
         let secret = self.secret.clone();
+        let api_keys = self.api_keys;
 
         Box::pin(async move {
+            // API keys are validated by the registered validator instead of as JWTs
+            if let Some(raw_key) = extract_api_key(req.headers()) {
+                if !api_keys {
+                    return Ok(req.into_response(
+                        HttpResponse::Forbidden()
+                            .json(serde_json::json!({
+                                "success": false,
+                                "message": "API keys are not accepted on this route"
+                            }))
+                            .map_into_right_body(),
+                    ));
+                }
+
+                let validator = req.app_data::<web::Data<dyn ApiKeyValidator>>().cloned();
+                let ip_address = req.connection_info().realip_remote_addr().map(String::from);
+
+                let result = match validator {
+                    Some(validator) => validator.validate(&raw_key, ip_address.as_deref()).await,
+                    None => Err(TokenValidationError::unauthorized("API keys are not enabled")),
+                };
+
+                match result {
+                    Ok(principal) => {
+                        req.extensions_mut().insert(TokenInfo {
+                            claims: principal.claims.clone(),
+                            token: raw_key,
+                        });
+                        req.extensions_mut().insert(principal.claims);
+                        req.extensions_mut().insert(principal.info);
+                    }
+                    Err(e) => {
+                        let status = actix_web::http::StatusCode::from_u16(e.status_code)
+                            .unwrap_or(actix_web::http::StatusCode::UNAUTHORIZED);
+
+                        return Ok(req.into_response(
+                            HttpResponse::build(status)
+                                .json(serde_json::json!({
+                                    "success": false,
+                                    "message": e.message
+                                }))
+                                .map_into_right_body(),
+                        ));
+                    }
+                }
+
+                let res = svc.call(req).await;
+                return Ok(res?.map_into_left_body());
+            }
+
             // Extract token from Authorization header
             let token_opt = req
                 .headers()
@@ -170,6 +272,9 @@ where
 pub trait JwtClaims {
     fn claims(&self) -> Option<Claims>;
     fn token_info(&self) -> Option<TokenInfo>;
+    fn api_key(&self) -> Option<ApiKeyInfo>;
+    /// Whether the request carries an impersonation token
+    fn is_impersonated(&self) -> bool;
 }
 
 impl JwtClaims for actix_web::HttpRequest {
@@ -180,6 +285,14 @@ impl JwtClaims for actix_web::HttpRequest {
     fn token_info(&self) -> Option<TokenInfo> {
         self.extensions().get::<TokenInfo>().cloned()
     }
+
+    fn api_key(&self) -> Option<ApiKeyInfo> {
+        self.extensions().get::<ApiKeyInfo>().cloned()
+    }
+
+    fn is_impersonated(&self) -> bool {
+        self.extensions().get::<Claims>().is_some_and(Claims::is_impersonated)
+    }
 }
 
 // Implement FromRequest for Claims to allow direct extraction in routes
@@ -1,17 +1,23 @@
 //! Middleware types
 
+pub mod api_key;
 pub mod cors;
+pub mod impersonation;
 pub mod jwt;
 pub mod rate_limit;
 pub mod sessions;
+pub mod step_up;
 pub mod logger;
 pub mod token_validation;
 
 pub use actix_cors::Cors;
+pub use api_key::{ApiKeyInfo, ApiKeyPrincipal, ApiKeyValidator};
 pub use cors::CorsConfig;
-pub use jwt::{Claims, JwtClaims, JwtConfig, JwtMiddleware};
+pub use impersonation::DenyImpersonation;
+pub use jwt::{ActorClaim, Claims, JwtClaims, JwtConfig, JwtMiddleware};
 pub use rate_limit::{RateLimitConfig, RateLimiter};
 pub use sessions::{SessionConfig, SessionData, SessionStore};
+pub use step_up::{RecentAuth, RequireRecentAuth};
 pub use token_validation::{ExtractedTokenInfo, TokenValidationError, validate_token_extraction};
 
 
@@ -28,7 +28,7 @@ struct RateLimitEntry {
 impl RateLimitEntry {
     fn new() -> Self {
         Self {
-            count: 1,
+            count: 0,
             window_start: Instant::now(),
         }
     }
@@ -49,6 +49,11 @@ impl RateLimiter {
     }
 
     pub fn check(&self, key: &str) -> bool {
+        self.check_with_limit(key, self.config.max_requests)
+    }
+
+    /// Check a key against its own request limit, using the configured window
+    pub fn check_with_limit(&self, key: &str, max_requests: u32) -> bool {
         let mut entries = self.entries.write();
         let window = Duration::from_secs(self.config.window_seconds);
         let entry = entries
@@ -58,10 +63,33 @@ impl RateLimiter {
         if entry.window_start.elapsed() > window {
             *entry = RateLimitEntry::new();
         }
-        if entry.count > self.config.max_requests {
+        if entry.count >= max_requests {
             return false;
         }
         entry.count += 1;
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows_exactly_the_limit_per_window() {
+        let limiter = RateLimiter::new(RateLimitConfig::new(3, 60));
+
+        let allowed = (0..5).filter(|_| limiter.check("client")).count();
+        assert_eq!(allowed, 3);
+
+        // Other keys have their own budget
+        assert!(limiter.check_with_limit("other", 1));
+        assert!(!limiter.check_with_limit("other", 1));
+    }
+
+    #[test]
+    fn test_zero_limit_rejects_everything() {
+        let limiter = RateLimiter::new(RateLimitConfig::new(0, 60));
+        assert!(!limiter.check("client"));
+    }
+}
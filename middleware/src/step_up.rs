@@ -0,0 +1,197 @@
+//! Step-up authentication
+//!
+//! Refreshing keeps a session alive for weeks, so a valid access token says
+//! little about whether the user is still at the keyboard. Sensitive routes
+//! require the token's `auth_time` to be recent - wrap them in
+//! `RequireRecentAuth`, or take a `RecentAuth` argument in the handler. Stale
+//! tokens get a 401 with an RFC 9470 challenge, and the client re-authenticates
+//! to get a fresh token.
+
+use actix_web::{
+    Error, HttpMessage, HttpRequest, HttpResponse,
+    dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header,
+};
+use futures_util::future::{LocalBoxFuture, Ready, ready};
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use crate::jwt::Claims;
+
+/// Default window after authenticating in which sensitive actions are allowed
+pub const DEFAULT_MAX_AUTH_AGE_SECONDS: i64 = 5 * 60;
+
+fn is_recent(claims: Option<&Claims>, max_age: i64) -> bool {
+    let now = chrono::Utc::now().timestamp();
+    claims.is_some_and(|c| c.authenticated_within(max_age, now))
+}
+
+fn reauthentication_required(max_age: i64) -> HttpResponse {
+    HttpResponse::Unauthorized()
+        .insert_header((
+            header::WWW_AUTHENTICATE,
+            format!(
+                "Bearer error=\"insufficient_user_authentication\", max_age={}",
+                max_age
+            ),
+        ))
+        .json(serde_json::json!({
+            "success": false,
+            "message": "Recent authentication required, please re-authenticate"
+        }))
+}
+
+/// Refuses tokens whose `auth_time` is older than `max_age` seconds
+#[derive(Debug, Clone, Copy)]
+pub struct RequireRecentAuth {
+    max_age: i64,
+}
+
+impl RequireRecentAuth {
+    pub fn new(max_age_seconds: i64) -> Self {
+        Self { max_age: max_age_seconds }
+    }
+}
+
+impl Default for RequireRecentAuth {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_AUTH_AGE_SECONDS)
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequireRecentAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<actix_web::body::EitherBody<B>>;
+    type Error = Error;
+    type Transform = RequireRecentAuthImpl<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireRecentAuthImpl {
+            service: Rc::new(service),
+            max_age: self.max_age,
+        }))
+    }
+}
+
+pub struct RequireRecentAuthImpl<S> {
+    service: Rc<S>,
+    max_age: i64,
+}
+
+impl<S, B> Service<ServiceRequest> for RequireRecentAuthImpl<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<actix_web::body::EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let svc = Rc::clone(&self.service);
+        let max_age = self.max_age;
+
+        Box::pin(async move {
+            if !is_recent(req.extensions().get::<Claims>(), max_age) {
+                return Ok(req.into_response(
+                    reauthentication_required(max_age).map_into_right_body(),
+                ));
+            }
+
+            let res = svc.call(req).await;
+            Ok(res?.map_into_left_body())
+        })
+    }
+}
+
+/// Extractor for handlers that need a recent authentication
+/// (within `DEFAULT_MAX_AUTH_AGE_SECONDS`)
+#[derive(Debug, Clone)]
+pub struct RecentAuth(pub Claims);
+
+impl actix_web::FromRequest for RecentAuth {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let claims = req.extensions().get::<Claims>().cloned();
+
+        match claims {
+            Some(claims) if is_recent(Some(&claims), DEFAULT_MAX_AUTH_AGE_SECONDS) => {
+                ready(Ok(RecentAuth(claims)))
+            }
+            _ => ready(Err(actix_web::error::InternalError::from_response(
+                "Recent authentication required",
+                reauthentication_required(DEFAULT_MAX_AUTH_AGE_SECONDS),
+            )
+            .into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{TestRequest, call_service, init_service};
+    use actix_web::{App, http::StatusCode, web};
+
+    fn claims(auth_time: Option<i64>) -> Claims {
+        Claims {
+            sub:       "user".to_string(),
+            email:     None,
+            exp:       0,
+            iat:       0,
+            org_id:    None,
+            act:       None,
+            auth_time,
+            amr:       Vec::new(),
+        }
+    }
+
+    async fn status_for(claims: Claims) -> StatusCode {
+        let app = init_service(
+            App::new()
+                .wrap(RequireRecentAuth::new(60))
+                .route("/", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::post().uri("/").to_request();
+        req.extensions_mut().insert(claims);
+
+        call_service(&app, req).await.status()
+    }
+
+    #[test]
+    fn test_authenticated_within() {
+        let claims = claims(Some(1_000));
+
+        assert!(claims.authenticated_within(60, 1_030));
+        assert!(!claims.authenticated_within(60, 1_061));
+        assert!(!claims.authenticated_within(60, 900));
+    }
+
+    #[actix_web::test]
+    async fn test_allows_recent_authentication() {
+        let now = chrono::Utc::now().timestamp();
+        assert_eq!(status_for(claims(Some(now - 10))).await, StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_refuses_stale_or_missing_auth_time() {
+        let now = chrono::Utc::now().timestamp();
+        assert_eq!(status_for(claims(Some(now - 120))).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status_for(claims(None)).await, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -37,6 +37,13 @@ impl TokenValidationError {
         }
     }
 
+    pub fn too_many_requests(msg: &str) -> Self {
+        Self {
+            message: msg.to_string(),
+            status_code: 429,
+        }
+    }
+
     pub fn internal_error(msg: &str) -> Self {
         Self {
             message: msg.to_string(),
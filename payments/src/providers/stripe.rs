@@ -798,14 +798,16 @@ impl PaymentGateway for StripeGateway {
             completed_payouts.push(result);
         }
 
+        let total_amount = Amount::new(
+            completed_payouts.iter().map(|p| p.amount.value).sum(),
+            completed_payouts.first().map(|p| p.amount.currency.clone()).unwrap_or_else(|| "usd".to_string()),
+        );
+
         Ok(BatchPayout {
             id: format!("batch_{}", uuid::Uuid::new_v4()),
             provider: PaymentProvider::Visa,
             payouts: completed_payouts,
-            total_amount: Amount::new(
-                completed_payouts.iter().map(|p| p.amount.value).sum(),
-                completed_payouts.first().map(|p| p.amount.currency.clone()).unwrap_or_else(|| "usd".to_string()),
-            ),
+            total_amount,
             status: BatchPayoutStatus::Completed,
             created_at: chrono::Utc::now(),
             completed_at: Some(chrono::Utc::now()),
@@ -854,11 +856,13 @@ impl PaymentGateway for StripeGateway {
             .map(|b| Amount::new(b.amount, &b.currency))
             .unwrap_or_else(|| Amount::new(0, "usd"));
 
+        let currency = available.currency.clone();
+
         Ok(WalletBalance {
             account_id: "main".to_string(),
             available,
             pending,
-            currency: available.currency.clone(),
+            currency,
         })
     }
 
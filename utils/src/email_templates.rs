@@ -263,6 +263,224 @@ pub mod magic_link {
     }
 }
 
+/// Organization Invitation Template
+pub mod org_invitation {
+    use super::*;
+
+    /// Build an organization invitation email
+    pub fn build(
+        config: &EmailTemplateConfig,
+        org_name: &str,
+        inviter_name: &str,
+        accept_link: &str,
+    ) -> Email {
+        let content = format!(
+            r#"<p style="margin: 0 0 20px 0; font-size: 16px;">Hello,</p>
+<p style="margin: 0 0 20px 0; font-size: 16px;">{} has invited you to join <strong>{}</strong> on {}.</p>
+<table width="100%" cellpadding="0" cellspacing="0" style="margin: 20px 0;">
+    <tr>
+        <td align="center">
+            <a href="{}" style="background: {}; color: #ffffff; padding: 14px 32px; text-decoration: none; border-radius: 6px; font-weight: 600; font-size: 16px; display: inline-block;">Accept Invitation</a>
+        </td>
+    </tr>
+</table>
+<p style="margin: 0 0 20px 0; font-size: 14px; color: #666;">This invitation will expire in 7 days.</p>
+<hr style="border: none; border-top: 1px solid #e9ecef; margin: 25px 0;">
+<p style="margin: 0; font-size: 12px; color: #999;">If the button doesn't work, copy and paste this link into your browser:<br><a href="{}" style="color: {}; text-decoration: underline;">{}</a></p>"#,
+            inviter_name,
+            org_name,
+            config.app_name,
+            accept_link,
+            config.primary_color,
+            accept_link,
+            config.primary_color,
+            accept_link
+        );
+
+        let subject = format!("You're invited to join {}", org_name);
+        let html = build_html_wrapper(config, "You're Invited", &content);
+
+        Email::new("noreply@example.com", "placeholder@example.com", &subject).html(html)
+    }
+}
+
+/// Account Invitation Email Template
+pub mod account_invitation {
+    use super::*;
+
+    /// Build an account invitation email - the invitee sets a password from the link
+    pub fn build(config: &EmailTemplateConfig, inviter_name: &str, setup_link: &str) -> Email {
+        let content = format!(
+            r#"<p style="margin: 0 0 20px 0; font-size: 16px;">Hello,</p>
+<p style="margin: 0 0 20px 0; font-size: 16px;">{} has created an account for you on {}. Set a password to get started.</p>
+<table width="100%" cellpadding="0" cellspacing="0" style="margin: 20px 0;">
+    <tr>
+        <td align="center">
+            <a href="{}" style="background: {}; color: #ffffff; padding: 14px 32px; text-decoration: none; border-radius: 6px; font-weight: 600; font-size: 16px; display: inline-block;">Set Up Account</a>
+        </td>
+    </tr>
+</table>
+<p style="margin: 0 0 20px 0; font-size: 14px; color: #666;">This link will expire in 7 days.</p>
+<hr style="border: none; border-top: 1px solid #e9ecef; margin: 25px 0;">
+<p style="margin: 0; font-size: 12px; color: #999;">If the button doesn't work, copy and paste this link into your browser:<br><a href="{}" style="color: {}; text-decoration: underline;">{}</a></p>"#,
+            inviter_name,
+            config.app_name,
+            setup_link,
+            config.primary_color,
+            setup_link,
+            config.primary_color,
+            setup_link
+        );
+
+        let subject = format!("You've been invited to {}", config.app_name);
+        let html = build_html_wrapper(config, "Set Up Your Account", &content);
+
+        Email::new("noreply@example.com", "placeholder@example.com", &subject).html(html)
+    }
+}
+
+/// Contact Change Code Email Template - sent to the new address
+pub mod contact_change_code {
+    use super::*;
+
+    /// Build the confirmation code email for a new email address
+    pub fn build(config: &EmailTemplateConfig, code: &str) -> Email {
+        let content = format!(
+            r#"<p style="margin: 0 0 20px 0; font-size: 16px;">Hello,</p>
+<p style="margin: 0 0 20px 0; font-size: 16px;">Use this code to confirm your new email address on {}:</p>
+<div style="background: #f8f9fa; padding: 15px; border-radius: 6px; text-align: center; margin: 20px 0;">
+    <p style="margin: 0; font-size: 24px; font-weight: bold; letter-spacing: 4px; color: {};">{}</p>
+</div>
+<p style="margin: 0 0 20px 0; font-size: 14px; color: #666;">This code will expire in 15 minutes. If you didn't request this change, you can ignore this email.</p>"#,
+            config.app_name, config.primary_color, code
+        );
+
+        let html = build_html_wrapper(config, "Confirm Your New Email", &content);
+
+        Email::new(
+            "noreply@example.com",
+            "placeholder@example.com",
+            "Confirm Your New Email",
+        )
+        .html(html)
+    }
+}
+
+/// Contact Change Notice Email Template - sent to the old address
+pub mod contact_change_notice {
+    use super::*;
+
+    /// Build the security notice for a requested email or phone change
+    pub fn build(config: &EmailTemplateConfig, contact_label: &str, revert_link: &str) -> Email {
+        let content = format!(
+            r#"<p style="margin: 0 0 20px 0; font-size: 16px;">Hello,</p>
+<p style="margin: 0 0 20px 0; font-size: 16px;">A request was made to change the {} on your {} account.</p>
+<p style="margin: 0 0 20px 0; font-size: 16px;">If this wasn't you, undo the change and secure your account:</p>
+<table width="100%" cellpadding="0" cellspacing="0" style="margin: 20px 0;">
+    <tr>
+        <td align="center">
+            <a href="{}" style="background: #dc3545; color: #ffffff; padding: 14px 32px; text-decoration: none; border-radius: 6px; font-weight: 600; font-size: 16px; display: inline-block;">Undo Change</a>
+        </td>
+    </tr>
+</table>
+<p style="margin: 0 0 20px 0; font-size: 14px; color: #666;">This link will expire in 7 days.</p>"#,
+            contact_label, config.app_name, revert_link
+        );
+
+        let subject = format!("Your {} is being changed", contact_label);
+        let html = build_html_wrapper(config, "Security Notice", &content);
+
+        Email::new("noreply@example.com", "placeholder@example.com", &subject).html(html)
+    }
+}
+
+/// Account Deletion Template
+pub mod account_deletion {
+    use super::*;
+
+    /// Build the notice sent when account deletion is scheduled
+    pub fn build(config: &EmailTemplateConfig, grace_days: i64, cancel_link: &str) -> Email {
+        let content = format!(
+            r#"<p style="margin: 0 0 20px 0; font-size: 16px;">Hello,</p>
+<p style="margin: 0 0 20px 0; font-size: 16px;">Your {} account is scheduled for deletion. All of your data will be permanently removed in {} days.</p>
+<p style="margin: 0 0 20px 0; font-size: 16px;">If you change your mind, sign in and cancel the deletion before then:</p>
+<table width="100%" cellpadding="0" cellspacing="0" style="margin: 20px 0;">
+    <tr>
+        <td align="center">
+            <a href="{}" style="background: {}; color: #ffffff; padding: 14px 32px; text-decoration: none; border-radius: 6px; font-weight: 600; font-size: 16px; display: inline-block;">Keep My Account</a>
+        </td>
+    </tr>
+</table>
+<p style="margin: 0 0 20px 0; font-size: 14px; color: #666;">If this wasn't you, cancel the deletion and change your password right away.</p>"#,
+            config.app_name, grace_days, cancel_link, config.primary_color
+        );
+
+        let subject = format!("Your {} account is scheduled for deletion", config.app_name);
+        let html = build_html_wrapper(config, "Account Deletion", &content);
+
+        Email::new("noreply@example.com", "placeholder@example.com", &subject).html(html)
+    }
+}
+
+/// Login Code Email Template - second step of a challenged sign-in
+pub mod login_code {
+    use super::*;
+
+    /// Build the one-time code email for a sign-in that needs extra verification
+    pub fn build(config: &EmailTemplateConfig, code: &str) -> Email {
+        let content = format!(
+            r#"<p style="margin: 0 0 20px 0; font-size: 16px;">Hello,</p>
+<p style="margin: 0 0 20px 0; font-size: 16px;">We noticed a sign-in to your {} account that looks different from usual. Enter this code to finish signing in:</p>
+<div style="background: #f8f9fa; padding: 15px; border-radius: 6px; text-align: center; margin: 20px 0;">
+    <p style="margin: 0; font-size: 24px; font-weight: bold; letter-spacing: 4px; color: {};">{}</p>
+</div>
+<p style="margin: 0 0 20px 0; font-size: 14px; color: #666;">This code will expire in 10 minutes. If you aren't signing in, change your password right away.</p>"#,
+            config.app_name, config.primary_color, code
+        );
+
+        let html = build_html_wrapper(config, "Verify Your Sign-In", &content);
+
+        Email::new("noreply@example.com", "placeholder@example.com", "Verify Your Sign-In")
+            .html(html)
+    }
+}
+
+/// New Sign-In Alert Email Template
+pub mod new_sign_in {
+    use super::*;
+
+    /// Build the alert sent after a successful sign-in from somewhere new
+    pub fn build(
+        config: &EmailTemplateConfig,
+        location: &str,
+        device: &str,
+        security_link: &str,
+    ) -> Email {
+        let content = format!(
+            r#"<p style="margin: 0 0 20px 0; font-size: 16px;">Hello,</p>
+<p style="margin: 0 0 20px 0; font-size: 16px;">Your {} account was just signed in to from a new location or device.</p>
+<div style="background: #f8f9fa; padding: 15px; border-radius: 6px; margin: 20px 0;">
+    <p style="margin: 0 0 5px 0; font-size: 14px;"><strong>Location:</strong> {}</p>
+    <p style="margin: 0; font-size: 14px;"><strong>Device:</strong> {}</p>
+</div>
+<p style="margin: 0 0 20px 0; font-size: 16px;">If this was you, there's nothing to do. If not, review your sessions and change your password:</p>
+<table width="100%" cellpadding="0" cellspacing="0" style="margin: 20px 0;">
+    <tr>
+        <td align="center">
+            <a href="{}" style="background: #dc3545; color: #ffffff; padding: 14px 32px; text-decoration: none; border-radius: 6px; font-weight: 600; font-size: 16px; display: inline-block;">Secure My Account</a>
+        </td>
+    </tr>
+</table>"#,
+            config.app_name, location, device, security_link
+        );
+
+        let subject = format!("New sign-in to your {} account", config.app_name);
+        let html = build_html_wrapper(config, "New Sign-In", &content);
+
+        Email::new("noreply@example.com", "placeholder@example.com", &subject).html(html)
+    }
+}
+
 /// Generic Notification Template
 pub mod notification {
     use super::*;
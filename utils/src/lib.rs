@@ -2,6 +2,7 @@ pub mod email;
 pub mod email_templates;
 pub mod encryption;
 pub mod hash;
+pub mod phone;
 pub mod response;
 pub mod signature;
 pub mod sms;
@@ -0,0 +1,210 @@
+//! Phone Number Module
+//!
+//! E.164 parsing and normalization. Numbers written without a country code
+//! (`0712 345 678`) are read in a default region, so `0712345678`,
+//! `254712345678` and `+254 712 345 678` all become `+254712345678`.
+//!
+//! Only the regions below know their national number lengths. Other numbers
+//! are accepted in international form (`+...`) if they fit E.164.
+
+/// E.164 allows at most 15 digits, country code included
+const MAX_DIGITS: usize = 15;
+/// Shortest numbers in use (country code included)
+const MIN_DIGITS: usize = 8;
+
+/// Numbering rules of a region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhoneRegion {
+    pub code:         &'static str, // ISO 3166-1 alpha-2
+    pub calling_code: &'static str,
+    pub min_length:   usize, // National significant number length
+    pub max_length:   usize,
+    pub trunk_prefix: &'static str, // Dialled before national numbers, e.g. "0"
+}
+
+const fn region_rule(
+    code: &'static str,
+    calling_code: &'static str,
+    min_length: usize,
+    max_length: usize,
+    trunk_prefix: &'static str,
+) -> PhoneRegion {
+    PhoneRegion { code, calling_code, min_length, max_length, trunk_prefix }
+}
+
+const REGIONS: &[PhoneRegion] = &[
+    region_rule("KE", "254", 9, 9, "0"),
+    region_rule("UG", "256", 9, 9, "0"),
+    region_rule("TZ", "255", 9, 9, "0"),
+    region_rule("RW", "250", 9, 9, "0"),
+    region_rule("ET", "251", 9, 9, "0"),
+    region_rule("NG", "234", 10, 10, "0"),
+    region_rule("GH", "233", 9, 9, "0"),
+    region_rule("ZA", "27", 9, 9, "0"),
+    region_rule("EG", "20", 10, 10, "0"),
+    region_rule("AE", "971", 8, 9, "0"),
+    region_rule("IN", "91", 10, 10, "0"),
+    region_rule("GB", "44", 9, 10, "0"),
+    region_rule("FR", "33", 9, 9, "0"),
+    region_rule("DE", "49", 6, 13, "0"),
+    region_rule("US", "1", 10, 10, "1"),
+    region_rule("CA", "1", 10, 10, "1"),
+];
+
+/// Phone number errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PhoneError {
+    InvalidCharacters,
+    MissingCountryCode,
+    InvalidLength,
+    UnknownRegion(String),
+}
+
+impl std::fmt::Display for PhoneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PhoneError::InvalidCharacters => write!(f, "phone numbers may only contain digits"),
+            PhoneError::MissingCountryCode => write!(f, "phone number needs a country code"),
+            PhoneError::InvalidLength => write!(f, "phone number has the wrong length"),
+            PhoneError::UnknownRegion(code) => write!(f, "unknown phone region: {}", code),
+        }
+    }
+}
+
+impl std::error::Error for PhoneError {}
+
+/// Numbering rules of a region by ISO code (case-insensitive)
+pub fn region(code: &str) -> Option<&'static PhoneRegion> {
+    REGIONS.iter().find(|r| r.code.eq_ignore_ascii_case(code))
+}
+
+/// Region rules for an international number, by its country code prefix
+fn region_of(digits: &str) -> Option<&'static PhoneRegion> {
+    REGIONS.iter().find(|r| digits.starts_with(r.calling_code))
+}
+
+/// Digits of a number with the usual separators removed
+///
+/// Returns whether it was written in international form (`+` or `00`).
+fn digits_of(raw: &str) -> Result<(String, bool), PhoneError> {
+    let compact: String = raw
+        .trim()
+        .chars()
+        .filter(|c| !(c.is_whitespace() || matches!(c, '-' | '.' | '(' | ')')))
+        .collect();
+
+    let (digits, international) = match compact.strip_prefix('+') {
+        Some(rest) => (rest, true),
+        None => match compact.strip_prefix("00") {
+            Some(rest) => (rest, true),
+            None => (compact.as_str(), false),
+        },
+    };
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(PhoneError::InvalidCharacters);
+    }
+
+    Ok((digits.to_string(), international))
+}
+
+/// Check an international number (digits after `+`) and drop a trunk prefix
+/// dialled by mistake, e.g. `+2540712...`
+fn international(digits: &str) -> Result<String, PhoneError> {
+    if digits.starts_with('0') {
+        return Err(PhoneError::MissingCountryCode);
+    }
+
+    let digits = match region_of(digits) {
+        Some(region) => {
+            let national = &digits[region.calling_code.len()..];
+            let national = match national.strip_prefix(region.trunk_prefix) {
+                Some(rest) if national.len() > region.max_length => rest,
+                _ => national,
+            };
+            if !(region.min_length..=region.max_length).contains(&national.len()) {
+                return Err(PhoneError::InvalidLength);
+            }
+            format!("{}{}", region.calling_code, national)
+        }
+        None => digits.to_string(),
+    };
+
+    if !(MIN_DIGITS..=MAX_DIGITS).contains(&digits.len()) {
+        return Err(PhoneError::InvalidLength);
+    }
+
+    Ok(format!("+{}", digits))
+}
+
+/// Normalize a phone number to E.164 (`+254712345678`)
+///
+/// `default_region` is used for numbers written without a country code.
+pub fn to_e164(raw: &str, default_region: Option<&str>) -> Result<String, PhoneError> {
+    let (digits, is_international) = digits_of(raw)?;
+    if is_international {
+        return international(&digits);
+    }
+
+    let region = match default_region {
+        Some(code) => region(code).ok_or_else(|| PhoneError::UnknownRegion(code.to_string()))?,
+        None => return Err(PhoneError::MissingCountryCode),
+    };
+    let fits = |national: &str| (region.min_length..=region.max_length).contains(&national.len());
+
+    // National form with the trunk prefix (0712...), without it (712...),
+    // or international without the "+" (254712...)
+    if let Some(national) = digits.strip_prefix(region.trunk_prefix).filter(|n| fits(n)) {
+        return international(&format!("{}{}", region.calling_code, national));
+    }
+    if fits(&digits) {
+        return international(&format!("{}{}", region.calling_code, digits));
+    }
+    if digits.starts_with(region.calling_code) {
+        return international(&digits);
+    }
+
+    Err(PhoneError::InvalidLength)
+}
+
+/// Whether an identifier is written like a phone number (digits and separators)
+pub fn looks_like_phone(raw: &str) -> bool {
+    digits_of(raw).is_ok_and(|(digits, _)| (7..=MAX_DIGITS).contains(&digits.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_national_forms_share_one_number() {
+        for raw in ["0712345678", "0712 345 678", "712345678", "254712345678", "+254 712-345-678", "00254712345678"] {
+            assert_eq!(to_e164(raw, Some("KE")).unwrap(), "+254712345678", "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_international_ignores_default_region() {
+        assert_eq!(to_e164("+44 20 7946 0958", Some("KE")).unwrap(), "+442079460958");
+        assert_eq!(to_e164("+1 (202) 555-0123", None).unwrap(), "+12025550123");
+        // Trunk prefix dialled after the country code
+        assert_eq!(to_e164("+2540712345678", None).unwrap(), "+254712345678");
+    }
+
+    #[test]
+    fn test_rejects_invalid_numbers() {
+        assert_eq!(to_e164("0712345678", None), Err(PhoneError::MissingCountryCode));
+        assert_eq!(to_e164("07123", Some("KE")), Err(PhoneError::InvalidLength));
+        assert_eq!(to_e164("+25471234567890", None), Err(PhoneError::InvalidLength));
+        assert_eq!(to_e164("0712abc678", Some("KE")), Err(PhoneError::InvalidCharacters));
+        assert!(matches!(to_e164("0712345678", Some("XX")), Err(PhoneError::UnknownRegion(_))));
+    }
+
+    #[test]
+    fn test_looks_like_phone() {
+        assert!(looks_like_phone("+254 712 345 678"));
+        assert!(looks_like_phone("0712345678"));
+        assert!(!looks_like_phone("amani"));
+        assert!(!looks_like_phone("12345"));
+    }
+}
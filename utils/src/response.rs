@@ -10,7 +10,7 @@ pub struct ApiResponse<T> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<ApiError>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub meta: Option<ResponseMeta>,
+    pub meta: Option<PageMeta>,
 }
 
 impl<T> ApiResponse<T> {
@@ -63,7 +63,13 @@ impl<T> ApiResponse<T> {
 
     /// Add pagination metadata to response
     pub fn with_meta(mut self, meta: ResponseMeta) -> Self {
-        self.meta = Some(meta);
+        self.meta = Some(PageMeta::Offset(meta));
+        self
+    }
+
+    /// Add cursor pagination metadata to response
+    pub fn with_cursor(mut self, meta: CursorMeta) -> Self {
+        self.meta = Some(PageMeta::Cursor(meta));
         self
     }
 }
@@ -104,13 +110,42 @@ impl ResponseMeta {
     }
 }
 
+/// Cursor pagination metadata
+///
+/// Pass `next_cursor` back as `cursor` to fetch the following page.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CursorMeta {
+    pub per_page: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+impl CursorMeta {
+    pub fn new(per_page: u32, next_cursor: Option<String>) -> Self {
+        Self {
+            per_page,
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }
+}
+
+/// Page number or cursor metadata, serialized as either one
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum PageMeta {
+    Offset(ResponseMeta),
+    Cursor(CursorMeta),
+}
+
 /// Paginated response helper
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PaginatedResponse<T> {
     pub success: bool,
     pub message: String,
     pub data: Vec<T>,
-    pub meta: ResponseMeta,
+    pub meta: PageMeta,
 }
 
 impl<T> PaginatedResponse<T> {
@@ -119,7 +154,17 @@ impl<T> PaginatedResponse<T> {
             success: true,
             message: message.into(),
             data,
-            meta,
+            meta: PageMeta::Offset(meta),
+        }
+    }
+
+    /// Page fetched with a cursor
+    pub fn with_cursor(message: impl Into<String>, data: Vec<T>, meta: CursorMeta) -> Self {
+        Self {
+            success: true,
+            message: message.into(),
+            data,
+            meta: PageMeta::Cursor(meta),
         }
     }
 }
@@ -345,7 +345,8 @@ pub fn verify_signed_url(
         return Err(SignatureError::InvalidSignature);
     }
 
-    let signature = sig_parts[0];
+    // The signature is URL-encoded by `create_signed_url`
+    let signature = urlencoding::decode(sig_parts[0]).map_err(|_| SignatureError::InvalidSignature)?;
     let timestamp: i64 = sig_parts[1]
         .parse()
         .map_err(|_| SignatureError::InvalidSignature)?;
@@ -354,7 +355,7 @@ pub fn verify_signed_url(
     let full_message = format!("{}?{}", path, query);
 
     // Verify
-    Signer::quick_verify(&full_message, signature, timestamp, key, max_age_minutes)
+    Signer::quick_verify(&full_message, &signature, timestamp, key, max_age_minutes)
 }
 
 #[cfg(test)]
@@ -406,4 +407,16 @@ mod tests {
 
         assert!(request.verify(&key, 5).unwrap());
     }
+
+    #[test]
+    fn test_signed_url() {
+        let key = Signer::generate_key();
+        let query = create_signed_url("/invite", &[("id", "42"), ("email", "a@b.com")], &key).unwrap();
+
+        assert!(verify_signed_url("/invite", &query, &key, 5).unwrap());
+
+        // Tampered params should fail
+        let tampered = query.replace("id=42", "id=43");
+        assert!(!verify_signed_url("/invite", &tampered, &key, 5).unwrap());
+    }
 }
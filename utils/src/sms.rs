@@ -6,6 +6,8 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
+use crate::phone;
+
 /// SMS provider type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SmsProvider {
@@ -257,6 +259,16 @@ pub mod templates {
         )
     }
 
+    pub fn contact_change_notice(to: &str, revert_link: &str) -> SmsMessage {
+        SmsMessage::new(
+            to,
+            format!(
+                "A request was made to change the phone number on your account. Not you? Undo it: {}",
+                revert_link
+            ),
+        )
+    }
+
     pub fn alert(to: &str, message: &str) -> SmsMessage {
         SmsMessage::new(to, format!("Alert: {}", message))
     }
@@ -333,8 +345,12 @@ impl SmsSender for NexmoSender {
 }
 
 /// SMS Service
+///
+/// Recipients are normalized to E.164 before sending - numbers without a
+/// country code need a default region.
 pub struct SmsService {
-    sender: Box<dyn SmsSender + Send + Sync + 'static>,
+    sender:         Box<dyn SmsSender + Send + Sync + 'static>,
+    default_region: Option<String>,
 }
 
 impl SmsService {
@@ -362,27 +378,42 @@ impl SmsService {
                 return Err(SmsError::Config("HTTP API not implemented".into()));
             }
         };
-        Ok(Self { sender })
+        Ok(Self { sender, default_region: None })
     }
 
     pub fn twilio(config: TwilioConfig) -> Self {
         Self {
             sender: Box::new(TwilioSender::new(config)),
+            default_region: None,
         }
     }
     pub fn sns(config: SnsConfig) -> Self {
         Self {
             sender: Box::new(SnsSender::new(config)),
+            default_region: None,
         }
     }
     pub fn nexmo(config: NexmoConfig) -> Self {
         Self {
             sender: Box::new(NexmoSender::new(config)),
+            default_region: None,
         }
     }
 
+    /// Region for recipients written without a country code (ISO code, e.g. "KE")
+    pub fn with_default_region(mut self, region: impl Into<String>) -> Self {
+        self.default_region = Some(region.into());
+        self
+    }
+
     pub async fn send(&self, message: &SmsMessage) -> SmsResult {
-        self.sender.send(message).await
+        let to = match phone::to_e164(&message.to, self.default_region.as_deref()) {
+            Ok(to) => to,
+            Err(e) => return SmsResult::failed(SmsError::InvalidNumber(e.to_string()).to_string()),
+        };
+
+        let message = SmsMessage { to, ..message.clone() };
+        self.sender.send(&message).await
     }
     pub async fn send_to_multiple(&self, to: Vec<String>, body: &str) -> Vec<SmsResult> {
         let mut results = Vec::new();